@@ -1,42 +1,146 @@
-//! ThreatForge MCP Server binary — stdio transport.
+//! ThreatForge MCP Server binary — stdio or streamable HTTP transport.
 //!
 //! Usage:
-//!   threatforge-mcp <path-to-model.thf>
+//!   threatforge-mcp [--read-only] [--audit-dir <dir>] [--http <port>] [--allow-origin <origin>]... <path-to-model.thf | workspace-dir>
 //!
-//! External AI tools (Claude Code, VS Code Copilot, Cursor) spawn this
-//! process and communicate via MCP over stdin/stdout.
+//! `--read-only` disables every tool that changes a model. Changes are logged
+//! to `<model>.audit.jsonl` beside each model, or under `--audit-dir`.
+//!
+//! Given a directory, the server runs in workspace mode: every `.thf` below it
+//! is a model that clients pick with `list_models` and `open_model`.
+//!
+//! By default, external AI tools (Claude Code, VS Code Copilot, Cursor) spawn
+//! this process and communicate via MCP over stdin/stdout. With `--http`, the
+//! server instead listens on `http://127.0.0.1:<port>/mcp` so several clients
+//! can share one model; the bearer token that opens a session is printed to
+//! stderr (or taken from `THREATFORGE_MCP_TOKEN`), and each session then uses
+//! the token returned in its `Mcp-Session-Token` header.
 
 use std::path::PathBuf;
 
 use rmcp::transport::stdio;
 use rmcp::ServiceExt;
+use tokio_util::sync::CancellationToken;
 
+use threat_forge_lib::mcp::http::{self, HttpOptions};
 use threat_forge_lib::mcp::server::ThreatForgeServer;
 
+const USAGE: &str =
+    "Usage: threatforge-mcp [--read-only] [--audit-dir <dir>] [--http <port>] [--allow-origin <origin>]... <path-to-model.thf | workspace-dir>";
+
+struct Args {
+    file_path: PathBuf,
+    read_only: bool,
+    audit_dir: Option<PathBuf>,
+    http_port: Option<u16>,
+    allowed_origins: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut file_path = None;
+    let mut read_only = false;
+    let mut audit_dir = None;
+    let mut http_port = None;
+    let mut allowed_origins = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--read-only" => read_only = true,
+            "--audit-dir" => {
+                audit_dir = Some(PathBuf::from(
+                    args.next().ok_or("--audit-dir needs a directory")?,
+                ));
+            }
+            "--http" => {
+                let port = args.next().ok_or("--http needs a port")?;
+                http_port = Some(
+                    port.parse::<u16>()
+                        .map_err(|_| format!("Invalid port: {port}"))?,
+                );
+            }
+            "--allow-origin" => {
+                allowed_origins.push(args.next().ok_or("--allow-origin needs an origin")?);
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {arg}")),
+            _ if file_path.is_none() => file_path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {arg}")),
+        }
+    }
+
+    if !allowed_origins.is_empty() && http_port.is_none() {
+        return Err("--allow-origin only applies with --http".to_string());
+    }
+    if read_only && audit_dir.is_some() {
+        // A read-only server changes nothing, so there is nothing to audit and
+        // no reason to create the directory.
+        return Err("--audit-dir cannot be combined with --read-only".to_string());
+    }
+    Ok(Args {
+        file_path: file_path.ok_or("Missing path to a .thf file or workspace directory")?,
+        read_only,
+        audit_dir,
+        http_port,
+        allowed_origins,
+    })
+}
+
 #[tokio::main]
 async fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: threatforge-mcp <path-to-model.thf>");
-        std::process::exit(1);
-    }
-    let file_path = PathBuf::from(&args[1]);
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            std::process::exit(1);
+        }
+    };
+    let file_path = args.file_path;
     if !file_path.exists() {
         eprintln!("File not found: {}", file_path.display());
         std::process::exit(1);
     }
-    if file_path.extension().and_then(|e| e.to_str()) != Some("thf") {
-        eprintln!("Expected a .thf file, got: {}", file_path.display());
+    let is_workspace = file_path.is_dir();
+    if !is_workspace && file_path.extension().and_then(|e| e.to_str()) != Some("thf") {
+        eprintln!(
+            "Expected a .thf file or a directory, got: {}",
+            file_path.display()
+        );
         std::process::exit(1);
     }
 
-    let server = match ThreatForgeServer::new(file_path) {
+    let server = if is_workspace {
+        ThreatForgeServer::workspace(file_path)
+    } else {
+        ThreatForgeServer::new(file_path)
+    };
+    let mut server = match server {
+        Ok(s) if args.read_only => s.read_only(),
         Ok(s) => s,
         Err(e) => {
             eprintln!("Failed to initialize MCP server: {e}");
             std::process::exit(1);
         }
     };
+    if let Some(dir) = args.audit_dir {
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            eprintln!("Failed to create audit directory {}: {e}", dir.display());
+            std::process::exit(1);
+        }
+        server = server.audit_dir(dir);
+    }
+
+    if let Some(port) = args.http_port {
+        let mut options = HttpOptions::localhost(port);
+        options.allowed_origins = args.allowed_origins;
+        let from_env = std::env::var(http::TOKEN_ENV)
+            .ok()
+            .filter(|t| !t.is_empty());
+        if let Some(token) = &from_env {
+            options.token = token.clone();
+        }
+        serve_http(server, options, from_env.is_none()).await;
+        return;
+    }
 
     let service = match server.serve(stdio()).await {
         Ok(s) => s,
@@ -51,3 +155,42 @@ async fn main() {
         std::process::exit(1);
     }
 }
+
+async fn serve_http(server: ThreatForgeServer, options: HttpOptions, print_token: bool) {
+    let listener = match http::bind(&options).await {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Failed to bind {}: {e}", options.addr);
+            std::process::exit(1);
+        }
+    };
+    let addr = match listener.local_addr() {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("Failed to read the bound address: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // The first line is stable so scripts (and the integration tests) can
+    // pick up the port when `--http 0` chose a free one.
+    eprintln!(
+        "threatforge-mcp listening on http://{addr}{}",
+        http::MCP_PATH
+    );
+    if print_token {
+        eprintln!("Bearer token: {}", options.token);
+    }
+
+    let shutdown = CancellationToken::new();
+    let on_signal = shutdown.clone();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        on_signal.cancel();
+    });
+
+    if let Err(e) = http::serve(server, listener, options, shutdown).await {
+        eprintln!("MCP server error: {e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,184 @@
+//! ThreatForge report generator — renders a threat model as a Markdown
+//! report without the desktop app, for CI jobs and pre-commit hooks.
+//!
+//! Usage:
+//!   threatforge-report [--template <name|file>] [--output <file>] <path-to-model.thf>
+//!   threatforge-report --sarif [--rules <template.tb7>] [--output <file>] <path-to-model.thf>
+//!   threatforge-report --svg|--png [--diagram <id|name>] [--scale <n>] [--output <file>] <path-to-model.thf>
+//!
+//! The report goes to stdout unless `--output` names a file. It depends only
+//! on the model, so a report committed beside its `.thf` changes exactly when
+//! the model does.
+//!
+//! `--template` renders through a report template instead of the standard
+//! Markdown report: `html`, `markdown` or `text` for a built-in one, the file
+//! name of a template in `.threatforge/reports/` beside the model, or the
+//! path to a template file.
+//!
+//! `--sarif` writes the open threats as a SARIF 2.1.0 log for code-scanning
+//! dashboards instead of a report. Results point at the model's lines,
+//! relative to the git repository holding it. With `--rules`, threats the
+//! rules of a Threat Modeling Tool template generated are reported under
+//! those rules' ids rather than their STRIDE category.
+//!
+//! `--svg` and `--png` draw a diagram instead: the first one, or the one
+//! `--diagram` names by id or name. `--scale` sets the PNG's pixels per
+//! canvas unit (default 2).
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use threat_forge_lib::report::{self, template::BUILTIN_TEMPLATES};
+
+const USAGE: &str =
+    "Usage: threatforge-report [--template <name|file>] [--output <file>] <path-to-model.thf>
+       threatforge-report --sarif [--rules <template.tb7>] [--output <file>] <path-to-model.thf>
+       threatforge-report --svg|--png [--diagram <id|name>] [--scale <n>] [--output <file>] <path-to-model.thf>";
+
+/// PNG pixels per canvas unit unless `--scale` says otherwise.
+const DEFAULT_SCALE: f32 = 2.0;
+
+struct Args {
+    model_path: PathBuf,
+    template: Option<String>,
+    sarif: bool,
+    rules: Option<PathBuf>,
+    svg: bool,
+    png: bool,
+    diagram: Option<String>,
+    scale: Option<f32>,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut model_path = None;
+    let mut template = None;
+    let mut sarif = false;
+    let mut rules = None;
+    let mut svg = false;
+    let mut png = false;
+    let mut diagram = None;
+    let mut scale = None;
+    let mut output = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--template" | "-t" => {
+                template = Some(args.next().ok_or("--template needs a name or file")?);
+            }
+            "--sarif" => sarif = true,
+            "--rules" => {
+                rules = Some(PathBuf::from(
+                    args.next().ok_or("--rules needs a .tb7 file")?,
+                ));
+            }
+            "--svg" => svg = true,
+            "--png" => png = true,
+            "--diagram" | "-d" => {
+                diagram = Some(args.next().ok_or("--diagram needs an id or name")?);
+            }
+            "--scale" => {
+                let value = args.next().ok_or("--scale needs a number")?;
+                scale = Some(
+                    value
+                        .parse::<f32>()
+                        .map_err(|_| format!("--scale needs a number, not {value}"))?,
+                );
+            }
+            "--output" | "-o" => {
+                output = Some(PathBuf::from(args.next().ok_or("--output needs a file")?));
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {arg}")),
+            _ if model_path.is_none() => model_path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {arg}")),
+        }
+    }
+
+    if sarif && template.is_some() {
+        return Err("--sarif and --template cannot be combined".to_string());
+    }
+    if rules.is_some() && !sarif {
+        return Err("--rules only applies to --sarif".to_string());
+    }
+    let formats = [template.is_some(), sarif, svg, png];
+    if formats.iter().filter(|set| **set).count() > 1 {
+        return Err("Choose one of --template, --sarif, --svg and --png".to_string());
+    }
+    if diagram.is_some() && !(svg || png) {
+        return Err("--diagram only applies to --svg and --png".to_string());
+    }
+    if scale.is_some() && !png {
+        return Err("--scale only applies to --png".to_string());
+    }
+
+    Ok(Args {
+        model_path: model_path.ok_or("Missing path to a .thf file")?,
+        template,
+        sarif,
+        rules,
+        svg,
+        png,
+        diagram,
+        scale,
+        output,
+    })
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            std::process::exit(1);
+        }
+    };
+
+    let model = match report::read_model(&args.model_path) {
+        Ok(model) => model,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let diagram = args.diagram.as_deref();
+    let output = match args.template.as_deref() {
+        None if args.png => {
+            report::render_png(&model, diagram, args.scale.unwrap_or(DEFAULT_SCALE))
+        }
+        None if args.svg => report::render_svg(&model, diagram).map(String::into_bytes),
+        None if args.sarif => report::render_sarif(&model, &args.model_path, args.rules.as_deref())
+            .map(String::into_bytes),
+        None => Ok(report::render_markdown(&model).into_bytes()),
+        Some(template)
+            if !BUILTIN_TEMPLATES.iter().any(|t| t.name == template)
+                && Path::new(template).is_file() =>
+        {
+            report::render_template_file(&model, Path::new(template)).map(String::into_bytes)
+        }
+        Some(template) => report::render_template(&model, Some(&args.model_path), template)
+            .map(String::into_bytes),
+    };
+    let bytes = match output {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    match args.output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, bytes) {
+                eprintln!("Failed to write {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+        None => {
+            if let Err(e) = std::io::stdout().write_all(&bytes) {
+                eprintln!("Failed to write the output: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
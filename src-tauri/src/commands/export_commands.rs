@@ -0,0 +1,34 @@
+use crate::exporters::{otm, threat_dragon, threat_register, tm7};
+use crate::models::ThreatModel;
+use crate::report;
+use std::path::{Path, PathBuf};
+
+/// `model_path` is the `.thf` file the model was opened from or saved to;
+/// SARIF results point at lines in it.
+#[tauri::command]
+pub fn export_threat_model(
+    path: String,
+    format: String,
+    model: ThreatModel,
+    model_path: Option<String>,
+) -> Result<(), String> {
+    let contents = match format.as_str() {
+        "tm7" => tm7::write_tm7(&model).into_bytes(),
+        "threatdragon" => threat_dragon::write_threat_dragon(&model).into_bytes(),
+        "otm" => otm::write_otm(&model).into_bytes(),
+        "markdown" => report::render_markdown(&model).into_bytes(),
+        "csv" => threat_register::write_csv(&model).into_bytes(),
+        "xlsx" => threat_register::write_xlsx(&model, false)?,
+        // The register plus a sheet of the elements and flows.
+        "xlsx-architecture" => threat_register::write_xlsx(&model, true)?,
+        "sarif" => {
+            let model_path = model_path
+                .ok_or("Save the threat model first: SARIF results point at its .thf file")?;
+            report::render_sarif(&model, Path::new(&model_path), None)?.into_bytes()
+        }
+        _ => return Err(format!("Unsupported export format: {format}")),
+    };
+
+    let path = PathBuf::from(&path);
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write file: {e}"))
+}
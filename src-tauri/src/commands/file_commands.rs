@@ -1,7 +1,17 @@
-use crate::file_io;
+use crate::file_io::{self, FileStamp, FileWatchRegistry};
 use crate::models::{DiagramLayout, ThreatModel};
+use serde::Serialize;
 use std::fs;
 use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, State};
+
+/// Payload of the `model-changed-on-disk` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelChangedOnDisk {
+    path: String,
+    stamp: FileStamp,
+}
 
 #[tauri::command]
 pub fn create_new_model(title: String, author: String) -> Result<ThreatModel, String> {
@@ -16,10 +26,57 @@ pub fn open_threat_model(path: String) -> Result<ThreatModel, String> {
     Ok(model)
 }
 
+/// Save a model, refusing if the file changed on disk since `expected` was taken.
+///
+/// `expected` is the stamp returned by [`threat_model_stamp`] or by the
+/// previous save; omit it for Save As. Returns the stamp of the written file.
 #[tauri::command]
-pub fn save_threat_model(path: String, model: ThreatModel) -> Result<(), String> {
+pub fn save_threat_model(
+    path: String,
+    model: ThreatModel,
+    expected: Option<FileStamp>,
+) -> Result<FileStamp, String> {
     let path = PathBuf::from(path);
-    file_io::write_threat_model(&path, &model).map_err(|e| e.to_string())
+    file_io::write_threat_model_guarded(&path, &model, expected.as_ref()).map_err(|e| e.to_string())
+}
+
+/// Stamp a `.thf` file so a later [`save_threat_model`] can detect concurrent edits.
+#[tauri::command]
+pub fn threat_model_stamp(path: String) -> Result<FileStamp, String> {
+    let path = PathBuf::from(path);
+    file_io::read_file_stamp(&path).map_err(|e| e.to_string())
+}
+
+/// Emit `model-changed-on-disk` whenever another writer — typically the MCP
+/// server — changes `path`. `baseline` is the stamp the frontend already holds.
+#[tauri::command]
+pub fn watch_threat_model(
+    app: AppHandle,
+    watches: State<'_, FileWatchRegistry>,
+    path: String,
+    baseline: Option<FileStamp>,
+) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    watches.watch(&path, baseline, move |path, stamp| {
+        let payload = ModelChangedOnDisk {
+            path: path.display().to_string(),
+            stamp: stamp.clone(),
+        };
+        if app.emit("model-changed-on-disk", payload).is_err() {
+            eprintln!("Failed to deliver model-changed-on-disk notification");
+        }
+    });
+    Ok(())
+}
+
+/// Stop the watch started by [`watch_threat_model`].
+#[tauri::command]
+pub fn unwatch_threat_model(
+    watches: State<'_, FileWatchRegistry>,
+    path: String,
+) -> Result<(), String> {
+    watches.unwatch(&PathBuf::from(path));
+    Ok(())
 }
 
 #[tauri::command]
@@ -1,15 +1,87 @@
-use crate::importers::tm7;
+use crate::importers::mitigations::{self, MitigationImport};
+use crate::importers::report::ImportedModel;
+use crate::importers::{
+    compose, drawio, kubernetes, openapi, otm, pytm, terraform, threagile, threat_dragon, tm7,
+};
 use crate::models::ThreatModel;
+use serde::Deserialize;
 use std::path::PathBuf;
 
 #[tauri::command]
-pub fn import_threat_model(path: String, format: String) -> Result<ThreatModel, String> {
+pub fn import_threat_model(path: String, format: String) -> Result<ImportedModel, String> {
     let path = PathBuf::from(&path);
     let contents =
         std::fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {e}"))?;
 
     match format.as_str() {
         "tm7" => tm7::parse_tm7(&contents).map_err(|e| e.to_string()),
+        "threatdragon" => threat_dragon::parse_threat_dragon(&contents).map_err(|e| e.to_string()),
+        "otm" => otm::parse_otm(&contents).map_err(|e| e.to_string()),
+        "compose" => compose::parse_compose(&contents).map_err(|e| e.to_string()),
+        "kubernetes" => kubernetes::parse_kubernetes(&contents).map_err(|e| e.to_string()),
+        "terraform" => terraform::parse_terraform(&contents).map_err(|e| e.to_string()),
+        "openapi" => openapi::parse_openapi(&contents).map_err(|e| e.to_string()),
+        "pytm" => pytm::parse_pytm(&contents).map_err(|e| e.to_string()),
+        "threagile" => threagile::parse_threagile(&contents).map_err(|e| e.to_string()),
+        "drawio" => drawio::parse_drawio(&contents).map_err(|e| e.to_string()),
+        // Threat Dragon, OTM, Terraform, OpenAPI and pytm all use `.json`; each
+        // but Threat Dragon carries a key the others lack.
+        "json" => match first_json_key(
+            &contents,
+            &["otmVersion", "format_version", "openapi", "flows"],
+        ) {
+            Some("otmVersion") => otm::parse_otm(&contents).map_err(|e| e.to_string()),
+            Some("openapi") => openapi::parse_openapi(&contents).map_err(|e| e.to_string()),
+            Some("format_version") => {
+                terraform::parse_terraform(&contents).map_err(|e| e.to_string())
+            }
+            Some("flows") => pytm::parse_pytm(&contents).map_err(|e| e.to_string()),
+            _ => threat_dragon::parse_threat_dragon(&contents).map_err(|e| e.to_string()),
+        },
+        // OTM, OpenAPI, Threagile, compose files and Kubernetes manifests all
+        // use `.yaml`.
+        "yaml" => match first_yaml_key(
+            &contents,
+            &[
+                "otmVersion",
+                "openapi",
+                "technical_assets",
+                "services",
+                "kind",
+            ],
+        ) {
+            Some("otmVersion") => otm::parse_otm(&contents).map_err(|e| e.to_string()),
+            Some("openapi") => openapi::parse_openapi(&contents).map_err(|e| e.to_string()),
+            Some("technical_assets") => {
+                threagile::parse_threagile(&contents).map_err(|e| e.to_string())
+            }
+            Some("kind") => kubernetes::parse_kubernetes(&contents).map_err(|e| e.to_string()),
+            _ => compose::parse_compose(&contents).map_err(|e| e.to_string()),
+        },
         _ => Err(format!("Unsupported import format: {format}")),
     }
 }
+
+/// Read the mitigation changes a triaged threat register CSV makes to
+/// `model`, for the frontend to apply.
+#[tauri::command]
+pub fn import_mitigations(path: String, model: ThreatModel) -> Result<MitigationImport, String> {
+    let contents = std::fs::read_to_string(PathBuf::from(&path))
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+    mitigations::parse_mitigation_csv(&model, &contents).map_err(|e| e.to_string())
+}
+
+/// The first of `keys` the JSON document has at its top level.
+fn first_json_key<'a>(contents: &str, keys: &[&'a str]) -> Option<&'a str> {
+    let document = serde_json::from_str::<serde_json::Value>(contents).ok()?;
+    keys.iter().copied().find(|key| document.get(key).is_some())
+}
+
+/// The first of `keys` the first non-empty YAML document has at its top
+/// level.
+fn first_yaml_key<'a>(contents: &str, keys: &[&'a str]) -> Option<&'a str> {
+    let document = serde_yaml::Deserializer::from_str(contents)
+        .map_while(|document| serde_yaml::Value::deserialize(document).ok())
+        .find(|document| !document.is_null())?;
+    keys.iter().copied().find(|key| document.get(key).is_some())
+}
@@ -1,5 +1,6 @@
 mod ai_commands;
 mod app_commands;
+mod export_commands;
 mod file_commands;
 mod import_commands;
 mod stride_commands;
@@ -7,6 +8,7 @@ mod update_commands;
 
 pub use ai_commands::*;
 pub use app_commands::*;
+pub use export_commands::*;
 pub use file_commands::*;
 pub use import_commands::*;
 pub use stride_commands::*;
@@ -1,3 +1,4 @@
+use crate::importers::tb7;
 use crate::models::{Threat, ThreatModel};
 use crate::stride;
 
@@ -5,3 +6,16 @@ use crate::stride;
 pub fn analyze_stride(model: ThreatModel) -> Result<Vec<Threat>, String> {
     Ok(stride::analyze(&model))
 }
+
+/// Run the threat types of a Threat Modeling Tool template (`.tb7`) over the
+/// model instead of the built-in rules.
+#[tauri::command]
+pub fn analyze_stride_with_template(
+    model: ThreatModel,
+    template_path: String,
+) -> Result<Vec<Threat>, String> {
+    let contents = std::fs::read_to_string(&template_path)
+        .map_err(|e| format!("Failed to read template: {e}"))?;
+    let template = tb7::parse_tb7(&contents).map_err(|e| e.to_string())?;
+    Ok(stride::analyze_with_rule_pack(&model, &template.pack))
+}
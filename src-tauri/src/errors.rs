@@ -52,6 +52,12 @@ pub enum ThreatForgeError {
     #[error("Circular group nesting detected at group '{id}'")]
     CircularGroupNesting { id: String },
 
+    /// The file changed on disk after it was loaded, so saving would discard
+    /// someone else's edits. Raised by both the desktop save path and the MCP
+    /// server; the caller reloads and retries rather than overwriting.
+    #[error("'{path}' was modified by another writer since it was loaded; reload it and retry")]
+    ConcurrentModification { path: String },
+
     #[error("Key storage error: {message}")]
     KeyStorage { message: String },
 
@@ -0,0 +1,5 @@
+pub mod otm;
+pub mod sarif;
+pub mod threat_dragon;
+pub mod threat_register;
+pub mod tm7;
@@ -0,0 +1,422 @@
+//! Writer for Open Threat Model (OTM) JSON documents.
+//!
+//! The inverse of [`crate::importers::otm::parse_otm`]: rectangular trust
+//! boundaries become trust zones nested by what they contain, elements become
+//! components inside their trust zone, data flows become dataflows, and every
+//! threat becomes a threat definition instantiated on the flow or element it
+//! was raised against. A threat's mitigation text becomes a mitigation of its
+//! own, implemented once the threat is mitigated. Each diagram becomes a
+//! diagram representation holding the positions drawn on it.
+//!
+//! Line boundaries, layers, groups, relationships and styling are not
+//! written. An element whose trust zone is not one of the boundaries goes in
+//! the innermost boundary holding it, then in a zone named after its trust
+//! zone. Severity is written as impact and likelihood scores the importer
+//! reads back to the same severity.
+
+use super::tm7::stride_category_name;
+use crate::importers::otm::{
+    OtmComponent, OtmDataflow, OtmDocument, OtmElementRepresentation, OtmMitigation,
+    OtmMitigationInstance, OtmParent, OtmPosition, OtmProject, OtmRepresentation, OtmSize,
+    OtmThreat, OtmThreatInstance, OtmThreatRisk, OtmTrustZone, OtmTrustZoneRisk,
+    DIAGRAM_REPRESENTATION,
+};
+use crate::models::layout::line_points;
+use crate::models::{
+    DataFlow, Element, MitigationStatus, Position, Severity, Threat, ThreatModel, TrustBoundary,
+};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The OTM schema version written.
+const OTM_VERSION: &str = "0.2.0";
+
+/// Trust rating of every written zone; we do not score boundaries.
+const TRUST_RATING: f64 = 50.0;
+
+/// Zone for elements with neither a boundary nor a trust zone.
+const DEFAULT_ZONE: &str = "default";
+
+/// Serialize a `ThreatModel` as an OTM JSON document.
+pub fn write_otm(model: &ThreatModel) -> String {
+    let main_diagram = model.diagrams.first().map(|d| d.id.as_str());
+    let diagram_of = |diagram: &Option<String>| -> Option<String> {
+        diagram.as_deref().or(main_diagram).map(str::to_string)
+    };
+
+    let zones: Vec<&TrustBoundary> = model
+        .trust_boundaries
+        .iter()
+        .filter(|b| line_points(b).is_none())
+        .collect();
+
+    let mut trust_zones: Vec<OtmTrustZone> = zones
+        .iter()
+        .map(|zone| OtmTrustZone {
+            id: zone.id.clone(),
+            name: zone.name.clone(),
+            description: None,
+            risk: OtmTrustZoneRisk {
+                trust_rating: TRUST_RATING,
+            },
+            parent: enclosing_zone(&zones, zone).map(|parent| OtmParent {
+                trust_zone: Some(parent.id.clone()),
+                component: None,
+            }),
+            representations: diagram_of(&zone.diagram)
+                .map(|diagram| OtmElementRepresentation {
+                    representation: diagram,
+                    id: format!("{}-representation", zone.id),
+                    position: zone.position.as_ref().map(otm_position),
+                    size: zone.size.as_ref().map(|s| OtmSize {
+                        width: s.width,
+                        height: s.height,
+                    }),
+                })
+                .into_iter()
+                .collect(),
+        })
+        .collect();
+
+    let mut components = Vec::new();
+    for element in &model.elements {
+        let zone = component_zone(&zones, element);
+        if !trust_zones.iter().any(|z| z.id == zone) {
+            trust_zones.push(OtmTrustZone {
+                id: zone.clone(),
+                name: zone.clone(),
+                description: None,
+                risk: OtmTrustZoneRisk {
+                    trust_rating: TRUST_RATING,
+                },
+                parent: None,
+                representations: Vec::new(),
+            });
+        }
+        components.push(component(element, zone, diagram_of(&element.diagram)));
+    }
+
+    let mut dataflows: Vec<OtmDataflow> = model.data_flows.iter().map(dataflow).collect();
+
+    let mut threats = Vec::new();
+    let mut mitigations = Vec::new();
+    for threat in &model.threats {
+        threats.push(threat_definition(threat));
+        let mitigation = threat
+            .mitigation
+            .as_ref()
+            .filter(|m| !m.description.trim().is_empty());
+        if let Some(m) = mitigation {
+            mitigations.push(OtmMitigation {
+                id: format!("{}-mitigation", threat.id),
+                name: m.description.lines().next().unwrap_or_default().to_string(),
+                description: Some(m.description.clone()),
+                risk_reduction: 100.0,
+            });
+        }
+
+        let status = threat.mitigation.as_ref().map(|m| &m.status);
+        let instance = OtmThreatInstance {
+            threat: threat.id.clone(),
+            state: threat_state(status).to_string(),
+            mitigations: mitigation
+                .map(|_| OtmMitigationInstance {
+                    mitigation: format!("{}-mitigation", threat.id),
+                    state: if status == Some(&MitigationStatus::Mitigated) {
+                        "implemented"
+                    } else {
+                        "required"
+                    }
+                    .to_string(),
+                })
+                .into_iter()
+                .collect(),
+        };
+        // A threat raised on a flow between two elements belongs to the flow.
+        let flow = threat
+            .flow
+            .as_deref()
+            .and_then(|id| dataflows.iter_mut().find(|f| f.id == id));
+        if let Some(flow) = flow {
+            flow.threats.push(instance);
+        } else if let Some(component) = threat
+            .element
+            .as_deref()
+            .and_then(|id| components.iter_mut().find(|c| c.id == id))
+        {
+            component.threats.push(instance);
+        }
+    }
+
+    let document = OtmDocument {
+        otm_version: OTM_VERSION.to_string(),
+        project: OtmProject {
+            name: model.metadata.title.clone(),
+            id: project_id(&model.metadata.title),
+            description: (!model.metadata.description.is_empty())
+                .then(|| model.metadata.description.clone()),
+            owner: (!model.metadata.author.is_empty()).then(|| model.metadata.author.clone()),
+        },
+        representations: model
+            .diagrams
+            .iter()
+            .map(|d| OtmRepresentation {
+                name: d.name.clone(),
+                id: d.id.clone(),
+                kind: DIAGRAM_REPRESENTATION.to_string(),
+                description: d.description.clone(),
+            })
+            .collect(),
+        trust_zones,
+        components,
+        dataflows,
+        threats,
+        mitigations,
+    };
+    let mut json = serde_json::to_string_pretty(&document).expect("OTM document serializes");
+    json.push('\n');
+    json
+}
+
+/// The smallest zone that holds everything `zone` holds and more, so nested
+/// boundaries nest as trust zones. An empty zone is never nested.
+fn enclosing_zone<'a>(
+    zones: &[&'a TrustBoundary],
+    zone: &TrustBoundary,
+) -> Option<&'a TrustBoundary> {
+    if zone.contains.is_empty() {
+        return None;
+    }
+    zones
+        .iter()
+        .filter(|other| {
+            other.id != zone.id
+                && other.contains.len() > zone.contains.len()
+                && zone.contains.iter().all(|id| other.contains.contains(id))
+        })
+        .min_by_key(|other| other.contains.len())
+        .copied()
+}
+
+/// The trust zone a component is written into.
+fn component_zone(zones: &[&TrustBoundary], element: &Element) -> String {
+    if zones.iter().any(|z| z.id == element.trust_zone) {
+        return element.trust_zone.clone();
+    }
+    let innermost = zones
+        .iter()
+        .filter(|z| z.contains.contains(&element.id))
+        .min_by_key(|z| z.contains.len());
+    match innermost {
+        Some(zone) => zone.id.clone(),
+        None if !element.trust_zone.is_empty() => element.trust_zone.clone(),
+        None => DEFAULT_ZONE.to_string(),
+    }
+}
+
+fn component(element: &Element, zone: String, diagram: Option<String>) -> OtmComponent {
+    let mut attributes = BTreeMap::new();
+    if !element.technologies.is_empty() {
+        attributes.insert(
+            "technologies".to_string(),
+            Value::from(element.technologies.clone()),
+        );
+    }
+    if let Some(stores) = &element.stores {
+        attributes.insert("stores".to_string(), Value::from(stores.clone()));
+    }
+    if let Some(encryption) = &element.encryption {
+        attributes.insert("encryption".to_string(), Value::from(encryption.clone()));
+    }
+    OtmComponent {
+        id: element.id.clone(),
+        name: element.name.clone(),
+        kind: element.element_type.replace('_', "-"),
+        description: (!element.description.is_empty()).then(|| element.description.clone()),
+        parent: OtmParent {
+            trust_zone: Some(zone),
+            component: None,
+        },
+        tags: element.tags.clone(),
+        representations: diagram
+            .map(|diagram| OtmElementRepresentation {
+                representation: diagram,
+                id: format!("{}-representation", element.id),
+                position: element.position.as_ref().map(otm_position),
+                size: None,
+            })
+            .into_iter()
+            .collect(),
+        threats: Vec::new(),
+        attributes,
+    }
+}
+
+fn dataflow(flow: &DataFlow) -> OtmDataflow {
+    let mut attributes = BTreeMap::new();
+    if !flow.protocol.is_empty() {
+        attributes.insert("protocol".to_string(), Value::from(flow.protocol.clone()));
+    }
+    if flow.authenticated {
+        attributes.insert("authenticated".to_string(), Value::from(true));
+    }
+    if !flow.data.is_empty() {
+        attributes.insert("data".to_string(), Value::from(flow.data.clone()));
+    }
+    OtmDataflow {
+        id: flow.id.clone(),
+        name: flow.name.clone(),
+        description: None,
+        source: flow.from.clone(),
+        destination: flow.to.clone(),
+        threats: Vec::new(),
+        attributes,
+    }
+}
+
+fn threat_definition(threat: &Threat) -> OtmThreat {
+    let score = severity_score(&threat.severity);
+    OtmThreat {
+        id: threat.id.clone(),
+        name: threat.title.clone(),
+        description: (!threat.description.is_empty()).then(|| threat.description.clone()),
+        categories: vec![stride_category_name(&threat.category).to_string()],
+        risk: Some(OtmThreatRisk {
+            likelihood: score,
+            impact: score,
+        }),
+    }
+}
+
+fn otm_position(position: &Position) -> OtmPosition {
+    OtmPosition {
+        x: position.x,
+        y: position.y,
+    }
+}
+
+/// The title as a lowercase, dash-separated id.
+fn project_id(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "threat-model".to_string()
+    } else {
+        slug
+    }
+}
+
+/// An impact score inside the importer's band for `severity`.
+fn severity_score(severity: &Severity) -> f64 {
+    match severity {
+        Severity::Critical => 100.0,
+        Severity::High => 75.0,
+        Severity::Medium => 50.0,
+        Severity::Low => 25.0,
+        Severity::Info => 0.0,
+    }
+}
+
+fn threat_state(status: Option<&MitigationStatus>) -> &'static str {
+    match status {
+        None | Some(MitigationStatus::NotStarted) => "exposed",
+        Some(MitigationStatus::InProgress) => "partly-mitigated",
+        Some(MitigationStatus::Mitigated) => "mitigated",
+        Some(MitigationStatus::Accepted) => "accepted",
+        Some(MitigationStatus::Transferred) => "transferred",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::pin_dates;
+    use crate::importers::otm::parse_otm;
+    use crate::models::{BoundaryShape, Mitigation};
+
+    /// The importer's fixture; exporting its import and importing that again
+    /// must land on the same pinned result.
+    const FIXTURE_YAML: &str = include_str!("../importers/fixtures/otm-sample.otm.yaml");
+    const FIXTURE_EXPECTED_YAML: &str =
+        include_str!("../importers/fixtures/otm-sample.expected.yaml");
+
+    fn import(text: &str) -> ThreatModel {
+        pin_dates(parse_otm(text).expect("exported model should import").model)
+    }
+
+    #[test]
+    fn fixture_round_trips_through_export() {
+        let model = import(FIXTURE_YAML);
+        let exported = write_otm(&model);
+        let reimported = serde_yaml::to_string(&import(&exported)).unwrap();
+        assert_eq!(reimported, FIXTURE_EXPECTED_YAML);
+    }
+
+    #[test]
+    fn export_is_deterministic() {
+        let model = import(FIXTURE_YAML);
+        assert_eq!(write_otm(&model), write_otm(&model));
+    }
+
+    #[test]
+    fn zones_nest_and_threats_carry_their_mitigation_state() {
+        let model = import(FIXTURE_YAML);
+        let exported: Value = serde_json::from_str(&write_otm(&model)).unwrap();
+        assert_eq!(exported["trustZones"][2]["id"], "data-tier");
+        assert_eq!(exported["trustZones"][2]["parent"]["trustZone"], "dmz");
+        assert!(exported["trustZones"][1].get("parent").is_none());
+
+        let ledger = &exported["components"][3];
+        assert_eq!(ledger["type"], "sql-database");
+        assert_eq!(ledger["threats"][0]["state"], "mitigated");
+        assert_eq!(
+            ledger["threats"][0]["mitigations"][0]["state"],
+            "implemented"
+        );
+        let checkout = &exported["dataflows"][0];
+        assert_eq!(checkout["threats"][0]["threat"], "spoofed-callers-2");
+        assert_eq!(checkout["threats"][0]["state"], "exposed");
+        assert_eq!(exported["threats"][1]["risk"]["impact"], 100.0);
+    }
+
+    #[test]
+    fn elements_outside_every_zone_get_one() {
+        let mut model = import(FIXTURE_YAML);
+        model.trust_boundaries.retain(|b| b.id == "dmz");
+        model.trust_boundaries[0].shape = Some(BoundaryShape::Line);
+        model.trust_boundaries[0].points =
+            vec![Position { x: 0.0, y: 0.0 }, Position { x: 10.0, y: 10.0 }];
+        model.elements[0].trust_zone = String::new();
+        model.threats[0].mitigation = Some(Mitigation {
+            status: MitigationStatus::Transferred,
+            description: String::new(),
+        });
+
+        let exported: Value = serde_json::from_str(&write_otm(&model)).unwrap();
+        let zones: Vec<_> = exported["trustZones"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|z| z["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(zones, [DEFAULT_ZONE, "dmz", "data-tier"]);
+        assert_eq!(
+            exported["components"][0]["parent"]["trustZone"],
+            DEFAULT_ZONE
+        );
+        assert_eq!(
+            exported["components"][1]["threats"][0]["state"],
+            "transferred"
+        );
+        assert!(exported["mitigations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|m| m["id"] != "spoofed-callers-mitigation"));
+        import(&write_otm(&model));
+    }
+}
@@ -0,0 +1,761 @@
+//! Writer for SARIF 2.1.0 logs of the threats still open in a model.
+//!
+//! Code-scanning dashboards (GitHub code scanning, Azure DevOps, SonarQube and
+//! others) read SARIF, so exporting a model this way lists its open threats
+//! beside the findings of static analysis. Every unmitigated threat — no
+//! mitigation, or one not started or in progress — becomes a result:
+//!
+//! - its rule is the threat's STRIDE category (`stride/tampering`), or the
+//!   rule-pack rule that generated it when a [`RulePack`] is given;
+//! - its level follows the severity: critical and high are `error`, medium is
+//!   `warning`, low and info are `note`;
+//! - its location is the `.thf` file and the line the threat starts on, with
+//!   the element or flow it is raised against as a logical location. If
+//!   that line cannot be found unambiguously, the result points at the file
+//!   alone.
+//!
+//! Results carry the threat id as a fingerprint, so a dashboard tracks a
+//! threat across edits that move it in the file. The log contains no
+//! timestamps or version numbers; exporting an unchanged model gives the
+//! same text.
+
+use crate::models::{MitigationStatus, Severity, StrideCategory, Threat, ThreatModel};
+use crate::stride::{rule_for_threat, RulePack};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const INFORMATION_URI: &str = "https://github.com/exit-zero-labs/threat-forge";
+
+/// The base a relative artifact URI is resolved against: the root of the
+/// checkout the log was produced in.
+const SOURCE_ROOT: &str = "%SRCROOT%";
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    name: String,
+    short_description: Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    properties: RuleProperties,
+}
+
+#[derive(Serialize)]
+struct RuleProperties {
+    tags: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    partial_fingerprints: Fingerprints,
+    properties: ResultProperties,
+}
+
+#[derive(Serialize)]
+struct Fingerprints {
+    #[serde(rename = "threatId/v1")]
+    threat_id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    logical_locations: Vec<LogicalLocation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogicalLocation {
+    name: String,
+    fully_qualified_name: String,
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultProperties {
+    threat_id: String,
+    category: StrideCategory,
+    severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mitigation_status: Option<MitigationStatus>,
+}
+
+/// Serialize the open threats of `model` as a SARIF log.
+///
+/// `model_uri` is where results point, as given by [`artifact_uri`], and
+/// `source` the text of that `.thf` file, read for the line of each threat.
+/// With `pack`, threats it generated are reported under its rule ids.
+pub fn write_sarif(
+    model: &ThreatModel,
+    model_uri: &str,
+    source: &str,
+    pack: Option<&RulePack>,
+) -> String {
+    let lines = threat_lines(source);
+    let mut rules: Vec<Rule> = Vec::new();
+    let mut rule_indexes: HashMap<String, usize> = HashMap::new();
+
+    let results: Vec<SarifResult> = model
+        .threats
+        .iter()
+        .filter(|t| t.is_unmitigated())
+        .map(|threat| {
+            let rule = match pack.and_then(|p| rule_for_threat(p, model, threat)) {
+                Some(pack_rule) => rule(
+                    &pack_rule.id,
+                    &pack_rule.title,
+                    &pack_rule.description,
+                    &pack_rule.category,
+                ),
+                None => {
+                    let (id, name, description) = category_rule(&threat.category);
+                    rule(id, name, description, &threat.category)
+                }
+            };
+            let rule_id = rule.id.clone();
+            let rule_index = *rule_indexes.entry(rule_id.clone()).or_insert_with(|| {
+                rules.push(rule);
+                rules.len() - 1
+            });
+            result(model, threat, rule_id, rule_index, model_uri, &lines)
+        })
+        .collect();
+
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "ThreatForge",
+                    information_uri: INFORMATION_URI,
+                    rules,
+                },
+            },
+            results,
+        }],
+    };
+    let mut out = serde_json::to_string_pretty(&log).expect("SARIF log serializes");
+    out.push('\n');
+    out
+}
+
+/// The URI results point at for the model at `path`. A model inside a git
+/// repository gets its path from the repository root, which code-scanning
+/// services resolve against their checkout. Elsewhere a relative path is kept
+/// as given and an absolute one becomes a `file://` URI.
+pub fn artifact_uri(path: &Path) -> String {
+    if let Ok(absolute) = path.canonicalize() {
+        let root = absolute
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.join(".git").exists());
+        if let Some(relative) = root.and_then(|root| absolute.strip_prefix(root).ok()) {
+            return encode_path(relative);
+        }
+    }
+    if path.is_absolute() {
+        let encoded = encode_path(path);
+        let separator = if encoded.starts_with('/') { "" } else { "/" };
+        format!("file://{separator}{encoded}")
+    } else {
+        encode_path(path)
+    }
+}
+
+/// A path with `/` separators, percent-encoding what a URI may not contain.
+fn encode_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn rule(id: &str, name: &str, description: &str, category: &StrideCategory) -> Rule {
+    Rule {
+        id: id.to_string(),
+        name: name.to_string(),
+        short_description: Message {
+            text: name.to_string(),
+        },
+        full_description: (!description.is_empty()).then(|| Message {
+            text: description.to_string(),
+        }),
+        properties: RuleProperties {
+            tags: vec!["security", "threat-model", category_rule(category).0],
+        },
+    }
+}
+
+fn result(
+    model: &ThreatModel,
+    threat: &Threat,
+    rule_id: String,
+    rule_index: usize,
+    model_uri: &str,
+    lines: &HashMap<String, usize>,
+) -> SarifResult {
+    let mut message = threat.title.clone();
+    if !threat.description.trim().is_empty() {
+        message.push_str("\n\n");
+        message.push_str(threat.description.trim_end());
+    }
+
+    SarifResult {
+        rule_id,
+        rule_index,
+        level: level(&threat.severity),
+        message: Message { text: message },
+        locations: vec![Location {
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation {
+                    uri: model_uri.to_string(),
+                    uri_base_id: (!model_uri.contains("://")).then_some(SOURCE_ROOT),
+                },
+                region: lines
+                    .get(&threat.id)
+                    .map(|&start_line| Region { start_line }),
+            },
+            logical_locations: logical_location(model, threat).into_iter().collect(),
+        }],
+        partial_fingerprints: Fingerprints {
+            threat_id: threat.id.clone(),
+        },
+        properties: ResultProperties {
+            threat_id: threat.id.clone(),
+            category: threat.category.clone(),
+            severity: threat.severity.clone(),
+            mitigation_status: threat.mitigation.as_ref().map(|m| m.status.clone()),
+        },
+    }
+}
+
+/// The element or flow a threat is raised against, when it exists. A threat
+/// on a flow names the flow, even if it also records the flow's source.
+fn logical_location(model: &ThreatModel, threat: &Threat) -> Option<LogicalLocation> {
+    let flow = threat
+        .flow
+        .as_deref()
+        .and_then(|id| model.data_flows.iter().find(|f| f.id == id));
+    if let Some(flow) = flow {
+        return Some(LogicalLocation {
+            name: flow.name.clone(),
+            fully_qualified_name: flow.id.clone(),
+            kind: "dataFlow",
+        });
+    }
+    let element = threat
+        .element
+        .as_deref()
+        .and_then(|id| model.elements.iter().find(|e| e.id == id))?;
+    Some(LogicalLocation {
+        name: element.name.clone(),
+        fully_qualified_name: element.id.clone(),
+        kind: "element",
+    })
+}
+
+fn level(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low | Severity::Info => "note",
+    }
+}
+
+/// Rule id, name and description of a STRIDE category.
+fn category_rule(category: &StrideCategory) -> (&'static str, &'static str, &'static str) {
+    match category {
+        StrideCategory::Spoofing => (
+            "stride/spoofing",
+            "Spoofing",
+            "An attacker pretends to be another user, component or system.",
+        ),
+        StrideCategory::Tampering => (
+            "stride/tampering",
+            "Tampering",
+            "Data or code is modified without authorization, in transit or at rest.",
+        ),
+        StrideCategory::Repudiation => (
+            "stride/repudiation",
+            "Repudiation",
+            "An actor can deny an action because nothing records who performed it.",
+        ),
+        StrideCategory::InformationDisclosure => (
+            "stride/information-disclosure",
+            "Information Disclosure",
+            "Information is exposed to someone not authorized to see it.",
+        ),
+        StrideCategory::DenialOfService => (
+            "stride/denial-of-service",
+            "Denial of Service",
+            "A component is made unavailable or degraded for legitimate users.",
+        ),
+        StrideCategory::ElevationOfPrivilege => (
+            "stride/elevation-of-privilege",
+            "Elevation of Privilege",
+            "An actor gains capabilities beyond those it was granted.",
+        ),
+    }
+}
+
+/// The 1-based line each threat starts on in a `.thf` file: the line holding
+/// its `id` key inside the top-level `threats` list, block or flow style.
+/// Only keys of the list's own items count, so an `id:` inside a multi-line
+/// description or a nested mapping cannot pin the wrong line. An id found on
+/// more than one line is left out, so its result carries no region rather
+/// than a guessed one.
+fn threat_lines(source: &str) -> HashMap<String, usize> {
+    let mut found: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut in_threats = false;
+    // Columns of the threats list's `-` and of the current item's keys.
+    let mut item_indent = None;
+    let mut key_indent = None;
+    // Lines up to this one belong to a flow collection already scanned.
+    let mut skip_to = 0;
+    let mut offset = 0;
+    for (index, raw) in source.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += raw.len();
+        let number = index + 1;
+        if number <= skip_to {
+            continue;
+        }
+        let line = raw.trim_end_matches(['\n', '\r']);
+        let is_top_level_key = !line.trim().is_empty() && !line.starts_with([' ', '\t', '-', '#']);
+        if is_top_level_key {
+            let (key, value) = line.split_once(':').unwrap_or((line, ""));
+            in_threats = key.trim() == "threats";
+            item_indent = None;
+            key_indent = None;
+            let value = value.trim_start();
+            if in_threats && value.starts_with('[') {
+                let at = line_start + line.len() - value.len();
+                skip_to = flow_ids(&source[at..], number, &['[', '{'], &mut found);
+                in_threats = false;
+            }
+            continue;
+        }
+        let rest = line.trim_start_matches(' ');
+        if !in_threats || rest.is_empty() || rest.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - rest.len();
+        let dash_item = rest
+            .strip_prefix('-')
+            .filter(|after| after.is_empty() || after.starts_with(' '));
+        if dash_item.is_some() && item_indent.is_none() {
+            item_indent = Some(indent);
+        }
+        let item = match dash_item {
+            Some(after) if item_indent == Some(indent) => {
+                let key = after.trim_start();
+                key_indent = (!key.is_empty()).then(|| line.len() - key.len());
+                if key.starts_with('{') {
+                    let at = line_start + line.len() - key.len();
+                    skip_to = flow_ids(&source[at..], number, &['{'], &mut found);
+                    continue;
+                }
+                key
+            }
+            _ if item_indent.is_some_and(|item| indent > item) => {
+                if *key_indent.get_or_insert(indent) != indent {
+                    continue;
+                }
+                rest
+            }
+            _ => continue,
+        };
+        if let Some(id) = item
+            .strip_prefix("id:")
+            .and_then(|v| scalar(v.trim_start(), false))
+        {
+            found.entry(id).or_default().push(number);
+        }
+    }
+    found
+        .into_iter()
+        .filter_map(|(id, lines)| match lines.as_slice() {
+            [line] => Some((id, *line)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Record the `id` key of every mapping nested as `within` (e.g. `[`, `{`:
+/// the items of a flow sequence) in the flow collection `text` starts with,
+/// which begins on line `first_line`. Returns the line the collection ends on.
+fn flow_ids(
+    text: &str,
+    first_line: usize,
+    within: &[char],
+    found: &mut HashMap<String, Vec<usize>>,
+) -> usize {
+    let mut open: Vec<char> = Vec::new();
+    let mut line = first_line;
+    let mut expect_key = false;
+    let mut i = 0;
+    while let Some(c) = text[i..].chars().next() {
+        let rest = &text[i..];
+        let at_key = expect_key && open == within;
+        expect_key = false;
+        match c {
+            '\n' => {
+                line += 1;
+                expect_key = at_key;
+            }
+            ' ' | '\t' | '\r' => expect_key = at_key,
+            '#' if text[..i].ends_with([' ', '\t', '\n']) => {
+                i += rest.find('\n').unwrap_or(rest.len());
+                expect_key = at_key;
+                continue;
+            }
+            '[' | '{' => {
+                open.push(c);
+                expect_key = c == '{' && open == within;
+            }
+            ']' | '}' => {
+                open.pop();
+                if open.is_empty() {
+                    return line;
+                }
+            }
+            ',' => expect_key = open == within,
+            ':' => {}
+            _ => {
+                let key_len = if c == '"' || c == '\'' {
+                    quoted_len(rest)
+                } else {
+                    rest.find([':', ',', '}', ']', '\n'])
+                };
+                let Some(key_len) = key_len else {
+                    break;
+                };
+                let key = &rest[..key_len];
+                let after = rest[key_len..].trim_start_matches([' ', '\t']);
+                if at_key && scalar(key, true).as_deref() == Some("id") {
+                    if let Some(value) = after.strip_prefix(':') {
+                        if let Some(id) = scalar(value.trim_start(), true) {
+                            found.entry(id).or_default().push(line);
+                        }
+                    }
+                }
+                line += key.matches('\n').count();
+                i += key_len;
+                continue;
+            }
+        }
+        i += c.len_utf8();
+    }
+    line
+}
+
+/// The scalar at the start of `text`. Quoted scalars are decoded by
+/// serde_yaml; plain ones end at a comment, the end of the line, or in flow
+/// context at the next `,`, `}` or `]`.
+fn scalar(text: &str, flow: bool) -> Option<String> {
+    if text.starts_with(['"', '\'']) {
+        let len = quoted_len(text)?;
+        return serde_yaml::from_str(&text[..len]).ok();
+    }
+    let mut end = text.find('\n').unwrap_or(text.len());
+    end = [" #", "\t#"]
+        .iter()
+        .filter_map(|comment| text[..end].find(comment))
+        .fold(end, usize::min);
+    if flow {
+        end = text[..end].find([',', '}', ']']).unwrap_or(end);
+    }
+    let plain = text[..end].trim();
+    (!plain.is_empty()).then(|| plain.to_string())
+}
+
+/// Length in bytes, quotes included, of the quoted scalar `text` starts
+/// with, if it is closed.
+fn quoted_len(text: &str) -> Option<usize> {
+    let quote = text.chars().next()?;
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' && quote == '"' {
+            chars.next();
+        } else if c == quote {
+            // `''` is an escaped quote inside a single-quoted scalar.
+            if quote == '\'' && text[i + 1..].starts_with('\'') {
+                chars.next();
+                continue;
+            }
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stride::{Filter, PackRule, PackStencil, Subject, GENERIC_PROCESS};
+    use serde_json::Value;
+
+    const FIXTURE_THF: &str = include_str!("../report/fixtures/report-sample.thf");
+    /// The log for [`FIXTURE_THF`], regenerated only when the mapping is
+    /// deliberately changed.
+    const EXPECTED_SARIF: &str = include_str!("../report/fixtures/report-sample.expected.sarif");
+    const QUOTED_IDS_THF: &str = include_str!("../report/fixtures/sarif-quoted-ids.thf");
+    const FLOW_THREATS_THF: &str = include_str!("../report/fixtures/sarif-flow-threats.thf");
+
+    fn fixture() -> ThreatModel {
+        serde_yaml::from_str(FIXTURE_THF).expect("fixture should parse")
+    }
+
+    #[test]
+    fn fixture_export_is_byte_identical_to_the_pinned_log() {
+        let sarif = write_sarif(&fixture(), "models/payments.thf", FIXTURE_THF, None);
+        assert_eq!(sarif, EXPECTED_SARIF);
+    }
+
+    #[test]
+    fn only_open_threats_are_reported_at_their_line() {
+        let model = fixture();
+        let sarif: Value =
+            serde_json::from_str(&write_sarif(&model, "payments.thf", FIXTURE_THF, None)).unwrap();
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        let open: Vec<&str> = model
+            .threats
+            .iter()
+            .filter(|t| t.is_unmitigated())
+            .map(|t| t.id.as_str())
+            .collect();
+        let reported: Vec<&str> = results
+            .iter()
+            .map(|r| r["properties"]["threatId"].as_str().unwrap())
+            .collect();
+        assert_eq!(reported, open);
+
+        for result in results {
+            let id = result["properties"]["threatId"].as_str().unwrap();
+            let line = result["locations"][0]["physicalLocation"]["region"]["startLine"]
+                .as_u64()
+                .unwrap() as usize;
+            assert_eq!(
+                FIXTURE_THF.lines().nth(line - 1).unwrap().trim(),
+                format!("- id: {id}")
+            );
+        }
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["ruleId"], "stride/spoofing");
+    }
+
+    #[test]
+    fn threat_lines_read_quoted_flow_style_and_later_keys() {
+        let source = "threats:\n\
+                      - { id: \"t-1\", title: A }\n\
+                      -   title: B\n    \
+                          id: 't-2' # second\n\
+                      diagrams:\n  \
+                        - id: t-3\n";
+        let lines = threat_lines(source);
+        assert_eq!(lines.get("t-1"), Some(&2));
+        assert_eq!(lines.get("t-2"), Some(&4));
+        assert_eq!(lines.get("t-3"), None);
+    }
+
+    #[test]
+    fn threat_lines_ignore_ids_inside_descriptions_and_nested_keys() {
+        let source = "threats:\n  \
+                        - title: A\n    \
+                          description: |\n      \
+                            Same root cause as\n      \
+                            id: t-2\n    \
+                          mitigation:\n      \
+                            id: t-2\n    \
+                          id: t-1\n  \
+                        -\n    \
+                          title: B\n    \
+                          id: t-2\n";
+        let lines = threat_lines(source);
+        assert_eq!(lines.get("t-1"), Some(&8));
+        assert_eq!(lines.get("t-2"), Some(&11));
+    }
+
+    /// The `startLine` of each result, by threat id.
+    fn regions(source: &str) -> Vec<(String, Option<u64>)> {
+        let model: ThreatModel = serde_yaml::from_str(source).expect("fixture should parse");
+        let sarif: Value =
+            serde_json::from_str(&write_sarif(&model, "m.thf", source, None)).unwrap();
+        sarif["runs"][0]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| {
+                (
+                    r["properties"]["threatId"].as_str().unwrap().to_string(),
+                    r["locations"][0]["physicalLocation"]["region"]["startLine"].as_u64(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quoted_ids_are_decoded_before_they_are_matched() {
+        assert_eq!(
+            regions(QUOTED_IDS_THF),
+            [
+                ("threat #1".to_string(), Some(16)),
+                ("threat-2".to_string(), Some(27)),
+            ]
+        );
+    }
+
+    #[test]
+    fn ids_in_a_flow_style_threats_list_are_located() {
+        // threat-3 is mitigated, so it is not reported.
+        assert_eq!(
+            regions(FLOW_THREATS_THF),
+            [
+                ("threat-1".to_string(), Some(16)),
+                ("threat-2".to_string(), Some(18)),
+            ]
+        );
+    }
+
+    #[test]
+    fn threat_lines_leave_out_ids_found_more_than_once() {
+        let source = "threats:\n\
+                      - id: t-1\n\
+                      - id: t-1\n\
+                      - id: t-2\n";
+        let lines = threat_lines(source);
+        assert_eq!(lines.get("t-1"), None);
+        assert_eq!(lines.get("t-2"), Some(&4));
+    }
+
+    #[test]
+    fn threats_generated_by_a_rule_pack_are_reported_under_its_rule() {
+        let mut model = fixture();
+        let pack = RulePack {
+            name: "Team rules".to_string(),
+            stencils: vec![PackStencil {
+                id: GENERIC_PROCESS.to_string(),
+                name: "Process".to_string(),
+                parent: None,
+                element_type: None,
+            }],
+            rules: vec![PackRule {
+                id: "TH42".to_string(),
+                title: "{target.Name} trusts {source.Name} blindly".to_string(),
+                description: "Input from the source is not validated.".to_string(),
+                category: StrideCategory::Tampering,
+                severity: Severity::Medium,
+                include: Some(Filter::Is {
+                    subject: Subject::Source,
+                    stencil: GENERIC_PROCESS.to_string(),
+                }),
+                exclude: None,
+            }],
+        };
+        model
+            .threats
+            .extend(crate::stride::analyze_with_rule_pack(&model, &pack));
+
+        let sarif: Value = serde_json::from_str(&write_sarif(&model, "m.thf", "", Some(&pack)))
+            .expect("valid JSON");
+        let run = &sarif["runs"][0];
+        let last = run["results"].as_array().unwrap().last().unwrap().clone();
+        assert_eq!(last["ruleId"], "TH42");
+        assert_eq!(last["level"], "warning");
+        assert!(last["locations"][0]["physicalLocation"]
+            .get("region")
+            .is_none());
+        let rule = &run["tool"]["driver"]["rules"][last["ruleIndex"].as_u64().unwrap() as usize];
+        assert_eq!(rule["id"], "TH42");
+        assert_eq!(rule["properties"]["tags"][2], "stride/tampering");
+    }
+
+    #[test]
+    fn artifact_uris_are_relative_to_the_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        let model = dir.path().join("threat models/payments.thf");
+        std::fs::create_dir_all(model.parent().unwrap()).unwrap();
+        std::fs::write(&model, "").unwrap();
+        assert_eq!(artifact_uri(&model), "threat%20models/payments.thf");
+        assert_eq!(
+            artifact_uri(Path::new("missing/model.thf")),
+            "missing/model.thf"
+        );
+    }
+}
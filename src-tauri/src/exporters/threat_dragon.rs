@@ -0,0 +1,491 @@
+//! Writer for OWASP Threat Dragon v2 JSON models.
+//!
+//! The inverse of [`crate::importers::threat_dragon::parse_threat_dragon`]:
+//! each diagram becomes a Threat Dragon diagram whose cells are the boundaries,
+//! elements and flows drawn on it, and each threat is written onto the flow
+//! or element it was raised against.
+//!
+//! Threat Dragon only knows actors, processes and stores, so element types
+//! collapse onto those by their STRIDE role; a `web_server` is written as a
+//! web application process so it comes back as one. Layers, groups and
+//! relationships are not written, flows between diagrams and threats with no
+//! element or flow are dropped, `info` severity becomes `Low`, and accepted
+//! or transferred threats become `NotApplicable`. Cell ids are derived from
+//! our ids, so exporting the same model twice yields the same file.
+
+use super::tm7::{guid, mitigation_state, severity_priority};
+use crate::importers::threat_dragon::OUT_OF_SCOPE_TAG;
+use crate::models::layout::{
+    boundary_bounds, grid_origin, line_points, surface_index, DEFAULT_DIAGRAM_NAME,
+};
+use crate::models::{
+    DataFlow, Element, Position, Size, StrideCategory, Threat, ThreatModel, TrustBoundary,
+};
+use crate::stride::{stride_category_for_type, ComponentStrideCategory};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// The Threat Dragon schema version written.
+const VERSION: &str = "2.2.0";
+
+const THUMBNAIL: &str = "./public/content/images/thumbnail.stride.jpg";
+
+/// Threat Dragon's status for a threat nobody has dealt with yet.
+const OPEN_STATUS: &str = "Open";
+
+/// Default shape sizes, as Threat Dragon draws new actors, stores, processes
+/// and text blocks.
+const ENTITY_WIDTH: f64 = 160.0;
+const ENTITY_HEIGHT: f64 = 80.0;
+const PROCESS_SIZE: f64 = 100.0;
+const TEXT_WIDTH: f64 = 200.0;
+const TEXT_HEIGHT: f64 = 60.0;
+
+/// Serialize a `ThreatModel` as a Threat Dragon v2 JSON document.
+pub fn write_threat_dragon(model: &ThreatModel) -> String {
+    let diagram_count = model.diagrams.len().max(1);
+
+    // Threat Dragon numbers threats across the whole model.
+    let mut threats_by_cell: HashMap<(&str, &str), Vec<(usize, &Threat)>> = HashMap::new();
+    for (index, threat) in model.threats.iter().enumerate() {
+        let flow = threat
+            .flow
+            .as_deref()
+            .filter(|id| model.data_flows.iter().any(|f| f.id == *id));
+        let key = match (flow, threat.element.as_deref()) {
+            (Some(flow), _) => ("flow", flow),
+            (None, Some(element)) => ("element", element),
+            (None, None) => continue,
+        };
+        threats_by_cell
+            .entry(key)
+            .or_default()
+            .push((index + 1, threat));
+    }
+    let threats_for = |kind: &str, id: &str| -> Vec<Value> {
+        threats_by_cell
+            .get(&(kind, id))
+            .map(|threats| threats.iter().map(|(n, t)| threat_json(*n, t)).collect())
+            .unwrap_or_default()
+    };
+
+    let elements_on = |index: usize| -> Vec<&Element> {
+        model
+            .elements
+            .iter()
+            .filter(|e| surface_index(model, e.diagram.as_deref()) == index)
+            .collect()
+    };
+
+    let mut diagrams = Vec::new();
+    for index in 0..diagram_count {
+        let diagram = model.diagrams.get(index);
+        let elements = elements_on(index);
+        let mut placed = 0;
+        let origins: HashMap<&str, Position> = elements
+            .iter()
+            .map(|e| {
+                let origin = e.position.clone().unwrap_or_else(|| {
+                    placed += 1;
+                    grid_origin(placed - 1)
+                });
+                (e.id.as_str(), origin)
+            })
+            .collect();
+
+        let mut cells = Vec::new();
+        for boundary in model
+            .trust_boundaries
+            .iter()
+            .filter(|b| surface_index(model, b.diagram.as_deref()) == index)
+        {
+            cells.push(boundary_json(boundary, &origins, cells.len()));
+        }
+        for element in &elements {
+            let threats = threats_for("element", &element.id);
+            cells.push(element_json(
+                element,
+                &origins[element.id.as_str()],
+                threats,
+                cells.len(),
+            ));
+        }
+        // A flow is drawn on its source's diagram, and only when its target
+        // is there too.
+        for flow in model.data_flows.iter().filter(|f| {
+            origins.contains_key(f.from.as_str()) && origins.contains_key(f.to.as_str())
+        }) {
+            let threats = threats_for("flow", &flow.id);
+            cells.push(flow_json(flow, threats, cells.len()));
+        }
+
+        diagrams.push(json!({
+            "id": index,
+            "title": match diagram {
+                Some(d) if !d.name.is_empty() => d.name.as_str(),
+                _ => DEFAULT_DIAGRAM_NAME,
+            },
+            "diagramType": "STRIDE",
+            "placeholder": diagram.and_then(|d| d.description.as_deref()).unwrap_or(""),
+            "thumbnail": THUMBNAIL,
+            "version": VERSION,
+            "cells": cells,
+        }));
+    }
+
+    let document = json!({
+        "version": VERSION,
+        "summary": {
+            "title": model.metadata.title,
+            "owner": model.metadata.author,
+            "description": model.metadata.description,
+            "id": 0,
+        },
+        "detail": {
+            "contributors": [],
+            "diagrams": diagrams,
+            "diagramTop": diagram_count,
+            "reviewer": "",
+            "threatTop": model.threats.len(),
+        },
+    });
+    let mut out = serde_json::to_string_pretty(&document).expect("a JSON value always serializes");
+    out.push('\n');
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Cell writers
+// ---------------------------------------------------------------------------
+
+/// The Threat Dragon shape, `data.type` and default size for an element.
+fn shape_for(element: &Element) -> (&'static str, &'static str, f64, f64) {
+    match stride_category_for_type(&element.element_type) {
+        ComponentStrideCategory::Actor => ("actor", "tm.Actor", ENTITY_WIDTH, ENTITY_HEIGHT),
+        ComponentStrideCategory::Store => ("store", "tm.Store", ENTITY_WIDTH, ENTITY_HEIGHT),
+        ComponentStrideCategory::Annotation => {
+            ("td-text-block", "tm.Text", TEXT_WIDTH, TEXT_HEIGHT)
+        }
+        ComponentStrideCategory::Service => ("process", "tm.Process", PROCESS_SIZE, PROCESS_SIZE),
+    }
+}
+
+fn element_json(element: &Element, origin: &Position, threats: Vec<Value>, z: usize) -> Value {
+    let (shape, data_type, width, height) = shape_for(element);
+    let out_of_scope = element.tags.iter().any(|t| t == OUT_OF_SCOPE_TAG);
+    let mut data = json!({
+        "type": data_type,
+        "name": element.name,
+        "description": element.description,
+    });
+    if shape != "td-text-block" {
+        let extra = json!({
+            "outOfScope": out_of_scope,
+            "reasonOutOfScope": "",
+            "hasOpenThreats": has_open_threats(&threats),
+            "threats": threats,
+        });
+        merge(&mut data, extra);
+    }
+    match shape {
+        "actor" => merge(&mut data, json!({ "providesAuthentication": false })),
+        "store" => merge(
+            &mut data,
+            json!({
+                "isALog": false,
+                "isEncrypted": element.encryption.is_some(),
+                "isSigned": false,
+                "storesCredentials": false,
+            }),
+        ),
+        "process" => merge(
+            &mut data,
+            json!({
+                "handlesCardPayment": false,
+                "handlesGoodsOrServices": false,
+                "isWebApplication": element.element_type == "web_server",
+                "privilegeLevel": "",
+            }),
+        ),
+        _ => {}
+    }
+    json!({
+        "position": { "x": origin.x, "y": origin.y },
+        "size": { "width": width, "height": height },
+        "attrs": { "text": { "text": element.name } },
+        "visible": true,
+        "shape": shape,
+        "zIndex": z,
+        "id": guid("element", &element.id),
+        "data": data,
+    })
+}
+
+fn flow_json(flow: &DataFlow, threats: Vec<Value>, z: usize) -> Value {
+    json!({
+        "shape": "flow",
+        "attrs": {
+            "line": {
+                "stroke": "#333333",
+                "targetMarker": { "name": "block" },
+                "sourceMarker": { "name": "" },
+                "strokeDasharray": null,
+            },
+        },
+        "width": 200,
+        "height": 100,
+        "zIndex": z,
+        "connector": "smooth",
+        "labels": [flow.name],
+        "id": guid("flow", &flow.id),
+        "data": {
+            "type": "tm.Flow",
+            "name": flow.name,
+            "description": "",
+            "outOfScope": false,
+            "reasonOutOfScope": "",
+            "hasOpenThreats": has_open_threats(&threats),
+            "isBidirectional": false,
+            "isEncrypted": false,
+            "isPublicNetwork": false,
+            "protocol": flow.protocol,
+            "threats": threats,
+        },
+        "source": { "cell": guid("element", &flow.from) },
+        "target": { "cell": guid("element", &flow.to) },
+        "vertices": [],
+    })
+}
+
+fn boundary_json(boundary: &TrustBoundary, origins: &HashMap<&str, Position>, z: usize) -> Value {
+    let data = json!({
+        "type": if line_points(boundary).is_some() { "tm.Boundary" } else { "tm.BoundaryBox" },
+        "name": boundary.name,
+        "description": "",
+        "isTrustBoundary": true,
+        "hasOpenThreats": false,
+    });
+    let id = guid("boundary", &boundary.id);
+
+    if let Some(points) = line_points(boundary) {
+        let point = |p: &Position| json!({ "x": p.x, "y": p.y });
+        let (first, rest) = points.split_first().expect("a line has two points");
+        let (last, vertices) = rest.split_last().expect("a line has two points");
+        return json!({
+            "shape": "trust-boundary-curve",
+            "width": 200,
+            "height": 100,
+            "zIndex": z,
+            "connector": "smooth",
+            "attrs": {
+                "line": { "targetMarker": { "name": "" }, "sourceMarker": { "name": "" } },
+            },
+            "labels": [boundary.name],
+            "id": id,
+            "data": data,
+            "source": point(first),
+            "target": point(last),
+            "vertices": vertices.iter().map(point).collect::<Vec<_>>(),
+        });
+    }
+
+    let (origin, width, height) = box_geometry(boundary, origins);
+    json!({
+        "position": { "x": origin.x, "y": origin.y },
+        "size": { "width": width, "height": height },
+        "attrs": { "label": { "text": boundary.name } },
+        "visible": true,
+        "shape": "trust-boundary-box",
+        "zIndex": -1,
+        "id": id,
+        "data": data,
+    })
+}
+
+fn threat_json(number: usize, threat: &Threat) -> Value {
+    let status = threat
+        .mitigation
+        .as_ref()
+        .map_or(OPEN_STATUS, |m| mitigation_state(&m.status, OPEN_STATUS));
+    json!({
+        "id": guid("threat", &threat.id),
+        "title": threat.title,
+        "status": status,
+        "severity": severity_priority(&threat.severity),
+        "type": threat_type(&threat.category),
+        "description": threat.description,
+        "mitigation": threat.mitigation.as_ref().map_or("", |m| m.description.as_str()),
+        "modelType": "STRIDE",
+        "new": false,
+        "number": number,
+        "score": "",
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Conversion helpers
+// ---------------------------------------------------------------------------
+
+fn merge(target: &mut Value, extra: Value) {
+    if let (Value::Object(target), Value::Object(extra)) = (target, extra) {
+        target.extend(extra);
+    }
+}
+
+fn has_open_threats(threats: &[Value]) -> bool {
+    threats.iter().any(|t| t["status"] == "Open")
+}
+
+/// A boundary's own rectangle, or a box around the elements it contains.
+fn box_geometry(
+    boundary: &TrustBoundary,
+    origins: &HashMap<&str, Position>,
+) -> (Position, f64, f64) {
+    if let (Some(position), Some(size)) = (&boundary.position, &boundary.size) {
+        return (position.clone(), size.width, size.height);
+    }
+    // Actors and stores are the widest shapes, processes the tallest.
+    let members = boundary.contains.iter().filter_map(|id| {
+        let size = Size {
+            width: ENTITY_WIDTH,
+            height: PROCESS_SIZE,
+        };
+        Some((origins.get(id.as_str())?.clone(), size))
+    });
+    match boundary_bounds(members) {
+        Some((origin, size)) => (origin, size.width, size.height),
+        None => {
+            let origin = boundary
+                .position
+                .clone()
+                .unwrap_or(Position { x: 0.0, y: 0.0 });
+            (origin, 200.0, 100.0)
+        }
+    }
+}
+
+fn threat_type(category: &StrideCategory) -> &'static str {
+    match category {
+        StrideCategory::Spoofing => "Spoofing",
+        StrideCategory::Tampering => "Tampering",
+        StrideCategory::Repudiation => "Repudiation",
+        StrideCategory::InformationDisclosure => "Information disclosure",
+        StrideCategory::DenialOfService => "Denial of service",
+        StrideCategory::ElevationOfPrivilege => "Elevation of privilege",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::pin_dates;
+    use crate::importers::threat_dragon::parse_threat_dragon;
+    use crate::models::{Mitigation, MitigationStatus, Severity};
+
+    /// The importer's fixture; exporting its import and importing that again
+    /// must land on the same pinned result.
+    const FIXTURE_JSON: &str = include_str!("../importers/fixtures/threat-dragon-v2-sample.json");
+    const FIXTURE_EXPECTED_YAML: &str =
+        include_str!("../importers/fixtures/threat-dragon-v2-sample.expected.yaml");
+
+    fn import(json: &str) -> ThreatModel {
+        pin_dates(
+            parse_threat_dragon(json)
+                .expect("exported model should import")
+                .model,
+        )
+    }
+
+    #[test]
+    fn fixture_round_trips_through_export() {
+        let model = import(FIXTURE_JSON);
+        let exported = write_threat_dragon(&model);
+        let reimported = serde_yaml::to_string(&import(&exported)).unwrap();
+        assert_eq!(reimported, FIXTURE_EXPECTED_YAML);
+    }
+
+    #[test]
+    fn export_is_deterministic() {
+        let model = import(FIXTURE_JSON);
+        assert_eq!(write_threat_dragon(&model), write_threat_dragon(&model));
+    }
+
+    #[test]
+    fn statuses_and_severities_map_onto_threat_dragon_vocabulary() {
+        let mut model = import(FIXTURE_JSON);
+        model.threats[0].severity = Severity::Info;
+        model.threats[0].mitigation = Some(Mitigation {
+            status: MitigationStatus::Transferred,
+            description: "Covered by the identity provider.".to_string(),
+        });
+        model.threats[1].mitigation = Some(Mitigation {
+            status: MitigationStatus::InProgress,
+            description: String::new(),
+        });
+        model.threats[3].mitigation = None;
+
+        let exported: Value = serde_json::from_str(&write_threat_dragon(&model)).unwrap();
+        let shopper = &exported["detail"]["diagrams"][0]["cells"][2]["data"];
+        assert_eq!(shopper["name"], "Shopper");
+        assert_eq!(shopper["hasOpenThreats"], false);
+        let threat = &shopper["threats"][0];
+        assert_eq!(threat["severity"], "Low");
+        assert_eq!(threat["status"], "NotApplicable");
+        assert_eq!(threat["number"], 1);
+        assert_eq!(threat["mitigation"], "Covered by the identity provider.");
+
+        let reimported = import(&write_threat_dragon(&model));
+        let statuses: Vec<_> = reimported
+            .threats
+            .iter()
+            .map(|t| t.mitigation.as_ref().unwrap().status.clone())
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                MitigationStatus::Accepted,
+                MitigationStatus::NotStarted,
+                MitigationStatus::Mitigated,
+                MitigationStatus::NotStarted,
+            ]
+        );
+    }
+
+    #[test]
+    fn unplaced_and_undrawable_items_are_laid_out_or_dropped() {
+        let mut model = import(FIXTURE_JSON);
+        for element in &mut model.elements {
+            element.position = None;
+        }
+        model.trust_boundaries[0].position = None;
+        model.trust_boundaries[0].size = None;
+        // Typed stores become Threat Dragon stores.
+        model.elements[2].element_type = "sql_database".to_string();
+        // The worker's read now starts on the other diagram, so it is dropped.
+        model.data_flows[2].from = "orders-db".to_string();
+        // A threat on nothing has no cell to live on.
+        model.threats[1].element = None;
+
+        let reimported = import(&write_threat_dragon(&model));
+        assert!(reimported.elements.iter().all(|e| e.position.is_some()));
+        assert_eq!(reimported.elements[2].element_type, "data_store");
+        assert_eq!(reimported.data_flows.len(), 2);
+        assert_eq!(
+            reimported.trust_boundaries[0].contains,
+            ["web-storefront", "orders-db"]
+        );
+        let titles: Vec<&str> = reimported
+            .threats
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "Credential stuffing against Shopper accounts",
+                "Session cookie theft over \"Browse & Order\"",
+                "Queue flooding stalls fulfilment",
+            ]
+        );
+    }
+}
@@ -0,0 +1,380 @@
+//! Threat register for spreadsheets, as CSV or an XLSX workbook.
+//!
+//! One row per threat, in file order: its id, title, STRIDE category and
+//! severity, the names of the element and flow it is raised against, its
+//! mitigation status and text, and its description. The columns are the ones
+//! [`crate::importers::mitigations`] reads back, so a register triaged in a
+//! spreadsheet can update the model's mitigations.
+//!
+//! The workbook can carry a second sheet listing the elements and data flows
+//! with how many threats each has. Its document properties take the model's
+//! title, author and creation date rather than the time of export, so the
+//! same model gives the same file.
+
+use crate::models::{Threat, ThreatModel};
+use chrono::Datelike;
+use rust_xlsxwriter::{DocProperties, ExcelDateTime, Format, Workbook, Worksheet, XlsxError};
+use std::collections::HashMap;
+
+/// Header of the threat register, shared by the CSV and the workbook.
+pub const REGISTER_COLUMNS: [&str; 9] = [
+    "ID",
+    "Title",
+    "Category",
+    "Severity",
+    "Element",
+    "Flow",
+    "Mitigation status",
+    "Mitigation",
+    "Description",
+];
+
+const ARCHITECTURE_COLUMNS: [&str; 11] = [
+    "Kind",
+    "ID",
+    "Name",
+    "Type",
+    "Trust zone",
+    "From",
+    "To",
+    "Protocol",
+    "Data",
+    "Authenticated",
+    "Threats",
+];
+
+/// Column widths of the threat register sheet, in characters.
+const REGISTER_WIDTHS: [f64; 9] = [14.0, 40.0, 22.0, 10.0, 24.0, 24.0, 16.0, 48.0, 60.0];
+
+/// Column widths of the elements and flows sheet, in characters.
+const ARCHITECTURE_WIDTHS: [f64; 11] = [
+    10.0, 18.0, 28.0, 18.0, 14.0, 24.0, 24.0, 12.0, 30.0, 14.0, 9.0,
+];
+
+/// Serialize the threat register as CSV.
+pub fn write_csv(model: &ThreatModel) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    // Writing records to a `Vec` cannot fail.
+    let _ = writer.write_record(REGISTER_COLUMNS);
+    for row in register_rows(model) {
+        let _ = writer.write_record(row.iter().map(|cell| spreadsheet_safe(cell)));
+    }
+    let bytes = writer.into_inner().unwrap_or_default();
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+/// Serialize the threat register as an XLSX workbook, with a sheet of
+/// elements and data flows after it when `include_architecture` is set.
+pub fn write_xlsx(model: &ThreatModel, include_architecture: bool) -> Result<Vec<u8>, String> {
+    build_workbook(model, include_architecture).map_err(|e| format!("Failed to write XLSX: {e}"))
+}
+
+fn build_workbook(model: &ThreatModel, include_architecture: bool) -> Result<Vec<u8>, XlsxError> {
+    let created = model.metadata.created;
+    let mut workbook = Workbook::new();
+    workbook.set_properties(
+        &DocProperties::new()
+            .set_title(&model.metadata.title)
+            .set_author(&model.metadata.author)
+            .set_creation_datetime(&ExcelDateTime::from_ymd(
+                u16::try_from(created.year()).unwrap_or(1970),
+                created.month() as u8,
+                created.day() as u8,
+            )?),
+    );
+
+    let header = Format::new().set_bold();
+    let wrapped = Format::new().set_text_wrap();
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Threats")?;
+    let rows = register_rows(model);
+    write_sheet(
+        sheet,
+        &header,
+        &REGISTER_COLUMNS,
+        &REGISTER_WIDTHS,
+        &rows,
+        &[],
+    )?;
+    // Mitigation and description hold paragraphs.
+    sheet.set_column_format(7, &wrapped)?;
+    sheet.set_column_format(8, &wrapped)?;
+
+    if include_architecture {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Elements and flows")?;
+        let rows = architecture_rows(model);
+        write_sheet(
+            sheet,
+            &header,
+            &ARCHITECTURE_COLUMNS,
+            &ARCHITECTURE_WIDTHS,
+            &rows,
+            &[ARCHITECTURE_COLUMNS.len() - 1],
+        )?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+/// A bold, frozen header with a filter on it, then one row per record. Cells
+/// in `numeric` columns are written as numbers so they can be summed.
+fn write_sheet<const N: usize>(
+    sheet: &mut Worksheet,
+    header: &Format,
+    columns: &[&str; N],
+    widths: &[f64; N],
+    rows: &[[String; N]],
+    numeric: &[usize],
+) -> Result<(), XlsxError> {
+    for (col, (name, width)) in (0u16..).zip(columns.iter().zip(widths)) {
+        sheet.write_string_with_format(0, col, *name, header)?;
+        sheet.set_column_width(col, *width)?;
+    }
+    for (row, record) in (1u32..).zip(rows) {
+        for (col, cell) in (0u16..).zip(record) {
+            match cell.parse::<f64>() {
+                Ok(number) if numeric.contains(&usize::from(col)) => {
+                    sheet.write_number(row, col, number)?;
+                }
+                _ if cell.is_empty() => {}
+                _ => {
+                    sheet.write_string(row, col, cell)?;
+                }
+            }
+        }
+    }
+    let last_row = u32::try_from(rows.len()).unwrap_or(u32::MAX);
+    sheet.autofilter(0, 0, last_row, N as u16 - 1)?;
+    sheet.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+fn register_rows(model: &ThreatModel) -> Vec<[String; 9]> {
+    let elements: HashMap<&str, &str> = model
+        .elements
+        .iter()
+        .map(|e| (e.id.as_str(), e.name.as_str()))
+        .collect();
+    let flows: HashMap<&str, &str> = model
+        .data_flows
+        .iter()
+        .map(|f| (f.id.as_str(), f.name.as_str()))
+        .collect();
+    // A reference to an item that is gone keeps its id rather than a blank.
+    let resolve = |names: &HashMap<&str, &str>, id: Option<&String>| {
+        id.map(|id| {
+            names
+                .get(id.as_str())
+                .copied()
+                .unwrap_or(id)
+                .trim()
+                .to_string()
+        })
+        .unwrap_or_default()
+    };
+
+    model
+        .threats
+        .iter()
+        .map(|threat| {
+            let mitigation = threat.mitigation.as_ref();
+            [
+                threat.id.clone(),
+                threat.title.trim().to_string(),
+                threat.category.as_str().to_string(),
+                threat.severity.label().to_string(),
+                resolve(&elements, threat.element.as_ref()),
+                resolve(&flows, threat.flow.as_ref()),
+                mitigation
+                    .map(|m| m.status.label())
+                    .unwrap_or_default()
+                    .to_string(),
+                mitigation
+                    .map(|m| m.description.trim().to_string())
+                    .unwrap_or_default(),
+                threat.description.trim().to_string(),
+            ]
+        })
+        .collect()
+}
+
+fn architecture_rows(model: &ThreatModel) -> Vec<[String; 11]> {
+    let names: HashMap<&str, &str> = model
+        .elements
+        .iter()
+        .map(|e| (e.id.as_str(), e.name.as_str()))
+        .collect();
+    let name = |id: &str| names.get(id).copied().unwrap_or(id).trim().to_string();
+    let threat_count = |matches: &dyn Fn(&Threat) -> bool| {
+        model
+            .threats
+            .iter()
+            .filter(|t| matches(t))
+            .count()
+            .to_string()
+    };
+
+    let elements = model
+        .elements
+        .iter()
+        .filter(|e| e.element_type != "text")
+        .map(|element| {
+            [
+                "Element".to_string(),
+                element.id.clone(),
+                element.name.trim().to_string(),
+                element.element_type.clone(),
+                element.trust_zone.clone(),
+                String::new(),
+                String::new(),
+                String::new(),
+                element.technologies.join(", "),
+                String::new(),
+                threat_count(&|t| t.element.as_deref() == Some(element.id.as_str())),
+            ]
+        });
+    let flows = model.data_flows.iter().map(|flow| {
+        [
+            "Data flow".to_string(),
+            flow.id.clone(),
+            flow.name.trim().to_string(),
+            String::new(),
+            String::new(),
+            name(&flow.from),
+            name(&flow.to),
+            flow.protocol.clone(),
+            flow.data.join(", "),
+            if flow.authenticated { "yes" } else { "no" }.to_string(),
+            threat_count(&|t| t.flow.as_deref() == Some(flow.id.as_str())),
+        ]
+    });
+    elements.chain(flows).collect()
+}
+
+/// A cell a spreadsheet will show as text: one starting like a formula gets
+/// a leading apostrophe, so a crafted threat title cannot run as one when
+/// the CSV is opened.
+fn spreadsheet_safe(cell: &str) -> String {
+    if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{cell}")
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Mitigation, MitigationStatus, Severity, StrideCategory};
+    use std::io::Read;
+
+    const FIXTURE_THF: &str = include_str!("../report/fixtures/report-sample.thf");
+
+    fn fixture() -> ThreatModel {
+        serde_yaml::from_str(FIXTURE_THF).expect("fixture should parse")
+    }
+
+    /// The named part of the workbook `bytes`, as text.
+    fn xlsx_part(bytes: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut part = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut part)
+            .unwrap();
+        part
+    }
+
+    #[test]
+    fn csv_register_resolves_names_and_labels() {
+        let csv = write_csv(&fixture());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "ID,Title,Category,Severity,Element,Flow,Mitigation status,Mitigation,Description"
+        );
+        assert_eq!(
+            lines[1],
+            "threat-1,Stolen session replayed against the API,Spoofing,High,Payments API,,In progress,\
+             Bind sessions to the client certificate.,A session token taken from the browser is replayed."
+        );
+        assert_eq!(
+            lines[2],
+            "threat-2,Amount altered in transit,Tampering,Critical,,Pay,Mitigated,\
+             TLS 1.3 and a signed payload.,The amount is changed between checkout and the API."
+        );
+        // Multi-line descriptions are quoted, not split across rows.
+        let rows = csv::Reader::from_reader(csv.as_bytes()).records().count();
+        assert_eq!(rows, 7);
+    }
+
+    #[test]
+    fn csv_cells_that_look_like_formulas_are_neutralised() {
+        let mut model = fixture();
+        model.threats.truncate(1);
+        model.threats[0].title = "=HYPERLINK(\"http://evil\")".to_string();
+        model.threats[0].mitigation = Some(Mitigation {
+            status: MitigationStatus::NotStarted,
+            description: "-1".to_string(),
+        });
+        let csv = write_csv(&model);
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(&record[1], "'=HYPERLINK(\"http://evil\")");
+        assert_eq!(&record[7], "'-1");
+    }
+
+    #[test]
+    fn xlsx_register_has_an_optional_architecture_sheet() {
+        let model = fixture();
+
+        let bytes = write_xlsx(&model, false).unwrap();
+        let workbook = xlsx_part(&bytes, "xl/workbook.xml");
+        assert!(workbook.contains(r#"name="Threats""#));
+        assert!(!workbook.contains("Elements and flows"));
+        let strings = xlsx_part(&bytes, "xl/sharedStrings.xml");
+        assert!(strings.contains("Stolen session replayed against the API"));
+        assert!(strings.contains("Record payment"));
+
+        let bytes = write_xlsx(&model, true).unwrap();
+        assert!(xlsx_part(&bytes, "xl/workbook.xml").contains(r#"name="Elements and flows""#));
+        let strings = xlsx_part(&bytes, "xl/sharedStrings.xml");
+        assert!(strings.contains("Data flow"));
+        assert!(strings.contains("PostgreSQL"));
+        // Text annotations are not architecture.
+        assert!(!strings.contains("Reviewed with the platform team"));
+    }
+
+    #[test]
+    fn xlsx_output_depends_only_on_the_model() {
+        let model = fixture();
+        assert_eq!(
+            write_xlsx(&model, true).unwrap(),
+            write_xlsx(&model, true).unwrap()
+        );
+        assert!(
+            xlsx_part(&write_xlsx(&model, false).unwrap(), "docProps/core.xml")
+                .contains("2026-03-02T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn threats_on_missing_items_keep_the_id() {
+        let mut model = fixture();
+        model.threats = vec![Threat {
+            id: "t".to_string(),
+            title: "Orphan".to_string(),
+            category: StrideCategory::Spoofing,
+            element: Some("gone".to_string()),
+            flow: None,
+            severity: Severity::Low,
+            description: String::new(),
+            mitigation: None,
+        }];
+        let csv = write_csv(&model);
+        assert_eq!(csv.lines().nth(1), Some("t,Orphan,Spoofing,Low,gone,,,,"));
+    }
+}
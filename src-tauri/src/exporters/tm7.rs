@@ -0,0 +1,722 @@
+//! Writer for Microsoft Threat Modeling Tool `.tm7` XML files.
+//!
+//! The inverse of [`crate::importers::tm7::parse_tm7`]: elements become
+//! stencils, data flows become connectors, trust boundaries become border or
+//! line boundaries, each diagram becomes its own `DrawingSurfaceModel`, and
+//! threats become `ThreatInstances` carrying their priority, state and
+//! justification. The output is the same .NET DataContract shape TMT 2016
+//! (schema 4.3) writes.
+//!
+//! TMT has no equivalent for some of our data, so the mapping is lossy in a
+//! few places: text elements, layers, groups and relationships are not
+//! written, flows between diagrams are dropped, `info` severity becomes
+//! `Low`, in-progress threats become `NeedsInvestigation`, and accepted or
+//! transferred threats become `NotApplicable`. GUIDs are derived from ids,
+//! so exporting the same model twice yields the same file.
+
+use crate::models::layout::{
+    boundary_bounds, grid_origin, line_points, surface_index, DEFAULT_DIAGRAM_NAME,
+};
+use crate::models::{
+    DataFlow, Element, MitigationStatus, Position, Severity, Size, StrideCategory, Threat,
+    ThreatModel, TrustBoundary,
+};
+use quick_xml::escape::escape;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+const XSI_NS: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema";
+const MODEL_NS: &str = "http://schemas.datacontract.org/2004/07/ThreatModeling.Model";
+const ABSTRACTS_NS: &str = "http://schemas.datacontract.org/2004/07/ThreatModeling.Model.Abstracts";
+const KNOWLEDGE_BASE_NS: &str =
+    "http://schemas.datacontract.org/2004/07/ThreatModeling.KnowledgeBase";
+const ARRAYS_NS: &str = "http://schemas.microsoft.com/2003/10/Serialization/Arrays";
+const SERIALIZATION_NS: &str = "http://schemas.microsoft.com/2003/10/Serialization/";
+
+/// TMT writes this for "no element", e.g. the endpoints of a line boundary.
+const NIL_GUID: &str = "00000000-0000-0000-0000-000000000000";
+
+/// TMT's state for a threat nobody has dealt with yet.
+const OPEN_STATE: &str = "NeedsInvestigation";
+
+/// Stencil size; elements carry no size of their own. Matches the importer's
+/// fallback so containment is computed the same way on re-import.
+const STENCIL_SIZE: f64 = 100.0;
+
+/// A stencil placed on a drawing surface.
+struct Stencil<'a> {
+    element: &'a Element,
+    guid: String,
+    origin: Position,
+    /// Index of the drawing surface it sits on.
+    surface: usize,
+}
+
+impl Stencil<'_> {
+    fn center(&self) -> Position {
+        Position {
+            x: self.origin.x + STENCIL_SIZE / 2.0,
+            y: self.origin.y + STENCIL_SIZE / 2.0,
+        }
+    }
+}
+
+/// A drawing surface: one per diagram.
+struct Surface<'a> {
+    name: &'a str,
+    guid: String,
+}
+
+/// Serialize a `ThreatModel` as a TMT `.tm7` document.
+pub fn write_tm7(model: &ThreatModel) -> String {
+    let surfaces: Vec<Surface> = if model.diagrams.is_empty() {
+        vec![Surface {
+            name: DEFAULT_DIAGRAM_NAME,
+            guid: guid("diagram", "main-dfd"),
+        }]
+    } else {
+        model
+            .diagrams
+            .iter()
+            .enumerate()
+            .map(|(index, d)| Surface {
+                name: match d.name.as_str() {
+                    "" if index == 0 => DEFAULT_DIAGRAM_NAME,
+                    name => name,
+                },
+                guid: guid("diagram", &d.id),
+            })
+            .collect()
+    };
+
+    let mut placed = vec![0; surfaces.len()];
+    let stencils: Vec<Stencil> = model
+        .elements
+        .iter()
+        .filter(|e| stencil_kind(&e.element_type).is_some())
+        .map(|element| {
+            let surface = surface_index(model, element.diagram.as_deref());
+            let origin = element.position.clone().unwrap_or_else(|| {
+                placed[surface] += 1;
+                grid_origin(placed[surface] - 1)
+            });
+            Stencil {
+                element,
+                guid: guid("element", &element.id),
+                origin,
+                surface,
+            }
+        })
+        .collect();
+    let by_id: HashMap<&str, &Stencil> = stencils
+        .iter()
+        .map(|s| (s.element.id.as_str(), s))
+        .collect();
+
+    // Flows whose endpoints were not exported (text elements, dangling ids)
+    // have nothing to attach to in TMT, and a connector cannot leave its
+    // drawing surface.
+    let flows: Vec<(&DataFlow, &Stencil, &Stencil)> = model
+        .data_flows
+        .iter()
+        .filter_map(|f| Some((f, *by_id.get(f.from.as_str())?, *by_id.get(f.to.as_str())?)))
+        .filter(|(_, from, to)| from.surface == to.surface)
+        .collect();
+
+    let mut xml = XmlOut::default();
+    xml.line(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    xml.open(&format!(
+        r#"ThreatModel xmlns:i="{XSI_NS}" xmlns="{MODEL_NS}""#
+    ));
+    xml.open("DrawingSurfaceList");
+    for (index, surface) in surfaces.iter().enumerate() {
+        let boundaries: Vec<&TrustBoundary> = model
+            .trust_boundaries
+            .iter()
+            .filter(|b| surface_index(model, b.diagram.as_deref()) == index)
+            .collect();
+
+        xml.open(&format!(
+            r#"DrawingSurfaceModel z:Id="i{}" xmlns:z="{SERIALIZATION_NS}""#,
+            index + 1
+        ));
+        xml.abstract_leaf("GenericTypeId", "DRAWINGSURFACE");
+        xml.abstract_leaf("Guid", &surface.guid);
+        xml.open_properties();
+        xml.open(&format!(
+            r#"a:anyType i:type="b:HeaderDisplayAttribute" xmlns:b="{KNOWLEDGE_BASE_NS}""#
+        ));
+        xml.leaf("b:DisplayName", "Diagram");
+        xml.close("a:anyType");
+        xml.string_property("Name", surface.name);
+        xml.close("Properties");
+        xml.abstract_leaf("TypeId", "DRAWINGSURFACE");
+
+        xml.open(&format!(r#"Borders xmlns:a="{ARRAYS_NS}""#));
+        for stencil in stencils.iter().filter(|s| s.surface == index) {
+            write_stencil(&mut xml, stencil);
+        }
+        for boundary in boundaries.iter().filter(|b| line_points(b).is_none()) {
+            write_border_boundary(&mut xml, boundary, &by_id);
+        }
+        xml.close("Borders");
+
+        xml.open(&format!(r#"Lines xmlns:a="{ARRAYS_NS}""#));
+        for (flow, from, to) in flows.iter().filter(|(_, from, _)| from.surface == index) {
+            write_connector(&mut xml, flow, from, to);
+        }
+        for boundary in &boundaries {
+            if let Some(points) = line_points(boundary) {
+                write_line_boundary(&mut xml, boundary, points);
+            }
+        }
+        xml.close("Lines");
+        xml.close("DrawingSurfaceModel");
+    }
+    xml.close("DrawingSurfaceList");
+
+    xml.open("MetaInformation");
+    xml.empty("Assumptions");
+    xml.empty("Contributors");
+    xml.empty("ExternalDependencies");
+    xml.leaf("HighLevelSystemDescription", &model.metadata.description);
+    xml.leaf("Owner", &model.metadata.author);
+    xml.empty("Reviewer");
+    xml.leaf("ThreatModelName", &model.metadata.title);
+    xml.close("MetaInformation");
+    xml.empty("Notes");
+
+    let flow_guids: HashMap<&str, (&DataFlow, String)> = flows
+        .iter()
+        .map(|(f, _, _)| (f.id.as_str(), (*f, guid("flow", &f.id))))
+        .collect();
+    let modified_at = format!("{}T00:00:00", model.metadata.modified);
+    xml.open(&format!(r#"ThreatInstances xmlns:a="{ARRAYS_NS}""#));
+    for (index, threat) in model.threats.iter().enumerate() {
+        let endpoints = threat_endpoints(threat, &by_id, &flow_guids);
+        write_threat_instance(
+            &mut xml,
+            index + 1,
+            threat,
+            &endpoints,
+            &surfaces[endpoints.surface].guid,
+            &modified_at,
+        );
+    }
+    xml.close("ThreatInstances");
+    xml.leaf("ThreatGenerationEnabled", "true");
+    xml.empty("Validations");
+    xml.leaf("Version", "4.3");
+    xml.close("ThreatModel");
+    xml.buf
+}
+
+// ---------------------------------------------------------------------------
+// Section writers
+// ---------------------------------------------------------------------------
+
+fn write_stencil(xml: &mut XmlOut, stencil: &Stencil) {
+    let Some((stencil_type, generic_type_id)) = stencil_kind(&stencil.element.element_type) else {
+        return;
+    };
+    xml.open("a:KeyValueOfguidanyType");
+    xml.leaf("a:Key", &stencil.guid);
+    xml.open(&format!(r#"a:Value i:type="{stencil_type}""#));
+    xml.abstract_leaf("GenericTypeId", generic_type_id);
+    xml.abstract_leaf("Guid", &stencil.guid);
+    xml.open_properties();
+    xml.string_property("Name", &stencil.element.name);
+    xml.open(&format!(
+        r#"a:anyType i:type="b:BooleanDisplayAttribute" xmlns:b="{KNOWLEDGE_BASE_NS}""#
+    ));
+    xml.leaf("b:DisplayName", "Out Of Scope");
+    xml.line(&format!(
+        r#"<b:Value i:type="c:boolean" xmlns:c="{XSD_NS}">false</b:Value>"#
+    ));
+    xml.close("a:anyType");
+    xml.close("Properties");
+    xml.abstract_leaf("TypeId", generic_type_id);
+    xml.abstract_leaf("Height", &STENCIL_SIZE.to_string());
+    xml.abstract_leaf("Left", &stencil.origin.x.to_string());
+    xml.abstract_leaf("Top", &stencil.origin.y.to_string());
+    xml.abstract_leaf("Width", &STENCIL_SIZE.to_string());
+    xml.close("a:Value");
+    xml.close("a:KeyValueOfguidanyType");
+}
+
+fn write_border_boundary(
+    xml: &mut XmlOut,
+    boundary: &TrustBoundary,
+    stencils: &HashMap<&str, &Stencil>,
+) {
+    let (origin, width, height) = boundary_geometry(boundary, stencils);
+    let boundary_guid = guid("boundary", &boundary.id);
+    xml.open("a:KeyValueOfguidanyType");
+    xml.leaf("a:Key", &boundary_guid);
+    xml.open(r#"a:Value i:type="BorderBoundary""#);
+    xml.abstract_leaf("GenericTypeId", "GE.TB.B");
+    xml.abstract_leaf("Guid", &boundary_guid);
+    xml.open_properties();
+    xml.string_property("Name", &boundary.name);
+    xml.close("Properties");
+    xml.abstract_leaf("TypeId", "GE.TB.B");
+    xml.abstract_leaf("Height", &height.to_string());
+    xml.abstract_leaf("Left", &origin.x.to_string());
+    xml.abstract_leaf("Top", &origin.y.to_string());
+    xml.abstract_leaf("Width", &width.to_string());
+    xml.close("a:Value");
+    xml.close("a:KeyValueOfguidanyType");
+}
+
+/// A line boundary: TMT draws it from its source to its target point,
+/// curved through an optional handle, so a longer polyline keeps its ends
+/// and its middle vertex.
+fn write_line_boundary(xml: &mut XmlOut, boundary: &TrustBoundary, points: &[Position]) {
+    let boundary_guid = guid("boundary", &boundary.id);
+    let source = &points[0];
+    let target = &points[points.len() - 1];
+    xml.open("a:KeyValueOfguidanyType");
+    xml.leaf("a:Key", &boundary_guid);
+    xml.open(r#"a:Value i:type="LineBoundary""#);
+    xml.abstract_leaf("GenericTypeId", "GE.TB.L");
+    xml.abstract_leaf("Guid", &boundary_guid);
+    xml.open_properties();
+    xml.string_property("Name", &boundary.name);
+    xml.close("Properties");
+    xml.abstract_leaf("TypeId", "GE.TB.L");
+    if points.len() > 2 {
+        let handle = &points[points.len() / 2];
+        xml.abstract_leaf("HandleX", &handle.x.to_string());
+        xml.abstract_leaf("HandleY", &handle.y.to_string());
+    }
+    xml.abstract_leaf("SourceGuid", NIL_GUID);
+    xml.abstract_leaf("SourceX", &source.x.to_string());
+    xml.abstract_leaf("SourceY", &source.y.to_string());
+    xml.abstract_leaf("TargetGuid", NIL_GUID);
+    xml.abstract_leaf("TargetX", &target.x.to_string());
+    xml.abstract_leaf("TargetY", &target.y.to_string());
+    xml.close("a:Value");
+    xml.close("a:KeyValueOfguidanyType");
+}
+
+fn write_connector(xml: &mut XmlOut, flow: &DataFlow, from: &Stencil, to: &Stencil) {
+    let flow_guid = guid("flow", &flow.id);
+    let source = from.center();
+    let target = to.center();
+    xml.open("a:KeyValueOfguidanyType");
+    xml.leaf("a:Key", &flow_guid);
+    xml.open(r#"a:Value i:type="Connector""#);
+    xml.abstract_leaf("GenericTypeId", "GE.DF");
+    xml.abstract_leaf("Guid", &flow_guid);
+    xml.open_properties();
+    xml.string_property("Name", &flow.name);
+    xml.close("Properties");
+    xml.abstract_leaf("TypeId", "GE.DF");
+    xml.abstract_leaf("HandleX", &((source.x + target.x) / 2.0).to_string());
+    xml.abstract_leaf("HandleY", &((source.y + target.y) / 2.0).to_string());
+    xml.abstract_leaf("SourceGuid", &from.guid);
+    xml.abstract_leaf("SourceX", &source.x.to_string());
+    xml.abstract_leaf("SourceY", &source.y.to_string());
+    xml.abstract_leaf("TargetGuid", &to.guid);
+    xml.abstract_leaf("TargetX", &target.x.to_string());
+    xml.abstract_leaf("TargetY", &target.y.to_string());
+    xml.close("a:Value");
+    xml.close("a:KeyValueOfguidanyType");
+}
+
+/// The GUIDs a threat instance points at, and the surface it is listed on.
+struct ThreatEndpoints {
+    source: String,
+    flow: String,
+    target: String,
+    surface: usize,
+}
+
+/// Resolve where a threat attaches. TMT threats sit on an interaction: the
+/// importer reads the threat's element back from `SourceGuid`, so that is the
+/// threat's own element when it has one, and the flow's source otherwise.
+fn threat_endpoints(
+    threat: &Threat,
+    stencils: &HashMap<&str, &Stencil>,
+    flows: &HashMap<&str, (&DataFlow, String)>,
+) -> ThreatEndpoints {
+    let stencil = |id: &str| stencils.get(id).copied();
+    let element = threat.element.as_deref().and_then(stencil);
+    let flow = threat.flow.as_deref().and_then(|id| flows.get(id));
+    let guid_of = |s: Option<&Stencil>| s.map_or_else(|| NIL_GUID.to_string(), |s| s.guid.clone());
+
+    let (source, target) = match (element, flow) {
+        (Some(element), Some((flow, _))) => {
+            let other = if threat.element.as_deref() == Some(flow.from.as_str()) {
+                &flow.to
+            } else {
+                &flow.from
+            };
+            (Some(element), stencil(other).or(Some(element)))
+        }
+        (Some(element), None) => (Some(element), Some(element)),
+        (None, Some((flow, _))) => (stencil(&flow.from), stencil(&flow.to)),
+        (None, None) => (None, None),
+    };
+    ThreatEndpoints {
+        surface: source.map_or(0, |s| s.surface),
+        source: guid_of(source),
+        flow: flow.map_or_else(|| NIL_GUID.to_string(), |(_, guid)| guid.clone()),
+        target: guid_of(target),
+    }
+}
+
+fn write_threat_instance(
+    xml: &mut XmlOut,
+    id: usize,
+    threat: &Threat,
+    endpoints: &ThreatEndpoints,
+    surface_guid: &str,
+    modified_at: &str,
+) {
+    let type_id = stride_type_id(&threat.category);
+    let priority = severity_priority(&threat.severity);
+    let (state, justification) = match &threat.mitigation {
+        Some(m) => (
+            mitigation_state(&m.status, OPEN_STATE),
+            m.description.as_str(),
+        ),
+        None => (OPEN_STATE, ""),
+    };
+
+    xml.open("a:KeyValueOfstringThreatpc_P0_PhOB");
+    xml.leaf("a:Key", &format!("{type_id}-{id}"));
+    xml.open(&format!(r#"a:Value xmlns:b="{KNOWLEDGE_BASE_NS}""#));
+    xml.line(r#"<b:ChangedBy i:nil="true"/>"#);
+    xml.leaf("b:DrawingSurfaceGuid", surface_guid);
+    xml.leaf("b:FlowGuid", &endpoints.flow);
+    xml.leaf("b:Id", &id.to_string());
+    xml.leaf(
+        "b:InteractionKey",
+        &format!(
+            "{}:{}:{}",
+            endpoints.source, endpoints.flow, endpoints.target
+        ),
+    );
+    xml.leaf("b:ModifiedAt", modified_at);
+    xml.leaf("b:Priority", priority);
+    xml.open("b:Properties");
+    for (key, value) in [
+        ("Title", threat.title.as_str()),
+        ("UserThreatCategory", stride_category_name(&threat.category)),
+        ("UserThreatDescription", threat.description.as_str()),
+        ("Priority", priority),
+    ] {
+        xml.open("a:KeyValueOfstringstring");
+        xml.leaf("a:Key", key);
+        xml.leaf("a:Value", value);
+        xml.close("a:KeyValueOfstringstring");
+    }
+    xml.close("b:Properties");
+    xml.leaf("b:SourceGuid", &endpoints.source);
+    xml.leaf("b:State", state);
+    xml.leaf("b:StateInformation", justification);
+    xml.leaf("b:TargetGuid", &endpoints.target);
+    xml.line(r#"<b:Title i:nil="true"/>"#);
+    xml.leaf("b:TypeId", type_id);
+    xml.leaf("b:Upgraded", "false");
+    xml.close("a:Value");
+    xml.close("a:KeyValueOfstringThreatpc_P0_PhOB");
+}
+
+// ---------------------------------------------------------------------------
+// XML output
+// ---------------------------------------------------------------------------
+
+/// Indented XML text in the layout TMT itself writes. Element content is
+/// escaped; tag strings are trusted.
+#[derive(Default)]
+struct XmlOut {
+    buf: String,
+    depth: usize,
+}
+
+impl XmlOut {
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.depth {
+            self.buf.push_str("  ");
+        }
+        self.buf.push_str(text);
+        self.buf.push('\n');
+    }
+
+    /// Open `<start>`, where `start` is the tag name plus any attributes.
+    fn open(&mut self, start: &str) {
+        self.line(&format!("<{start}>"));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.line(&format!("</{name}>"));
+    }
+
+    fn empty(&mut self, name: &str) {
+        self.line(&format!("<{name}/>"));
+    }
+
+    fn leaf(&mut self, name: &str, text: &str) {
+        self.line(&format!("<{name}>{}</{name}>", escape(text)));
+    }
+
+    /// A leaf in the `Model.Abstracts` namespace, as stencil fields are.
+    fn abstract_leaf(&mut self, name: &str, text: &str) {
+        self.line(&format!(
+            r#"<{name} xmlns="{ABSTRACTS_NS}">{}</{name}>"#,
+            escape(text)
+        ));
+    }
+
+    fn open_properties(&mut self) {
+        self.open(&format!(
+            r#"Properties xmlns="{ABSTRACTS_NS}" xmlns:a="{ARRAYS_NS}""#
+        ));
+    }
+
+    fn string_property(&mut self, display_name: &str, value: &str) {
+        self.open(&format!(
+            r#"a:anyType i:type="b:StringDisplayAttribute" xmlns:b="{KNOWLEDGE_BASE_NS}""#
+        ));
+        self.leaf("b:DisplayName", display_name);
+        self.line(&format!(
+            r#"<b:Value i:type="c:string" xmlns:c="{XSD_NS}">{}</b:Value>"#,
+            escape(value)
+        ));
+        self.close("a:anyType");
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Conversion helpers
+// ---------------------------------------------------------------------------
+
+/// A stable GUID for the item `id` of the given kind.
+pub(super) fn guid(kind: &str, id: &str) -> String {
+    let digest = Sha256::digest(format!("{kind}:{id}").as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes)
+        .into_uuid()
+        .to_string()
+}
+
+/// The stencil `i:type` and `GenericTypeId` for an element type, or `None`
+/// for types TMT cannot draw.
+fn stencil_kind(element_type: &str) -> Option<(&'static str, &'static str)> {
+    match element_type {
+        "process" => Some(("StencilEllipse", "GE.P")),
+        "data_store" => Some(("StencilParallelLines", "GE.DS")),
+        "external_entity" => Some(("StencilRectangle", "GE.EI")),
+        _ => None,
+    }
+}
+
+/// A boundary's own geometry, or a box around the stencils it contains.
+fn boundary_geometry(
+    boundary: &TrustBoundary,
+    stencils: &HashMap<&str, &Stencil>,
+) -> (Position, f64, f64) {
+    if let (Some(position), Some(size)) = (&boundary.position, &boundary.size) {
+        return (position.clone(), size.width, size.height);
+    }
+    let members = boundary.contains.iter().filter_map(|id| {
+        let stencil = stencils.get(id.as_str())?;
+        let size = Size {
+            width: STENCIL_SIZE,
+            height: STENCIL_SIZE,
+        };
+        Some((stencil.origin.clone(), size))
+    });
+    match boundary_bounds(members) {
+        Some((origin, size)) => (origin, size.width, size.height),
+        None => {
+            let origin = boundary
+                .position
+                .clone()
+                .unwrap_or(Position { x: 0.0, y: 0.0 });
+            (origin, 200.0, 100.0)
+        }
+    }
+}
+
+/// The category name TMT shows in its threat list.
+pub(super) fn stride_category_name(category: &StrideCategory) -> &'static str {
+    match category {
+        StrideCategory::Spoofing => "Spoofing",
+        StrideCategory::Tampering => "Tampering",
+        StrideCategory::Repudiation => "Repudiation",
+        StrideCategory::InformationDisclosure => "Information Disclosure",
+        StrideCategory::DenialOfService => "Denial Of Service",
+        StrideCategory::ElevationOfPrivilege => "Elevation Of Privilege",
+    }
+}
+
+/// The STRIDE letter TMT threat type ids start with.
+fn stride_type_id(category: &StrideCategory) -> &'static str {
+    match category {
+        StrideCategory::Spoofing => "S",
+        StrideCategory::Tampering => "T",
+        StrideCategory::Repudiation => "R",
+        StrideCategory::InformationDisclosure => "I",
+        StrideCategory::DenialOfService => "D",
+        StrideCategory::ElevationOfPrivilege => "E",
+    }
+}
+
+/// The priority TMT, and Threat Dragon as its severity, give a threat. Both
+/// stop at `Low`, so `info` joins it.
+pub(super) fn severity_priority(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "Critical",
+        Severity::High => "High",
+        Severity::Medium => "Medium",
+        Severity::Low | Severity::Info => "Low",
+    }
+}
+
+/// The state TMT, and Threat Dragon as its status, give a mitigation. Work
+/// not yet done is `open`, which the two formats name differently.
+pub(super) fn mitigation_state(status: &MitigationStatus, open: &'static str) -> &'static str {
+    match status {
+        MitigationStatus::Mitigated => "Mitigated",
+        MitigationStatus::Accepted | MitigationStatus::Transferred => "NotApplicable",
+        MitigationStatus::NotStarted | MitigationStatus::InProgress => open,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::pin_dates;
+    use crate::importers::tm7::parse_tm7;
+    use crate::models::Mitigation;
+
+    /// The importer's fixture; exporting its import and importing that again
+    /// must land on the same pinned result.
+    const FIXTURE_TM7: &str = include_str!("../importers/fixtures/tmt-4.3-sample.tm7");
+    const FIXTURE_EXPECTED_YAML: &str =
+        include_str!("../importers/fixtures/tmt-4.3-sample.expected.yaml");
+
+    fn import(xml: &str) -> ThreatModel {
+        pin_dates(parse_tm7(xml).expect("exported .tm7 should import").model)
+    }
+
+    #[test]
+    fn fixture_round_trips_through_export() {
+        let model = import(FIXTURE_TM7);
+        let exported = write_tm7(&model);
+        let reimported = serde_yaml::to_string(&import(&exported)).unwrap();
+        assert_eq!(reimported, FIXTURE_EXPECTED_YAML);
+    }
+
+    #[test]
+    fn export_is_deterministic() {
+        let model = import(FIXTURE_TM7);
+        assert_eq!(write_tm7(&model), write_tm7(&model));
+        assert_eq!(guid("element", "api"), guid("element", "api"));
+        assert_ne!(guid("element", "api"), guid("flow", "api"));
+    }
+
+    #[test]
+    fn statuses_and_severities_map_onto_tmt_vocabulary() {
+        let mut model = import(FIXTURE_TM7);
+        model.threats[0].severity = Severity::Info;
+        model.threats[0].mitigation = Some(Mitigation {
+            status: MitigationStatus::Transferred,
+            description: "Covered by the payment processor's <SLA>.".to_string(),
+        });
+        model.threats[1].mitigation = Some(Mitigation {
+            status: MitigationStatus::InProgress,
+            description: String::new(),
+        });
+        model.threats[2].mitigation = None;
+
+        let reimported = import(&write_tm7(&model));
+        let threats = &reimported.threats;
+        assert_eq!(threats[0].severity, Severity::Low);
+        let mitigation = threats[0].mitigation.as_ref().unwrap();
+        assert_eq!(mitigation.status, MitigationStatus::Accepted);
+        assert_eq!(
+            mitigation.description,
+            "Covered by the payment processor's <SLA>."
+        );
+        for threat in &threats[1..] {
+            let status = &threat.mitigation.as_ref().unwrap().status;
+            assert_eq!(*status, MitigationStatus::NotStarted);
+        }
+    }
+
+    #[test]
+    fn unplaced_and_undrawable_items_are_laid_out_or_skipped() {
+        let mut model = import(FIXTURE_TM7);
+        for element in &mut model.elements {
+            element.position = None;
+        }
+        for boundary in &mut model.trust_boundaries {
+            boundary.position = None;
+            boundary.size = None;
+        }
+        // A text label cannot be a TMT stencil, and the flow into it goes too.
+        model.elements[4].element_type = "text".to_string();
+        model.data_flows[1].to = model.elements[4].id.clone();
+        // A threat on a flow alone is attached to the flow's source.
+        model.threats[1].element = None;
+
+        let reimported = import(&write_tm7(&model));
+        assert_eq!(reimported.elements.len(), 4);
+        assert!(reimported.elements.iter().all(|e| e.position.is_some()));
+        assert_eq!(reimported.data_flows.len(), 1);
+        assert_eq!(
+            reimported.trust_boundaries[0].contains,
+            ["payments-billing-api", "billing-database"]
+        );
+        assert_eq!(reimported.threats[1].element.as_deref(), Some("customer"));
+        assert_eq!(reimported.threats[1].flow.as_deref(), Some("flow-1"));
+    }
+
+    #[test]
+    fn diagrams_become_surfaces_and_lines_keep_their_vertices() {
+        let mut model = import(FIXTURE_TM7);
+        let mut detail = model.diagrams[0].clone();
+        detail.id = "reporting".to_string();
+        detail.name = "Reporting".to_string();
+        model.diagrams.push(detail);
+        // Reporting Job moves to the second diagram; the flow from the
+        // customer would cross surfaces, so it is dropped.
+        model.elements[3].diagram = Some("reporting".to_string());
+        model.data_flows[0].to = model.elements[3].id.clone();
+        model.trust_boundaries[1]
+            .points
+            .insert(1, Position { x: 260.0, y: 280.0 });
+        model.trust_boundaries[1]
+            .points
+            .push(Position { x: 300.0, y: 600.0 });
+
+        let reimported = import(&write_tm7(&model));
+        let diagrams: Vec<_> = reimported.diagrams.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(diagrams, ["main-dfd", "reporting"]);
+        let reporting: Vec<_> = reimported
+            .elements
+            .iter()
+            .filter(|e| e.diagram.is_some())
+            .map(|e| e.id.as_str())
+            .collect();
+        assert_eq!(reporting, ["reporting-job"]);
+        assert_eq!(reimported.data_flows.len(), 1);
+        // TMT keeps the ends and one handle of a longer polyline.
+        assert_eq!(
+            reimported.trust_boundaries[1].points,
+            [
+                Position { x: 240.0, y: 40.0 },
+                Position { x: 240.0, y: 520.0 },
+                Position { x: 300.0, y: 600.0 },
+            ]
+        );
+    }
+}
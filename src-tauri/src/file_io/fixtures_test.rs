@@ -552,6 +552,16 @@ fn architecture_invalid_fixtures_are_rejected_with_the_expected_error() {
         "elements[app].group",
         "backend",
     );
+    expect_invalid_reference(
+        "invalid/unknown-element-diagram.thf",
+        "elements[app].diagram",
+        "level-1",
+    );
+    expect_invalid_reference(
+        "invalid/unknown-boundary-diagram.thf",
+        "trust_boundaries[dmz].diagram",
+        "level-1",
+    );
     expect_invalid_reference(
         "invalid/unknown-group-parent.thf",
         "groups[cluster-1].parent",
@@ -582,8 +592,8 @@ fn architecture_invalid_fixtures_are_rejected_with_the_expected_error() {
 /// `architecture-canonical-full.thf`, and the TypeScript contract test mirrors it structurally.
 fn max_filled_model() -> ThreatModel {
     use crate::models::{
-        DataFlow, Diagram, Element, FileSettings, Group, Layer, Metadata, Mitigation, Relationship,
-        Threat, TrustBoundary,
+        ApiOperation, BoundaryShape, DataFlow, Diagram, Element, FileSettings, Group, Layer,
+        Metadata, Mitigation, Relationship, Threat, TrustBoundary,
     };
     use chrono::NaiveDate;
 
@@ -684,6 +694,7 @@ fn max_filled_model() -> ThreatModel {
                 trust_zone: "internal".to_string(),
                 layer: Some("presentation".to_string()),
                 group: Some("frontend-cluster".to_string()),
+                diagram: Some("main-arch".to_string()),
                 subtype: None,
                 icon: None,
                 description: "React storefront".to_string(),
@@ -691,6 +702,7 @@ fn max_filled_model() -> ThreatModel {
                 tags: vec!["tier-1".to_string()],
                 stores: None,
                 encryption: None,
+                operations: Vec::new(),
                 position: Some(Position { x: 100.0, y: 120.0 }),
                 fill_color: None,
                 stroke_color: None,
@@ -706,6 +718,7 @@ fn max_filled_model() -> ThreatModel {
                 trust_zone: "dmz".to_string(),
                 layer: Some("application".to_string()),
                 group: Some("api-subcluster".to_string()),
+                diagram: None,
                 subtype: Some("api_gateway".to_string()),
                 icon: Some("router".to_string()),
                 description: "Terminates TLS and routes requests".to_string(),
@@ -713,6 +726,15 @@ fn max_filled_model() -> ThreatModel {
                 tags: vec!["pci".to_string(), "tier-1".to_string()],
                 stores: None,
                 encryption: None,
+                operations: vec![ApiOperation {
+                    method: "POST".to_string(),
+                    path: "/orders".to_string(),
+                    summary: Some("Place an order".to_string()),
+                    authenticated: true,
+                    rate_limited: true,
+                    request_fields: vec!["items.sku".to_string(), "items.quantity".to_string()],
+                    response_fields: vec!["order_id".to_string()],
+                }],
                 position: Some(Position { x: 480.0, y: 120.0 }),
                 fill_color: Some("#3b82f6".to_string()),
                 stroke_color: Some("#1e40af".to_string()),
@@ -728,6 +750,7 @@ fn max_filled_model() -> ThreatModel {
                 trust_zone: "internal".to_string(),
                 layer: Some("data".to_string()),
                 group: Some("backend-cluster".to_string()),
+                diagram: None,
                 subtype: Some("sql_database".to_string()),
                 icon: Some("database".to_string()),
                 description: "Primary order store".to_string(),
@@ -735,6 +758,7 @@ fn max_filled_model() -> ThreatModel {
                 tags: vec!["pci".to_string()],
                 stores: Some(vec!["order_records".to_string()]),
                 encryption: Some("AES-256-at-rest".to_string()),
+                operations: Vec::new(),
                 position: Some(Position { x: 480.0, y: 320.0 }),
                 fill_color: Some("#22c55e".to_string()),
                 stroke_color: Some("#15803d".to_string()),
@@ -806,24 +830,43 @@ fn max_filled_model() -> ThreatModel {
                 stroke_opacity: None,
             },
         ],
-        trust_boundaries: vec![TrustBoundary {
-            id: "boundary-1".to_string(),
-            name: "Corporate Network".to_string(),
-            contains: vec![
-                "web-app".to_string(),
-                "api-gateway".to_string(),
-                "orders-db".to_string(),
-            ],
-            position: Some(Position { x: 20.0, y: 20.0 }),
-            size: Some(Size {
-                width: 840.0,
-                height: 400.0,
-            }),
-            fill_color: Some("#22c55e".to_string()),
-            stroke_color: Some("#15803d".to_string()),
-            fill_opacity: Some(0.1),
-            stroke_opacity: Some(0.6),
-        }],
+        trust_boundaries: vec![
+            TrustBoundary {
+                id: "boundary-1".to_string(),
+                name: "Corporate Network".to_string(),
+                contains: vec![
+                    "web-app".to_string(),
+                    "api-gateway".to_string(),
+                    "orders-db".to_string(),
+                ],
+                diagram: None,
+                shape: None,
+                points: Vec::new(),
+                position: Some(Position { x: 20.0, y: 20.0 }),
+                size: Some(Size {
+                    width: 840.0,
+                    height: 400.0,
+                }),
+                fill_color: Some("#22c55e".to_string()),
+                stroke_color: Some("#15803d".to_string()),
+                fill_opacity: Some(0.1),
+                stroke_opacity: Some(0.6),
+            },
+            TrustBoundary {
+                id: "internet-edge".to_string(),
+                name: "Internet Edge".to_string(),
+                contains: Vec::new(),
+                diagram: Some("main-arch".to_string()),
+                shape: Some(BoundaryShape::Line),
+                points: vec![Position { x: 0.0, y: 0.0 }, Position { x: 0.0, y: 440.0 }],
+                position: None,
+                size: None,
+                fill_color: None,
+                stroke_color: Some("#dc2626".to_string()),
+                fill_opacity: None,
+                stroke_opacity: Some(0.8),
+            },
+        ],
         threats: vec![Threat {
             id: "threat-1".to_string(),
             title: "SQL injection on order queries".to_string(),
@@ -905,6 +948,7 @@ fn architecture_only_model() -> ThreatModel {
                 trust_zone: String::new(),
                 layer: Some("presentation".to_string()),
                 group: Some("core".to_string()),
+                diagram: None,
                 subtype: None,
                 icon: None,
                 description: String::new(),
@@ -912,6 +956,7 @@ fn architecture_only_model() -> ThreatModel {
                 tags: Vec::new(),
                 stores: None,
                 encryption: None,
+                operations: Vec::new(),
                 position: None,
                 fill_color: None,
                 stroke_color: None,
@@ -927,6 +972,7 @@ fn architecture_only_model() -> ThreatModel {
                 trust_zone: String::new(),
                 layer: Some("data".to_string()),
                 group: Some("core".to_string()),
+                diagram: None,
                 subtype: None,
                 icon: None,
                 description: String::new(),
@@ -934,6 +980,7 @@ fn architecture_only_model() -> ThreatModel {
                 tags: Vec::new(),
                 stores: None,
                 encryption: None,
+                operations: Vec::new(),
                 position: None,
                 fill_color: None,
                 stroke_color: None,
@@ -1,8 +1,12 @@
 mod reader;
+mod stamp;
+mod watch;
 mod writer;
 
 #[cfg(test)]
 mod fixtures_test;
 
 pub use reader::*;
+pub use stamp::*;
+pub use watch::*;
 pub use writer::*;
@@ -112,12 +112,13 @@ fn validate_version(version: &str) -> Result<(), ThreatForgeError> {
 }
 
 /// Validate cross-references within the model
-fn validate_references(model: &ThreatModel) -> Result<(), ThreatForgeError> {
+pub fn validate_references(model: &ThreatModel) -> Result<(), ThreatForgeError> {
     let element_ids: Vec<&str> = model.elements.iter().map(|e| e.id.as_str()).collect();
     let flow_ids: Vec<&str> = model.data_flows.iter().map(|f| f.id.as_str()).collect();
     let layer_ids: Vec<&str> = model.layers.iter().map(|l| l.id.as_str()).collect();
     let group_ids: Vec<&str> = model.groups.iter().map(|g| g.id.as_str()).collect();
     let relationship_ids: Vec<&str> = model.relationships.iter().map(|r| r.id.as_str()).collect();
+    let diagram_ids: Vec<&str> = model.diagrams.iter().map(|d| d.id.as_str()).collect();
     let boundary_ids: Vec<&str> = model
         .trust_boundaries
         .iter()
@@ -239,6 +240,15 @@ fn validate_references(model: &ThreatModel) -> Result<(), ThreatForgeError> {
                 });
             }
         }
+        if let Some(diagram) = &element.diagram {
+            if !diagram_ids.contains(&diagram.as_str()) {
+                return Err(ThreatForgeError::InvalidReference {
+                    field: format!("elements[{}].diagram", element.id),
+                    reference: diagram.clone(),
+                    valid: diagram_ids.iter().map(|s| s.to_string()).collect(),
+                });
+            }
+        }
     }
 
     // Validate group parent references
@@ -305,6 +315,15 @@ fn validate_references(model: &ThreatModel) -> Result<(), ThreatForgeError> {
                 });
             }
         }
+        if let Some(diagram) = &boundary.diagram {
+            if !diagram_ids.contains(&diagram.as_str()) {
+                return Err(ThreatForgeError::InvalidReference {
+                    field: format!("trust_boundaries[{}].diagram", boundary.id),
+                    reference: diagram.clone(),
+                    valid: diagram_ids.iter().map(|s| s.to_string()).collect(),
+                });
+            }
+        }
     }
 
     // Validate threat references
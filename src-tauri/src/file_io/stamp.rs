@@ -0,0 +1,115 @@
+use crate::errors::ThreatForgeError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Fingerprint of a `.thf` file as it was last read or written.
+///
+/// Two writers share one file: the desktop app and the MCP server. Each keeps
+/// the stamp from its own last load and compares it with the file on disk
+/// before saving, so a save never silently replaces edits it has not seen.
+/// `sha256` is the authority; `modified_ms` is carried for display and as a
+/// cheap hint, because a coarse filesystem clock can leave it unchanged across
+/// two quick writes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileStamp {
+    pub sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_ms: Option<i64>,
+}
+
+impl FileStamp {
+    /// Build a stamp from file contents and an optional modification time.
+    pub fn from_contents(contents: &[u8], modified_ms: Option<i64>) -> Self {
+        let digest = Sha256::digest(contents);
+        let sha256 = digest.iter().map(|b| format!("{b:02x}")).collect();
+        Self {
+            sha256,
+            modified_ms,
+        }
+    }
+
+    /// Whether both stamps describe the same file contents.
+    pub fn same_contents(&self, other: &FileStamp) -> bool {
+        self.sha256 == other.sha256
+    }
+}
+
+/// Stamp the file currently at `path`.
+pub fn read_file_stamp(path: &Path) -> Result<FileStamp, ThreatForgeError> {
+    let contents = std::fs::read(path).map_err(|e| ThreatForgeError::FileRead {
+        path: path.display().to_string(),
+        source: e,
+    })?;
+    Ok(FileStamp::from_contents(&contents, modified_ms(path)))
+}
+
+/// Modification time of `path` in milliseconds since the Unix epoch, if the
+/// platform reports one.
+pub fn modified_ms(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let millis = modified.duration_since(UNIX_EPOCH).ok()?.as_millis();
+    i64::try_from(millis).ok()
+}
+
+/// Fail with [`ThreatForgeError::ConcurrentModification`] unless the file at
+/// `path` still has the contents described by `expected`.
+///
+/// A missing file counts as changed: it was loaded from somewhere, so its
+/// disappearance is a concurrent edit too.
+pub fn ensure_unchanged(path: &Path, expected: &FileStamp) -> Result<(), ThreatForgeError> {
+    let unchanged = match read_file_stamp(path) {
+        Ok(current) => current.same_contents(expected),
+        Err(_) => false,
+    };
+    if unchanged {
+        Ok(())
+    } else {
+        Err(ThreatForgeError::ConcurrentModification {
+            path: path.display().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn stamp_depends_only_on_contents_for_comparison() {
+        let a = FileStamp::from_contents(b"version: '1.0'\n", Some(1));
+        let b = FileStamp::from_contents(b"version: '1.0'\n", Some(2));
+        let c = FileStamp::from_contents(b"version: '1.1'\n", Some(1));
+        assert!(a.same_contents(&b), "mtime alone must not signal a change");
+        assert!(!a.same_contents(&c));
+        assert_eq!(a.sha256.len(), 64);
+    }
+
+    #[test]
+    fn ensure_unchanged_detects_an_external_write() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("model.thf");
+        std::fs::write(&path, "first").unwrap();
+        let loaded = read_file_stamp(&path).unwrap();
+        assert!(ensure_unchanged(&path, &loaded).is_ok());
+
+        std::fs::write(&path, "second").unwrap();
+        let err = ensure_unchanged(&path, &loaded).unwrap_err();
+        assert!(matches!(
+            err,
+            ThreatForgeError::ConcurrentModification { .. }
+        ));
+    }
+
+    #[test]
+    fn ensure_unchanged_treats_a_deleted_file_as_changed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("model.thf");
+        std::fs::write(&path, "first").unwrap();
+        let loaded = read_file_stamp(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(ensure_unchanged(&path, &loaded).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use super::{read_file_stamp, FileStamp};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// How often a watched file is re-stamped. Polling keeps the watcher free of a
+/// platform notification dependency; a second is well below the time it takes
+/// a person to switch from an assistant back to the canvas.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls open `.thf` files and reports when their contents change on disk.
+///
+/// The desktop app registers each file it opens so that a save made by the
+/// MCP server (or any other writer) surfaces as a notification instead of
+/// being silently overwritten by the next desktop save.
+#[derive(Default)]
+pub struct FileWatchRegistry {
+    watches: Arc<Mutex<HashMap<PathBuf, Arc<AtomicBool>>>>,
+}
+
+impl FileWatchRegistry {
+    /// Lock the map, recovering from poisoning. An entry is only a stop flag,
+    /// so a panicking holder cannot leave it half-written.
+    fn lock(&self) -> MutexGuard<'_, HashMap<PathBuf, Arc<AtomicBool>>> {
+        match self.watches.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Start watching `path`, calling `on_change` with each new stamp.
+    ///
+    /// `baseline` is the stamp the caller already knows about, so the first
+    /// poll does not report the caller's own load. Watching an already
+    /// watched path replaces the previous watch.
+    pub fn watch<F>(&self, path: &Path, baseline: Option<FileStamp>, on_change: F)
+    where
+        F: Fn(&Path, &FileStamp) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self.lock().insert(path.to_path_buf(), stop.clone()) {
+            previous.store(true, Ordering::SeqCst);
+        }
+
+        let path = path.to_path_buf();
+        std::thread::spawn(move || {
+            let mut last = baseline;
+            while !stop.load(Ordering::SeqCst) {
+                std::thread::sleep(POLL_INTERVAL);
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                // A file that is briefly unreadable (mid-write, or replaced by
+                // an editor's rename) is retried on the next poll.
+                let Ok(current) = read_file_stamp(&path) else {
+                    continue;
+                };
+                let changed = last
+                    .as_ref()
+                    .is_none_or(|previous| !previous.same_contents(&current));
+                if changed {
+                    on_change(&path, &current);
+                    last = Some(current);
+                }
+            }
+        });
+    }
+
+    /// Stop watching `path`. Unknown paths are a no-op.
+    pub fn unwatch(&self, path: &Path) {
+        if let Some(stop) = self.lock().remove(path) {
+            stop.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+impl Drop for FileWatchRegistry {
+    fn drop(&mut self) {
+        for stop in self.lock().values() {
+            stop.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reports_an_external_write_but_not_the_baseline() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("model.thf");
+        std::fs::write(&path, "first").unwrap();
+        let baseline = read_file_stamp(&path).unwrap();
+
+        let registry = FileWatchRegistry::default();
+        let (tx, rx) = mpsc::channel();
+        registry.watch(&path, Some(baseline.clone()), move |_, stamp| {
+            let _ = tx.send(stamp.clone());
+        });
+
+        assert!(
+            rx.recv_timeout(POLL_INTERVAL * 2).is_err(),
+            "an unchanged file must not be reported"
+        );
+
+        std::fs::write(&path, "second").unwrap();
+        let reported = rx
+            .recv_timeout(POLL_INTERVAL * 5)
+            .expect("the external write should be reported");
+        assert!(!reported.same_contents(&baseline));
+
+        registry.unwatch(&path);
+    }
+}
@@ -1,33 +1,70 @@
+use super::{ensure_unchanged, modified_ms, FileStamp};
 use crate::errors::ThreatForgeError;
 use crate::models::{DiagramLayout, ThreatModel};
 use std::path::Path;
 
 /// Write a threat model to a `.thf` file
 pub fn write_threat_model(path: &Path, model: &ThreatModel) -> Result<(), ThreatForgeError> {
-    // Add header comment
+    let content = threat_model_contents(model)?;
+    create_parent_dir(path)?;
+    write_contents(path, &content)
+}
+
+/// Write a threat model only if the file on disk still matches `expected`.
+///
+/// `expected` is the stamp taken when the caller loaded the file; `None` means
+/// the caller never loaded it (a new file or Save As) and the write is
+/// unconditional. Returns the stamp of the written file so the caller can
+/// guard its next save.
+///
+/// The model is serialized before the stamp is checked, so the check sits
+/// right before the write. The two are still separate filesystem calls with
+/// no lock between them: a writer that lands in that window is overwritten
+/// rather than reported. The returned stamp is taken from the bytes written,
+/// not re-read, so such a write is at least caught by the next save.
+pub fn write_threat_model_guarded(
+    path: &Path,
+    model: &ThreatModel,
+    expected: Option<&FileStamp>,
+) -> Result<FileStamp, ThreatForgeError> {
+    let content = threat_model_contents(model)?;
+    create_parent_dir(path)?;
+    if let Some(expected) = expected {
+        ensure_unchanged(path, expected)?;
+    }
+    write_contents(path, &content)?;
+    Ok(FileStamp::from_contents(
+        content.as_bytes(),
+        modified_ms(path),
+    ))
+}
+
+/// A model's `.thf` text, with the header comment.
+fn threat_model_contents(model: &ThreatModel) -> Result<String, ThreatForgeError> {
     let yaml =
         serde_yaml::to_string(model).map_err(|e| ThreatForgeError::YamlSerialize { source: e })?;
-
-    let content = format!(
+    Ok(format!(
         "# ThreatForge Threat Model\n# Generated by ThreatForge v{}\n{}",
         env!("CARGO_PKG_VERSION"),
         yaml
-    );
+    ))
+}
 
-    // Ensure parent directory exists
+fn create_parent_dir(path: &Path) -> Result<(), ThreatForgeError> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| ThreatForgeError::FileWrite {
             path: parent.display().to_string(),
             source: e,
         })?;
     }
+    Ok(())
+}
 
+fn write_contents(path: &Path, content: &str) -> Result<(), ThreatForgeError> {
     std::fs::write(path, content).map_err(|e| ThreatForgeError::FileWrite {
         path: path.display().to_string(),
         source: e,
-    })?;
-
-    Ok(())
+    })
 }
 
 /// Write a diagram layout to a JSON file
@@ -54,7 +91,7 @@ pub fn write_layout(path: &Path, layout: &DiagramLayout) -> Result<(), ThreatFor
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::file_io::read_threat_model;
+    use crate::file_io::{read_file_stamp, read_threat_model};
     use crate::models::ThreatModel;
     use tempfile::TempDir;
 
@@ -94,4 +131,31 @@ mod tests {
         assert!(content.starts_with("# ThreatForge Threat Model"));
         assert!(content.contains("# Generated by ThreatForge"));
     }
+
+    #[test]
+    fn test_guarded_write_refuses_when_the_file_changed_since_load() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.thf");
+
+        let model = ThreatModel::new("Guarded", "Test Author");
+        let loaded = write_threat_model_guarded(&path, &model, None).unwrap();
+        assert!(loaded.same_contents(&read_file_stamp(&path).unwrap()));
+
+        // Another writer (e.g. the MCP server) saves in between.
+        let mut theirs = model.clone();
+        theirs.metadata.title = "Theirs".to_string();
+        write_threat_model(&path, &theirs).unwrap();
+
+        let err = write_threat_model_guarded(&path, &model, Some(&loaded)).unwrap_err();
+        assert!(matches!(
+            err,
+            ThreatForgeError::ConcurrentModification { .. }
+        ));
+        assert_eq!(read_threat_model(&path).unwrap().metadata.title, "Theirs");
+
+        // A fresh stamp lets the save through.
+        let current = read_file_stamp(&path).unwrap();
+        write_threat_model_guarded(&path, &model, Some(&current)).unwrap();
+        assert_eq!(read_threat_model(&path).unwrap().metadata.title, "Guarded");
+    }
 }
@@ -0,0 +1,420 @@
+//! Parser for docker-compose files, producing a model skeleton.
+//!
+//! Each service becomes an element typed by its image, so `postgres` is a
+//! SQL database and `redis` a cache, and each network becomes a trust
+//! boundary holding the services attached to it. `depends_on` and `links`
+//! become data flows from the dependent service, published ports become flows
+//! from an external client, and services mounting secrets are fed from a
+//! single secret store. Flow protocols come from the target's port, then its
+//! image.
+//!
+//! Nothing is placed; the canvas lays the skeleton out, and STRIDE analysis
+//! fills in the threats. Ports bound to the loopback address are not reachable
+//! from outside and get no external flow. Dependencies on services the file
+//! does not define, and images typed by fallback, go into the
+//! [`ImportReport`].
+
+use super::report::{ImportReport, ImportedModel};
+use super::services::{
+    boundary, element, element_type_for_image, flow, protocol_for, report_unknown_image,
+    skeleton_model, DEFAULT_SERVICE_TYPE, EXTERNAL_CLIENT_NAME,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// The network a service without `networks` is attached to.
+const DEFAULT_NETWORK: &str = "default";
+
+#[derive(Debug, Error)]
+pub enum ComposeError {
+    #[error("YAML parsing error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("No services found; is this a docker-compose file?")]
+    NoServices,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComposeFile {
+    #[serde(default)]
+    name: String,
+    /// Kept as a mapping so services stay in file order.
+    #[serde(default)]
+    services: serde_yaml::Mapping,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ComposeService {
+    image: Option<String>,
+    #[serde(default)]
+    ports: Vec<ComposePort>,
+    #[serde(default)]
+    expose: Vec<ComposePort>,
+    #[serde(default)]
+    depends_on: NameList,
+    #[serde(default)]
+    links: Vec<String>,
+    #[serde(default)]
+    networks: NameList,
+    network_mode: Option<String>,
+    #[serde(default)]
+    secrets: Vec<ComposeSecretRef>,
+}
+
+/// A list of names, or a mapping keyed by them with per-name options.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NameList {
+    List(Vec<String>),
+    Map(serde_yaml::Mapping),
+}
+
+impl Default for NameList {
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+
+impl NameList {
+    fn names(&self) -> Vec<&str> {
+        match self {
+            Self::List(names) => names.iter().map(String::as_str).collect(),
+            Self::Map(map) => map.keys().filter_map(|k| k.as_str()).collect(),
+        }
+    }
+}
+
+/// A port in short (`"8080:80"`, `80`) or long (`target: 80`) syntax.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ComposePort {
+    Number(u16),
+    Short(String),
+    Long {
+        target: u16,
+        host_ip: Option<String>,
+    },
+}
+
+impl ComposePort {
+    /// The container port, and whether the port is published beyond the
+    /// host's loopback address.
+    fn container_port(&self) -> (Option<u16>, bool) {
+        match self {
+            Self::Number(port) => (Some(*port), true),
+            Self::Long { target, host_ip } => (Some(*target), !is_loopback(host_ip.as_deref())),
+            Self::Short(spec) => {
+                let spec = spec.split('/').next().unwrap_or(spec);
+                let parts: Vec<&str> = spec.rsplitn(3, ':').collect();
+                let port = parts[0].split('-').next().and_then(|p| p.parse().ok());
+                let host_ip = (parts.len() == 3).then(|| parts[2]);
+                (port, !is_loopback(host_ip))
+            }
+        }
+    }
+}
+
+/// A secret in short (`db_password`) or long (`source: db_password`) syntax.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ComposeSecretRef {
+    Short(String),
+    Long { source: String },
+}
+
+fn is_loopback(host_ip: Option<&str>) -> bool {
+    matches!(host_ip, Some("127.0.0.1" | "localhost" | "::1" | "[::1]"))
+}
+
+/// The networks a service is attached to. A service sharing another's
+/// network stack (`network_mode: service:api`) is on that service's networks;
+/// any other network mode attaches it to none.
+fn attached_networks<'a>(
+    services: &'a [(&str, ComposeService)],
+    service: &'a ComposeService,
+) -> Vec<&'a str> {
+    let mut current = service;
+    // Bounded, so a cycle of services sharing each other's stack ends.
+    for _ in 0..=services.len() {
+        let Some(mode) = current.network_mode.as_deref() else {
+            let names = current.networks.names();
+            return if names.is_empty() {
+                vec![DEFAULT_NETWORK]
+            } else {
+                names
+            };
+        };
+        let shared = mode.strip_prefix("service:");
+        match shared.and_then(|name| services.iter().find(|(n, _)| *n == name)) {
+            Some((_, owner)) => current = owner,
+            None => break,
+        }
+    }
+    Vec::new()
+}
+
+/// Parse a docker-compose file into a model skeleton.
+pub fn parse_compose(yaml: &str) -> Result<ImportedModel, ComposeError> {
+    let file: ComposeFile = serde_yaml::from_str(yaml)?;
+    let mut services = Vec::new();
+    for (name, service) in &file.services {
+        let Some(name) = name.as_str() else { continue };
+        // A service with no keys at all is `null`.
+        let service: ComposeService = if service.is_null() {
+            ComposeService::default()
+        } else {
+            serde_yaml::from_value(service.clone())?
+        };
+        services.push((name, service));
+    }
+    if services.is_empty() {
+        return Err(ComposeError::NoServices);
+    }
+
+    let mut report = ImportReport::default();
+    let mut node_counter: HashMap<String, u32> = HashMap::new();
+    let mut flow_counter: HashMap<String, u32> = HashMap::new();
+    let ids: HashMap<&str, String> = services
+        .iter()
+        .map(|(name, _)| (*name, report.unique_id(name, "service", &mut node_counter)))
+        .collect();
+
+    let mut elements = Vec::new();
+    let mut networks: Vec<(&str, Vec<String>)> = Vec::new();
+    for (name, service) in &services {
+        let attached = attached_networks(&services, service);
+        for network in &attached {
+            match networks.iter_mut().find(|(n, _)| n == network) {
+                Some((_, members)) => members.push(ids[name].clone()),
+                None => networks.push((network, vec![ids[name].clone()])),
+            }
+        }
+
+        let element_type = service
+            .image
+            .as_deref()
+            .map_or(DEFAULT_SERVICE_TYPE, element_type_for_image);
+        if element_type == DEFAULT_SERVICE_TYPE {
+            report_unknown_image(
+                &mut report,
+                format!("services.{name}.image"),
+                service.image.as_deref(),
+                element_type,
+            );
+        }
+        let mut service_element = element(
+            ids[name].clone(),
+            element_type,
+            name,
+            attached.first().copied().unwrap_or_default(),
+        );
+        service_element.technologies = service.image.iter().cloned().collect();
+        elements.push(service_element);
+    }
+
+    // The protocol each service is reached on.
+    let protocols: HashMap<&str, &str> = services
+        .iter()
+        .map(|(name, service)| {
+            let port = service
+                .ports
+                .iter()
+                .chain(&service.expose)
+                .find_map(|p| p.container_port().0);
+            (*name, protocol_for(service.image.as_deref(), port))
+        })
+        .collect();
+
+    let mut data_flows = Vec::new();
+    for (name, service) in &services {
+        let links = service
+            .links
+            .iter()
+            .map(|link| ("links", link.split(':').next().unwrap_or(link)));
+        let dependencies = service
+            .depends_on
+            .names()
+            .into_iter()
+            .map(|target| ("depends_on", target));
+        let mut targets: Vec<&str> = Vec::new();
+        for (key, target) in dependencies.chain(links) {
+            if !ids.contains_key(target) {
+                report.skip(
+                    format!("services.{name}.{key}.{target}"),
+                    "names a service the file does not define",
+                );
+            } else if target != *name && !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        for target in targets {
+            let flow_name = format!("{name} to {target}");
+            data_flows.push(flow(
+                report.unique_id(&flow_name, "flow", &mut flow_counter),
+                flow_name,
+                &ids[name],
+                &ids[target],
+                protocols[target],
+            ));
+        }
+    }
+
+    let published: Vec<(&str, u16)> = services
+        .iter()
+        .filter_map(|(name, service)| {
+            service.ports.iter().find_map(|p| match p.container_port() {
+                (Some(port), true) => Some((*name, port)),
+                _ => None,
+            })
+        })
+        .collect();
+    if !published.is_empty() {
+        let client = report.unique_id(EXTERNAL_CLIENT_NAME, "client", &mut node_counter);
+        elements.push(element(
+            client.clone(),
+            "external_entity",
+            EXTERNAL_CLIENT_NAME,
+            "external",
+        ));
+        for (name, port) in published {
+            let flow_name = format!("Requests to {name}");
+            let image = services
+                .iter()
+                .find(|(n, _)| *n == name)
+                .and_then(|(_, s)| s.image.as_deref());
+            data_flows.push(flow(
+                report.unique_id(&flow_name, "flow", &mut flow_counter),
+                flow_name,
+                &client,
+                &ids[name],
+                protocol_for(image, Some(port)),
+            ));
+        }
+    }
+
+    let secret_users: Vec<(&str, Vec<String>)> = services
+        .iter()
+        .filter(|(_, service)| !service.secrets.is_empty())
+        .map(|(name, service)| {
+            let secrets = service
+                .secrets
+                .iter()
+                .map(|s| match s {
+                    ComposeSecretRef::Short(name) | ComposeSecretRef::Long { source: name } => {
+                        name.clone()
+                    }
+                })
+                .collect();
+            (*name, secrets)
+        })
+        .collect();
+    if !secret_users.is_empty() {
+        let store = report.unique_id("Secrets", "secrets", &mut node_counter);
+        elements.push(element(store.clone(), "secret_manager", "Secrets", ""));
+        for (name, secrets) in secret_users {
+            let flow_name = format!("Secrets for {name}");
+            let mut secret_flow = flow(
+                report.unique_id(&flow_name, "flow", &mut flow_counter),
+                flow_name,
+                &store,
+                &ids[name],
+                "",
+            );
+            secret_flow.data = secrets;
+            data_flows.push(secret_flow);
+        }
+    }
+
+    let trust_boundaries = networks
+        .into_iter()
+        .map(|(network, members)| {
+            let name = format!("{network} network");
+            boundary(
+                report.unique_id(&name, "network", &mut node_counter),
+                &name,
+                members,
+            )
+        })
+        .collect();
+
+    let title = if file.name.is_empty() {
+        "Imported Threat Model"
+    } else {
+        &file.name
+    };
+    let model = skeleton_model(title, elements, data_flows, trust_boundaries);
+    Ok(ImportedModel::new(model, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::{assert_matches_pinned, stride_on_valid_import};
+
+    /// A compose file covering every construct the importer reads.
+    const FIXTURE_YAML: &str = include_str!("fixtures/compose-sample.yml");
+
+    /// The import result pinned for [`FIXTURE_YAML`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/compose-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_compose(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        assert_matches_pinned(model, FIXTURE_EXPECTED_YAML, "compose-sample.expected.yaml");
+    }
+
+    #[test]
+    fn skeleton_is_valid_and_ready_for_stride() {
+        let model = parse_compose(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        let threats = stride_on_valid_import(&model);
+        assert!(threats
+            .iter()
+            .any(|t| t.flow.as_deref() == Some("requests-to-proxy")));
+        assert!(threats.iter().any(|t| t.element.as_deref() == Some("db")));
+    }
+
+    #[test]
+    fn report_lists_unknown_dependencies_and_untyped_images() {
+        let yaml = "services:\n  api:\n    build: .\n    depends_on: [db, cache]\n  db:\n    image: postgres:16\n  Db:\n    image: acme/ledger:2\n";
+        let report = parse_compose(yaml).expect("services import").report;
+
+        assert_eq!(report.counts.elements, 3);
+        let skipped: Vec<_> = report.skipped.iter().map(|s| s.item.as_str()).collect();
+        assert_eq!(skipped, ["services.api.depends_on.cache"]);
+        let fallbacks: Vec<_> = report
+            .fallbacks
+            .iter()
+            .map(|f| (f.item.as_str(), f.source.as_str(), f.mapped_to.as_str()))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            [
+                ("services.api.image", "", "microservice"),
+                ("services.Db.image", "acme/ledger:2", "microservice"),
+            ]
+        );
+        let renames: Vec<_> = report
+            .renames
+            .iter()
+            .map(|r| (r.name.as_str(), r.id.as_str()))
+            .collect();
+        assert_eq!(renames, [("Db", "db-2")]);
+    }
+
+    #[test]
+    fn files_without_services_are_rejected() {
+        let err = parse_compose("version: '3'\nvolumes:\n  data:\n").expect_err("no services");
+        assert!(matches!(err, ComposeError::NoServices));
+
+        let err = parse_compose("services: [").expect_err("not YAML");
+        assert!(
+            err.to_string().starts_with("YAML parsing error: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+}
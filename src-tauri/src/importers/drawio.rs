@@ -0,0 +1,861 @@
+//! Parser for draw.io / diagrams.net files (`.drawio`, mxGraph XML).
+//!
+//! A file is an `mxfile` of pages, each either a plain `mxGraphModel` or the
+//! model deflated, base64-encoded and URI-escaped as the page's text, which is
+//! how draw.io saves by default. A bare `mxGraphModel` is read as one page.
+//! Each page becomes its own diagram, with the first as the main one.
+//!
+//! Shapes become elements typed by their style: the shape name and the icon
+//! of the AWS, Azure and other libraries (`shape=cylinder3`,
+//! `resIcon=mxgraph.aws4.rds`…), with anything unrecognised `generic` and
+//! free text `text`. Edges between two shapes become data flows named by
+//! their label. Containers (swimlanes, groups, shapes with children) become
+//! groups, unless drawn dashed, labelled as a boundary or zone, or an AWS
+//! account, cloud, VPC or security group, in which case they become trust
+//! boundaries; a dashed plain rectangle is a trust boundary too, and a dashed
+//! edge with no ends a line boundary. A boundary holds the elements whose
+//! centre it covers. Positions are kept, made absolute where a shape sits in a
+//! container, along with fill and stroke colours and font size, so the model
+//! opens looking like the original.
+//!
+//! Edges that do not connect two shapes, and shapes whose stencil is not
+//! recognised, go into the [`ImportReport`].
+
+use super::report::{ImportReport, ImportedModel};
+use super::services::{boundary, element, flow, skeleton_model};
+use super::tm7::{imported_diagram, local_name, MAIN_DIAGRAM_ID};
+use crate::models::layout::DEFAULT_DIAGRAM_NAME;
+use crate::models::{BoundaryShape, Group, Position, Size};
+use base64::Engine as _;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use std::collections::HashMap;
+use std::io::Read as _;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DrawioError {
+    #[error("XML parsing error: {0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("Compressed diagram could not be read: {0}")]
+    Compressed(String),
+    #[error("No diagram found; is this a draw.io file?")]
+    NoDiagrams,
+}
+
+/// Largest a compressed page may inflate to. Real diagrams stay far below
+/// it; the cap keeps a crafted file from exhausting memory.
+const MAX_INFLATED_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Element type of each shape or library icon, matched against the shape's
+/// style names, `shape`, `resIcon` and `prIcon`. Earlier entries win.
+const SHAPE_HINTS: &[(&[&str], &str)] = &[
+    (
+        &["umlactor", "mxgraph.aws4.user", "actor", "person"],
+        "external_entity",
+    ),
+    (
+        &[
+            "rds",
+            "aurora",
+            "sql_database",
+            "sql_server",
+            "mysql",
+            "postgres",
+        ],
+        "sql_database",
+    ),
+    (
+        &["dynamodb", "documentdb", "cosmos", "mongo"],
+        "nosql_database",
+    ),
+    (&["elasticache", "redis", "memcached", "cache"], "cache"),
+    (&["lambda", "function"], "serverless_function"),
+    (&["api_gateway", "api_management"], "api_gateway"),
+    (&["cloudfront", "front_door", "cdn"], "cdn"),
+    (&["load_balanc"], "load_balancer"),
+    (&["waf"], "waf"),
+    (&["firewall"], "firewall"),
+    (
+        &["cognito", "active_directory", "identity"],
+        "identity_provider",
+    ),
+    (
+        &[".s3", "simple_storage", "bucket", "blob", "storage_account"],
+        "object_storage",
+    ),
+    (&["key_management", ".kms", "key_vault"], "key_management"),
+    (&["secret"], "secret_manager"),
+    (
+        &["sqs", "simple_queue", ".mq", "service_bus", "queue"],
+        "message_queue",
+    ),
+    (
+        &["sns", "eventbridge", "event_hub", "event_grid"],
+        "event_bus",
+    ),
+    (
+        &["kinesis", "stream_analytics", "kafka"],
+        "stream_processor",
+    ),
+    (&[".eks", ".aks", "kubernetes", "k8s"], "kubernetes"),
+    (
+        &[".ecs", "fargate", "container_instance", "docker"],
+        "container",
+    ),
+    (&["mobile", "phone", "android"], "mobile_app"),
+    (&["browser"], "web_browser"),
+    (
+        &["ec2", "virtual_machine", "server", "instance"],
+        "web_server",
+    ),
+    (
+        &["cylinder", "datastore", "database", "storage"],
+        "data_store",
+    ),
+    (&["ellipse", "process"], "process"),
+];
+
+/// AWS group icons that stand for a trust boundary rather than a grouping.
+const BOUNDARY_GROUP_ICONS: &[&str] = &[
+    "group_account",
+    "group_aws_cloud",
+    "group_vpc",
+    "group_security_group",
+];
+
+/// Words that mark a labelled container as a trust boundary.
+const BOUNDARY_LABELS: &[&str] = &["boundary", "trust", "zone", "dmz"];
+
+/// One page of the file.
+#[derive(Debug, Default)]
+struct Page {
+    name: String,
+    cells: Vec<Cell>,
+    /// The deflated model, when the page was saved compressed.
+    compressed: String,
+}
+
+#[derive(Debug, Default)]
+struct Cell {
+    id: String,
+    value: String,
+    tooltip: String,
+    style: String,
+    parent: Option<String>,
+    source: Option<String>,
+    target: Option<String>,
+    vertex: bool,
+    edge: bool,
+    geometry: Geometry,
+}
+
+#[derive(Debug, Default)]
+struct Geometry {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    source_point: Option<Position>,
+    target_point: Option<Position>,
+    points: Vec<Position>,
+}
+
+/// A cell's `style`: its bare names (`ellipse`, `text`, `swimlane`) and its
+/// `key=value` pairs.
+struct Style<'a> {
+    names: Vec<&'a str>,
+    values: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Style<'a> {
+    fn parse(style: &'a str) -> Self {
+        let mut names = Vec::new();
+        let mut values = HashMap::new();
+        for part in style.split(';').filter(|part| !part.is_empty()) {
+            match part.split_once('=') {
+                Some((key, value)) => {
+                    values.insert(key, value);
+                }
+                None => names.push(part),
+            }
+        }
+        Self { names, values }
+    }
+
+    fn has_name(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    fn flag(&self, key: &str) -> bool {
+        self.values.get(key) == Some(&"1")
+    }
+
+    /// Everything that names the shape, lower case.
+    fn hint(&self) -> String {
+        let mut parts = self.names.clone();
+        parts.extend(
+            ["shape", "resIcon", "prIcon"]
+                .iter()
+                .filter_map(|key| self.values.get(key).copied()),
+        );
+        parts.join(" ").to_ascii_lowercase()
+    }
+
+    fn color(&self, key: &str) -> Option<String> {
+        self.values
+            .get(key)
+            .filter(|value| value.starts_with('#'))
+            .map(|value| value.to_string())
+    }
+
+    fn number(&self, key: &str) -> Option<f64> {
+        self.values.get(key)?.parse().ok()
+    }
+}
+
+/// What a vertex becomes.
+enum Kind {
+    Element(&'static str),
+    Boundary,
+    Group,
+    /// The label of the edge it sits on.
+    EdgeLabel,
+}
+
+pub fn parse_drawio(xml: &str) -> Result<ImportedModel, DrawioError> {
+    let mut pages = read_pages(xml)?;
+    for page in &mut pages {
+        if page.cells.is_empty() && !page.compressed.trim().is_empty() {
+            let inflated = inflate(&page.compressed)?;
+            page.cells = read_pages(&inflated)?
+                .into_iter()
+                .next()
+                .map(|inner| inner.cells)
+                .unwrap_or_default();
+        }
+    }
+    if pages.is_empty() {
+        return Err(DrawioError::NoDiagrams);
+    }
+
+    let mut report = ImportReport::default();
+    let mut id_counter: HashMap<String, u32> = HashMap::new();
+    let mut diagram_counter: HashMap<String, u32> =
+        HashMap::from([(MAIN_DIAGRAM_ID.to_string(), 1)]);
+    let mut elements = Vec::new();
+    let mut groups: Vec<Group> = Vec::new();
+    let mut boundaries = Vec::new();
+    let mut flows = Vec::new();
+    let mut diagrams = Vec::new();
+
+    for (index, page) in pages.iter().enumerate() {
+        let membership =
+            (index > 0).then(|| report.unique_id(&page.name, "diagram", &mut diagram_counter));
+        let name = if !page.name.is_empty() {
+            page.name.clone()
+        } else if index == 0 {
+            DEFAULT_DIAGRAM_NAME.to_string()
+        } else {
+            format!("Page {}", index + 1)
+        };
+        diagrams.push(imported_diagram(
+            membership.as_deref().unwrap_or(MAIN_DIAGRAM_ID),
+            name.clone(),
+        ));
+
+        let cells: HashMap<&str, &Cell> = page.cells.iter().map(|c| (c.id.as_str(), c)).collect();
+        let has_children: Vec<&str> = page
+            .cells
+            .iter()
+            .filter(|c| c.vertex)
+            .filter_map(|c| c.parent.as_deref())
+            .filter(|parent| cells.get(parent).is_some_and(|p| p.vertex))
+            .collect();
+
+        let mut cell_to_element: HashMap<&str, String> = HashMap::new();
+        let mut cell_to_group: HashMap<&str, String> = HashMap::new();
+        let mut edge_labels: HashMap<&str, Vec<String>> = HashMap::new();
+        let mut centers: Vec<(String, Position)> = Vec::new();
+        let first_boundary = boundaries.len();
+        let first_element = elements.len();
+
+        for cell in page.cells.iter().filter(|c| c.vertex) {
+            let style = Style::parse(&cell.style);
+            let label = plain_text(&cell.value, &style);
+            let kind = classify(
+                cell,
+                &style,
+                &label,
+                &cells,
+                has_children.contains(&cell.id.as_str()),
+            );
+            let origin = absolute(cell, &cells);
+            let size = Size {
+                width: cell.geometry.width,
+                height: cell.geometry.height,
+            };
+            match kind {
+                Kind::EdgeLabel => {
+                    if let Some(parent) = cell.parent.as_deref() {
+                        if !label.is_empty() {
+                            edge_labels.entry(parent).or_default().push(label);
+                        }
+                    }
+                }
+                Kind::Boundary => {
+                    let id = report.unique_id(&label, "boundary", &mut id_counter);
+                    let mut imported = boundary(id, &label, Vec::new());
+                    imported.diagram = membership.clone();
+                    imported.position = Some(origin);
+                    imported.size = Some(size);
+                    imported.fill_color = style.color("fillColor");
+                    imported.stroke_color = style.color("strokeColor");
+                    boundaries.push(imported);
+                }
+                Kind::Group => {
+                    let id = report.unique_id(&label, "group", &mut id_counter);
+                    cell_to_group.insert(&cell.id, id.clone());
+                    groups.push(Group {
+                        id,
+                        name: label,
+                        r#type: None,
+                        parent: None,
+                        description: (!cell.tooltip.is_empty()).then(|| cell.tooltip.clone()),
+                        position: Some(origin),
+                        size: Some(size),
+                        fill_color: style.color("fillColor"),
+                        stroke_color: style.color("strokeColor"),
+                        fill_opacity: None,
+                        stroke_opacity: None,
+                    });
+                }
+                Kind::Element(element_type) => {
+                    if element_type == "generic" {
+                        report.fall_back(
+                            format!("{name}.cells.{}", cell.id),
+                            style.hint(),
+                            element_type,
+                            "stencil not recognised",
+                        );
+                    }
+                    let id = report.unique_id(&label, element_type, &mut id_counter);
+                    cell_to_element.insert(&cell.id, id.clone());
+                    centers.push((
+                        id.clone(),
+                        Position {
+                            x: origin.x + size.width / 2.0,
+                            y: origin.y + size.height / 2.0,
+                        },
+                    ));
+                    let mut imported = element(id, element_type, &label, "");
+                    imported.diagram = membership.clone();
+                    imported.description = cell.tooltip.clone();
+                    imported.position = Some(origin);
+                    imported.fill_color = style.color("fillColor");
+                    imported.stroke_color = style.color("strokeColor");
+                    imported.font_size = style.number("fontSize");
+                    imported.font_weight = style
+                        .number("fontStyle")
+                        .filter(|font_style| *font_style as u32 & 1 == 1)
+                        .map(|_| "bold".to_string());
+                    elements.push(imported);
+                }
+            }
+        }
+
+        // Elements and groups sit in the nearest group above them.
+        let nearest_group = |cell: &Cell| {
+            let mut parent = cell.parent.as_deref();
+            while let Some(id) = parent {
+                if let Some(group) = cell_to_group.get(id) {
+                    return Some(group.clone());
+                }
+                parent = cells.get(id).and_then(|c| c.parent.as_deref());
+            }
+            None
+        };
+        for cell in page.cells.iter().filter(|c| c.vertex) {
+            if let Some(id) = cell_to_element.get(cell.id.as_str()) {
+                let group = nearest_group(cell);
+                if let Some(imported) = elements[first_element..].iter_mut().find(|e| &e.id == id) {
+                    imported.group = group;
+                }
+            } else if let Some(id) = cell_to_group.get(cell.id.as_str()) {
+                let parent = nearest_group(cell);
+                if let Some(imported) = groups.iter_mut().find(|g| &g.id == id) {
+                    imported.parent = parent;
+                }
+            }
+        }
+
+        for cell in page.cells.iter().filter(|c| c.edge) {
+            let style = Style::parse(&cell.style);
+            let mut label = plain_text(&cell.value, &style);
+            if label.is_empty() {
+                label = edge_labels
+                    .get(cell.id.as_str())
+                    .map(|labels| labels.join(" "))
+                    .unwrap_or_default();
+            }
+            let endpoint = |end: &Option<String>| {
+                end.as_deref()
+                    .and_then(|end| cell_to_element.get(end))
+                    .cloned()
+            };
+            match (endpoint(&cell.source), endpoint(&cell.target)) {
+                (Some(from), Some(to)) => {
+                    let number = flows.len() as u32 + 1;
+                    let mut imported = flow(format!("flow-{number}"), label, &from, &to, "");
+                    imported.flow_number = Some(number);
+                    imported.stroke_color = style.color("strokeColor");
+                    flows.push(imported);
+                }
+                _ if cell.source.is_none() && cell.target.is_none() && style.flag("dashed") => {
+                    let offset = cell
+                        .parent
+                        .as_deref()
+                        .and_then(|parent| cells.get(parent))
+                        .map_or(Position { x: 0.0, y: 0.0 }, |parent| {
+                            absolute(parent, &cells)
+                        });
+                    let points: Vec<Position> = cell
+                        .geometry
+                        .source_point
+                        .iter()
+                        .chain(&cell.geometry.points)
+                        .chain(&cell.geometry.target_point)
+                        .map(|p| Position {
+                            x: p.x + offset.x,
+                            y: p.y + offset.y,
+                        })
+                        .collect();
+                    let id = report.unique_id(&label, "boundary", &mut id_counter);
+                    let mut imported = boundary(id, &label, Vec::new());
+                    imported.diagram = membership.clone();
+                    imported.shape = Some(BoundaryShape::Line);
+                    imported.points = points;
+                    imported.stroke_color = style.color("strokeColor");
+                    boundaries.push(imported);
+                }
+                _ => {
+                    let what = if label.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" {label:?}")
+                    };
+                    report.skip(
+                        format!("{name}.cells.{}", cell.id),
+                        format!("edge{what} does not connect two shapes; containers and loose ends have no data flow"),
+                    );
+                }
+            }
+        }
+
+        // A box holds the elements whose centre it covers.
+        for imported in &mut boundaries[first_boundary..] {
+            let (Some(p), Some(s)) = (&imported.position, &imported.size) else {
+                continue;
+            };
+            imported.contains = centers
+                .iter()
+                .filter(|(_, c)| {
+                    c.x >= p.x && c.x <= p.x + s.width && c.y >= p.y && c.y <= p.y + s.height
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+        }
+    }
+
+    let mut model = skeleton_model("Imported Threat Model", elements, flows, boundaries);
+    model.groups = groups;
+    model.diagrams = diagrams;
+    Ok(ImportedModel::new(model, report))
+}
+
+fn classify(
+    cell: &Cell,
+    style: &Style<'_>,
+    label: &str,
+    cells: &HashMap<&str, &Cell>,
+    has_children: bool,
+) -> Kind {
+    if cell
+        .parent
+        .as_deref()
+        .and_then(|parent| cells.get(parent))
+        .is_some_and(|parent| parent.edge)
+    {
+        return Kind::EdgeLabel;
+    }
+    let dashed = style.flag("dashed");
+    let boundary_label = {
+        let label = label.to_lowercase();
+        BOUNDARY_LABELS.iter().any(|word| label.contains(word))
+    };
+    let boundary_icon = style
+        .values
+        .get("grIcon")
+        .is_some_and(|icon| BOUNDARY_GROUP_ICONS.iter().any(|b| icon.contains(b)));
+    let container = has_children
+        || style.flag("container")
+        || style.has_name("swimlane")
+        || style.has_name("group");
+    if container {
+        return if dashed || boundary_label || boundary_icon {
+            Kind::Boundary
+        } else {
+            Kind::Group
+        };
+    }
+    if style.has_name("text") {
+        return Kind::Element("text");
+    }
+    let hint = style.hint();
+    match SHAPE_HINTS
+        .iter()
+        .find(|(hints, _)| hints.iter().any(|h| hint.contains(h)))
+    {
+        Some((_, element_type)) => Kind::Element(element_type),
+        None if dashed || boundary_icon => Kind::Boundary,
+        None => Kind::Element("generic"),
+    }
+}
+
+/// A vertex's top-left corner on the page; children of a container are
+/// positioned relative to it.
+fn absolute(cell: &Cell, cells: &HashMap<&str, &Cell>) -> Position {
+    let mut position = Position {
+        x: cell.geometry.x,
+        y: cell.geometry.y,
+    };
+    let mut parent = cell.parent.as_deref();
+    // Bounded, so a cycle in a hand-edited file cannot hang the import.
+    for _ in 0..cells.len() {
+        let Some(container) = parent.and_then(|id| cells.get(id)).filter(|c| c.vertex) else {
+            break;
+        };
+        position.x += container.geometry.x;
+        position.y += container.geometry.y;
+        parent = container.parent.as_deref();
+    }
+    position
+}
+
+/// A label as plain text on one line: HTML labels lose their markup, and
+/// line breaks become spaces.
+fn plain_text(value: &str, style: &Style<'_>) -> String {
+    let text = if style.flag("html") {
+        let mut text = String::with_capacity(value.len());
+        let mut in_tag = false;
+        for c in value.chars() {
+            match c {
+                '<' => {
+                    in_tag = true;
+                    text.push(' ');
+                }
+                '>' if in_tag => in_tag = false,
+                _ if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        text.replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&")
+    } else {
+        value.to_string()
+    };
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Undo draw.io's page compression: base64, then raw deflate, then URI
+/// escaping. A page that inflates past [`MAX_INFLATED_BYTES`] is refused.
+fn inflate(compressed: &str) -> Result<String, DrawioError> {
+    let cleaned: String = compressed.split_whitespace().collect();
+    let deflated = base64::engine::general_purpose::STANDARD
+        .decode(cleaned)
+        .map_err(|e| DrawioError::Compressed(e.to_string()))?;
+    let mut escaped = Vec::new();
+    flate2::read::DeflateDecoder::new(deflated.as_slice())
+        .take(MAX_INFLATED_BYTES + 1)
+        .read_to_end(&mut escaped)
+        .map_err(|e| DrawioError::Compressed(e.to_string()))?;
+    if escaped.len() as u64 > MAX_INFLATED_BYTES {
+        return Err(DrawioError::Compressed(format!(
+            "page inflates to more than {} MiB",
+            MAX_INFLATED_BYTES / (1024 * 1024)
+        )));
+    }
+    let escaped = String::from_utf8(escaped).map_err(|e| DrawioError::Compressed(e.to_string()))?;
+    percent_decode(&escaped)
+}
+
+fn percent_decode(escaped: &str) -> Result<String, DrawioError> {
+    let bytes = escaped.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match byte {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|e| DrawioError::Compressed(e.to_string()))
+}
+
+/// Read every page's cells, or the compressed text standing in for them.
+fn read_pages(xml: &str) -> Result<Vec<Page>, DrawioError> {
+    let mut reader = Reader::from_str(xml);
+    let mut pages: Vec<Page> = Vec::new();
+    let mut in_diagram = false;
+    // `UserObject` and `object` wrap a cell, carrying its id and label.
+    let mut wrapper: Option<Cell> = None;
+    let mut in_geometry = false;
+
+    loop {
+        let event = reader.read_event()?;
+        let (start, empty) = match &event {
+            Event::Start(e) => (Some(e), false),
+            Event::Empty(e) => (Some(e), true),
+            _ => (None, false),
+        };
+        if let Some(e) = start {
+            match local_name(e).as_str() {
+                "diagram" => {
+                    pages.push(Page {
+                        name: attribute(e, "name").unwrap_or_default(),
+                        ..Page::default()
+                    });
+                    in_diagram = !empty;
+                }
+                "mxGraphModel" if pages.is_empty() => pages.push(Page::default()),
+                "UserObject" | "object" => {
+                    wrapper = Some(Cell {
+                        id: attribute(e, "id").unwrap_or_default(),
+                        value: attribute(e, "label").unwrap_or_default(),
+                        tooltip: attribute(e, "tooltip").unwrap_or_default(),
+                        ..Cell::default()
+                    });
+                }
+                "mxCell" => {
+                    let wrapped = wrapper.take().unwrap_or_default();
+                    let cell = Cell {
+                        id: attribute(e, "id").unwrap_or(wrapped.id),
+                        value: attribute(e, "value").unwrap_or(wrapped.value),
+                        tooltip: wrapped.tooltip,
+                        style: attribute(e, "style").unwrap_or_default(),
+                        parent: attribute(e, "parent"),
+                        source: attribute(e, "source"),
+                        target: attribute(e, "target"),
+                        vertex: attribute(e, "vertex").as_deref() == Some("1"),
+                        edge: attribute(e, "edge").as_deref() == Some("1"),
+                        geometry: Geometry::default(),
+                    };
+                    if let Some(page) = pages.last_mut() {
+                        page.cells.push(cell);
+                    }
+                }
+                "mxGeometry" => {
+                    if let Some(cell) = pages.last_mut().and_then(|p| p.cells.last_mut()) {
+                        let number = |key| attribute(e, key).and_then(|v| v.parse().ok());
+                        cell.geometry.x = number("x").unwrap_or(0.0);
+                        cell.geometry.y = number("y").unwrap_or(0.0);
+                        cell.geometry.width = number("width").unwrap_or(0.0);
+                        cell.geometry.height = number("height").unwrap_or(0.0);
+                    }
+                    in_geometry = !empty;
+                }
+                "mxPoint" if in_geometry => {
+                    if let Some(cell) = pages.last_mut().and_then(|p| p.cells.last_mut()) {
+                        let number = |key| attribute(e, key).and_then(|v| v.parse().ok());
+                        let point = Position {
+                            x: number("x").unwrap_or(0.0),
+                            y: number("y").unwrap_or(0.0),
+                        };
+                        match attribute(e, "as").as_deref() {
+                            Some("sourcePoint") => cell.geometry.source_point = Some(point),
+                            Some("targetPoint") => cell.geometry.target_point = Some(point),
+                            // Waypoints, inside `<Array as="points">`.
+                            None => cell.geometry.points.push(point),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match event {
+            Event::End(e) => match String::from_utf8_lossy(e.local_name().as_ref()).as_ref() {
+                "diagram" => in_diagram = false,
+                "mxGeometry" => in_geometry = false,
+                "UserObject" | "object" => wrapper = None,
+                _ => {}
+            },
+            Event::Text(e) if in_diagram => {
+                if let Some(page) = pages.last_mut() {
+                    page.compressed.push_str(&String::from_utf8_lossy(&e));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(pages)
+}
+
+fn attribute(e: &BytesStart<'_>, name: &str) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == name.as_bytes())
+        .and_then(|attr| {
+            attr.normalized_value(XmlVersion::Implicit1_0)
+                .ok()
+                .map(|value| value.into_owned())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::assert_matches_pinned;
+
+    /// A two-page file: the first page plain, with AWS icons, a DFD cylinder
+    /// and actor, a VPC group, a dashed boundary box and a swimlane holding a
+    /// nested container; the second compressed, with a line boundary.
+    const FIXTURE_XML: &str = include_str!("fixtures/drawio-sample.drawio");
+
+    /// The import result pinned for [`FIXTURE_XML`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/drawio-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_drawio(FIXTURE_XML)
+            .expect("fixture should import")
+            .model;
+        assert_matches_pinned(model, FIXTURE_EXPECTED_YAML, "drawio-sample.expected.yaml");
+    }
+
+    #[test]
+    fn compressed_pages_read_like_plain_ones() {
+        let imported = parse_drawio(FIXTURE_XML).expect("fixture should import");
+        crate::file_io::validate_references(&imported.model).expect("references resolve");
+
+        let second: Vec<&str> = imported
+            .model
+            .elements
+            .iter()
+            .filter(|e| e.diagram.is_some())
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(second, ["Admin", "Admin Console"]);
+        let items: Vec<&str> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|skipped| skipped.item.as_str())
+            .collect();
+        assert_eq!(items, ["Production.cells.e9"]);
+        let fallbacks: Vec<&str> = imported
+            .report
+            .fallbacks
+            .iter()
+            .map(|fallback| fallback.item.as_str())
+            .collect();
+        assert_eq!(fallbacks, ["Production.cells.pay", "Production.cells.api"]);
+        assert_eq!(imported.report.counts.diagrams, 2);
+    }
+
+    #[test]
+    fn edges_missing_an_end_are_skipped() {
+        let xml = r#"<mxfile><diagram name="Page-1"><mxGraphModel><root>
+            <mxCell id="0" />
+            <mxCell id="1" parent="0" />
+            <mxCell id="a" value="App" style="rounded=0;" vertex="1" parent="1">
+              <mxGeometry x="0" y="0" width="120" height="60" as="geometry" />
+            </mxCell>
+            <mxCell id="b" value="Store" style="shape=cylinder3;" vertex="1" parent="1">
+              <mxGeometry x="300" y="0" width="80" height="100" as="geometry" />
+            </mxCell>
+            <mxCell id="e1" value="Writes" edge="1" parent="1" source="a" target="b">
+              <mxGeometry relative="1" as="geometry" />
+            </mxCell>
+            <mxCell id="e2" value="Reads" edge="1" parent="1" source="b">
+              <mxGeometry relative="1" as="geometry" />
+            </mxCell>
+            <mxCell id="e3" edge="1" parent="1" target="a">
+              <mxGeometry relative="1" as="geometry" />
+            </mxCell>
+            <mxCell id="e4" edge="1" parent="1">
+              <mxGeometry relative="1" as="geometry" />
+            </mxCell>
+          </root></mxGraphModel></diagram></mxfile>"#;
+        let imported = parse_drawio(xml).expect("diagram should import");
+        crate::file_io::validate_references(&imported.model).expect("references resolve");
+
+        assert_eq!(imported.model.data_flows.len(), 1);
+        assert_eq!(imported.model.data_flows[0].name, "Writes");
+        assert!(imported.model.trust_boundaries.is_empty());
+        let skipped: Vec<_> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|s| s.item.as_str())
+            .collect();
+        assert_eq!(
+            skipped,
+            ["Page-1.cells.e2", "Page-1.cells.e3", "Page-1.cells.e4"]
+        );
+        assert!(imported.report.skipped[0]
+            .reason
+            .starts_with("edge \"Reads\" "));
+    }
+
+    #[test]
+    fn non_diagrams_are_rejected() {
+        let err = parse_drawio("<svg/>").expect_err("no pages");
+        assert!(matches!(err, DrawioError::NoDiagrams));
+
+        let err = parse_drawio(r#"<mxfile><diagram name="x">not base64!</diagram></mxfile>"#)
+            .expect_err("garbage compressed page");
+        assert!(
+            err.to_string()
+                .starts_with("Compressed diagram could not be read: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+
+    #[test]
+    fn pages_that_inflate_past_the_cap_are_rejected() {
+        use flate2::write::DeflateEncoder;
+        use std::io::Write as _;
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder
+            .write_all(&vec![b'a'; MAX_INFLATED_BYTES as usize + 1])
+            .unwrap();
+        let page = base64::engine::general_purpose::STANDARD.encode(encoder.finish().unwrap());
+        let file = format!(r#"<mxfile><diagram name="Bomb">{page}</diagram></mxfile>"#);
+
+        let err = parse_drawio(&file).expect_err("oversized page");
+        assert!(
+            matches!(err, DrawioError::Compressed(ref reason) if reason.contains("more than 16 MiB")),
+            "got: {err}"
+        );
+    }
+}
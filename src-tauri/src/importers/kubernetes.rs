@@ -0,0 +1,728 @@
+//! Parser for Kubernetes manifests, producing a model skeleton.
+//!
+//! Reads multi-document YAML as written by hand, by `helm template` or by
+//! `kubectl get -o yaml` (a `List`). Each workload (Deployment, StatefulSet,
+//! DaemonSet, Job, CronJob or bare Pod) becomes an element typed by its first
+//! container's image, and each Ingress a load balancer. Namespaces become
+//! trust boundaries, and so does every NetworkPolicy that selects pods by
+//! label, nested inside its namespace.
+//!
+//! Data flows come from what the manifests say about traffic: an external
+//! client reaches each Ingress and each `LoadBalancer` or `NodePort`
+//! Service, an Ingress reaches the workloads behind its backends, and a
+//! workload whose environment names a Service reaches the workloads that
+//! Service selects. Secrets a workload mounts or reads into its environment
+//! flow from one secret store per namespace. Other kinds, and Ingress
+//! backends naming Services the manifests do not define, are listed in the
+//! [`ImportReport`].
+
+use super::report::{ImportReport, ImportedModel};
+use super::services::{
+    boundary, element, element_type_for_image, flow, protocol_for, report_unknown_image,
+    skeleton_model, DEFAULT_SERVICE_TYPE, EXTERNAL_CLIENT_NAME,
+};
+use crate::models::{DataFlow, Element};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+/// The namespace of an object that names none.
+const DEFAULT_NAMESPACE: &str = "default";
+
+/// Element type of a run-to-completion workload whose image is not
+/// recognised.
+const BATCH_TYPE: &str = "background_worker";
+
+/// Kinds read for something other than a workload: Services and Ingresses
+/// for traffic, NetworkPolicies for boundaries, and Namespaces and Secrets,
+/// which the workloads referencing them already stand for.
+const READ_KINDS: &[&str] = &["Service", "Ingress", "NetworkPolicy", "Namespace", "Secret"];
+
+#[derive(Debug, Error)]
+pub enum KubernetesError {
+    #[error("YAML parsing error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error(
+        "No workloads found; expected a Deployment, StatefulSet, DaemonSet, Job, CronJob or Pod"
+    )]
+    NoWorkloads,
+}
+
+#[derive(Debug, Deserialize)]
+struct K8sObject {
+    #[serde(default)]
+    kind: String,
+    #[serde(default)]
+    metadata: K8sMetadata,
+    #[serde(default)]
+    spec: serde_yaml::Value,
+    /// The objects of a `List`.
+    #[serde(default)]
+    items: Vec<serde_yaml::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct K8sMetadata {
+    #[serde(default)]
+    name: String,
+    namespace: Option<String>,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+/// The `spec` of a Deployment, StatefulSet, DaemonSet or Job.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkloadSpec {
+    template: PodTemplate,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CronJobSpec {
+    job_template: CronJobTemplate,
+}
+
+#[derive(Debug, Deserialize)]
+struct CronJobTemplate {
+    spec: WorkloadSpec,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PodTemplate {
+    #[serde(default)]
+    metadata: K8sMetadata,
+    #[serde(default)]
+    spec: PodSpec,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PodSpec {
+    #[serde(default)]
+    containers: Vec<Container>,
+    #[serde(default)]
+    volumes: Vec<Volume>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Container {
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    env: Vec<EnvVar>,
+    #[serde(default)]
+    env_from: Vec<EnvFrom>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnvVar {
+    value: Option<String>,
+    value_from: Option<EnvVarSource>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnvVarSource {
+    secret_key_ref: Option<NameRef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnvFrom {
+    secret_ref: Option<NameRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NameRef {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Volume {
+    secret: Option<SecretVolume>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretVolume {
+    secret_name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ServiceSpec {
+    #[serde(default, rename = "type")]
+    service_type: String,
+    #[serde(default)]
+    selector: BTreeMap<String, String>,
+    #[serde(default)]
+    ports: Vec<ServicePort>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServicePort {
+    port: u16,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IngressSpec {
+    #[serde(default)]
+    tls: Vec<serde_yaml::Value>,
+    default_backend: Option<IngressBackend>,
+    #[serde(default)]
+    rules: Vec<IngressRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IngressRule {
+    http: Option<IngressHttp>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IngressHttp {
+    #[serde(default)]
+    paths: Vec<IngressPath>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IngressPath {
+    backend: IngressBackend,
+}
+
+#[derive(Debug, Deserialize)]
+struct IngressBackend {
+    service: Option<NameRef>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NetworkPolicySpec {
+    #[serde(default)]
+    pod_selector: LabelSelector,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LabelSelector {
+    #[serde(default)]
+    match_labels: BTreeMap<String, String>,
+}
+
+/// A workload as read from its manifest.
+struct Workload {
+    id: String,
+    name: String,
+    namespace: String,
+    labels: BTreeMap<String, String>,
+    pod: PodSpec,
+}
+
+/// A Service, resolved to the workloads it selects.
+struct Service {
+    name: String,
+    namespace: String,
+    spec: ServiceSpec,
+    targets: Vec<usize>,
+}
+
+/// Parse Kubernetes manifests into a model skeleton.
+pub fn parse_kubernetes(yaml: &str) -> Result<ImportedModel, KubernetesError> {
+    let mut objects = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(yaml) {
+        let value = serde_yaml::Value::deserialize(document)?;
+        // Empty documents between `---` separators are `null`.
+        if value.is_null() {
+            continue;
+        }
+        let object: K8sObject = serde_yaml::from_value(value)?;
+        if object.kind == "List" {
+            for item in &object.items {
+                objects.push(serde_yaml::from_value::<K8sObject>(item.clone())?);
+            }
+        } else {
+            objects.push(object);
+        }
+    }
+    let namespace_of = |o: &K8sObject| {
+        o.metadata
+            .namespace
+            .clone()
+            .unwrap_or(DEFAULT_NAMESPACE.to_string())
+    };
+
+    let mut report = ImportReport::default();
+    let mut node_counter: HashMap<String, u32> = HashMap::new();
+    let mut flow_counter: HashMap<String, u32> = HashMap::new();
+
+    let mut workloads: Vec<Workload> = Vec::new();
+    let mut elements: Vec<Element> = Vec::new();
+    for object in &objects {
+        let template = match object.kind.as_str() {
+            "Deployment" | "StatefulSet" | "DaemonSet" | "ReplicaSet" | "Job" => {
+                serde_yaml::from_value::<WorkloadSpec>(object.spec.clone())?.template
+            }
+            "CronJob" => {
+                serde_yaml::from_value::<CronJobSpec>(object.spec.clone())?
+                    .job_template
+                    .spec
+                    .template
+            }
+            "Pod" => PodTemplate {
+                metadata: K8sMetadata {
+                    labels: object.metadata.labels.clone(),
+                    ..K8sMetadata::default()
+                },
+                spec: serde_yaml::from_value(object.spec.clone())?,
+            },
+            kind => {
+                if !READ_KINDS.contains(&kind) {
+                    report.skip(
+                        format!("{kind}/{}", object.metadata.name),
+                        "kind has no counterpart in the model",
+                    );
+                }
+                continue;
+            }
+        };
+        let namespace = namespace_of(object);
+        let id = report.unique_id(&object.metadata.name, "workload", &mut node_counter);
+        let image = template.spec.containers.first().map(|c| c.image.as_str());
+        let batch = matches!(object.kind.as_str(), "Job" | "CronJob");
+        let element_type = match image.map(element_type_for_image) {
+            Some(element_type) if element_type != DEFAULT_SERVICE_TYPE => element_type,
+            _ if batch => BATCH_TYPE,
+            _ => DEFAULT_SERVICE_TYPE,
+        };
+        if element_type == DEFAULT_SERVICE_TYPE || element_type == BATCH_TYPE {
+            report_unknown_image(
+                &mut report,
+                format!(
+                    "{}/{}.containers[0].image",
+                    object.kind, object.metadata.name
+                ),
+                image.filter(|image| !image.is_empty()),
+                element_type,
+            );
+        }
+        let mut workload_element =
+            element(id.clone(), element_type, &object.metadata.name, &namespace);
+        workload_element.technologies = template
+            .spec
+            .containers
+            .iter()
+            .map(|c| c.image.clone())
+            .filter(|image| !image.is_empty())
+            .collect();
+        elements.push(workload_element);
+        workloads.push(Workload {
+            id,
+            name: object.metadata.name.clone(),
+            namespace,
+            labels: template.metadata.labels,
+            pod: template.spec,
+        });
+    }
+    if workloads.is_empty() {
+        return Err(KubernetesError::NoWorkloads);
+    }
+
+    let selects = |selector: &BTreeMap<String, String>, workload: &Workload| {
+        !selector.is_empty()
+            && selector
+                .iter()
+                .all(|(key, value)| workload.labels.get(key) == Some(value))
+    };
+    let mut services = Vec::new();
+    for object in objects.iter().filter(|o| o.kind == "Service") {
+        let spec: ServiceSpec = serde_yaml::from_value(object.spec.clone())?;
+        let namespace = namespace_of(object);
+        let targets = workloads
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| w.namespace == namespace && selects(&spec.selector, w))
+            .map(|(index, _)| index)
+            .collect();
+        services.push(Service {
+            name: object.metadata.name.clone(),
+            namespace,
+            spec,
+            targets,
+        });
+    }
+    let find_service = |name: &str, namespace: &str| {
+        services
+            .iter()
+            .find(|s| s.name == name && s.namespace == namespace)
+    };
+
+    let mut data_flows: Vec<DataFlow> = Vec::new();
+    let mut push_flow = |report: &mut ImportReport,
+                         flows: &mut Vec<DataFlow>,
+                         flow_name: String,
+                         from: &str,
+                         to: &str,
+                         protocol: &str| {
+        if !flows.iter().any(|f| f.from == from && f.to == to) {
+            flows.push(flow(
+                report.unique_id(&flow_name, "flow", &mut flow_counter),
+                flow_name,
+                from,
+                to,
+                protocol,
+            ));
+        }
+    };
+    // The port named in a connection string, else the Service's first.
+    let protocol_to = |service: &Service, target: usize, port: Option<u16>| {
+        let image = workloads[target]
+            .pod
+            .containers
+            .first()
+            .map(|c| c.image.as_str());
+        protocol_for(image, port.or(service.spec.ports.first().map(|p| p.port)))
+    };
+
+    // Calls between workloads, found by Service host names in the environment.
+    for workload in &workloads {
+        let values = workload
+            .pod
+            .containers
+            .iter()
+            .flat_map(|c| &c.env)
+            .filter_map(|e| e.value.as_deref());
+        for value in values {
+            for (host, port) in host_names(value) {
+                let mut labels = host.split('.');
+                let Some(name) = labels.next() else { continue };
+                let namespace = labels.next().unwrap_or(&workload.namespace);
+                let Some(service) = find_service(name, namespace) else {
+                    continue;
+                };
+                for &target in &service.targets {
+                    if workloads[target].id != workload.id {
+                        push_flow(
+                            &mut report,
+                            &mut data_flows,
+                            format!("{} to {}", workload.name, workloads[target].name),
+                            &workload.id,
+                            &workloads[target].id,
+                            protocol_to(service, target, port),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Traffic from outside the cluster.
+    let mut external: Vec<(String, String, &str)> = Vec::new();
+    let mut ingress_ids: Vec<(String, String)> = Vec::new();
+    let mut ingress_flows: Vec<(String, &str, usize)> = Vec::new();
+    for object in objects.iter().filter(|o| o.kind == "Ingress") {
+        let spec: IngressSpec = serde_yaml::from_value(object.spec.clone())?;
+        let namespace = namespace_of(object);
+        let id = report.unique_id(&object.metadata.name, "ingress", &mut node_counter);
+        elements.push(element(
+            id.clone(),
+            "load_balancer",
+            &object.metadata.name,
+            &namespace,
+        ));
+        let protocol = if spec.tls.is_empty() { "HTTP" } else { "HTTPS" };
+        external.push((id.clone(), object.metadata.name.clone(), protocol));
+        let backends = spec
+            .rules
+            .iter()
+            .filter_map(|r| r.http.as_ref())
+            .flat_map(|http| http.paths.iter().map(|p| &p.backend))
+            .chain(&spec.default_backend)
+            .filter_map(|b| b.service.as_ref());
+        for backend in backends {
+            let Some(service) = find_service(&backend.name, &namespace) else {
+                report.skip(
+                    format!("Ingress/{}.backend.{}", object.metadata.name, backend.name),
+                    "names a Service the manifests do not define",
+                );
+                continue;
+            };
+            for &target in &service.targets {
+                ingress_flows.push((id.clone(), &object.metadata.name, target));
+            }
+        }
+        ingress_ids.push((id, namespace));
+    }
+    for service in &services {
+        if matches!(
+            service.spec.service_type.as_str(),
+            "LoadBalancer" | "NodePort"
+        ) {
+            for &target in &service.targets {
+                let workload = &workloads[target];
+                external.push((
+                    workload.id.clone(),
+                    workload.name.clone(),
+                    protocol_to(service, target, None),
+                ));
+            }
+        }
+    }
+    // Ingresses route HTTP by definition.
+    for (ingress, name, target) in &ingress_flows {
+        let workload = &workloads[*target];
+        push_flow(
+            &mut report,
+            &mut data_flows,
+            format!("{name} to {}", workload.name),
+            ingress,
+            &workload.id,
+            "HTTP",
+        );
+    }
+    if !external.is_empty() {
+        let client = report.unique_id(EXTERNAL_CLIENT_NAME, "client", &mut node_counter);
+        elements.push(element(
+            client.clone(),
+            "external_entity",
+            EXTERNAL_CLIENT_NAME,
+            "external",
+        ));
+        for (target, name, protocol) in &external {
+            push_flow(
+                &mut report,
+                &mut data_flows,
+                format!("Requests to {name}"),
+                &client,
+                target,
+                protocol,
+            );
+        }
+    }
+
+    // Namespaces, in the order their first workload appears.
+    let mut namespaces: Vec<(&str, Vec<String>)> = Vec::new();
+    for (id, namespace) in workloads
+        .iter()
+        .map(|w| (&w.id, w.namespace.as_str()))
+        .chain(ingress_ids.iter().map(|(id, ns)| (id, ns.as_str())))
+    {
+        match namespaces.iter_mut().find(|(n, _)| *n == namespace) {
+            Some((_, members)) => members.push(id.clone()),
+            None => namespaces.push((namespace, vec![id.clone()])),
+        }
+    }
+
+    // One secret store per namespace, feeding each workload its secrets.
+    for (namespace, members) in &mut namespaces {
+        let mut store = None;
+        for workload in workloads.iter().filter(|w| w.namespace == *namespace) {
+            let secrets = secret_names(&workload.pod);
+            if secrets.is_empty() {
+                continue;
+            }
+            let store = store.get_or_insert_with(|| {
+                let id = report.unique_id(
+                    &format!("{namespace} secrets"),
+                    "secrets",
+                    &mut node_counter,
+                );
+                elements.push(element(id.clone(), "secret_manager", "Secrets", namespace));
+                members.push(id.clone());
+                id
+            });
+            let flow_name = format!("Secrets for {}", workload.name);
+            let mut secret_flow = flow(
+                report.unique_id(&flow_name, "flow", &mut flow_counter),
+                flow_name,
+                store,
+                &workload.id,
+                "",
+            );
+            secret_flow.data = secrets;
+            data_flows.push(secret_flow);
+        }
+    }
+
+    let mut trust_boundaries: Vec<_> = namespaces
+        .into_iter()
+        .map(|(namespace, members)| {
+            let name = format!("{namespace} namespace");
+            boundary(
+                report.unique_id(&name, "namespace", &mut node_counter),
+                &name,
+                members,
+            )
+        })
+        .collect();
+    for object in objects.iter().filter(|o| o.kind == "NetworkPolicy") {
+        let spec: NetworkPolicySpec = serde_yaml::from_value(object.spec.clone())?;
+        let namespace = namespace_of(object);
+        // An empty selector covers the whole namespace, which already is one.
+        let members: Vec<String> = workloads
+            .iter()
+            .filter(|w| w.namespace == namespace && selects(&spec.pod_selector.match_labels, w))
+            .map(|w| w.id.clone())
+            .collect();
+        if !members.is_empty() {
+            trust_boundaries.push(boundary(
+                report.unique_id(&object.metadata.name, "policy", &mut node_counter),
+                &object.metadata.name,
+                members,
+            ));
+        }
+    }
+
+    let model = skeleton_model(
+        "Imported Threat Model",
+        elements,
+        data_flows,
+        trust_boundaries,
+    );
+    Ok(ImportedModel::new(model, report))
+}
+
+/// The words of an environment value that could be host names, each with
+/// the port written after it, as in `postgres://db.data:5432/app`.
+fn host_names(value: &str) -> Vec<(&str, Option<u16>)> {
+    let is_host_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '.';
+    let mut hosts = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find(is_host_char) {
+        rest = &rest[start..];
+        let end = rest.find(|c| !is_host_char(c)).unwrap_or(rest.len());
+        let (host, mut after) = rest.split_at(end);
+        let mut port = None;
+        if let Some(digits) = after.strip_prefix(':') {
+            let len = digits
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(digits.len());
+            port = digits[..len].parse().ok();
+            // The port is not a host name of its own.
+            if port.is_some() {
+                after = &digits[len..];
+            }
+        }
+        hosts.push((host, port));
+        rest = after;
+    }
+    hosts
+}
+
+/// The Secrets a pod mounts or reads into its environment, in the order the
+/// pod spec names them.
+fn secret_names(pod: &PodSpec) -> Vec<String> {
+    let env = pod.containers.iter().flat_map(|c| {
+        c.env
+            .iter()
+            .filter_map(|e| e.value_from.as_ref()?.secret_key_ref.as_ref())
+            .chain(c.env_from.iter().filter_map(|e| e.secret_ref.as_ref()))
+            .map(|r| r.name.as_str())
+    });
+    let volumes = pod
+        .volumes
+        .iter()
+        .filter_map(|v| v.secret.as_ref())
+        .map(|s| s.secret_name.as_str());
+    let mut names: Vec<String> = Vec::new();
+    for name in env.chain(volumes) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::{assert_matches_pinned, stride_on_valid_import};
+
+    /// Manifests covering every kind and reference the importer reads.
+    const FIXTURE_YAML: &str = include_str!("fixtures/kubernetes-sample.yaml");
+
+    /// The import result pinned for [`FIXTURE_YAML`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/kubernetes-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_kubernetes(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        assert_matches_pinned(
+            model,
+            FIXTURE_EXPECTED_YAML,
+            "kubernetes-sample.expected.yaml",
+        );
+    }
+
+    #[test]
+    fn skeleton_is_valid_and_ready_for_stride() {
+        let model = parse_kubernetes(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        let threats = stride_on_valid_import(&model);
+        assert!(threats
+            .iter()
+            .any(|t| t.flow.as_deref() == Some("requests-to-storefront")));
+    }
+
+    #[test]
+    fn report_lists_ignored_kinds_and_unknown_backends() {
+        let yaml = FIXTURE_YAML.replace(
+            "                name: api\n",
+            "                name: apii\n",
+        ) + "---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: shop-config\n";
+        let report = parse_kubernetes(&yaml)
+            .expect("fixture should import")
+            .report;
+
+        let skipped: Vec<_> = report.skipped.iter().map(|s| s.item.as_str()).collect();
+        assert_eq!(
+            skipped,
+            ["ConfigMap/shop-config", "Ingress/storefront.backend.apii"]
+        );
+        let fallbacks: Vec<_> = report
+            .fallbacks
+            .iter()
+            .map(|f| (f.item.as_str(), f.mapped_to.as_str()))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            [
+                ("Deployment/web.containers[0].image", "microservice"),
+                ("Deployment/api.containers[0].image", "microservice"),
+                ("CronJob/reports.containers[0].image", "background_worker"),
+            ]
+        );
+    }
+
+    #[test]
+    fn host_names_carry_the_port_written_after_them() {
+        assert_eq!(
+            host_names("postgres://app@db.data:5432/app?sslmode=require"),
+            [
+                ("postgres", None),
+                ("app", None),
+                ("db.data", Some(5432)),
+                ("app", None),
+                ("sslmode", None),
+                ("require", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn manifests_without_workloads_are_rejected() {
+        let service = "apiVersion: v1\nkind: Service\nmetadata:\n  name: web\n";
+        let err = parse_kubernetes(service).expect_err("a lone Service has no workload");
+        assert!(matches!(err, KubernetesError::NoWorkloads));
+
+        let err = parse_kubernetes("kind: [").expect_err("not YAML");
+        assert!(
+            err.to_string().starts_with("YAML parsing error: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+}
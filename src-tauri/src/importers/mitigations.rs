@@ -0,0 +1,292 @@
+//! Mitigation updates read back from a threat register CSV.
+//!
+//! Risk managers triage the register exported by
+//! [`crate::exporters::threat_register`] in a spreadsheet and hand it back.
+//! Rather than a model of its own, the CSV yields the new mitigation of each
+//! threat it changes, matched by threat id; the frontend applies them to the
+//! open model as one undoable edit.
+//!
+//! Columns are found by their header, so they may be reordered, removed or
+//! joined by others. The `ID` column is required, and at least one of
+//! `Mitigation status` and `Mitigation`; headers match whatever their case or
+//! separators (`mitigation_status` works too). A status may be written as the
+//! export labels it (`In progress`) or as the `.thf` file does
+//! (`in_progress`). A blank status keeps the threat's current one, or makes a
+//! new mitigation `not_started`; a blank `Mitigation` cell clears the text.
+//! Rows whose id matches no threat, or whose status is not recognised, are
+//! reported and left out. Files saved by spreadsheets with a byte-order mark
+//! or with semicolons between fields are read as well.
+
+use super::report::SkippedItem;
+use crate::models::{Mitigation, MitigationStatus, ThreatModel};
+use serde::Serialize;
+use std::collections::HashSet;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MitigationCsvError {
+    #[error("CSV parsing error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("No ID column; export the threat register to see the expected columns")]
+    MissingIdColumn,
+    #[error("No Mitigation status or Mitigation column to import")]
+    NoMitigationColumns,
+}
+
+/// What a mitigation CSV changes in a model.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct MitigationImport {
+    /// The new mitigation of each threat the CSV changes, in row order. When
+    /// several rows name one threat, the last one wins.
+    pub updates: Vec<MitigationUpdate>,
+    /// Rows that matched a threat but left its mitigation as it was.
+    pub unchanged: usize,
+    /// Rows left out, by CSV line, with the reason.
+    pub skipped: Vec<SkippedItem>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MitigationUpdate {
+    pub threat_id: String,
+    pub mitigation: Mitigation,
+}
+
+/// Read mitigation updates for `model` from a threat register CSV.
+pub fn parse_mitigation_csv(
+    model: &ThreatModel,
+    contents: &str,
+) -> Result<MitigationImport, MitigationCsvError> {
+    let contents = contents.trim_start_matches('\u{feff}');
+    let header_line = contents.lines().next().unwrap_or_default();
+    let delimiter = if !header_line.contains(',') && header_line.contains(';') {
+        b';'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let headers: Vec<String> = reader.headers()?.iter().map(normalize).collect();
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+    let id_column = column(&["id", "threat_id"]).ok_or(MitigationCsvError::MissingIdColumn)?;
+    let status_column = column(&["mitigation_status", "status"]);
+    let text_column = column(&["mitigation", "mitigation_description"]);
+    if status_column.is_none() && text_column.is_none() {
+        return Err(MitigationCsvError::NoMitigationColumns);
+    }
+
+    let mut import = MitigationImport::default();
+    let mut matched = HashSet::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = format!(
+            "line {}",
+            record.position().map_or(0, |position| position.line())
+        );
+        let cell = |column: Option<usize>| column.and_then(|c| record.get(c)).map(str::trim);
+
+        let id = cell(Some(id_column)).unwrap_or_default();
+        if id.is_empty() && record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        }
+        let Some(threat) = model.threats.iter().find(|t| t.id == id) else {
+            import.skip(line, format!("no threat with id '{id}'"));
+            continue;
+        };
+        let status = match cell(status_column).filter(|s| !s.is_empty()) {
+            None => None,
+            Some(status) => match parse_status(status) {
+                Some(status) => Some(status),
+                None => {
+                    import.skip(line, format!("mitigation status '{status}' not recognised"));
+                    continue;
+                }
+            },
+        };
+
+        // Earlier rows for the same threat count as its current mitigation.
+        let current = import
+            .updates
+            .iter()
+            .find(|u| u.threat_id == id)
+            .map(|u| &u.mitigation)
+            .or(threat.mitigation.as_ref());
+        let description = match cell(text_column) {
+            Some(text) => unprotect(text).to_string(),
+            None => current.map(|m| m.description.clone()).unwrap_or_default(),
+        };
+        let updated = match (status, current) {
+            (Some(status), _) => Some(Mitigation {
+                status,
+                description,
+            }),
+            (None, Some(current)) => Some(Mitigation {
+                status: current.status.clone(),
+                description,
+            }),
+            (None, None) if !description.is_empty() => Some(Mitigation {
+                status: MitigationStatus::NotStarted,
+                description,
+            }),
+            (None, None) => None,
+        };
+
+        matched.insert(id.to_string());
+        if updated.as_ref() == threat.mitigation.as_ref() {
+            import.updates.retain(|u| u.threat_id != id);
+            continue;
+        }
+        let Some(mitigation) = updated else { continue };
+        match import.updates.iter_mut().find(|u| u.threat_id == id) {
+            Some(update) => update.mitigation = mitigation,
+            None => import.updates.push(MitigationUpdate {
+                threat_id: id.to_string(),
+                mitigation,
+            }),
+        }
+    }
+    import.unchanged = matched.len() - import.updates.len();
+    Ok(import)
+}
+
+impl MitigationImport {
+    fn skip(&mut self, item: String, reason: String) {
+        self.skipped.push(SkippedItem { item, reason });
+    }
+}
+
+/// A header or status in one spelling: lower case, words joined by `_`.
+fn normalize(value: &str) -> String {
+    value
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn parse_status(value: &str) -> Option<MitigationStatus> {
+    match normalize(value).as_str() {
+        "not_started" => Some(MitigationStatus::NotStarted),
+        "in_progress" => Some(MitigationStatus::InProgress),
+        "mitigated" => Some(MitigationStatus::Mitigated),
+        "accepted" => Some(MitigationStatus::Accepted),
+        "transferred" => Some(MitigationStatus::Transferred),
+        _ => None,
+    }
+}
+
+/// Undo the apostrophe the register export puts before text a spreadsheet
+/// would take for a formula.
+fn unprotect(text: &str) -> &str {
+    match text.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(['=', '+', '-', '@']) => rest,
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporters::threat_register::write_csv;
+
+    const FIXTURE_THF: &str = include_str!("../report/fixtures/report-sample.thf");
+
+    fn fixture() -> ThreatModel {
+        serde_yaml::from_str(FIXTURE_THF).expect("fixture should parse")
+    }
+
+    #[test]
+    fn an_unedited_export_changes_nothing() {
+        let model = fixture();
+        let import = parse_mitigation_csv(&model, &write_csv(&model)).unwrap();
+        assert!(import.updates.is_empty());
+        assert!(import.skipped.is_empty());
+        // threat-5 and threat-7 have no mitigation and an empty row for it.
+        assert_eq!(import.unchanged, 7);
+    }
+
+    #[test]
+    fn triaged_rows_update_status_and_text_by_id() {
+        let model = fixture();
+        let csv = "\u{feff}Mitigation; ID ;mitigation_status\n\
+                   Require mTLS to the ledger;threat-5;In progress\n\
+                   ;threat-1;MITIGATED\n\
+                   '=SUM(A1);threat-7;\n\
+                   Rotated keys;threat-99;mitigated\n\
+                   ;threat-3;done\n\
+                   ;;\n";
+        let import = parse_mitigation_csv(&model, csv).unwrap();
+
+        let updates: Vec<(&str, &MitigationStatus, &str)> = import
+            .updates
+            .iter()
+            .map(|u| {
+                (
+                    u.threat_id.as_str(),
+                    &u.mitigation.status,
+                    u.mitigation.description.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            updates,
+            [
+                (
+                    "threat-5",
+                    &MitigationStatus::InProgress,
+                    "Require mTLS to the ledger"
+                ),
+                ("threat-1", &MitigationStatus::Mitigated, ""),
+                ("threat-7", &MitigationStatus::NotStarted, "=SUM(A1)"),
+            ]
+        );
+        let skipped: Vec<(&str, &str)> = import
+            .skipped
+            .iter()
+            .map(|s| (s.item.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                ("line 5", "no threat with id 'threat-99'"),
+                ("line 6", "mitigation status 'done' not recognised"),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_status_column_alone_keeps_the_mitigation_text() {
+        let model = fixture();
+        let import = parse_mitigation_csv(
+            &model,
+            "id,status\nthreat-6,accepted\nthreat-6,transferred\n",
+        )
+        .unwrap();
+        // The second row puts threat-6 back as it was.
+        assert!(import.updates.is_empty());
+        assert_eq!(import.unchanged, 1);
+
+        let import = parse_mitigation_csv(&model, "id,status\nthreat-6,accepted\n").unwrap();
+        assert_eq!(
+            import.updates[0].mitigation.description,
+            "The card processor keeps the authoritative record."
+        );
+    }
+
+    #[test]
+    fn files_without_the_needed_columns_are_refused() {
+        let model = fixture();
+        assert!(matches!(
+            parse_mitigation_csv(&model, "Title,Mitigation\nx,y\n"),
+            Err(MitigationCsvError::MissingIdColumn)
+        ));
+        assert!(matches!(
+            parse_mitigation_csv(&model, "ID,Title\nthreat-1,y\n"),
+            Err(MitigationCsvError::NoMitigationColumns)
+        ));
+    }
+}
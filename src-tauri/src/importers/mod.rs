@@ -1 +1,47 @@
+pub mod compose;
+pub mod drawio;
+pub mod kubernetes;
+pub mod mitigations;
+pub mod openapi;
+pub mod otm;
+pub mod pytm;
+pub mod report;
+mod services;
+pub mod tb7;
+pub mod terraform;
+pub mod threagile;
+pub mod threat_dragon;
 pub mod tm7;
+
+/// Checks shared by the importers' and exporters' fixture tests.
+#[cfg(test)]
+pub(crate) mod fixture_tests {
+    use crate::models::{Threat, ThreatModel};
+
+    /// Pin the two clock-derived metadata dates of an imported model so it is
+    /// comparable across runs.
+    pub(crate) fn pin_dates(mut model: ThreatModel) -> ThreatModel {
+        let pinned = chrono::NaiveDate::from_ymd_opt(2000, 1, 1).expect("literal date is valid");
+        model.metadata.created = pinned;
+        model.metadata.modified = pinned;
+        model
+    }
+
+    /// Assert that `model` serializes byte-for-byte to `expected`, the result
+    /// pinned in `src/importers/fixtures/{expected_file}`, once its dates are
+    /// pinned.
+    pub(crate) fn assert_matches_pinned(model: ThreatModel, expected: &str, expected_file: &str) {
+        assert_eq!(
+            serde_yaml::to_string(&pin_dates(model)).expect("imported model should serialize"),
+            expected,
+            "import drifted from src/importers/fixtures/{expected_file}"
+        );
+    }
+
+    /// Assert that an imported model's references resolve, as they must for the
+    /// app to open it, and return the threats STRIDE suggests for it.
+    pub(crate) fn stride_on_valid_import(model: &ThreatModel) -> Vec<Threat> {
+        crate::file_io::validate_references(model).expect("imported references resolve");
+        crate::stride::analyze(model)
+    }
+}
@@ -0,0 +1,530 @@
+//! Parser for OpenAPI 3.x documents (YAML or JSON), producing an API element
+//! with its operations.
+//!
+//! The API becomes one `api_endpoint` element, inside a boundary of its own,
+//! listing every operation with the fields its requests send and its
+//! successful responses return. Callers are a single API client with one data
+//! flow per way of calling the API: anonymously, or with each set of security
+//! schemes, marked `authenticated` accordingly and carrying the names of the
+//! schemas exchanged. STRIDE analysis then checks each operation for anonymous
+//! access, a missing rate limit and sensitive fields in its responses.
+//!
+//! An operation counts as rate limited when it, its path or the document
+//! carries a rate-limit extension (`x-rate-limit`, `x-ratelimit-*`,
+//! `x-throttling-*`), or when it documents a `429` response or rate-limit
+//! response headers. Only local `$ref`s are followed; the others, and local
+//! ones that point at nothing, are listed in the [`ImportReport`] with any
+//! webhooks. Swagger 2.0 documents are rejected.
+
+use super::report::{ImportReport, ImportedModel};
+use super::services::{boundary, element, flow, skeleton_model};
+use crate::models::ApiOperation;
+use serde_yaml::Value;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OpenApiError {
+    /// JSON is read as YAML, so one parser keeps paths in file order for both.
+    #[error("OpenAPI parsing error: {0}")]
+    Parse(#[from] serde_yaml::Error),
+    #[error("Unsupported API description ({0}); only OpenAPI 3.x documents can be imported")]
+    UnsupportedVersion(String),
+    #[error("No operations found; the document's `paths` is empty")]
+    NoOperations,
+}
+
+/// Path item keys that are operations.
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Name of the element calling the API.
+const API_CLIENT_NAME: &str = "API Client";
+
+/// How deep nested schema properties are listed, which also stops recursive
+/// schemas.
+const MAX_FIELD_DEPTH: usize = 4;
+
+/// How many `$ref`s in a row are followed before giving up on a cycle.
+const MAX_REF_HOPS: usize = 16;
+
+/// The value at a local `$ref` (`#/components/schemas/Pet`), or `value` itself
+/// when it is not a reference.
+fn resolve<'a>(document: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..MAX_REF_HOPS {
+        let Some(pointer) = value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix("#/"))
+        else {
+            break;
+        };
+        value = pointer.split('/').fold(document, |node, token| {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            node.get(token.as_str()).unwrap_or(&Value::Null)
+        });
+    }
+    value
+}
+
+/// Report each distinct `$ref` under `value` that points outside the document,
+/// or at nothing inside it.
+fn report_unresolved_refs(
+    document: &Value,
+    value: &Value,
+    seen: &mut Vec<String>,
+    report: &mut ImportReport,
+) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, child) in mapping {
+                match (key.as_str(), child.as_str()) {
+                    (Some("$ref"), Some(reference)) if !seen.iter().any(|s| s == reference) => {
+                        let reason = if !reference.starts_with('#') {
+                            "only references within the document are followed; the fields it describes are not listed"
+                        } else if resolve(document, value).is_null() {
+                            "nothing in the document is at this reference; the fields it describes are not listed"
+                        } else {
+                            continue;
+                        };
+                        seen.push(reference.to_string());
+                        report.skip(reference, reason);
+                    }
+                    _ => report_unresolved_refs(document, child, seen, report),
+                }
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                report_unresolved_refs(document, item, seen, report);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A mapping key as a string; response codes are often plain numbers in YAML.
+fn key_string(key: &Value) -> Option<String> {
+    match key {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn entries(value: &Value) -> impl Iterator<Item = (String, &Value)> {
+    value
+        .as_mapping()
+        .into_iter()
+        .flatten()
+        .filter_map(|(k, v)| Some((key_string(k)?, v)))
+}
+
+/// A key with `-` and `_` removed, lower-cased, for matching extension and
+/// header names however they are spelled.
+fn normalized(key: &str) -> String {
+    key.chars()
+        .filter(|c| !matches!(c, '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn has_rate_limit_extension(value: &Value) -> bool {
+    entries(value).any(|(key, _)| {
+        let key = normalized(&key);
+        key.starts_with("xratelimit") || key.starts_with("xthrottl")
+    })
+}
+
+/// Add the dotted names of `schema`'s properties under `prefix` to `out`.
+fn schema_fields(
+    document: &Value,
+    schema: &Value,
+    prefix: &str,
+    depth: usize,
+    out: &mut Vec<String>,
+) {
+    let schema = resolve(document, schema);
+    for combinator in ["allOf", "oneOf", "anyOf"] {
+        for part in schema[combinator].as_sequence().into_iter().flatten() {
+            schema_fields(document, part, prefix, depth, out);
+        }
+    }
+    if !schema["items"].is_null() {
+        schema_fields(document, &schema["items"], prefix, depth, out);
+    }
+    for (name, property) in entries(&schema["properties"]) {
+        let field = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}.{name}")
+        };
+        if !out.contains(&field) {
+            out.push(field.clone());
+        }
+        if depth + 1 < MAX_FIELD_DEPTH {
+            schema_fields(document, property, &field, depth + 1, out);
+        }
+    }
+}
+
+/// The schema of a request body or response, from its first media type.
+fn content_schema<'a>(document: &'a Value, body: &'a Value) -> Option<&'a Value> {
+    let body = resolve(document, body);
+    entries(&body["content"])
+        .map(|(_, media)| &media["schema"])
+        .find(|schema| !schema.is_null())
+}
+
+/// The component name of a schema given by reference, or of its items.
+fn schema_name(schema: &Value) -> Option<&str> {
+    let reference = schema["$ref"]
+        .as_str()
+        .or(schema["items"]["$ref"].as_str())?;
+    reference.rsplit('/').next()
+}
+
+/// Whether an operation is called, and how: `None` for anonymous access,
+/// otherwise the security schemes of each alternative, e.g. `bearerAuth` or
+/// `apiKey and oauth2`.
+fn caller(document: &Value, operation: &Value) -> Option<Vec<String>> {
+    let requirements = operation
+        .get("security")
+        .unwrap_or(&document["security"])
+        .as_sequence()?;
+    let alternatives: Vec<Vec<String>> = requirements
+        .iter()
+        .map(|requirement| entries(requirement).map(|(scheme, _)| scheme).collect())
+        .collect();
+    // An empty requirement, or none at all, lets anyone call the operation.
+    if alternatives.is_empty() || alternatives.iter().any(Vec::is_empty) {
+        return None;
+    }
+    Some(
+        alternatives
+            .into_iter()
+            .map(|schemes| schemes.join(" and "))
+            .collect(),
+    )
+}
+
+/// Parse an OpenAPI 3.x document into an API element and its callers.
+pub fn parse_openapi(contents: &str) -> Result<ImportedModel, OpenApiError> {
+    let document: Value = serde_yaml::from_str(contents)?;
+    let version = key_string(&document["openapi"]).unwrap_or_default();
+    if !version.starts_with("3.") {
+        let found = match key_string(&document["swagger"]) {
+            Some(swagger) => format!("Swagger {swagger}"),
+            None if version.is_empty() => "no `openapi` version".to_string(),
+            None => format!("OpenAPI {version}"),
+        };
+        return Err(OpenApiError::UnsupportedVersion(found));
+    }
+
+    let title = document["info"]["title"]
+        .as_str()
+        .filter(|t| !t.is_empty())
+        .unwrap_or("API");
+    let protocol = match document["servers"][0]["url"].as_str() {
+        Some(url) if url.starts_with("http://") => "HTTP",
+        _ => "HTTPS",
+    };
+    let document_rate_limited = has_rate_limit_extension(&document);
+
+    let mut operations = Vec::new();
+    // Each way of calling the API, with the schemas exchanged that way.
+    let mut callers: Vec<(Option<Vec<String>>, Vec<String>)> = Vec::new();
+    for (path, item) in entries(&document["paths"]) {
+        let item = resolve(&document, item);
+        for method in METHODS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+
+            let mut request_fields = Vec::new();
+            let parameters = item["parameters"]
+                .as_sequence()
+                .into_iter()
+                .chain(operation["parameters"].as_sequence())
+                .flatten();
+            for parameter in parameters {
+                if let Some(name) = resolve(&document, parameter)["name"].as_str() {
+                    if !request_fields.iter().any(|f| f == name) {
+                        request_fields.push(name.to_string());
+                    }
+                }
+            }
+            let request_schema = content_schema(&document, &operation["requestBody"]);
+            if let Some(schema) = request_schema {
+                schema_fields(&document, schema, "", 0, &mut request_fields);
+            }
+
+            let mut response_fields = Vec::new();
+            let mut response_schemas = Vec::new();
+            let mut rate_limited = document_rate_limited
+                || has_rate_limit_extension(item)
+                || has_rate_limit_extension(operation);
+            for (status, response) in entries(&operation["responses"]) {
+                let response = resolve(&document, response);
+                rate_limited |= status == "429"
+                    || entries(&response["headers"])
+                        .any(|(header, _)| normalized(&header).contains("ratelimit"));
+                if !status.starts_with('2') {
+                    continue;
+                }
+                if let Some(schema) = content_schema(&document, response) {
+                    schema_fields(&document, schema, "", 0, &mut response_fields);
+                    response_schemas.push(schema);
+                }
+            }
+
+            let caller = caller(&document, operation);
+            let exchanged = request_schema
+                .into_iter()
+                .chain(response_schemas)
+                .filter_map(schema_name);
+            let schemas = match callers.iter_mut().find(|(c, _)| *c == caller) {
+                Some((_, schemas)) => schemas,
+                None => {
+                    callers.push((caller.clone(), Vec::new()));
+                    &mut callers.last_mut().expect("just pushed").1
+                }
+            };
+            for name in exchanged {
+                if !schemas.iter().any(|s| s == name) {
+                    schemas.push(name.to_string());
+                }
+            }
+
+            operations.push(ApiOperation {
+                method: method.to_ascii_uppercase(),
+                path: path.clone(),
+                summary: operation["summary"]
+                    .as_str()
+                    .or(operation["operationId"].as_str())
+                    .map(str::to_string),
+                authenticated: caller.is_some(),
+                rate_limited,
+                request_fields,
+                response_fields,
+            });
+        }
+    }
+    if operations.is_empty() {
+        return Err(OpenApiError::NoOperations);
+    }
+
+    let mut report = ImportReport::default();
+    report_unresolved_refs(&document, &document, &mut Vec::new(), &mut report);
+    for (webhook, _) in entries(&document["webhooks"]) {
+        report.skip(
+            format!("webhooks.{webhook}"),
+            "webhooks are calls the API makes, not operations it serves",
+        );
+    }
+
+    let mut node_counter: HashMap<String, u32> = HashMap::new();
+    let mut flow_counter: HashMap<String, u32> = HashMap::new();
+    let api_id = report.unique_id(title, "api", &mut node_counter);
+    let client_id = report.unique_id(API_CLIENT_NAME, "client", &mut node_counter);
+
+    let mut api = element(api_id.clone(), "api_endpoint", title, "internal");
+    api.description = document["info"]["description"]
+        .as_str()
+        .unwrap_or_default()
+        .trim_end()
+        .to_string();
+    api.technologies = vec![format!("OpenAPI {version}")];
+    api.operations = operations;
+    let elements = vec![
+        element(client_id.clone(), "api_client", API_CLIENT_NAME, "external"),
+        api,
+    ];
+
+    let data_flows = callers
+        .into_iter()
+        .map(|(caller, schemas)| {
+            let flow_name = match &caller {
+                Some(schemes) => format!("Requests with {}", schemes.join(" or ")),
+                None => "Anonymous requests".to_string(),
+            };
+            let mut caller_flow = flow(
+                report.unique_id(&flow_name, "flow", &mut flow_counter),
+                flow_name,
+                &client_id,
+                &api_id,
+                protocol,
+            );
+            caller_flow.authenticated = caller.is_some();
+            caller_flow.data = schemas;
+            caller_flow
+        })
+        .collect();
+
+    // The API's own boundary, so calls from the client cross into it.
+    let boundary_name = format!("{title} service");
+    let service = boundary(
+        report.unique_id(&boundary_name, "service", &mut node_counter),
+        &boundary_name,
+        vec![api_id],
+    );
+
+    let model = skeleton_model(title, elements, data_flows, vec![service]);
+    Ok(ImportedModel::new(model, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::{assert_matches_pinned, stride_on_valid_import};
+
+    /// A document covering every construct the importer reads: global and
+    /// per-operation security, optional auth, referenced parameters, bodies and
+    /// responses, `allOf` and arrays, and each kind of rate-limit declaration.
+    const FIXTURE_YAML: &str = include_str!("fixtures/openapi-sample.yaml");
+
+    /// The import result pinned for [`FIXTURE_YAML`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/openapi-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_openapi(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        assert_matches_pinned(model, FIXTURE_EXPECTED_YAML, "openapi-sample.expected.yaml");
+    }
+
+    #[test]
+    fn stride_flags_each_operation() {
+        let model = parse_openapi(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        let titles: Vec<String> = stride_on_valid_import(&model)
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        let has = |title: &str| titles.iter().any(|t| t == title);
+        assert!(has("Unauthenticated operation GET /pets on Pet Store"));
+        assert!(has(
+            "Unauthenticated operation GET /pets/{petId} on Pet Store"
+        ));
+        assert!(!has("Unauthenticated operation POST /pets on Pet Store"));
+        assert!(has("No rate limit on DELETE /pets/{petId} of Pet Store"));
+        assert!(!has("No rate limit on GET /users/me of Pet Store"));
+        assert!(has(
+            "Sensitive fields returned by GET /users/me on Pet Store"
+        ));
+        assert!(has("Sensitive fields returned by GET /pets on Pet Store"));
+        assert!(!has(
+            "Sensitive fields returned by DELETE /pets/{petId} on Pet Store"
+        ));
+    }
+
+    #[test]
+    fn json_documents_import_like_yaml() {
+        let json = r#"{
+          "openapi": "3.1.0",
+          "info": {"title": "Ping"},
+          "servers": [{"url": "http://localhost:8080"}],
+          "paths": {"/ping": {"get": {"responses": {"204": {"description": "pong"}}}}}
+        }"#;
+        let model = parse_openapi(json).expect("JSON should import").model;
+        assert_eq!(model.elements[1].operations[0].path, "/ping");
+        assert_eq!(model.data_flows[0].protocol, "HTTP");
+        assert!(!model.data_flows[0].authenticated);
+    }
+
+    #[test]
+    fn remote_references_and_webhooks_are_reported() {
+        let yaml = r##"
+openapi: 3.1.0
+info: {title: Pets}
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          content:
+            application/json:
+              schema: {$ref: "common.yaml#/components/schemas/Pet"}
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema: {$ref: "common.yaml#/components/schemas/Pet"}
+      responses:
+        "201": {$ref: "#/components/responses/Created"}
+webhooks:
+  newPet:
+    post:
+      responses: {"200": {description: ok}}
+components:
+  responses:
+    Created: {description: created}
+"##;
+        let report = parse_openapi(yaml).expect("document should import").report;
+        let skipped: Vec<_> = report.skipped.iter().map(|s| s.item.as_str()).collect();
+        assert_eq!(
+            skipped,
+            ["common.yaml#/components/schemas/Pet", "webhooks.newPet"]
+        );
+    }
+
+    #[test]
+    fn references_to_missing_components_are_reported() {
+        let yaml = r##"
+openapi: 3.1.0
+info: {title: Pets}
+paths:
+  /pets:
+    get:
+      parameters: [{$ref: "#/components/parameters/Missing"}]
+      responses:
+        "200":
+          content:
+            application/json:
+              schema: {$ref: "#/components/schemas/Missing"}
+        "404": {$ref: "#/components/responses/NotFound"}
+components:
+  responses:
+    NotFound: {description: not found}
+"##;
+        let imported = parse_openapi(yaml).expect("document should import");
+        let skipped: Vec<_> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|s| s.item.as_str())
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                "#/components/parameters/Missing",
+                "#/components/schemas/Missing"
+            ]
+        );
+        let operation = &imported.model.elements[1].operations[0];
+        assert_eq!(operation.path, "/pets");
+        assert!(operation.response_fields.is_empty());
+    }
+
+    #[test]
+    fn other_documents_are_rejected() {
+        let err = parse_openapi("swagger: '2.0'\npaths: {}\n").expect_err("Swagger 2.0");
+        assert_eq!(
+            err.to_string(),
+            "Unsupported API description (Swagger 2.0); only OpenAPI 3.x documents can be imported"
+        );
+
+        let err = parse_openapi("openapi: 3.0.0\npaths: {}\n").expect_err("no operations");
+        assert!(matches!(err, OpenApiError::NoOperations));
+
+        let err = parse_openapi("openapi: [").expect_err("not YAML");
+        assert!(
+            err.to_string().starts_with("OpenAPI parsing error: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Parser for Open Threat Model (OTM) documents, in YAML or JSON.
+//!
+//! OTM is the vendor-neutral format IriusRisk and StartLeft exchange. Trust
+//! zones become rectangular trust boundaries holding every component beneath
+//! them, components become elements, dataflows become data flows, and each
+//! threat instance on a component or dataflow becomes a threat whose
+//! mitigation text is gathered from the mitigations applied to it. Diagram
+//! representations become diagrams, and a component's representation on one
+//! gives its position; the first diagram is the main one.
+//!
+//! The result goes through [`validate_references`] like a `.thf` file, so a
+//! dataflow to a missing component or a representation that does not exist is
+//! rejected rather than imported half-wired. Components nested in components
+//! are flattened into their trust zone, and a threat's severity is read from
+//! its impact score. Threat instances of undefined threats, categories past a
+//! threat's first and categories that are not STRIDE go into the
+//! [`ImportReport`].
+//!
+//! The `Otm*` types are shared with [`crate::exporters::otm`].
+
+use super::report::{ImportReport, ImportedModel};
+use super::tm7::{imported_diagram, stride_category_named, MAIN_DIAGRAM_ID};
+use crate::errors::ThreatForgeError;
+use crate::file_io::validate_references;
+use crate::models::layout::DEFAULT_DIAGRAM_NAME;
+use crate::models::{
+    DataFlow, Element, Metadata, Mitigation, MitigationStatus, Position, Severity, Size,
+    StrideCategory, Threat, ThreatModel, TrustBoundary,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OtmError {
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("YAML parsing error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Unsupported OTM version {0:?}")]
+    UnsupportedVersion(String),
+    #[error("Invalid OTM model: {0}")]
+    Invalid(#[from] ThreatForgeError),
+}
+
+/// The representation `type` of a diagram; `code` and `threat-model`
+/// representations carry no positions.
+pub(crate) const DIAGRAM_REPRESENTATION: &str = "diagram";
+
+/// Lowest impact score for each severity, most severe first.
+pub(crate) const IMPACT_THRESHOLDS: [(f64, Severity); 4] = [
+    (87.5, Severity::Critical),
+    (62.5, Severity::High),
+    (37.5, Severity::Medium),
+    (12.5, Severity::Low),
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OtmDocument {
+    pub otm_version: String,
+    pub project: OtmProject,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub representations: Vec<OtmRepresentation>,
+    #[serde(default)]
+    pub trust_zones: Vec<OtmTrustZone>,
+    #[serde(default)]
+    pub components: Vec<OtmComponent>,
+    #[serde(default)]
+    pub dataflows: Vec<OtmDataflow>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub threats: Vec<OtmThreat>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mitigations: Vec<OtmMitigation>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct OtmProject {
+    pub name: String,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmRepresentation {
+    pub name: String,
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OtmTrustZone {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub risk: OtmTrustZoneRisk,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<OtmParent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub representations: Vec<OtmElementRepresentation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OtmTrustZoneRisk {
+    pub trust_rating: f64,
+}
+
+/// Exactly one of the two is set.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OtmParent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust_zone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+}
+
+/// Where a component or trust zone is drawn in one representation.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmElementRepresentation {
+    pub representation: String,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<OtmPosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<OtmSize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmComponent {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub parent: OtmParent,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub representations: Vec<OtmElementRepresentation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub threats: Vec<OtmThreatInstance>,
+    /// Free-form; carries `technologies`, `encryption` and `stores`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmDataflow {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub source: String,
+    pub destination: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub threats: Vec<OtmThreatInstance>,
+    /// Free-form; carries `protocol`, `authenticated` and `data`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmThreatInstance {
+    pub threat: String,
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mitigations: Vec<OtmMitigationInstance>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmMitigationInstance {
+    pub mitigation: String,
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmThreat {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<OtmThreatRisk>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OtmThreatRisk {
+    pub likelihood: f64,
+    pub impact: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OtmMitigation {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub risk_reduction: f64,
+}
+
+/// Parse an OTM document, YAML or JSON, into a `ThreatModel`.
+pub fn parse_otm(text: &str) -> Result<ImportedModel, OtmError> {
+    // JSON is YAML too, but not when it is indented with tabs.
+    let otm: OtmDocument = if text.trim_start().starts_with('{') {
+        serde_json::from_str(text)?
+    } else {
+        serde_yaml::from_str(text)?
+    };
+    if !otm.otm_version.starts_with("0.") {
+        return Err(OtmError::UnsupportedVersion(otm.otm_version));
+    }
+
+    let mut diagrams: Vec<_> = otm
+        .representations
+        .iter()
+        .filter(|r| r.kind == DIAGRAM_REPRESENTATION)
+        .map(|r| {
+            let mut diagram = imported_diagram(&r.id, r.name.clone());
+            diagram.description = r.description.clone();
+            diagram
+        })
+        .collect();
+    if diagrams.is_empty() {
+        diagrams.push(imported_diagram(
+            MAIN_DIAGRAM_ID,
+            DEFAULT_DIAGRAM_NAME.to_string(),
+        ));
+    }
+    let main_diagram = diagrams[0].id.clone();
+    let placement = |representations| place(&otm, &main_diagram, representations);
+
+    let zone_parents: HashMap<&str, &str> = otm
+        .trust_zones
+        .iter()
+        .filter_map(|z| Some((z.id.as_str(), z.parent.as_ref()?.trust_zone.as_deref()?)))
+        .collect();
+    let component_parents: HashMap<&str, &OtmParent> = otm
+        .components
+        .iter()
+        .map(|c| (c.id.as_str(), &c.parent))
+        .collect();
+
+    let mut elements = Vec::new();
+    let mut members: HashMap<&str, Vec<String>> = HashMap::new();
+    for component in &otm.components {
+        let zone = component_zone(&component.parent, &component_parents);
+        // Every zone the component sits in, innermost first.
+        let mut enclosing = zone;
+        for _ in 0..=otm.trust_zones.len() {
+            let Some(id) = enclosing else { break };
+            members.entry(id).or_default().push(component.id.clone());
+            enclosing = zone_parents.get(id).copied();
+        }
+
+        let placed = placement(&component.representations);
+        let strings = |key: &str| -> Vec<String> {
+            component
+                .attributes
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|s| s.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let stores = strings("stores");
+        elements.push(Element {
+            id: component.id.clone(),
+            element_type: if component.kind.is_empty() {
+                "generic".to_string()
+            } else {
+                component.kind.to_lowercase().replace('-', "_")
+            },
+            name: component.name.clone(),
+            trust_zone: zone.unwrap_or_default().to_string(),
+            layer: None,
+            group: None,
+            diagram: placed.as_ref().and_then(|(diagram, _)| diagram.clone()),
+            subtype: None,
+            icon: None,
+            description: component.description.clone().unwrap_or_default(),
+            technologies: strings("technologies"),
+            tags: component.tags.clone(),
+            stores: (!stores.is_empty()).then_some(stores),
+            encryption: component
+                .attributes
+                .get("encryption")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            operations: Vec::new(),
+            position: placed
+                .and_then(|(_, r)| r.position.as_ref())
+                .map(|p| Position { x: p.x, y: p.y }),
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+            font_size: None,
+            font_weight: None,
+        });
+    }
+
+    let trust_boundaries = otm
+        .trust_zones
+        .iter()
+        .map(|zone| {
+            let placed = placement(&zone.representations);
+            let drawn = placed.as_ref().map(|(_, r)| r);
+            TrustBoundary {
+                id: zone.id.clone(),
+                name: zone.name.clone(),
+                contains: members.remove(zone.id.as_str()).unwrap_or_default(),
+                diagram: placed.as_ref().and_then(|(diagram, _)| diagram.clone()),
+                shape: None,
+                points: Vec::new(),
+                position: drawn
+                    .and_then(|r| r.position.as_ref())
+                    .map(|p| Position { x: p.x, y: p.y }),
+                size: drawn.and_then(|r| r.size.as_ref()).map(|s| Size {
+                    width: s.width,
+                    height: s.height,
+                }),
+                fill_color: None,
+                stroke_color: None,
+                fill_opacity: None,
+                stroke_opacity: None,
+            }
+        })
+        .collect();
+
+    let data_flows = otm
+        .dataflows
+        .iter()
+        .map(|flow| {
+            let attribute = |key: &str| flow.attributes.get(key);
+            DataFlow {
+                id: flow.id.clone(),
+                flow_number: None,
+                name: flow.name.clone(),
+                from: flow.source.clone(),
+                to: flow.destination.clone(),
+                protocol: attribute("protocol")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                data: attribute("data")
+                    .and_then(|v| v.as_array())
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|s| s.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                authenticated: attribute("authenticated")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                label_offset: None,
+                source_handle: None,
+                target_handle: None,
+                stroke_color: None,
+                stroke_opacity: None,
+            }
+        })
+        .collect();
+
+    let mut report = ImportReport::default();
+    let threats = convert_threats(&otm, &mut report);
+    let model = ThreatModel {
+        version: "1.0".to_string(),
+        metadata: Metadata {
+            title: if otm.project.name.is_empty() {
+                "Imported Threat Model".to_string()
+            } else {
+                otm.project.name.clone()
+            },
+            author: otm.project.owner.clone().unwrap_or_default(),
+            created: chrono::Local::now().date_naive(),
+            modified: chrono::Local::now().date_naive(),
+            description: otm.project.description.clone().unwrap_or_default(),
+            created_by: None,
+            modified_by: None,
+            last_edit_timestamp: None,
+            threat_analysis_enabled: None,
+            settings: None,
+        },
+        layers: Vec::new(),
+        groups: Vec::new(),
+        elements,
+        data_flows,
+        relationships: Vec::new(),
+        trust_boundaries,
+        threats,
+        diagrams,
+    };
+    validate_references(&model)?;
+    Ok(ImportedModel::new(model, report))
+}
+
+/// The first representation of an item drawn on a diagram, and the diagram
+/// it puts the item on. Members of the main diagram carry no reference;
+/// code representations are not diagrams and place nothing, while unknown
+/// ones are kept for validation to reject.
+fn place<'a>(
+    otm: &OtmDocument,
+    main_diagram: &str,
+    representations: &'a [OtmElementRepresentation],
+) -> Option<(Option<String>, &'a OtmElementRepresentation)> {
+    let drawn = representations.iter().find(|r| {
+        otm.representations
+            .iter()
+            .all(|known| known.id != r.representation || known.kind == DIAGRAM_REPRESENTATION)
+    })?;
+    let diagram = (drawn.representation != main_diagram).then(|| drawn.representation.clone());
+    Some((diagram, drawn))
+}
+
+/// The trust zone a component belongs to, through any components it is
+/// nested in. The walk is bounded, so a cyclic nesting cannot hang it.
+fn component_zone<'a>(
+    parent: &'a OtmParent,
+    parents: &HashMap<&str, &'a OtmParent>,
+) -> Option<&'a str> {
+    let mut current = parent;
+    for _ in 0..=parents.len() {
+        if let Some(zone) = current.trust_zone.as_deref() {
+            return Some(zone);
+        }
+        current = parents.get(current.component.as_deref()?)?;
+    }
+    None
+}
+
+/// One threat per threat instance, then one for each threat no component or
+/// dataflow instantiates. A threat instantiated more than once gets its id
+/// suffixed from the second instance on.
+fn convert_threats(otm: &OtmDocument, report: &mut ImportReport) -> Vec<Threat> {
+    let definitions: HashMap<&str, &OtmThreat> =
+        otm.threats.iter().map(|t| (t.id.as_str(), t)).collect();
+    let mitigations: HashMap<&str, &OtmMitigation> =
+        otm.mitigations.iter().map(|m| (m.id.as_str(), m)).collect();
+    let mut instance_counts: HashMap<&str, u32> = HashMap::new();
+    let mut threats = Vec::new();
+    for definition in &otm.threats {
+        report_categories(definition, report);
+    }
+
+    let instances = otm
+        .components
+        .iter()
+        .flat_map(|c| {
+            c.threats
+                .iter()
+                .map(move |t| (format!("components.{}", c.id), Some(&c.id), None, t))
+        })
+        .chain(otm.dataflows.iter().flat_map(|f| {
+            f.threats
+                .iter()
+                .map(move |t| (format!("dataflows.{}", f.id), None, Some(&f.id), t))
+        }));
+    for (item, element, flow, instance) in instances {
+        let item = format!("{item}.threats.{}", instance.threat);
+        let Some(definition) = definitions.get(instance.threat.as_str()) else {
+            report.skip(item, "instantiates a threat the document does not define");
+            continue;
+        };
+        for applied in &instance.mitigations {
+            if !mitigations.contains_key(applied.mitigation.as_str()) {
+                report.skip(
+                    format!("{item}.mitigations.{}", applied.mitigation),
+                    "applies a mitigation the document does not define",
+                );
+            }
+        }
+        let count = instance_counts.entry(&definition.id).or_insert(0);
+        *count += 1;
+        let mut threat = convert_threat(definition);
+        if *count > 1 {
+            threat.id = format!("{}-{count}", definition.id);
+            report.rename(definition.name.as_str(), threat.id.as_str());
+        }
+        threat.element = element.cloned();
+        threat.flow = flow.cloned();
+        threat.mitigation = Some(Mitigation {
+            status: parse_state(&instance.state),
+            description: instance
+                .mitigations
+                .iter()
+                .filter_map(|m| mitigations.get(m.mitigation.as_str()))
+                .map(|m| m.description.as_deref().unwrap_or(&m.name))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        });
+        threats.push(threat);
+    }
+
+    for definition in &otm.threats {
+        if !instance_counts.contains_key(definition.id.as_str()) {
+            threats.push(convert_threat(definition));
+        }
+    }
+    threats
+}
+
+/// A threat keeps its first category; one that is not STRIDE is tampering.
+fn threat_category(definition: &OtmThreat) -> Option<StrideCategory> {
+    stride_category_named(definition.categories.first()?)
+}
+
+/// Report the categories of a threat definition that do not carry over.
+fn report_categories(definition: &OtmThreat, report: &mut ImportReport) {
+    let item = format!("threats.{}", definition.id);
+    if definition.categories.len() > 1 {
+        report.skip(
+            format!("{item}.categories"),
+            format!(
+                "a threat has one category; {} dropped",
+                definition.categories[1..].join(", ")
+            ),
+        );
+    }
+    if threat_category(definition).is_none() {
+        report.fall_back(
+            item,
+            definition.categories.first().map_or("", String::as_str),
+            StrideCategory::Tampering.as_str(),
+            "category is not a STRIDE category",
+        );
+    }
+}
+
+fn convert_threat(definition: &OtmThreat) -> Threat {
+    let impact = definition.risk.as_ref().map_or(50.0, |r| r.impact);
+    Threat {
+        id: definition.id.clone(),
+        title: definition.name.clone(),
+        category: threat_category(definition).unwrap_or(StrideCategory::Tampering),
+        element: None,
+        flow: None,
+        severity: IMPACT_THRESHOLDS
+            .iter()
+            .find(|(min, _)| impact >= *min)
+            .map_or(Severity::Info, |(_, severity)| severity.clone()),
+        description: definition.description.clone().unwrap_or_default(),
+        mitigation: None,
+    }
+}
+
+/// Map an OTM threat instance state to a mitigation status.
+fn parse_state(state: &str) -> MitigationStatus {
+    match state.to_ascii_lowercase().as_str() {
+        "mitigated" => MitigationStatus::Mitigated,
+        "partly-mitigated" | "in-progress" => MitigationStatus::InProgress,
+        "accepted" | "not-applicable" => MitigationStatus::Accepted,
+        "transferred" => MitigationStatus::Transferred,
+        _ => MitigationStatus::NotStarted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::assert_matches_pinned;
+
+    /// An OTM 0.2 document covering every construct the importer reads.
+    const FIXTURE_YAML: &str = include_str!("fixtures/otm-sample.otm.yaml");
+
+    /// The import result pinned for [`FIXTURE_YAML`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/otm-sample.expected.yaml");
+
+    fn import(text: &str) -> ThreatModel {
+        parse_otm(text).expect("fixture model should import").model
+    }
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        assert_matches_pinned(
+            import(FIXTURE_YAML),
+            FIXTURE_EXPECTED_YAML,
+            "otm-sample.expected.yaml",
+        );
+    }
+
+    #[test]
+    fn json_documents_import_like_yaml_ones() {
+        let document: serde_json::Value = serde_yaml::from_str(FIXTURE_YAML).unwrap();
+        let json = serde_json::to_string_pretty(&document)
+            .unwrap()
+            .replace("  ", "\t");
+        assert_matches_pinned(
+            import(&json),
+            FIXTURE_EXPECTED_YAML,
+            "otm-sample.expected.yaml",
+        );
+    }
+
+    #[test]
+    fn import_reports_undefined_threats_unknown_categories_and_repeats() {
+        let yaml = FIXTURE_YAML.replace("threat: ledger-tampering", "threat: ledger-tamper");
+        let report = parse_otm(&yaml).expect("fixture should import").report;
+        let skipped: Vec<_> = report.skipped.iter().map(|s| s.item.as_str()).collect();
+        assert_eq!(skipped, ["components.ledger.threats.ledger-tamper"]);
+        let fallbacks: Vec<_> = report
+            .fallbacks
+            .iter()
+            .map(|f| (f.item.as_str(), f.source.as_str(), f.mapped_to.as_str()))
+            .collect();
+        assert_eq!(fallbacks, [("threats.injection", "Injection", "Tampering")]);
+        let renames: Vec<_> = report
+            .renames
+            .iter()
+            .map(|r| (r.name.as_str(), r.id.as_str()))
+            .collect();
+        assert_eq!(
+            renames,
+            [("Callers can impersonate a shopper", "spoofed-callers-2")]
+        );
+    }
+
+    #[test]
+    fn dangling_references_fail_validation() {
+        let yaml = FIXTURE_YAML.replace("destination: ledger", "destination: ledgr");
+        let err = parse_otm(&yaml).expect_err("a dataflow to a missing component must fail");
+        assert!(matches!(
+            err,
+            OtmError::Invalid(ThreatForgeError::InvalidReference { ref field, ref reference, .. })
+                if field == "data_flows[record-payment].to" && reference == "ledgr"
+        ));
+
+        let yaml = FIXTURE_YAML.replace(
+            "representation: storage-diagram\n        id: ledger-storage",
+            "representation: archive-diagram\n        id: ledger-storage",
+        );
+        let err = parse_otm(&yaml).expect_err("an unknown representation must fail");
+        assert!(
+            err.to_string().starts_with("Invalid OTM model: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+        assert!(matches!(
+            err,
+            OtmError::Invalid(ThreatForgeError::InvalidReference { ref field, .. })
+                if field == "elements[ledger].diagram"
+        ));
+    }
+
+    #[test]
+    fn unsupported_versions_are_rejected() {
+        let yaml = FIXTURE_YAML.replace("otmVersion: 0.2.0", "otmVersion: 1.0.0");
+        let err = parse_otm(&yaml).expect_err("OTM 1.x is not supported");
+        assert!(matches!(err, OtmError::UnsupportedVersion(ref v) if v == "1.0.0"));
+    }
+}
@@ -0,0 +1,563 @@
+//! Parser for pytm models, as written by `tm.py --json`.
+//!
+//! pytm serializes its `TM` with every actor and asset under `elements`
+//! (tagged with their Python `__class__`), boundaries and dataflows as flat
+//! lists that refer to each other by name, and the findings its threat
+//! library raised. Elements become elements typed after their class,
+//! boundaries become trust boundaries holding the elements drawn in them,
+//! dataflows become data flows, and findings become threats on the element or
+//! flow they target, with the STRIDE category taken from the threat id's
+//! family (`INP`, `AC`, `DO`…) and the finding's `response` read as its
+//! mitigation status. Assumptions are appended to the model description.
+//!
+//! pytm refers to everything by name, so when two elements share one, flows
+//! and findings naming it attach to the first. Nested boundaries, controls
+//! with no counterpart, flows with a loose end, findings on anything but an
+//! element or flow, and data classifications go into the [`ImportReport`].
+
+use super::report::{ImportReport, ImportedModel};
+use super::services::{boundary, element, flow, skeleton_model};
+use super::threat_dragon::{ENCRYPTED, OUT_OF_SCOPE_TAG};
+use crate::models::{Mitigation, MitigationStatus, Severity, StrideCategory, Threat};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PytmError {
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No elements found; export the model with `python tm.py --json <file>`")]
+    NoElements,
+}
+
+/// Element type of each pytm element class. Any other class is `generic`.
+const ELEMENT_TYPES: &[(&str, &str)] = &[
+    ("Actor", "external_entity"),
+    ("ExternalEntity", "external_entity"),
+    ("Server", "web_server"),
+    ("Process", "process"),
+    ("SetOfProcesses", "process"),
+    ("Lambda", "serverless_function"),
+    ("Datastore", "data_store"),
+];
+
+/// Element type of each pytm `DatastoreType`, matched against the end of its
+/// serialized name.
+const DATASTORE_TYPES: &[(&str, &str)] = &[
+    ("SQL", "sql_database"),
+    ("FILE_SYSTEM", "file_storage"),
+    ("AWS_S3", "object_storage"),
+    ("LDAP", "identity_provider"),
+];
+
+/// Controls carried over: `isEncrypted` on elements and the authentication
+/// controls on flows. The other controls are reported.
+const ELEMENT_CONTROLS: &[&str] = &["isEncrypted"];
+const FLOW_CONTROLS: &[&str] = &["authenticatesSource", "authenticatesDestination"];
+
+/// STRIDE category of each pytm threat library family, by threat id prefix.
+/// Custom threats outside these families are tampering.
+const THREAT_FAMILIES: &[(&str, StrideCategory)] = &[
+    ("AA", StrideCategory::Spoofing),
+    ("AC", StrideCategory::ElevationOfPrivilege),
+    ("CR", StrideCategory::InformationDisclosure),
+    ("DE", StrideCategory::InformationDisclosure),
+    ("DO", StrideCategory::DenialOfService),
+    ("DR", StrideCategory::InformationDisclosure),
+    ("DS", StrideCategory::InformationDisclosure),
+    ("HA", StrideCategory::Tampering),
+    ("INP", StrideCategory::Tampering),
+    ("LB", StrideCategory::Tampering),
+    ("SC", StrideCategory::Tampering),
+];
+
+#[derive(Debug, Deserialize)]
+struct PytmModel {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    elements: Vec<PytmElement>,
+    #[serde(default)]
+    boundaries: Vec<PytmElement>,
+    #[serde(default)]
+    flows: Vec<PytmFlow>,
+    #[serde(default)]
+    findings: Vec<PytmFinding>,
+    /// Plain strings before pytm 1.3, `Assumption` objects since.
+    #[serde(default)]
+    assumptions: Vec<Value>,
+    #[serde(default)]
+    data: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PytmElement {
+    #[serde(default, rename = "__class__")]
+    class: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default, rename = "inBoundary")]
+    in_boundary: Option<String>,
+    #[serde(default = "in_scope", rename = "inScope")]
+    in_scope: bool,
+    #[serde(default, rename = "isSQL")]
+    is_sql: bool,
+    /// A datastore's `DatastoreType`.
+    #[serde(default, rename = "type")]
+    store_type: Value,
+    #[serde(default)]
+    controls: Map<String, Value>,
+}
+
+fn in_scope() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct PytmFlow {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    source: String,
+    #[serde(default)]
+    sink: String,
+    #[serde(default)]
+    protocol: String,
+    /// Data names, or serialized `Data` objects.
+    #[serde(default)]
+    data: Vec<Value>,
+    /// pytm's default is -1, meaning unordered.
+    #[serde(default)]
+    order: Option<i64>,
+    #[serde(default)]
+    controls: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PytmFinding {
+    #[serde(default)]
+    threat_id: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    details: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    mitigations: String,
+    #[serde(default)]
+    response: String,
+}
+
+pub fn parse_pytm(json: &str) -> Result<ImportedModel, PytmError> {
+    let tm: PytmModel = serde_json::from_str(json)?;
+    if tm.elements.is_empty() {
+        return Err(PytmError::NoElements);
+    }
+
+    let mut report = ImportReport::default();
+    let mut id_counter: HashMap<String, u32> = HashMap::new();
+    let mut flow_counter: HashMap<String, u32> = HashMap::new();
+    let mut name_to_id: HashMap<&str, String> = HashMap::new();
+    let mut elements = Vec::new();
+
+    for source in &tm.elements {
+        let id = report.unique_id(&source.name, "element", &mut id_counter);
+        name_to_id
+            .entry(source.name.as_str())
+            .or_insert_with(|| id.clone());
+        let trust_zone = if source.class == "Actor" || source.class == "ExternalEntity" {
+            "external"
+        } else {
+            "internal"
+        };
+        let item = format!("elements.{}", source.name);
+        let element_type = element_type(source).unwrap_or_else(|| {
+            report.fall_back(&item, &source.class, "generic", "class not recognised");
+            "generic"
+        });
+        let mut imported = element(id, element_type, &source.name, trust_zone);
+        imported.description = source.description.clone();
+        if !source.in_scope {
+            imported.tags.push(OUT_OF_SCOPE_TAG.to_string());
+        }
+        if control(&source.controls, "isEncrypted") {
+            imported.encryption = Some(ENCRYPTED.to_string());
+        }
+        report_controls(&mut report, &item, &source.controls, ELEMENT_CONTROLS);
+        elements.push(imported);
+    }
+
+    let mut boundaries = Vec::new();
+    for source in &tm.boundaries {
+        let contains = tm
+            .elements
+            .iter()
+            .zip(&elements)
+            .filter(|(member, _)| member.in_boundary.as_deref() == Some(source.name.as_str()))
+            .map(|(_, imported)| imported.id.clone())
+            .collect();
+        let id = report.unique_id(&source.name, "boundary", &mut id_counter);
+        boundaries.push(boundary(id, &source.name, contains));
+        if let Some(parent) = &source.in_boundary {
+            report.skip(
+                format!("boundaries.{}.inBoundary", source.name),
+                format!("nested in {parent}; nested trust boundaries are imported side by side"),
+            );
+        }
+    }
+
+    let mut flows = Vec::new();
+    let mut flow_name_to_id: HashMap<&str, String> = HashMap::new();
+    for source in &tm.flows {
+        let item = format!("flows.{}", source.name);
+        let (Some(from), Some(to)) = (
+            name_to_id.get(source.source.as_str()),
+            name_to_id.get(source.sink.as_str()),
+        ) else {
+            report.skip(
+                item,
+                format!(
+                    "{} → {} does not connect two elements",
+                    source.source, source.sink
+                ),
+            );
+            continue;
+        };
+        let id = report.unique_id(&source.name, "flow", &mut flow_counter);
+        flow_name_to_id
+            .entry(source.name.as_str())
+            .or_insert_with(|| id.clone());
+        let mut imported = flow(id, source.name.clone(), from, to, &source.protocol);
+        imported.flow_number = source
+            .order
+            .and_then(|order| u32::try_from(order).ok())
+            .filter(|order| *order > 0);
+        imported.data = source.data.iter().filter_map(data_name).collect();
+        imported.authenticated = FLOW_CONTROLS
+            .iter()
+            .any(|name| control(&source.controls, name));
+        report_controls(&mut report, &item, &source.controls, FLOW_CONTROLS);
+        flows.push(imported);
+    }
+
+    if !tm.data.is_empty() {
+        report.skip(
+            "data",
+            "data classifications and flags have no counterpart; data names are kept on flows",
+        );
+    }
+
+    let mut threats = Vec::new();
+    for finding in &tm.findings {
+        let item = format!("findings.{} on {}", finding.threat_id, finding.target);
+        let (element, flow) = if let Some(id) = name_to_id.get(finding.target.as_str()) {
+            (Some(id.clone()), None)
+        } else if let Some(id) = flow_name_to_id.get(finding.target.as_str()) {
+            (None, Some(id.clone()))
+        } else {
+            report.skip(item, "threats can only sit on an element or data flow");
+            continue;
+        };
+        let category = threat_family(&finding.threat_id).unwrap_or_else(|| {
+            let category = StrideCategory::Tampering;
+            report.fall_back(
+                item,
+                &finding.threat_id,
+                category.as_str(),
+                "threat id family not recognised",
+            );
+            category
+        });
+        threats.push(Threat {
+            id: format!("threat-{}", threats.len() + 1),
+            title: finding.description.clone(),
+            category,
+            element,
+            flow,
+            severity: parse_severity(&finding.severity),
+            description: finding.details.clone(),
+            mitigation: mitigation(finding),
+        });
+    }
+
+    let mut model = skeleton_model(&tm.name, elements, flows, boundaries);
+    let assumptions: Vec<String> = tm.assumptions.iter().filter_map(assumption).collect();
+    model.metadata.description = if assumptions.is_empty() {
+        tm.description
+    } else {
+        let list = assumptions
+            .iter()
+            .map(|a| format!("- {a}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("{}\n\nAssumptions:\n{list}", tm.description)
+            .trim_start()
+            .to_string()
+    };
+    model.threats = threats;
+
+    Ok(ImportedModel::new(model, report))
+}
+
+fn element_type(source: &PytmElement) -> Option<&'static str> {
+    if source.class == "Datastore" {
+        if source.is_sql {
+            return Some("sql_database");
+        }
+        if let Some(store_type) = source.store_type.as_str() {
+            if let Some((_, element_type)) = DATASTORE_TYPES
+                .iter()
+                .find(|(name, _)| store_type.ends_with(name))
+            {
+                return Some(element_type);
+            }
+        }
+    }
+    ELEMENT_TYPES
+        .iter()
+        .find(|(class, _)| *class == source.class)
+        .map(|(_, element_type)| *element_type)
+}
+
+fn control(controls: &Map<String, Value>, name: &str) -> bool {
+    controls.get(name).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Report the controls set on an element or flow that are not carried over.
+fn report_controls(
+    report: &mut ImportReport,
+    item: &str,
+    controls: &Map<String, Value>,
+    carried: &[&str],
+) {
+    let dropped: Vec<&str> = controls
+        .iter()
+        .filter(|(name, value)| value.as_bool() == Some(true) && !carried.contains(&name.as_str()))
+        .map(|(name, _)| name.as_str())
+        .collect();
+    if !dropped.is_empty() {
+        report.skip(
+            format!("{item}.controls"),
+            format!("controls {} have no counterpart", dropped.join(", ")),
+        );
+    }
+}
+
+fn data_name(data: &Value) -> Option<String> {
+    match data {
+        Value::String(name) => Some(name.clone()),
+        Value::Object(data) => data.get("name")?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+fn assumption(assumption: &Value) -> Option<String> {
+    match assumption {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(assumption) => {
+            let name = assumption.get("name")?.as_str()?;
+            match assumption.get("description").and_then(Value::as_str) {
+                Some(description) if !description.is_empty() => {
+                    Some(format!("{name}: {description}"))
+                }
+                _ => Some(name.to_string()),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn threat_family(threat_id: &str) -> Option<StrideCategory> {
+    let prefix: String = threat_id
+        .chars()
+        .take_while(char::is_ascii_alphabetic)
+        .collect();
+    THREAT_FAMILIES
+        .iter()
+        .find(|(family, _)| *family == prefix)
+        .map(|(_, category)| category.clone())
+}
+
+fn parse_severity(severity: &str) -> Severity {
+    match severity.to_ascii_lowercase().as_str() {
+        "very high" | "critical" => Severity::Critical,
+        "high" => Severity::High,
+        "low" => Severity::Low,
+        "very low" | "info" => Severity::Info,
+        _ => Severity::Medium,
+    }
+}
+
+/// A finding's `response` is free text; its leading verb says what was done.
+/// Without one the library's suggested mitigations are still to do.
+fn mitigation(finding: &PytmFinding) -> Option<Mitigation> {
+    let response = finding.response.trim();
+    if response.is_empty() {
+        return (!finding.mitigations.is_empty()).then(|| Mitigation {
+            status: MitigationStatus::NotStarted,
+            description: finding.mitigations.clone(),
+        });
+    }
+    let lowered = response.to_ascii_lowercase();
+    let status = if lowered.starts_with("accept") {
+        MitigationStatus::Accepted
+    } else if lowered.starts_with("transfer") {
+        MitigationStatus::Transferred
+    } else if lowered.starts_with("mitigat") || lowered.starts_with("avoid") {
+        MitigationStatus::Mitigated
+    } else {
+        MitigationStatus::InProgress
+    };
+    Some(Mitigation {
+        status,
+        description: response.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::assert_matches_pinned;
+
+    /// A `tm.py --json` export covering each element class, nested boundaries,
+    /// flow controls and data objects, and findings in every response state.
+    const FIXTURE_JSON: &str = include_str!("fixtures/pytm-sample.json");
+
+    /// The import result pinned for [`FIXTURE_JSON`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/pytm-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_pytm(FIXTURE_JSON)
+            .expect("fixture should import")
+            .model;
+        assert_matches_pinned(model, FIXTURE_EXPECTED_YAML, "pytm-sample.expected.yaml");
+    }
+
+    #[test]
+    fn unmapped_constructs_are_reported() {
+        let imported = parse_pytm(FIXTURE_JSON).expect("fixture should import");
+        crate::file_io::validate_references(&imported.model).expect("references resolve");
+
+        let items: Vec<&str> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|skipped| skipped.item.as_str())
+            .collect();
+        assert_eq!(
+            items,
+            [
+                "elements.Web Server.controls",
+                "boundaries.Backend.inBoundary",
+                "flows.Place order.controls",
+                "flows.Archive receipt",
+                "data",
+                "findings.AA01 on DMZ",
+            ]
+        );
+        assert_eq!(
+            imported.report.skipped[0].reason,
+            "controls sanitizesInput, validatesInput have no counterpart"
+        );
+    }
+
+    #[test]
+    fn unknown_classes_and_threat_ids_fall_back_and_repeated_names_are_renamed() {
+        let imported = parse_pytm(
+            r#"{
+                "name": "Fallbacks",
+                "elements": [
+                    {"__class__": "Asset", "name": "Queue"},
+                    {"__class__": "Process", "name": "Queue"}
+                ],
+                "findings": [{"threat_id": "XYZ01", "target": "Queue"}]
+            }"#,
+        )
+        .expect("model should import");
+
+        let fallbacks: Vec<_> = imported
+            .report
+            .fallbacks
+            .iter()
+            .map(|f| (f.item.as_str(), f.source.as_str(), f.mapped_to.as_str()))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            [
+                ("elements.Queue", "Asset", "generic"),
+                ("findings.XYZ01 on Queue", "XYZ01", "Tampering"),
+            ]
+        );
+        let renames: Vec<_> = imported
+            .report
+            .renames
+            .iter()
+            .map(|r| (r.name.as_str(), r.id.as_str()))
+            .collect();
+        assert_eq!(renames, [("Queue", "queue-2")]);
+        assert_eq!(imported.report.counts.elements, 2);
+        assert_eq!(imported.report.counts.threats, 1);
+    }
+
+    #[test]
+    fn dataflows_to_unknown_elements_are_skipped() {
+        let imported = parse_pytm(
+            r#"{
+                "name": "Dangling",
+                "elements": [{"__class__": "Process", "name": "Web"}],
+                "flows": [
+                    {"name": "Query", "source": "Web", "sink": "Ghost DB"},
+                    {"name": "Login", "source": "Nobody", "sink": "Web"}
+                ],
+                "findings": [{"threat_id": "INP01", "target": "Query"}]
+            }"#,
+        )
+        .expect("model should import");
+        crate::file_io::validate_references(&imported.model).expect("references resolve");
+
+        assert!(imported.model.data_flows.is_empty());
+        let skipped: Vec<_> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|s| (s.item.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                (
+                    "flows.Query",
+                    "Web → Ghost DB does not connect two elements"
+                ),
+                ("flows.Login", "Nobody → Web does not connect two elements"),
+                (
+                    "findings.INP01 on Query",
+                    "threats can only sit on an element or data flow"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_models_are_rejected() {
+        let err = parse_pytm(r#"{"name": "Empty"}"#).expect_err("no elements");
+        assert!(matches!(err, PytmError::NoElements));
+
+        let err = parse_pytm("tm = TM('x')").expect_err("Python source is not JSON");
+        assert!(
+            err.to_string().starts_with("JSON parsing error: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+}
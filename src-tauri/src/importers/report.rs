@@ -0,0 +1,138 @@
+//! What an import produced, and what it could not carry over.
+//!
+//! No external format maps onto a ThreatForge model one to one: TMT holds
+//! out-of-scope stencils, pytm has controls, Threagile abuse cases and CIA
+//! ratings, and names that collide become suffixed ids. Every importer says so
+//! here instead of dropping or changing things silently, and the import command
+//! hands the report to the frontend next to the model.
+
+use super::tm7::make_unique_id;
+use crate::models::ThreatModel;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// An imported model together with the report on how it was imported.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedModel {
+    pub model: ThreatModel,
+    pub report: ImportReport,
+}
+
+impl ImportedModel {
+    /// Pair `model` with `report`, counting what the model ended up with.
+    pub(super) fn new(model: ThreatModel, mut report: ImportReport) -> Self {
+        report.counts = ImportCounts::of(&model);
+        Self { model, report }
+    }
+}
+
+/// How an import went, each list in the order the source was read.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ImportReport {
+    pub counts: ImportCounts,
+    /// Source constructs left out of the model.
+    pub skipped: Vec<SkippedItem>,
+    /// Source values with no direct counterpart, imported as something else.
+    pub fallbacks: Vec<FallbackMapping>,
+    /// Names whose id was already taken, so their id got a numeric suffix.
+    pub renames: Vec<Rename>,
+}
+
+/// How many of each section the imported model holds.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ImportCounts {
+    pub elements: usize,
+    pub data_flows: usize,
+    pub trust_boundaries: usize,
+    pub groups: usize,
+    pub threats: usize,
+    pub diagrams: usize,
+}
+
+impl ImportCounts {
+    fn of(model: &ThreatModel) -> Self {
+        Self {
+            elements: model.elements.len(),
+            data_flows: model.data_flows.len(),
+            trust_boundaries: model.trust_boundaries.len(),
+            groups: model.groups.len(),
+            threats: model.threats.len(),
+            diagrams: model.diagrams.len(),
+        }
+    }
+}
+
+/// One source construct left out of the imported model.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SkippedItem {
+    /// Where the construct sits in the source, e.g. `shared_runtimes.k8s`.
+    pub item: String,
+    /// Why it has no counterpart in the model.
+    pub reason: String,
+}
+
+/// A source value the importer did not recognise and mapped by a fallback.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FallbackMapping {
+    /// Where the value sits in the source.
+    pub item: String,
+    /// The value as the source has it; empty when it has none.
+    pub source: String,
+    /// What the model has instead, e.g. `Elevation of Privilege`.
+    pub mapped_to: String,
+    /// Which fallback was applied.
+    pub reason: String,
+}
+
+/// A name imported under a suffixed id because another item took its id.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Rename {
+    pub name: String,
+    pub id: String,
+}
+
+impl ImportReport {
+    pub(super) fn skip(&mut self, item: impl Into<String>, reason: impl Into<String>) {
+        self.skipped.push(SkippedItem {
+            item: item.into(),
+            reason: reason.into(),
+        });
+    }
+
+    pub(super) fn fall_back(
+        &mut self,
+        item: impl Into<String>,
+        source: impl Into<String>,
+        mapped_to: impl Into<String>,
+        reason: impl Into<String>,
+    ) {
+        self.fallbacks.push(FallbackMapping {
+            item: item.into(),
+            source: source.into(),
+            mapped_to: mapped_to.into(),
+            reason: reason.into(),
+        });
+    }
+
+    pub(super) fn rename(&mut self, name: impl Into<String>, id: impl Into<String>) {
+        self.renames.push(Rename {
+            name: name.into(),
+            id: id.into(),
+        });
+    }
+
+    /// [`make_unique_id`], noting the rename when `name`'s id was taken.
+    /// Unnamed items are numbered by design and not reported.
+    pub(super) fn unique_id(
+        &mut self,
+        name: &str,
+        fallback: &str,
+        counter: &mut HashMap<String, u32>,
+    ) -> String {
+        let id = make_unique_id(name, fallback, counter);
+        if !name.is_empty() && id != make_unique_id(name, fallback, &mut HashMap::new()) {
+            self.rename(name, id.as_str());
+        }
+        id
+    }
+}
@@ -0,0 +1,274 @@
+//! Shared pieces of the importers that sketch a model from infrastructure or
+//! API descriptions (docker-compose, Kubernetes, Terraform and OpenAPI): what
+//! element type and protocol a container image or port stands for, and how
+//! the skeleton model they produce is assembled. The pytm and Threagile
+//! importers build on the same skeleton.
+//!
+//! Images are recognised by the last segment of their repository name, so
+//! `docker.io/library/postgres:16` and `bitnami/postgresql` are both SQL
+//! databases. Anything unrecognised is a `microservice`.
+
+use super::report::ImportReport;
+use super::tm7::{imported_diagram, MAIN_DIAGRAM_ID};
+use crate::models::layout::DEFAULT_DIAGRAM_NAME;
+use crate::models::{DataFlow, Element, Metadata, ThreatModel, TrustBoundary};
+
+/// Element type of a workload whose image is not recognised.
+pub(super) const DEFAULT_SERVICE_TYPE: &str = "microservice";
+
+/// Protocol of a connection whose port and target are not recognised.
+pub(super) const DEFAULT_PROTOCOL: &str = "TCP";
+
+/// Name of the element standing for clients outside the deployment.
+pub(super) const EXTERNAL_CLIENT_NAME: &str = "External Client";
+
+/// Image names, the element type they run as and the protocol they speak.
+const KNOWN_IMAGES: &[(&[&str], &str, &str)] = &[
+    (
+        &[
+            "postgres",
+            "postgresql",
+            "postgis",
+            "timescaledb",
+            "cockroach",
+        ],
+        "sql_database",
+        "PostgreSQL",
+    ),
+    (&["mysql", "mariadb", "percona"], "sql_database", "MySQL"),
+    (
+        &["mssql-server", "mssql", "sqlserver"],
+        "sql_database",
+        "TDS",
+    ),
+    (&["mongo", "mongodb"], "nosql_database", "MongoDB"),
+    (&["cassandra", "scylla"], "nosql_database", "CQL"),
+    (&["couchdb", "couchbase", "neo4j"], "nosql_database", "HTTP"),
+    (&["redis", "valkey", "keydb"], "cache", "Redis"),
+    (&["memcached"], "cache", "Memcached"),
+    (
+        &["elasticsearch", "opensearch", "solr", "meilisearch"],
+        "search_index",
+        "HTTP",
+    ),
+    (
+        &["rabbitmq", "activemq", "artemis"],
+        "message_queue",
+        "AMQP",
+    ),
+    (&["nats"], "message_queue", "NATS"),
+    (&["kafka", "cp-kafka", "redpanda"], "event_bus", "Kafka"),
+    (&["minio"], "object_storage", "HTTPS"),
+    (&["vault"], "secret_manager", "HTTPS"),
+    (
+        &["keycloak", "dex", "authentik"],
+        "identity_provider",
+        "HTTPS",
+    ),
+    (&["registry", "harbor-core"], "container_registry", "HTTPS"),
+    (
+        &["traefik", "haproxy", "envoy", "ingress-nginx"],
+        "load_balancer",
+        "HTTP",
+    ),
+    (&["nginx", "httpd", "apache", "caddy"], "web_server", "HTTP"),
+];
+
+/// Well-known ports and the protocol spoken on them.
+const KNOWN_PORTS: &[(u16, &str)] = &[
+    (80, "HTTP"),
+    (443, "HTTPS"),
+    (1433, "TDS"),
+    (3000, "HTTP"),
+    (3306, "MySQL"),
+    (5432, "PostgreSQL"),
+    (5672, "AMQP"),
+    (6379, "Redis"),
+    (8080, "HTTP"),
+    (8443, "HTTPS"),
+    (9042, "CQL"),
+    (9092, "Kafka"),
+    (11211, "Memcached"),
+    (27017, "MongoDB"),
+];
+
+/// The repository name an image reference runs, without registry, tag or
+/// digest.
+fn image_name(image: &str) -> &str {
+    let image = image.split('@').next().unwrap_or(image);
+    let name = image.rsplit('/').next().unwrap_or(image);
+    name.split(':').next().unwrap_or(name)
+}
+
+fn known_image(
+    image: &str,
+) -> Option<&'static (&'static [&'static str], &'static str, &'static str)> {
+    let name = image_name(image).to_ascii_lowercase();
+    KNOWN_IMAGES
+        .iter()
+        .find(|(names, _, _)| names.contains(&name.as_str()))
+}
+
+/// The element type a container running `image` is drawn as.
+pub(super) fn element_type_for_image(image: &str) -> &'static str {
+    known_image(image).map_or(DEFAULT_SERVICE_TYPE, |(_, element_type, _)| element_type)
+}
+
+/// The protocol a connection to a container running `image` on `port`
+/// speaks: a well-known port says it best, so a proxy on 443 speaks HTTPS,
+/// and the image covers a database or broker moved off its usual port.
+pub(super) fn protocol_for(image: Option<&str>, port: Option<u16>) -> &'static str {
+    port.and_then(|port| {
+        KNOWN_PORTS
+            .iter()
+            .find(|(known, _)| *known == port)
+            .map(|(_, protocol)| *protocol)
+    })
+    .or_else(|| {
+        image
+            .and_then(known_image)
+            .map(|(_, _, protocol)| *protocol)
+    })
+    .unwrap_or(DEFAULT_PROTOCOL)
+}
+
+/// Note that a workload was typed `element_type` by fallback, because it
+/// runs no image or one [`element_type_for_image`] does not know.
+pub(super) fn report_unknown_image(
+    report: &mut ImportReport,
+    item: String,
+    image: Option<&str>,
+    element_type: &str,
+) {
+    let reason = match image {
+        Some(_) => "image not recognised",
+        None => "no image to recognise",
+    };
+    report.fall_back(item, image.unwrap_or_default(), element_type, reason);
+}
+
+/// An element with only the fields an infrastructure file can tell us.
+pub(super) fn element(id: String, element_type: &str, name: &str, trust_zone: &str) -> Element {
+    Element {
+        id,
+        element_type: element_type.to_string(),
+        name: name.to_string(),
+        trust_zone: trust_zone.to_string(),
+        layer: None,
+        group: None,
+        diagram: None,
+        subtype: None,
+        icon: None,
+        description: String::new(),
+        technologies: Vec::new(),
+        tags: Vec::new(),
+        stores: None,
+        encryption: None,
+        operations: Vec::new(),
+        position: None,
+        fill_color: None,
+        stroke_color: None,
+        fill_opacity: None,
+        stroke_opacity: None,
+        font_size: None,
+        font_weight: None,
+    }
+}
+
+/// A data flow with only the fields an infrastructure file can tell us.
+pub(super) fn flow(id: String, name: String, from: &str, to: &str, protocol: &str) -> DataFlow {
+    DataFlow {
+        id,
+        flow_number: None,
+        name,
+        from: from.to_string(),
+        to: to.to_string(),
+        protocol: protocol.to_string(),
+        data: Vec::new(),
+        authenticated: false,
+        label_offset: None,
+        source_handle: None,
+        target_handle: None,
+        stroke_color: None,
+        stroke_opacity: None,
+    }
+}
+
+/// A rectangular trust boundary, laid out by the canvas.
+pub(super) fn boundary(id: String, name: &str, contains: Vec<String>) -> TrustBoundary {
+    TrustBoundary {
+        id,
+        name: name.to_string(),
+        contains,
+        diagram: None,
+        shape: None,
+        points: Vec::new(),
+        position: None,
+        size: None,
+        fill_color: None,
+        stroke_color: None,
+        fill_opacity: None,
+        stroke_opacity: None,
+    }
+}
+
+/// A single-diagram model with no threats yet, ready for STRIDE analysis.
+pub(super) fn skeleton_model(
+    title: &str,
+    elements: Vec<Element>,
+    data_flows: Vec<DataFlow>,
+    trust_boundaries: Vec<TrustBoundary>,
+) -> ThreatModel {
+    ThreatModel {
+        version: "1.0".to_string(),
+        metadata: Metadata {
+            title: title.to_string(),
+            author: String::new(),
+            created: chrono::Local::now().date_naive(),
+            modified: chrono::Local::now().date_naive(),
+            description: String::new(),
+            created_by: None,
+            modified_by: None,
+            last_edit_timestamp: None,
+            threat_analysis_enabled: None,
+            settings: None,
+        },
+        layers: Vec::new(),
+        groups: Vec::new(),
+        elements,
+        data_flows,
+        relationships: Vec::new(),
+        trust_boundaries,
+        threats: Vec::new(),
+        diagrams: vec![imported_diagram(
+            MAIN_DIAGRAM_ID,
+            DEFAULT_DIAGRAM_NAME.to_string(),
+        )],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn images_are_recognised_by_repository_name() {
+        assert_eq!(
+            element_type_for_image("docker.io/library/postgres:16-alpine"),
+            "sql_database"
+        );
+        assert_eq!(element_type_for_image("bitnami/redis@sha256:abc"), "cache");
+        assert_eq!(
+            element_type_for_image("registry.example.com:5000/shop/api:1.2"),
+            DEFAULT_SERVICE_TYPE
+        );
+    }
+
+    #[test]
+    fn protocols_come_from_the_port_then_the_image() {
+        assert_eq!(protocol_for(Some("mysql:8"), Some(13306)), "MySQL");
+        assert_eq!(protocol_for(Some("nginx:1.27"), Some(8443)), "HTTPS");
+        assert_eq!(protocol_for(Some("nginx:1.27"), None), "HTTP");
+        assert_eq!(protocol_for(None, Some(7000)), DEFAULT_PROTOCOL);
+    }
+}
@@ -0,0 +1,767 @@
+//! Parser for Microsoft Threat Modeling Tool template (`.tb7`) files.
+//!
+//! A template is a `KnowledgeBase` XML document: the stencil hierarchy
+//! (`GenericElements` and `StandardElements`), the threat categories, and the
+//! threat types with the generation filters TMT evaluates per interaction.
+//! This parser turns it into a [`RulePack`] that
+//! [`crate::stride::analyze_with_rule_pack`] replays. Stencils map onto
+//! ThreatForge element types, and filter expressions are parsed into
+//! [`Filter`] trees. A threat type whose filter cannot be read is skipped and
+//! listed in [`Tb7Template::skipped`] rather than failing the import.
+
+use super::tm7::{
+    local_name, parse_severity, parse_stride_category, read_text_content, skip_element, Tm7Error,
+};
+use crate::models::Severity;
+use crate::stride::{
+    Filter, PackRule, PackStencil, RulePack, Subject, GENERIC_DATA_STORE, GENERIC_EXTERNAL_ENTITY,
+    GENERIC_PROCESS,
+};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Tb7Error {
+    #[error(transparent)]
+    Xml(#[from] Tm7Error),
+    #[error("Not a Threat Modeling Tool template: no KnowledgeBase element")]
+    NotATemplate,
+}
+
+impl From<quick_xml::Error> for Tb7Error {
+    fn from(e: quick_xml::Error) -> Self {
+        Tb7Error::Xml(Tm7Error::Xml(e))
+    }
+}
+
+/// The result of reading a template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tb7Template {
+    pub pack: RulePack,
+    /// Threat types left out of the pack, with the reason.
+    pub skipped: Vec<SkippedThreatType>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedThreatType {
+    pub id: String,
+    pub reason: String,
+}
+
+/// ThreatForge element types a stencil can narrow to, by a keyword in its
+/// name, and the generic root it must specialise for the keyword to apply.
+/// Order matters: the first match wins.
+const ELEMENT_TYPE_KEYWORDS: &[(&str, &str, &str)] = &[
+    ("browser", "web_browser", GENERIC_EXTERNAL_ENTITY),
+    ("mobile", "mobile_app", GENERIC_EXTERNAL_ENTITY),
+    ("iot", "iot_device", GENERIC_EXTERNAL_ENTITY),
+    ("device", "iot_device", GENERIC_EXTERNAL_ENTITY),
+    ("sql", "sql_database", GENERIC_DATA_STORE),
+    ("cosmos", "nosql_database", GENERIC_DATA_STORE),
+    ("nosql", "nosql_database", GENERIC_DATA_STORE),
+    ("redis", "cache", GENERIC_DATA_STORE),
+    ("cache", "cache", GENERIC_DATA_STORE),
+    ("key vault", "key_management", GENERIC_DATA_STORE),
+    ("blob", "object_storage", GENERIC_DATA_STORE),
+    ("file", "file_storage", GENERIC_DATA_STORE),
+    ("data lake", "data_lake", GENERIC_DATA_STORE),
+    ("api management", "api_gateway", GENERIC_PROCESS),
+    ("gateway", "api_gateway", GENERIC_PROCESS),
+    ("service bus", "message_queue", GENERIC_PROCESS),
+    ("queue", "message_queue", GENERIC_PROCESS),
+    ("event hub", "event_bus", GENERIC_PROCESS),
+    ("function", "serverless_function", GENERIC_PROCESS),
+    ("active directory", "identity_provider", GENERIC_PROCESS),
+    ("identity", "identity_provider", GENERIC_PROCESS),
+    ("load balancer", "load_balancer", GENERIC_PROCESS),
+    ("firewall", "firewall", GENERIC_PROCESS),
+    ("web app", "web_server", GENERIC_PROCESS),
+    ("web server", "web_server", GENERIC_PROCESS),
+    ("web api", "api_endpoint", GENERIC_PROCESS),
+];
+
+/// The ThreatForge element type of each generic root.
+fn root_element_type(root: &str) -> Option<&'static str> {
+    match root {
+        GENERIC_PROCESS => Some("process"),
+        GENERIC_DATA_STORE => Some("data_store"),
+        GENERIC_EXTERNAL_ENTITY => Some("external_entity"),
+        _ => None,
+    }
+}
+
+/// Parse a `.tb7` template into a rule pack.
+pub fn parse_tb7(xml: &str) -> Result<Tb7Template, Tb7Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+
+    let mut is_knowledge_base = false;
+    let mut name = String::new();
+    let mut stencils: Vec<RawStencil> = Vec::new();
+    let mut categories: HashMap<String, String> = HashMap::new();
+    let mut threat_types: Vec<RawThreatType> = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) => match local_name(e).as_str() {
+                "KnowledgeBase" => is_knowledge_base = true,
+                "Manifest" => {
+                    name = attribute(e, "name").unwrap_or_default();
+                    skip_element(&mut reader)?;
+                }
+                "ElementType" => stencils.push(parse_element_type(&mut reader)?),
+                "ThreatCategory" => {
+                    let (id, category) = parse_threat_category(&mut reader)?;
+                    categories.insert(id, category);
+                }
+                "ThreatType" => threat_types.push(parse_threat_type(&mut reader)?),
+                _ => {}
+            },
+            Event::Empty(ref e) if local_name(e) == "Manifest" => {
+                name = attribute(e, "name").unwrap_or_default();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !is_knowledge_base {
+        return Err(Tb7Error::NotATemplate);
+    }
+
+    let parents: HashMap<&str, &str> = stencils
+        .iter()
+        .filter_map(|s| Some((s.id.as_str(), s.parent.as_deref()?)))
+        .collect();
+    let root_of = |id: &str| {
+        let mut current = id;
+        for _ in 0..=parents.len() {
+            match parents.get(current) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current.to_string()
+    };
+
+    let pack_stencils = stencils
+        .iter()
+        .map(|s| {
+            let root = root_of(&s.id);
+            let lower = s.name.to_lowercase();
+            let narrowed = ELEMENT_TYPE_KEYWORDS
+                .iter()
+                .find(|(keyword, _, keyword_root)| *keyword_root == root && lower.contains(keyword))
+                .map(|(_, element_type, _)| *element_type);
+            PackStencil {
+                id: s.id.clone(),
+                name: s.name.clone(),
+                parent: s.parent.clone(),
+                element_type: narrowed
+                    .or_else(|| root_element_type(&root))
+                    .map(str::to_string),
+            }
+        })
+        .collect();
+
+    let mut rules = Vec::new();
+    let mut skipped = Vec::new();
+    for threat_type in threat_types {
+        let filters = parse_optional_filter(&threat_type.include)
+            .and_then(|include| Ok((include, parse_optional_filter(&threat_type.exclude)?)));
+        let (include, exclude) = match filters {
+            Ok(filters) => filters,
+            Err(reason) => {
+                skipped.push(SkippedThreatType {
+                    id: threat_type.id,
+                    reason,
+                });
+                continue;
+            }
+        };
+        let category_name = categories
+            .get(&threat_type.category)
+            .map(String::as_str)
+            .unwrap_or(&threat_type.category);
+        rules.push(PackRule {
+            category: parse_stride_category(category_name, &threat_type.category),
+            severity: threat_type
+                .priority
+                .as_deref()
+                .map_or(Severity::Medium, parse_severity),
+            id: threat_type.id,
+            title: threat_type.title,
+            description: threat_type.description,
+            include,
+            exclude,
+        });
+    }
+
+    Ok(Tb7Template {
+        pack: RulePack {
+            name,
+            stencils: pack_stencils,
+            rules,
+        },
+        skipped,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Section parsers
+// ---------------------------------------------------------------------------
+
+/// A stencil as written: the parent is the raw `ParentElement`, with TMT's
+/// `ROOT` marker already dropped.
+struct RawStencil {
+    id: String,
+    name: String,
+    parent: Option<String>,
+}
+
+#[derive(Default)]
+struct RawThreatType {
+    id: String,
+    title: String,
+    description: String,
+    category: String,
+    priority: Option<String>,
+    include: String,
+    exclude: String,
+}
+
+fn attribute(e: &BytesStart<'_>, name: &str) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == name.as_bytes())
+        .map(|a| String::from_utf8_lossy(&a.value).to_string())
+}
+
+fn parse_element_type(reader: &mut Reader<&[u8]>) -> Result<RawStencil, Tb7Error> {
+    let mut stencil = RawStencil {
+        id: String::new(),
+        name: String::new(),
+        parent: None,
+    };
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) => match local_name(e).as_str() {
+                "ID" => stencil.id = read_text_content(reader)?,
+                "Name" => stencil.name = read_text_content(reader)?,
+                "ParentElement" => {
+                    let parent = read_text_content(reader)?;
+                    if !parent.is_empty() && parent != "ROOT" {
+                        stencil.parent = Some(parent);
+                    }
+                }
+                _ => skip_element(reader)?,
+            },
+            Event::End(_) | Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(stencil)
+}
+
+fn parse_threat_category(reader: &mut Reader<&[u8]>) -> Result<(String, String), Tb7Error> {
+    let (mut id, mut name) = (String::new(), String::new());
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) => match local_name(e).as_str() {
+                "Id" => id = read_text_content(reader)?,
+                "Name" => name = read_text_content(reader)?,
+                _ => skip_element(reader)?,
+            },
+            Event::End(_) | Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok((id, name))
+}
+
+fn parse_threat_type(reader: &mut Reader<&[u8]>) -> Result<RawThreatType, Tb7Error> {
+    let mut threat_type = RawThreatType::default();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) => match local_name(e).as_str() {
+                "Id" => threat_type.id = read_text_content(reader)?,
+                "ShortTitle" => threat_type.title = read_text_content(reader)?,
+                "Description" => threat_type.description = read_text_content(reader)?,
+                "Category" => threat_type.category = read_text_content(reader)?,
+                "GenerationFilters" => {
+                    (threat_type.include, threat_type.exclude) = parse_generation_filters(reader)?;
+                }
+                "PropertiesMetaData" => threat_type.priority = parse_priority(reader)?,
+                _ => skip_element(reader)?,
+            },
+            Event::End(_) | Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(threat_type)
+}
+
+fn parse_generation_filters(reader: &mut Reader<&[u8]>) -> Result<(String, String), Tb7Error> {
+    let (mut include, mut exclude) = (String::new(), String::new());
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) => match local_name(e).as_str() {
+                "Include" => include = read_text_content(reader)?,
+                "Exclude" => exclude = read_text_content(reader)?,
+                _ => skip_element(reader)?,
+            },
+            Event::End(_) | Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok((include, exclude))
+}
+
+/// The default `Priority` a threat type declares among its property metadata.
+fn parse_priority(reader: &mut Reader<&[u8]>) -> Result<Option<String>, Tb7Error> {
+    let mut priority = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) if local_name(e) == "ThreatMetaDatum" => {
+                let (name, value) = parse_meta_datum(reader)?;
+                if name == "Priority" && value.is_some() {
+                    priority = value;
+                }
+            }
+            Event::Start(_) => skip_element(reader)?,
+            Event::End(_) | Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(priority)
+}
+
+/// A metadata entry's name and first value.
+fn parse_meta_datum(reader: &mut Reader<&[u8]>) -> Result<(String, Option<String>), Tb7Error> {
+    let (mut name, mut value) = (String::new(), None);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) => match local_name(e).as_str() {
+                "Name" => name = read_text_content(reader)?,
+                "Values" => value = parse_first_value(reader)?,
+                _ => skip_element(reader)?,
+            },
+            Event::End(_) | Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok((name, value))
+}
+
+/// The first non-empty `Value` in a `Values` list.
+fn parse_first_value(reader: &mut Reader<&[u8]>) -> Result<Option<String>, Tb7Error> {
+    let mut value = None;
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(ref e) if local_name(e) == "Value" => {
+                let text = read_text_content(reader)?;
+                if value.is_none() && !text.is_empty() {
+                    value = Some(text);
+                }
+            }
+            Event::Start(_) => skip_element(reader)?,
+            Event::End(_) | Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(value)
+}
+
+// ---------------------------------------------------------------------------
+// Generation filters
+// ---------------------------------------------------------------------------
+
+fn parse_optional_filter(text: &str) -> Result<Option<Filter>, String> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_filter(text).map(Some)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    /// A quoted or bracketed literal.
+    Literal(String),
+    Word(String),
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '\'' | '"' | '[' => {
+                chars.next();
+                let close = if c == '[' { ']' } else { c };
+                let literal: String = chars.by_ref().take_while(|&ch| ch != close).collect();
+                tokens.push(Token::Literal(literal));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || ch == '(' || ch == ')' || ch == '\'' || ch == '"' {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    tokens
+}
+
+/// Parse a TMT generation filter such as
+/// `(source is 'GE.EI' or source is 'GE.P') and flow crosses 'GE.TB.B'`.
+///
+/// `and` binds tighter than `or`, `not` negates the clause after it, and
+/// stencil ids may be quoted or bracketed.
+fn parse_filter(text: &str) -> Result<Filter, String> {
+    let tokens = tokenize(text);
+    let mut parser = FilterParser { tokens, pos: 0 };
+    let filter = parser.or()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(filter),
+        Some(token) => Err(format!("unexpected {token:?} in filter `{text}`")),
+    }
+}
+
+struct FilterParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl FilterParser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut clauses = vec![self.and()?];
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            clauses.push(self.and()?);
+        }
+        Ok(single_or(clauses, Filter::Any))
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut clauses = vec![self.unary()?];
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            clauses.push(self.unary()?);
+        }
+        Ok(single_or(clauses, Filter::All))
+    }
+
+    fn unary(&mut self) -> Result<Filter, String> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Filter::Not(Box::new(self.unary()?)));
+        }
+        match self.next() {
+            Some(Token::Open) => {
+                let inner = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    other => Err(format!("expected `)`, found {other:?}")),
+                }
+            }
+            Some(Token::Word(word)) => self.clause(&word),
+            other => Err(format!("expected a clause, found {other:?}")),
+        }
+    }
+
+    /// `subject is 'X'`, `flow crosses 'X'`, or `subject.Property is 'value'`.
+    fn clause(&mut self, word: &str) -> Result<Filter, String> {
+        let (subject_word, property) = match word.split_once('.') {
+            Some((subject, property)) => (subject, Some(property)),
+            None => (word, None),
+        };
+        let subject = match subject_word.to_ascii_lowercase().as_str() {
+            "source" => Subject::Source,
+            "target" => Subject::Target,
+            "flow" => Subject::Flow,
+            _ => return Err(format!("unknown filter subject `{subject_word}`")),
+        };
+        // Attribute properties are bracketed GUIDs: `target.[GUID] is 'Yes'`.
+        let property = property.map(|name| name.trim_matches(['[', ']']).to_string());
+
+        let operator = match self.next() {
+            Some(Token::Word(op)) => op.to_ascii_lowercase(),
+            other => return Err(format!("expected `is` or `crosses`, found {other:?}")),
+        };
+        let value = match self.next() {
+            Some(Token::Literal(value)) | Some(Token::Word(value)) => value,
+            other => return Err(format!("expected a value, found {other:?}")),
+        };
+
+        match (operator.as_str(), property) {
+            ("is", Some(name)) => Ok(Filter::Property {
+                subject,
+                name,
+                value,
+            }),
+            ("is", None) => Ok(Filter::Is {
+                subject,
+                stencil: value,
+            }),
+            ("crosses", None) if subject == Subject::Flow => Ok(Filter::Crosses { stencil: value }),
+            _ => Err(format!("unsupported filter operator `{operator}`")),
+        }
+    }
+}
+
+/// A one-clause list is the clause itself.
+fn single_or(mut clauses: Vec<Filter>, combine: fn(Vec<Filter>) -> Filter) -> Filter {
+    if clauses.len() == 1 {
+        clauses.remove(0)
+    } else {
+        combine(clauses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::tm7::parse_tm7;
+    use crate::models::{StrideCategory, ThreatModel};
+    use crate::stride::analyze_with_rule_pack;
+
+    /// Hand-authored knowledge base; see the header comment in the file.
+    const FIXTURE_TB7: &str = include_str!("fixtures/tmt-template-sample.tb7");
+    const FIXTURE_TM7: &str = include_str!("fixtures/tmt-4.3-sample.tm7");
+
+    fn template() -> Tb7Template {
+        parse_tb7(FIXTURE_TB7).expect("fixture template should parse")
+    }
+
+    #[test]
+    fn stencils_map_onto_element_types() {
+        let pack = template().pack;
+        assert_eq!(pack.name, "Contoso Cloud Template");
+
+        let stencils: Vec<(&str, &str, Option<&str>)> = pack
+            .stencils
+            .iter()
+            .map(|s| (s.id.as_str(), s.name.as_str(), s.element_type.as_deref()))
+            .collect();
+        assert_eq!(
+            stencils,
+            [
+                ("GE.P", "Generic Process", Some("process")),
+                ("GE.DS", "Generic Data Store", Some("data_store")),
+                (
+                    "GE.EI",
+                    "Generic External Interactor",
+                    Some("external_entity")
+                ),
+                ("GE.DF", "Generic Data Flow", None),
+                ("GE.TB.B", "Generic Trust Border Boundary", None),
+                ("GE.TB.L", "Generic Trust Line Boundary", None),
+                ("SE.P.TMCore.WebApp", "Web Application", Some("web_server")),
+                (
+                    "SE.DS.TMCore.AzureSQLDB",
+                    "Azure SQL Database",
+                    Some("sql_database")
+                ),
+                ("SE.EI.TMCore.Browser", "Browser", Some("web_browser")),
+                (
+                    "SE.P.Contoso.Batch",
+                    "Contoso Batch Runner",
+                    Some("process")
+                ),
+                ("SE.TB.L.TMCore.Internet", "Internet Boundary", None),
+            ]
+        );
+        assert_eq!(pack.stencils[0].parent, None);
+        assert_eq!(pack.stencils[6].parent.as_deref(), Some("GE.P"));
+    }
+
+    #[test]
+    fn threat_types_become_rules_and_unreadable_filters_are_skipped() {
+        let template = template();
+        let rules: Vec<(&str, StrideCategory, Severity)> = template
+            .pack
+            .rules
+            .iter()
+            .map(|r| (r.id.as_str(), r.category.clone(), r.severity.clone()))
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                ("TH1", StrideCategory::Spoofing, Severity::High),
+                ("TH2", StrideCategory::Tampering, Severity::Medium),
+                ("TH3", StrideCategory::InformationDisclosure, Severity::Low),
+                ("TH5", StrideCategory::DenialOfService, Severity::Medium),
+            ]
+        );
+
+        let th2 = &template.pack.rules[1];
+        assert_eq!(
+            th2.include,
+            Some(Filter::All(vec![
+                Filter::Is {
+                    subject: Subject::Target,
+                    stencil: "GE.DS".to_string(),
+                },
+                Filter::Crosses {
+                    stencil: "GE.TB.B".to_string(),
+                },
+            ]))
+        );
+        assert_eq!(
+            th2.exclude,
+            Some(Filter::Is {
+                subject: Subject::Source,
+                stencil: "SE.P.Contoso.Batch".to_string(),
+            })
+        );
+        assert_eq!(template.pack.rules[3].include, None);
+
+        assert_eq!(template.skipped.len(), 1);
+        assert_eq!(template.skipped[0].id, "TH4");
+        assert!(
+            template.skipped[0].reason.contains("expected a value"),
+            "{}",
+            template.skipped[0].reason
+        );
+    }
+
+    #[test]
+    fn filters_parse_with_precedence_and_negation() {
+        let filter = parse_filter(
+            "source is 'GE.EI' or target is [GE.P] and not flow.Protocol is \"HTTPS\"",
+        )
+        .unwrap();
+        assert_eq!(
+            filter,
+            Filter::Any(vec![
+                Filter::Is {
+                    subject: Subject::Source,
+                    stencil: "GE.EI".to_string(),
+                },
+                Filter::All(vec![
+                    Filter::Is {
+                        subject: Subject::Target,
+                        stencil: "GE.P".to_string(),
+                    },
+                    Filter::Not(Box::new(Filter::Property {
+                        subject: Subject::Flow,
+                        name: "Protocol".to_string(),
+                        value: "HTTPS".to_string(),
+                    })),
+                ]),
+            ])
+        );
+
+        assert_eq!(
+            parse_filter("target.[b3ec5f0e] is 'Yes'").unwrap(),
+            Filter::Property {
+                subject: Subject::Target,
+                name: "b3ec5f0e".to_string(),
+                value: "Yes".to_string(),
+            }
+        );
+
+        assert!(parse_filter("(source is 'GE.P'").is_err());
+        assert!(parse_filter("boundary is 'GE.TB.B'").is_err());
+        assert!(parse_filter("source crosses 'GE.TB.B'").is_err());
+        assert!(parse_filter("source is 'GE.P' target is 'GE.DS'").is_err());
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_templates() {
+        assert!(matches!(
+            parse_tb7(FIXTURE_TM7),
+            Err(Tb7Error::NotATemplate)
+        ));
+    }
+
+    #[test]
+    fn template_rules_replay_over_an_imported_model() {
+        let pack = template().pack;
+        let mut model = parse_tm7(FIXTURE_TM7)
+            .expect("fixture model should parse")
+            .model;
+        // The API is a web application in the template's terms.
+        model.elements[1].element_type = "web_server".to_string();
+
+        let suggested = |model: &ThreatModel| -> Vec<(String, String)> {
+            analyze_with_rule_pack(model, &pack)
+                .into_iter()
+                .map(|t| (t.flow.unwrap_or_default(), t.title))
+                .collect()
+        };
+
+        assert_eq!(
+            suggested(&model),
+            [
+                (
+                    "flow-1".to_string(),
+                    "An adversary may spoof Customer to reach Payments & Billing API".to_string()
+                ),
+                (
+                    "flow-1".to_string(),
+                    "Browse & Checkout crosses the internet unencrypted".to_string()
+                ),
+            ]
+        );
+
+        // With the database outside the corporate network, writes to it cross
+        // the border boundary.
+        model.trust_boundaries[0]
+            .contains
+            .retain(|id| id != "billing-database");
+        assert!(suggested(&model).contains(&(
+            "flow-2".to_string(),
+            "Billing Database can be tampered with from outside its boundary".to_string()
+        )));
+
+        // ...unless the writer is the excluded batch runner.
+        model.elements[1].subtype = Some("SE.P.Contoso.Batch".to_string());
+        assert!(!suggested(&model).iter().any(|(flow, _)| flow == "flow-2"));
+    }
+}
@@ -0,0 +1,785 @@
+//! Parser for Terraform JSON (`terraform show -json`), producing a model
+//! skeleton from an AWS deployment.
+//!
+//! Works on a saved plan or on state, entirely from the file. Managed
+//! resources with a counterpart element type become elements: RDS instances
+//! are SQL databases, S3 buckets object storage, load balancers load
+//! balancers, and so on. Each VPC becomes a trust boundary holding what runs
+//! inside it, and each subnet a group its resources belong to. Every ingress
+//! rule of a security group becomes data flows from the resources attached to
+//! the source group, or from an external client when the rule is open to the
+//! internet, to the resources attached to the group.
+//!
+//! References are followed through attribute values (ids, ARNs and names, as
+//! state has them) and through the plan's configuration expressions, where a
+//! resource not yet created only has its address. References passed in
+//! through module variables are not followed. Data sources, resources of other
+//! providers and AWS resources with no counterpart are listed in the
+//! [`ImportReport`]; egress rules are ignored.
+
+use super::report::{ImportReport, ImportedModel};
+use super::services::{
+    boundary, element, flow, protocol_for, skeleton_model, EXTERNAL_CLIENT_NAME,
+};
+use crate::models::{DataFlow, Element, Group};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TerraformError {
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("No AWS resources found; expected the output of `terraform show -json`")]
+    NoResources,
+}
+
+/// Resource types drawn as elements, and the element type they become.
+const ELEMENT_TYPES: &[(&str, &str)] = &[
+    ("aws_db_instance", "sql_database"),
+    ("aws_rds_cluster", "sql_database"),
+    ("aws_dynamodb_table", "nosql_database"),
+    ("aws_docdb_cluster", "nosql_database"),
+    ("aws_elasticache_cluster", "cache"),
+    ("aws_elasticache_replication_group", "cache"),
+    ("aws_opensearch_domain", "search_index"),
+    ("aws_elasticsearch_domain", "search_index"),
+    ("aws_s3_bucket", "object_storage"),
+    ("aws_efs_file_system", "file_storage"),
+    ("aws_lb", "load_balancer"),
+    ("aws_alb", "load_balancer"),
+    ("aws_elb", "load_balancer"),
+    ("aws_cloudfront_distribution", "cdn"),
+    ("aws_api_gateway_rest_api", "api_gateway"),
+    ("aws_apigatewayv2_api", "api_gateway"),
+    ("aws_instance", "generic"),
+    ("aws_ecs_service", "container"),
+    ("aws_eks_cluster", "kubernetes"),
+    ("aws_lambda_function", "serverless_function"),
+    ("aws_sqs_queue", "message_queue"),
+    ("aws_mq_broker", "message_queue"),
+    ("aws_sns_topic", "event_bus"),
+    ("aws_msk_cluster", "event_bus"),
+    ("aws_kinesis_stream", "stream_processor"),
+    ("aws_secretsmanager_secret", "secret_manager"),
+    ("aws_kms_key", "key_management"),
+    ("aws_cognito_user_pool", "identity_provider"),
+    ("aws_ecr_repository", "container_registry"),
+    ("aws_wafv2_web_acl", "waf"),
+];
+
+/// Resource types read for the network they describe rather than drawn.
+const NETWORK_TYPES: &[&str] = &[
+    "aws_vpc",
+    "aws_subnet",
+    "aws_db_subnet_group",
+    "aws_elasticache_subnet_group",
+    "aws_security_group",
+    "aws_security_group_rule",
+    "aws_vpc_security_group_ingress_rule",
+    "aws_vpc_security_group_egress_rule",
+];
+
+/// Attributes naming the security groups a resource is attached to, at the
+/// top level or inside a `vpc_config` or `network_configuration` block.
+const SECURITY_GROUP_ATTRIBUTES: &[&str] = &[
+    "vpc_security_group_ids",
+    "security_groups",
+    "security_group_ids",
+];
+
+/// Attributes naming the subnets a resource runs in.
+const SUBNET_ATTRIBUTES: &[&str] = &["subnet_id", "subnet_ids", "subnets"];
+
+/// Attributes naming a subnet group, whose `subnet_ids` are the subnets.
+const SUBNET_GROUP_ATTRIBUTES: &[&str] = &["db_subnet_group_name", "subnet_group_name"];
+
+/// Blocks a resource's network placement may be nested in.
+const NETWORK_BLOCKS: &[&str] = &["vpc_config", "network_configuration"];
+
+/// CIDR blocks that stand for the whole internet.
+const INTERNET_CIDRS: &[&str] = &["0.0.0.0/0", "::/0"];
+
+/// The group `type` of a subnet.
+const SUBNET_GROUP_TYPE: &str = "subnet";
+
+#[derive(Debug, Deserialize)]
+struct TfDocument {
+    /// Present in plan output.
+    planned_values: Option<TfValues>,
+    /// Present in state output.
+    values: Option<TfValues>,
+    #[serde(default)]
+    configuration: TfConfiguration,
+}
+
+#[derive(Debug, Deserialize)]
+struct TfValues {
+    #[serde(default)]
+    root_module: TfModule,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TfModule {
+    #[serde(default)]
+    resources: Vec<TfResource>,
+    #[serde(default)]
+    child_modules: Vec<TfModule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TfResource {
+    address: String,
+    #[serde(default)]
+    mode: String,
+    #[serde(rename = "type")]
+    resource_type: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    values: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TfConfiguration {
+    #[serde(default)]
+    root_module: TfConfigModule,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TfConfigModule {
+    #[serde(default)]
+    resources: Vec<TfConfigResource>,
+    #[serde(default)]
+    module_calls: BTreeMap<String, TfModuleCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TfModuleCall {
+    #[serde(default)]
+    module: TfConfigModule,
+}
+
+#[derive(Debug, Deserialize)]
+struct TfConfigResource {
+    address: String,
+    #[serde(default)]
+    expressions: Value,
+}
+
+/// A managed resource with its configuration expressions, if the plan has
+/// them.
+struct Resource<'a> {
+    address: &'a str,
+    /// The address without `count` or `for_each` indexes.
+    base_address: String,
+    /// `module.<name>.` for each module the resource is declared in.
+    module_prefix: String,
+    resource_type: &'a str,
+    name: &'a str,
+    values: &'a Value,
+    expressions: &'a Value,
+}
+
+impl Resource<'_> {
+    /// The resource's display name: its `Name` tag, then the identifiers AWS
+    /// shows, then its Terraform name.
+    fn display_name(&self) -> &str {
+        let tag = self.values.pointer("/tags/Name");
+        [
+            "name",
+            "identifier",
+            "bucket",
+            "function_name",
+            "cluster_id",
+        ]
+        .iter()
+        .map(|key| self.values.get(key))
+        .fold(tag, |found, next| found.or(next))
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(self.name)
+    }
+
+    /// An attribute's value and configuration expression, read from the
+    /// first `block` when one is given.
+    fn attribute(&self, block: Option<&str>, key: &str) -> (&Value, &Value) {
+        match block {
+            Some(block) => (
+                &self.values[block][0][key],
+                &self.expressions[block][0][key],
+            ),
+            None => (&self.values[key], &self.expressions[key]),
+        }
+    }
+}
+
+/// Parse `terraform show -json` output into a model skeleton.
+pub fn parse_terraform(json: &str) -> Result<ImportedModel, TerraformError> {
+    let document: TfDocument = serde_json::from_str(json)?;
+    let root = document
+        .planned_values
+        .as_ref()
+        .or(document.values.as_ref())
+        .map(|v| &v.root_module);
+
+    let mut expressions: HashMap<String, &Value> = HashMap::new();
+    collect_expressions(&document.configuration.root_module, "", &mut expressions);
+
+    let mut planned: Vec<&TfResource> = Vec::new();
+    if let Some(root) = root {
+        collect_resources(root, &mut planned);
+    }
+    let mut report = ImportReport::default();
+    for resource in &planned {
+        if resource.mode != "managed" {
+            report.skip(
+                &resource.address,
+                "data sources describe existing infrastructure, not this deployment",
+            );
+        } else if !resource.resource_type.starts_with("aws_") {
+            report.skip(&resource.address, "only AWS resources are read");
+        }
+    }
+    let resources: Vec<Resource> = planned
+        .into_iter()
+        .filter(|r| r.mode == "managed" && r.resource_type.starts_with("aws_"))
+        .map(|r| {
+            let base_address = without_indexes(&r.address);
+            let module_prefix = base_address
+                .rfind(&format!("{}.{}", r.resource_type, r.name))
+                .map_or("", |at| &base_address[..at])
+                .to_string();
+            Resource {
+                expressions: expressions
+                    .get(&base_address)
+                    .copied()
+                    .unwrap_or(&Value::Null),
+                address: &r.address,
+                base_address,
+                module_prefix,
+                resource_type: &r.resource_type,
+                name: &r.name,
+                values: &r.values,
+            }
+        })
+        .collect();
+    if resources.is_empty() {
+        return Err(TerraformError::NoResources);
+    }
+
+    let of_type = |indexes: Vec<usize>, resource_type: &str| -> Vec<usize> {
+        indexes
+            .into_iter()
+            .filter(|&i| resources[i].resource_type == resource_type)
+            .collect()
+    };
+
+    let mut node_counter: HashMap<String, u32> = HashMap::new();
+    let mut flow_counter: HashMap<String, u32> = HashMap::new();
+
+    // Subnets and VPCs first, so element membership can name them.
+    let mut vpc_ids: HashMap<usize, String> = HashMap::new();
+    for (index, resource) in resources.iter().enumerate() {
+        if resource.resource_type == "aws_vpc" {
+            vpc_ids.insert(
+                index,
+                report.unique_id(resource.display_name(), "vpc", &mut node_counter),
+            );
+        }
+    }
+    let vpc_of = |index: usize| -> Option<usize> {
+        let (values, expressions) = resources[index].attribute(None, "vpc_id");
+        of_type(resolve(&resources, index, values, expressions), "aws_vpc")
+            .first()
+            .copied()
+    };
+    let mut groups: Vec<Group> = Vec::new();
+    let mut subnet_ids: HashMap<usize, String> = HashMap::new();
+    for (index, resource) in resources.iter().enumerate() {
+        if resource.resource_type != "aws_subnet" {
+            continue;
+        }
+        let id = report.unique_id(resource.display_name(), "subnet", &mut node_counter);
+        let public = resource.values["map_public_ip_on_launch"].as_bool();
+        groups.push(Group {
+            id: id.clone(),
+            name: resource.display_name().to_string(),
+            r#type: Some(SUBNET_GROUP_TYPE.to_string()),
+            parent: None,
+            description: public.map(|public| {
+                if public {
+                    "Public subnet".to_string()
+                } else {
+                    "Private subnet".to_string()
+                }
+            }),
+            position: None,
+            size: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+        });
+        subnet_ids.insert(index, id);
+    }
+
+    let mut elements: Vec<Element> = Vec::new();
+    // Resource index of each element, and the security groups it is in.
+    let mut placed: Vec<(usize, Vec<usize>)> = Vec::new();
+    let mut vpc_members: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for (index, resource) in resources.iter().enumerate() {
+        let Some((_, element_type)) = ELEMENT_TYPES
+            .iter()
+            .find(|(resource_type, _)| *resource_type == resource.resource_type)
+        else {
+            if !NETWORK_TYPES.contains(&resource.resource_type) {
+                report.skip(
+                    resource.address,
+                    format!("{} has no element type", resource.resource_type),
+                );
+            }
+            continue;
+        };
+        let id = report.unique_id(resource.display_name(), "resource", &mut node_counter);
+        let security_groups = attached(&resources, index, SECURITY_GROUP_ATTRIBUTES);
+        let security_groups = of_type(security_groups, "aws_security_group");
+        let subnets = of_type(subnets_of(&resources, index), "aws_subnet");
+        let vpc = subnets
+            .iter()
+            .chain(&security_groups)
+            .find_map(|&i| vpc_of(i));
+
+        let trust_zone = vpc.map_or("", |vpc| resources[vpc].display_name());
+        let mut resource_element = element(
+            id.clone(),
+            element_type,
+            resource.display_name(),
+            trust_zone,
+        );
+        resource_element.group = subnets.first().map(|subnet| subnet_ids[subnet].clone());
+        resource_element.technologies = std::iter::once(resource.resource_type)
+            .chain(resource.values["engine"].as_str())
+            .map(str::to_string)
+            .collect();
+        elements.push(resource_element);
+        if let Some(vpc) = vpc {
+            vpc_members.entry(vpc).or_default().push(id);
+        }
+        placed.push((index, security_groups));
+    }
+
+    let mut data_flows: Vec<DataFlow> = Vec::new();
+    let mut client: Option<String> = None;
+    for rule in ingress_rules(&resources) {
+        let targets = placed
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, groups))| groups.contains(&rule.security_group));
+        for (target, (target_index, _)) in targets {
+            let target_id = elements[target].id.clone();
+            let engine = resources[*target_index].values["engine"].as_str();
+            let protocol = protocol_for(engine, rule.port);
+            let mut sources: Vec<String> = placed
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, groups))| groups.iter().any(|g| rule.sources.contains(g)))
+                .map(|(source, _)| elements[source].id.clone())
+                .collect();
+            if rule.from_internet {
+                let client = client.get_or_insert_with(|| {
+                    report.unique_id(EXTERNAL_CLIENT_NAME, "client", &mut node_counter)
+                });
+                sources.insert(0, client.clone());
+            }
+            for source in sources {
+                if source == target_id
+                    || data_flows
+                        .iter()
+                        .any(|f| f.from == source && f.to == target_id)
+                {
+                    continue;
+                }
+                let source_name = elements
+                    .iter()
+                    .find(|e| e.id == source)
+                    .map_or(EXTERNAL_CLIENT_NAME, |e| e.name.as_str());
+                let flow_name = format!("{source_name} to {}", elements[target].name);
+                data_flows.push(flow(
+                    report.unique_id(&flow_name, "flow", &mut flow_counter),
+                    flow_name,
+                    &source,
+                    &target_id,
+                    protocol,
+                ));
+            }
+        }
+    }
+    if let Some(client) = client {
+        elements.push(element(
+            client,
+            "external_entity",
+            EXTERNAL_CLIENT_NAME,
+            "external",
+        ));
+    }
+
+    let trust_boundaries = vpc_ids
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(vpc, id)| {
+            boundary(
+                id.clone(),
+                resources[*vpc].display_name(),
+                vpc_members.remove(vpc).unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let mut model = skeleton_model(
+        "Imported Threat Model",
+        elements,
+        data_flows,
+        trust_boundaries,
+    );
+    model.groups = groups;
+    Ok(ImportedModel::new(model, report))
+}
+
+/// A resource or module address with its `count` and `for_each` indexes
+/// dropped, so every instance shares the address configuration uses.
+fn without_indexes(address: &str) -> String {
+    let mut base = String::with_capacity(address.len());
+    let mut depth = 0;
+    for c in address.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ if depth == 0 => base.push(c),
+            _ => {}
+        }
+    }
+    base
+}
+
+fn collect_resources<'a>(module: &'a TfModule, out: &mut Vec<&'a TfResource>) {
+    out.extend(&module.resources);
+    for child in &module.child_modules {
+        collect_resources(child, out);
+    }
+}
+
+fn collect_expressions<'a>(
+    module: &'a TfConfigModule,
+    prefix: &str,
+    out: &mut HashMap<String, &'a Value>,
+) {
+    for resource in &module.resources {
+        out.insert(
+            format!("{prefix}{}", resource.address),
+            &resource.expressions,
+        );
+    }
+    for (name, call) in &module.module_calls {
+        collect_expressions(&call.module, &format!("{prefix}module.{name}."), out);
+    }
+}
+
+/// The resources an attribute points at, by the ids, ARNs or names in its
+/// value and by the addresses its configuration expression references.
+fn resolve(resources: &[Resource], from: usize, values: &Value, expressions: &Value) -> Vec<usize> {
+    let mut found: Vec<usize> = Vec::new();
+    let literals = match values {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    for literal in literals {
+        found.extend((0..resources.len()).filter(|&i| {
+            ["id", "arn", "name"]
+                .iter()
+                .any(|key| resources[i].values[key].as_str() == Some(literal))
+        }));
+    }
+    let references = expressions["references"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for reference in references {
+        let address = format!(
+            "{}{}",
+            resources[from].module_prefix,
+            without_indexes(reference)
+        );
+        found.extend((0..resources.len()).filter(|&i| resources[i].base_address == address));
+    }
+    let mut unique = Vec::new();
+    for index in found {
+        if index != from && !unique.contains(&index) {
+            unique.push(index);
+        }
+    }
+    unique
+}
+
+/// The resources named by any of `attributes`, at the top level or in a
+/// network block.
+fn attached(resources: &[Resource], index: usize, attributes: &[&str]) -> Vec<usize> {
+    let mut found = Vec::new();
+    for block in std::iter::once(None).chain(NETWORK_BLOCKS.iter().map(|b| Some(*b))) {
+        for key in attributes {
+            let (values, expressions) = resources[index].attribute(block, key);
+            found.extend(resolve(resources, index, values, expressions));
+        }
+    }
+    found
+}
+
+/// The subnets a resource runs in, directly or through a subnet group.
+fn subnets_of(resources: &[Resource], index: usize) -> Vec<usize> {
+    let mut subnets = attached(resources, index, SUBNET_ATTRIBUTES);
+    for group in attached(resources, index, SUBNET_GROUP_ATTRIBUTES) {
+        subnets.extend(attached(resources, group, SUBNET_ATTRIBUTES));
+    }
+    subnets
+}
+
+/// One ingress rule of a security group.
+struct IngressRule {
+    security_group: usize,
+    port: Option<u16>,
+    /// Security groups the rule admits traffic from.
+    sources: Vec<usize>,
+    from_internet: bool,
+}
+
+/// Every ingress rule, whether inline in `aws_security_group` or a rule
+/// resource of its own.
+fn ingress_rules(resources: &[Resource]) -> Vec<IngressRule> {
+    let port_of = |value: &Value| {
+        value
+            .as_u64()
+            .and_then(|p| u16::try_from(p).ok())
+            .filter(|p| *p > 0)
+    };
+    let open_to_internet = |values: &[&Value]| {
+        values.iter().any(|value| match value {
+            Value::String(cidr) => INTERNET_CIDRS.contains(&cidr.as_str()),
+            Value::Array(cidrs) => cidrs
+                .iter()
+                .any(|c| c.as_str().is_some_and(|c| INTERNET_CIDRS.contains(&c))),
+            _ => false,
+        })
+    };
+
+    let mut rules = Vec::new();
+    for (index, resource) in resources.iter().enumerate() {
+        match resource.resource_type {
+            "aws_security_group" => {
+                // Inline blocks; a plan only knows them from the configuration
+                // when they reference groups not created yet.
+                let from_values = resource.values["ingress"].as_array();
+                let from_config = resource.expressions["ingress"].as_array();
+                let count = from_values.or(from_config).map_or(0, Vec::len);
+                for i in 0..count {
+                    let values = &resource.values["ingress"][i];
+                    let expressions = &resource.expressions["ingress"][i];
+                    let field = |key: &str| {
+                        if values[key].is_null() {
+                            &expressions[key]["constant_value"]
+                        } else {
+                            &values[key]
+                        }
+                    };
+                    let mut sources = resolve(
+                        resources,
+                        index,
+                        &values["security_groups"],
+                        &expressions["security_groups"],
+                    );
+                    if field("self").as_bool() == Some(true) {
+                        sources.push(index);
+                    }
+                    rules.push(IngressRule {
+                        security_group: index,
+                        port: port_of(field("from_port")),
+                        sources,
+                        from_internet: open_to_internet(&[
+                            field("cidr_blocks"),
+                            field("ipv6_cidr_blocks"),
+                        ]),
+                    });
+                }
+            }
+            "aws_security_group_rule" | "aws_vpc_security_group_ingress_rule" => {
+                let standalone = resource.resource_type == "aws_security_group_rule";
+                if standalone && resource.values["type"].as_str() != Some("ingress") {
+                    continue;
+                }
+                let field = |key: &str| resource.attribute(None, key);
+                let (values, expressions) = field("security_group_id");
+                let Some(security_group) = resolve(resources, index, values, expressions)
+                    .into_iter()
+                    .find(|&i| resources[i].resource_type == "aws_security_group")
+                else {
+                    continue;
+                };
+                let source_key = if standalone {
+                    "source_security_group_id"
+                } else {
+                    "referenced_security_group_id"
+                };
+                let (values, expressions) = field(source_key);
+                let mut sources = resolve(resources, index, values, expressions);
+                if resource.values["self"].as_bool() == Some(true) {
+                    sources.push(security_group);
+                }
+                rules.push(IngressRule {
+                    security_group,
+                    port: port_of(&resource.values["from_port"]),
+                    sources,
+                    from_internet: open_to_internet(&[
+                        &resource.values["cidr_blocks"],
+                        &resource.values["ipv6_cidr_blocks"],
+                        &resource.values["cidr_ipv4"],
+                        &resource.values["cidr_ipv6"],
+                    ]),
+                });
+            }
+            _ => {}
+        }
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::{assert_matches_pinned, stride_on_valid_import};
+
+    /// A plan covering every construct the importer reads: inline and
+    /// standalone ingress rules, subnet groups, `count` and `for_each`
+    /// instances and a child module.
+    const FIXTURE_JSON: &str = include_str!("fixtures/terraform-sample.plan.json");
+
+    /// The import result pinned for [`FIXTURE_JSON`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/terraform-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_terraform(FIXTURE_JSON)
+            .expect("fixture should import")
+            .model;
+        assert_matches_pinned(
+            model,
+            FIXTURE_EXPECTED_YAML,
+            "terraform-sample.expected.yaml",
+        );
+    }
+
+    #[test]
+    fn skeleton_is_valid_and_ready_for_stride() {
+        let model = parse_terraform(FIXTURE_JSON)
+            .expect("fixture should import")
+            .model;
+        let threats = stride_on_valid_import(&model);
+        assert!(threats
+            .iter()
+            .any(|t| t.flow.as_deref() == Some("external-client-to-shop-web")));
+        assert!(threats
+            .iter()
+            .any(|t| t.element.as_deref() == Some("orders-db")));
+    }
+
+    #[test]
+    fn state_references_resolve_through_ids() {
+        let state = r#"{
+          "format_version": "1.0",
+          "values": { "root_module": { "resources": [
+            { "address": "aws_vpc.main", "mode": "managed", "type": "aws_vpc", "name": "main",
+              "values": { "id": "vpc-1" } },
+            { "address": "aws_subnet.a", "mode": "managed", "type": "aws_subnet", "name": "a",
+              "values": { "id": "subnet-1", "vpc_id": "vpc-1" } },
+            { "address": "aws_security_group.web", "mode": "managed",
+              "type": "aws_security_group", "name": "web",
+              "values": { "id": "sg-1", "vpc_id": "vpc-1", "ingress": [
+                { "from_port": 80, "cidr_blocks": ["0.0.0.0/0"], "security_groups": [] }
+              ] } },
+            { "address": "aws_instance.web", "mode": "managed", "type": "aws_instance",
+              "name": "web", "values": { "id": "i-1", "subnet_id": "subnet-1",
+              "vpc_security_group_ids": ["sg-1"] } }
+          ] } }
+        }"#;
+        let model = parse_terraform(state).expect("state should import").model;
+
+        let web = &model.elements[0];
+        assert_eq!((web.id.as_str(), web.group.as_deref()), ("web", Some("a")));
+        assert_eq!(model.trust_boundaries[0].contains, vec!["web"]);
+        assert_eq!(model.data_flows.len(), 1);
+        assert_eq!(model.data_flows[0].to, "web");
+        assert_eq!(model.data_flows[0].protocol, "HTTP");
+    }
+
+    #[test]
+    fn report_lists_resources_left_out_and_renamed() {
+        let state = r#"{
+          "format_version": "1.0",
+          "values": { "root_module": { "resources": [
+            { "address": "data.aws_caller_identity.current", "mode": "data",
+              "type": "aws_caller_identity", "name": "current", "values": {} },
+            { "address": "aws_s3_bucket.logs[0]", "mode": "managed", "type": "aws_s3_bucket",
+              "name": "logs", "values": { "bucket": "shop" } },
+            { "address": "aws_s3_bucket.logs[1]", "mode": "managed", "type": "aws_s3_bucket",
+              "name": "logs", "values": { "bucket": "shop" } },
+            { "address": "aws_iam_role.app", "mode": "managed", "type": "aws_iam_role",
+              "name": "app", "values": {} },
+            { "address": "google_storage_bucket.backup", "mode": "managed",
+              "type": "google_storage_bucket", "name": "backup", "values": {} }
+          ] } }
+        }"#;
+        let report = parse_terraform(state).expect("state should import").report;
+
+        let skipped: Vec<_> = report
+            .skipped
+            .iter()
+            .map(|s| (s.item.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                (
+                    "data.aws_caller_identity.current",
+                    "data sources describe existing infrastructure, not this deployment"
+                ),
+                (
+                    "google_storage_bucket.backup",
+                    "only AWS resources are read"
+                ),
+                ("aws_iam_role.app", "aws_iam_role has no element type"),
+            ]
+        );
+        let renames: Vec<_> = report
+            .renames
+            .iter()
+            .map(|r| (r.name.as_str(), r.id.as_str()))
+            .collect();
+        assert_eq!(renames, [("shop", "shop-2")]);
+    }
+
+    #[test]
+    fn files_without_resources_are_rejected() {
+        let err = parse_terraform(r#"{"format_version": "1.2", "planned_values": {}}"#)
+            .expect_err("no resources");
+        assert!(matches!(err, TerraformError::NoResources));
+
+        let err = parse_terraform("{").expect_err("not JSON");
+        assert!(
+            err.to_string().starts_with("JSON parsing error: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+}
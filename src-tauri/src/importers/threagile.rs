@@ -0,0 +1,939 @@
+//! Parser for Threagile YAML models.
+//!
+//! Technical assets become elements, typed after their `technology` (or their
+//! `type` when the technology has no counterpart), and their communication
+//! links become data flows carrying the titles of the data assets they send
+//! and receive, `authenticated` unless their authentication is `none`. Trust
+//! boundaries hold the assets inside them and shared runtimes become groups
+//! of the assets they run.
+//!
+//! Threagile computes its built-in risks when it runs, so a model only names
+//! them through `risk_tracking`; each tracked risk becomes a threat on the
+//! asset or link its id points at, with the STRIDE category Threagile gives
+//! the risk category and the tracking status as its mitigation. Individual
+//! risk categories carry their own risks, and become threats whether tracked
+//! or not.
+//!
+//! Abuse cases, security requirements, questions, data asset ratings, nested
+//! boundaries and wildcard risk tracking have no counterpart and go into the
+//! [`ImportReport`], as do links and risks pointing at nothing imported.
+
+use super::report::{ImportReport, ImportedModel};
+use super::services::{boundary, element, flow, skeleton_model};
+use super::threat_dragon::OUT_OF_SCOPE_TAG;
+use super::tm7::make_unique_id;
+use crate::models::{Group, Mitigation, MitigationStatus, Severity, StrideCategory, Threat};
+use serde::de::{DeserializeOwned, Deserializer, Error as _};
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ThreagileError {
+    #[error("YAML parsing error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("No technical assets found; is this a Threagile model?")]
+    NoTechnicalAssets,
+}
+
+/// Group type of an imported shared runtime.
+const SHARED_RUNTIME_GROUP_TYPE: &str = "shared_runtime";
+
+/// Element type of each Threagile technology with a counterpart.
+const TECHNOLOGIES: &[(&[&str], &str)] = &[
+    (&["browser"], "web_browser"),
+    (&["desktop", "devops-client"], "desktop_app"),
+    (&["mobile-app"], "mobile_app"),
+    (&["cli", "tool"], "cli_tool"),
+    (&["web-server", "web-application", "cms"], "web_server"),
+    (
+        &[
+            "application-server",
+            "web-service-rest",
+            "web-service-soap",
+            "ejb",
+            "erp",
+        ],
+        "microservice",
+    ),
+    (&["gateway"], "api_gateway"),
+    (&["function"], "serverless_function"),
+    (
+        &["batch-processing", "scheduler", "event-listener", "task"],
+        "background_worker",
+    ),
+    (&["database"], "sql_database"),
+    (
+        &["file-server", "local-file-system", "block-storage"],
+        "file_storage",
+    ),
+    (&["search-index", "search-engine"], "search_index"),
+    (&["data-lake", "big-data-platform"], "data_lake"),
+    (&["message-queue"], "message_queue"),
+    (&["stream-processing"], "stream_processor"),
+    (&["reverse-proxy"], "proxy"),
+    (&["load-balancer"], "load_balancer"),
+    (&["waf"], "waf"),
+    (&["ips"], "firewall"),
+    (&["ids", "monitoring"], "siem"),
+    (&["service-mesh"], "service_mesh"),
+    (&["container-platform"], "kubernetes"),
+    (
+        &["build-pipeline", "sourcecode-repository"],
+        "ci_cd_pipeline",
+    ),
+    (&["artifact-registry"], "container_registry"),
+    (
+        &[
+            "identity-provider",
+            "identity-store-ldap",
+            "identity-store-database",
+            "ldap-server",
+        ],
+        "identity_provider",
+    ),
+    (&["vault"], "secret_manager"),
+    (&["hsm"], "key_management"),
+    (&["iot-device"], "iot_device"),
+];
+
+/// STRIDE category of each built-in Threagile risk category. Any other
+/// category is tampering.
+const RISK_CATEGORIES: &[(&str, StrideCategory)] = &[
+    (
+        "accidental-secret-leak",
+        StrideCategory::InformationDisclosure,
+    ),
+    ("code-backdooring", StrideCategory::Tampering),
+    ("container-baseimage-backdooring", StrideCategory::Tampering),
+    (
+        "container-platform-escape",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("cross-site-request-forgery", StrideCategory::Spoofing),
+    ("cross-site-scripting", StrideCategory::Tampering),
+    (
+        "dos-risky-access-across-trust-boundary",
+        StrideCategory::DenialOfService,
+    ),
+    ("incomplete-model", StrideCategory::InformationDisclosure),
+    ("ldap-injection", StrideCategory::Tampering),
+    (
+        "missing-authentication",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    (
+        "missing-authentication-second-factor",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("missing-build-infrastructure", StrideCategory::Tampering),
+    ("missing-cloud-hardening", StrideCategory::Tampering),
+    ("missing-file-validation", StrideCategory::Spoofing),
+    ("missing-hardening", StrideCategory::Tampering),
+    (
+        "missing-identity-propagation",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    (
+        "missing-identity-provider-isolation",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("missing-identity-store", StrideCategory::Spoofing),
+    (
+        "missing-network-segmentation",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("missing-vault", StrideCategory::InformationDisclosure),
+    (
+        "missing-vault-isolation",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("missing-waf", StrideCategory::Tampering),
+    (
+        "mixed-targets-on-shared-runtime",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("path-traversal", StrideCategory::InformationDisclosure),
+    ("push-instead-of-pull-deployment", StrideCategory::Tampering),
+    ("search-query-injection", StrideCategory::Tampering),
+    (
+        "server-side-request-forgery",
+        StrideCategory::InformationDisclosure,
+    ),
+    ("service-registry-poisoning", StrideCategory::Spoofing),
+    ("sql-nosql-injection", StrideCategory::Tampering),
+    ("unchecked-deployment", StrideCategory::Tampering),
+    ("unencrypted-asset", StrideCategory::InformationDisclosure),
+    (
+        "unencrypted-communication",
+        StrideCategory::InformationDisclosure,
+    ),
+    (
+        "unguarded-access-from-internet",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    (
+        "unguarded-direct-datastore-access",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    (
+        "unnecessary-communication-link",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    (
+        "unnecessary-data-asset",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    (
+        "unnecessary-data-transfer",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    (
+        "unnecessary-technical-asset",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("untrusted-deserialization", StrideCategory::Tampering),
+    (
+        "wrong-communication-link-content",
+        StrideCategory::InformationDisclosure,
+    ),
+    (
+        "wrong-trust-boundary-content",
+        StrideCategory::ElevationOfPrivilege,
+    ),
+    ("xml-external-entity", StrideCategory::InformationDisclosure),
+];
+
+/// Top-level sections with nothing to map onto.
+const UNMAPPED_SECTIONS: &[(&str, &str)] = &[
+    ("abuse_cases", "abuse cases have no counterpart"),
+    (
+        "security_requirements",
+        "security requirements have no counterpart",
+    ),
+    ("questions", "questions and answers have no counterpart"),
+];
+
+#[derive(Debug, Deserialize)]
+struct ThreagileModel {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author: ThreagileAuthor,
+    #[serde(default)]
+    business_overview: ThreagileOverview,
+    #[serde(default, deserialize_with = "ordered")]
+    data_assets: Vec<(String, ThreagileDataAsset)>,
+    #[serde(default, deserialize_with = "ordered")]
+    technical_assets: Vec<(String, ThreagileTechnicalAsset)>,
+    #[serde(default, deserialize_with = "ordered")]
+    trust_boundaries: Vec<(String, ThreagileTrustBoundary)>,
+    #[serde(default, deserialize_with = "ordered")]
+    shared_runtimes: Vec<(String, ThreagileSharedRuntime)>,
+    #[serde(default, deserialize_with = "ordered")]
+    individual_risk_categories: Vec<(String, ThreagileRiskCategory)>,
+    #[serde(default, deserialize_with = "ordered")]
+    risk_tracking: Vec<(String, ThreagileRiskTracking)>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ThreagileAuthor {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ThreagileOverview {
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileDataAsset {
+    #[serde(default)]
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileTechnicalAsset {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default, rename = "type")]
+    asset_type: String,
+    #[serde(default)]
+    technology: String,
+    #[serde(default)]
+    out_of_scope: bool,
+    #[serde(default)]
+    internet: bool,
+    #[serde(default)]
+    encryption: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    data_assets_stored: Vec<String>,
+    #[serde(default, deserialize_with = "ordered")]
+    communication_links: Vec<(String, ThreagileLink)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileLink {
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    protocol: String,
+    #[serde(default)]
+    authentication: String,
+    #[serde(default)]
+    data_assets_sent: Vec<String>,
+    #[serde(default)]
+    data_assets_received: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileTrustBoundary {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    technical_assets_inside: Vec<String>,
+    #[serde(default)]
+    trust_boundaries_nested: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileSharedRuntime {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    technical_assets_running: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileRiskCategory {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    mitigation: String,
+    #[serde(default)]
+    stride: String,
+    #[serde(default, deserialize_with = "ordered")]
+    risks_identified: Vec<(String, ThreagileRisk)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileRisk {
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    most_relevant_data_asset: Option<String>,
+    #[serde(default)]
+    most_relevant_technical_asset: Option<String>,
+    #[serde(default)]
+    most_relevant_communication_link: Option<String>,
+    #[serde(default)]
+    most_relevant_trust_boundary: Option<String>,
+    #[serde(default)]
+    most_relevant_shared_runtime: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThreagileRiskTracking {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    justification: String,
+    #[serde(default)]
+    ticket: String,
+}
+
+/// Threagile keys most sections by title; read them as `(title, value)`
+/// pairs in file order.
+fn ordered<'de, D, T>(deserializer: D) -> Result<Vec<(String, T)>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let mapping = Option::<serde_yaml::Mapping>::deserialize(deserializer)?.unwrap_or_default();
+    mapping
+        .into_iter()
+        .map(|(key, value)| {
+            let key = match key {
+                serde_yaml::Value::String(key) => key,
+                other => serde_yaml::to_string(&other)
+                    .map_err(D::Error::custom)?
+                    .trim_end()
+                    .to_string(),
+            };
+            let value = serde_yaml::from_value(value).map_err(D::Error::custom)?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+pub fn parse_threagile(yaml: &str) -> Result<ImportedModel, ThreagileError> {
+    let tm: ThreagileModel = serde_yaml::from_str(yaml)?;
+    if tm.technical_assets.is_empty() {
+        return Err(ThreagileError::NoTechnicalAssets);
+    }
+    let mut report = ImportReport::default();
+    report_unmapped_sections(yaml, &mut report);
+    if !tm.data_assets.is_empty() {
+        report.skip(
+            "data_assets",
+            "data asset ratings and owners have no counterpart; their titles are kept on flows and stores",
+        );
+    }
+
+    let data_titles: HashMap<&str, &str> = tm
+        .data_assets
+        .iter()
+        .map(|(title, asset)| (asset.id.as_str(), title.as_str()))
+        .collect();
+    let data_title = |id: &String| {
+        data_titles
+            .get(id.as_str())
+            .unwrap_or(&id.as_str())
+            .to_string()
+    };
+
+    let mut id_counter: HashMap<String, u32> = HashMap::new();
+    let mut asset_to_id: HashMap<&str, String> = HashMap::new();
+    let mut elements = Vec::new();
+    for (title, asset) in &tm.technical_assets {
+        let id = report.unique_id(&asset.id, "element", &mut id_counter);
+        asset_to_id.insert(asset.id.as_str(), id.clone());
+        let trust_zone = if asset.internet {
+            "external"
+        } else {
+            "internal"
+        };
+        let element_type = technology_type(asset).unwrap_or_else(|| {
+            let element_type = asset_type(asset);
+            report.fall_back(
+                format!("technical_assets.{}.technology", asset.id),
+                &asset.technology,
+                element_type,
+                "technology not recognised; typed by asset type",
+            );
+            element_type
+        });
+        let mut imported = element(id, element_type, title, trust_zone);
+        imported.description = asset.description.clone();
+        if !asset.technology.is_empty() && asset.technology != "unknown-technology" {
+            imported.technologies.push(asset.technology.clone());
+        }
+        imported.tags = asset.tags.clone();
+        if asset.out_of_scope {
+            imported.tags.push(OUT_OF_SCOPE_TAG.to_string());
+        }
+        if !asset.encryption.is_empty() && asset.encryption != "none" {
+            imported.encryption = Some(asset.encryption.clone());
+        }
+        if !asset.data_assets_stored.is_empty() {
+            imported.stores = Some(asset.data_assets_stored.iter().map(data_title).collect());
+        }
+        elements.push(imported);
+    }
+
+    let mut flow_counter: HashMap<String, u32> = HashMap::new();
+    let mut link_to_id: HashMap<String, String> = HashMap::new();
+    let mut flows = Vec::new();
+    for (_, asset) in &tm.technical_assets {
+        for (title, link) in &asset.communication_links {
+            let Some(to) = asset_to_id.get(link.target.as_str()) else {
+                report.skip(
+                    format!("technical_assets.{}.communication_links.{title}", asset.id),
+                    format!("target {:?} is not a technical asset", link.target),
+                );
+                continue;
+            };
+            let id = report.unique_id(title, "flow", &mut flow_counter);
+            link_to_id.insert(link_id(&asset.id, title), id.clone());
+            let mut imported = flow(
+                id,
+                title.clone(),
+                &asset_to_id[asset.id.as_str()],
+                to,
+                &link.protocol,
+            );
+            for data in link
+                .data_assets_sent
+                .iter()
+                .chain(&link.data_assets_received)
+            {
+                let data = data_title(data);
+                if !imported.data.contains(&data) {
+                    imported.data.push(data);
+                }
+            }
+            imported.authenticated =
+                !link.authentication.is_empty() && link.authentication != "none";
+            flows.push(imported);
+        }
+    }
+
+    let mut boundaries = Vec::new();
+    for (title, source) in &tm.trust_boundaries {
+        let contains = source
+            .technical_assets_inside
+            .iter()
+            .filter_map(|asset| asset_to_id.get(asset.as_str()).cloned())
+            .collect();
+        let id = report.unique_id(&source.id, "boundary", &mut id_counter);
+        boundaries.push(boundary(id, title, contains));
+        if !source.trust_boundaries_nested.is_empty() {
+            report.skip(
+                format!("trust_boundaries.{}.trust_boundaries_nested", source.id),
+                format!(
+                    "nests {}; nested trust boundaries are imported side by side",
+                    source.trust_boundaries_nested.join(", ")
+                ),
+            );
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (title, runtime) in &tm.shared_runtimes {
+        let id = report.unique_id(&runtime.id, "group", &mut id_counter);
+        for asset in &runtime.technical_assets_running {
+            let Some(element) = asset_to_id
+                .get(asset.as_str())
+                .and_then(|asset| elements.iter_mut().find(|e| &e.id == asset))
+            else {
+                continue;
+            };
+            if element.group.is_some() {
+                report.skip(
+                    format!(
+                        "shared_runtimes.{}.technical_assets_running.{asset}",
+                        runtime.id
+                    ),
+                    "an element can run on only one shared runtime; it stays in the first",
+                );
+                continue;
+            }
+            element.group = Some(id.clone());
+        }
+        groups.push(Group {
+            id,
+            name: title.clone(),
+            r#type: Some(SHARED_RUNTIME_GROUP_TYPE.to_string()),
+            parent: None,
+            description: (!runtime.description.is_empty()).then(|| runtime.description.clone()),
+            position: None,
+            size: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+        });
+    }
+
+    let mut tracking: Vec<(&str, &ThreagileRiskTracking)> = tm
+        .risk_tracking
+        .iter()
+        .map(|(key, tracked)| (key.as_str(), tracked))
+        .collect();
+    let mut threats = Vec::new();
+
+    for (_, category) in &tm.individual_risk_categories {
+        let stride = parse_stride(&category.stride).unwrap_or_else(|| {
+            let stride = StrideCategory::Tampering;
+            report.fall_back(
+                format!("individual_risk_categories.{}.stride", category.id),
+                &category.stride,
+                stride.as_str(),
+                "not a STRIDE category",
+            );
+            stride
+        });
+        for (title, risk) in &category.risks_identified {
+            let synthetic_id = risk_id(&category.id, risk);
+            let position = tracking.iter().position(|(key, _)| *key == synthetic_id);
+            let tracked = position.map(|position| tracking.remove(position).1);
+            let element = risk
+                .most_relevant_technical_asset
+                .as_deref()
+                .and_then(|asset| asset_to_id.get(asset).cloned());
+            let flow = risk
+                .most_relevant_communication_link
+                .as_deref()
+                .and_then(|link| link_to_id.get(link).cloned());
+            if element.is_none() && flow.is_none() {
+                report.skip(
+                    format!("risks_identified.{synthetic_id}"),
+                    "threats can only sit on a technical asset or communication link",
+                );
+                continue;
+            }
+            let mitigation = match tracked {
+                Some(tracked) => tracked_mitigation(tracked),
+                None => (!category.mitigation.is_empty()).then(|| Mitigation {
+                    status: MitigationStatus::NotStarted,
+                    description: category.mitigation.clone(),
+                }),
+            };
+            threats.push(Threat {
+                id: format!("threat-{}", threats.len() + 1),
+                title: strip_markup(title),
+                category: stride.clone(),
+                element,
+                flow,
+                severity: parse_severity(&risk.severity),
+                description: category.description.clone(),
+                mitigation,
+            });
+        }
+    }
+
+    for (key, tracked) in tracking {
+        let item = format!("risk_tracking.{key}");
+        if key.contains('*') {
+            report.skip(
+                item,
+                "wildcard risk tracking matches risks Threagile computes; only exact risk ids are imported",
+            );
+            continue;
+        }
+        let mut parts = key.split('@');
+        let category = parts.next().unwrap_or_default();
+        let target = parts.find_map(|part| {
+            let element = asset_to_id.get(part).map(|id| (Some(id.clone()), None));
+            element.or_else(|| link_to_id.get(part).map(|id| (None, Some(id.clone()))))
+        });
+        let Some((element, flow)) = target else {
+            report.skip(
+                item,
+                "names no technical asset or communication link to put the threat on",
+            );
+            continue;
+        };
+        let target_name = element
+            .as_ref()
+            .or(flow.as_ref())
+            .and_then(|id| {
+                elements
+                    .iter()
+                    .find(|e| &e.id == id)
+                    .map(|e| e.name.as_str())
+                    .or_else(|| flows.iter().find(|f| &f.id == id).map(|f| f.name.as_str()))
+            })
+            .unwrap_or_default();
+        threats.push(Threat {
+            id: format!("threat-{}", threats.len() + 1),
+            title: format!("{} at {target_name}", humanize(category)),
+            category: risk_category(category).unwrap_or_else(|| {
+                let stride = StrideCategory::Tampering;
+                report.fall_back(
+                    item,
+                    category,
+                    stride.as_str(),
+                    "risk category not recognised",
+                );
+                stride
+            }),
+            element,
+            flow,
+            severity: Severity::Medium,
+            description: String::new(),
+            mitigation: tracked_mitigation(tracked),
+        });
+    }
+
+    let mut model = skeleton_model(&tm.title, elements, flows, boundaries);
+    model.metadata.author = tm.author.name;
+    model.metadata.description = tm.business_overview.description.trim().to_string();
+    model.groups = groups;
+    model.threats = threats;
+
+    Ok(ImportedModel::new(model, report))
+}
+
+/// Report the non-empty top-level sections that have nothing to map onto.
+fn report_unmapped_sections(yaml: &str, report: &mut ImportReport) {
+    let Ok(document) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else {
+        return;
+    };
+    for (section, reason) in UNMAPPED_SECTIONS {
+        let count = match document.get(section) {
+            Some(serde_yaml::Value::Mapping(entries)) => entries.len(),
+            Some(serde_yaml::Value::Sequence(entries)) => entries.len(),
+            _ => 0,
+        };
+        if count > 0 {
+            report.skip(format!("{section} ({count})"), *reason);
+        }
+    }
+}
+
+fn technology_type(asset: &ThreagileTechnicalAsset) -> Option<&'static str> {
+    TECHNOLOGIES
+        .iter()
+        .find(|(technologies, _)| technologies.contains(&asset.technology.as_str()))
+        .map(|(_, element_type)| *element_type)
+}
+
+fn asset_type(asset: &ThreagileTechnicalAsset) -> &'static str {
+    match asset.asset_type.as_str() {
+        "external-entity" => "external_entity",
+        "datastore" => "data_store",
+        "process" => "process",
+        _ => "generic",
+    }
+}
+
+/// Threagile's id for a communication link: the asset's id and the link's
+/// title, kebab-cased.
+fn link_id(asset: &str, title: &str) -> String {
+    let title = make_unique_id(title, "link", &mut HashMap::new());
+    format!("{asset}>{title}")
+}
+
+/// Threagile's synthetic risk id: the category followed by every most
+/// relevant item the risk names.
+fn risk_id(category: &str, risk: &ThreagileRisk) -> String {
+    [
+        &risk.most_relevant_data_asset,
+        &risk.most_relevant_technical_asset,
+        &risk.most_relevant_communication_link,
+        &risk.most_relevant_trust_boundary,
+        &risk.most_relevant_shared_runtime,
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|id| !id.is_empty())
+    .fold(category.to_string(), |id, part| format!("{id}@{part}"))
+}
+
+fn tracked_mitigation(tracked: &ThreagileRiskTracking) -> Option<Mitigation> {
+    let status = match tracked.status.as_str() {
+        "mitigated" => MitigationStatus::Mitigated,
+        "in-progress" => MitigationStatus::InProgress,
+        "accepted" | "false-positive" => MitigationStatus::Accepted,
+        // "unchecked", "in-discussion" and anything else
+        _ => MitigationStatus::NotStarted,
+    };
+    let mut description = tracked.justification.trim().to_string();
+    if tracked.status == "false-positive" {
+        description = format!("False positive. {description}").trim().to_string();
+    }
+    if !tracked.ticket.is_empty() {
+        description = if description.is_empty() {
+            format!("Ticket {}", tracked.ticket)
+        } else {
+            format!("{description} (ticket {})", tracked.ticket)
+        };
+    }
+    Some(Mitigation {
+        status,
+        description,
+    })
+}
+
+fn risk_category(category: &str) -> Option<StrideCategory> {
+    RISK_CATEGORIES
+        .iter()
+        .find(|(id, _)| *id == category)
+        .map(|(_, stride)| stride.clone())
+}
+
+fn parse_stride(stride: &str) -> Option<StrideCategory> {
+    let stride = match stride {
+        "spoofing" => StrideCategory::Spoofing,
+        "tampering" => StrideCategory::Tampering,
+        "repudiation" => StrideCategory::Repudiation,
+        "information-disclosure" => StrideCategory::InformationDisclosure,
+        "denial-of-service" => StrideCategory::DenialOfService,
+        "elevation-of-privilege" => StrideCategory::ElevationOfPrivilege,
+        _ => return None,
+    };
+    Some(stride)
+}
+
+fn parse_severity(severity: &str) -> Severity {
+    match severity {
+        "critical" => Severity::Critical,
+        "high" | "elevated" => Severity::High,
+        "low" => Severity::Low,
+        _ => Severity::Medium,
+    }
+}
+
+/// `unencrypted-communication` → `Unencrypted communication`.
+fn humanize(id: &str) -> String {
+    let words = id.replace('-', " ");
+    let mut chars = words.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Threagile risk titles carry `<b>` markup for its PDF report.
+fn strip_markup(title: &str) -> String {
+    let mut text = String::with_capacity(title.len());
+    let mut in_tag = false;
+    for c in title.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::assert_matches_pinned;
+
+    /// A Threagile model covering each asset type, shared runtimes, nested
+    /// boundaries, an individual risk category and every tracking status.
+    const FIXTURE_YAML: &str = include_str!("fixtures/threagile-sample.yaml");
+
+    /// The import result pinned for [`FIXTURE_YAML`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/threagile-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_threagile(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        assert_matches_pinned(
+            model,
+            FIXTURE_EXPECTED_YAML,
+            "threagile-sample.expected.yaml",
+        );
+    }
+
+    #[test]
+    fn unmapped_constructs_are_reported() {
+        let imported = parse_threagile(FIXTURE_YAML).expect("fixture should import");
+        crate::file_io::validate_references(&imported.model).expect("references resolve");
+
+        let items: Vec<&str> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|skipped| skipped.item.as_str())
+            .collect();
+        assert_eq!(
+            items,
+            [
+                "abuse_cases (2)",
+                "security_requirements (1)",
+                "data_assets",
+                "technical_assets.erp-system.communication_links.Legacy Export",
+                "trust_boundaries.web-dmz.trust_boundaries_nested",
+                "shared_runtimes.backend-cluster.technical_assets_running.sql-database",
+                "risk_tracking.ldap-injection@*@ldap-auth-server@*",
+                "risk_tracking.unnecessary-data-asset@customer-contracts",
+            ]
+        );
+        let fallbacks: Vec<_> = imported
+            .report
+            .fallbacks
+            .iter()
+            .map(|f| (f.item.as_str(), f.source.as_str(), f.mapped_to.as_str()))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            [(
+                "technical_assets.audit-sink.technology",
+                "mainframe",
+                "data_store"
+            )]
+        );
+    }
+
+    #[test]
+    fn tracking_statuses_map_onto_mitigations() {
+        let model = parse_threagile(FIXTURE_YAML)
+            .expect("fixture should import")
+            .model;
+        let status = |title: &str| {
+            model
+                .threats
+                .iter()
+                .find(|t| t.title == title)
+                .and_then(|t| t.mitigation.as_ref())
+                .map(|m| m.status.clone())
+        };
+        assert_eq!(
+            status("Untrusted deserialization at ERP System"),
+            Some(MitigationStatus::Accepted)
+        );
+        assert_eq!(
+            status("Unencrypted communication at Customer Traffic"),
+            Some(MitigationStatus::InProgress)
+        );
+        assert_eq!(
+            status("Example Individual Risk at Database"),
+            Some(MitigationStatus::Mitigated)
+        );
+    }
+
+    #[test]
+    fn links_to_missing_assets_are_skipped() {
+        let yaml = r#"
+technical_assets:
+  Web:
+    id: web
+    type: process
+    communication_links:
+      Lookup:
+        target: cache
+        protocol: https
+      Store:
+        target: db
+        protocol: jdbc
+  Database:
+    id: db
+    type: datastore
+"#;
+        let imported = parse_threagile(yaml).expect("model should import");
+        crate::file_io::validate_references(&imported.model).expect("references resolve");
+
+        let flows: Vec<_> = imported
+            .model
+            .data_flows
+            .iter()
+            .map(|f| (f.from.as_str(), f.to.as_str()))
+            .collect();
+        assert_eq!(flows, [("web", "db")]);
+        let skipped: Vec<_> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|s| (s.item.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [(
+                "technical_assets.web.communication_links.Lookup",
+                "target \"cache\" is not a technical asset"
+            )]
+        );
+    }
+
+    #[test]
+    fn non_models_are_rejected() {
+        let err = parse_threagile("title: Empty\n").expect_err("no technical assets");
+        assert!(matches!(err, ThreagileError::NoTechnicalAssets));
+
+        let err = parse_threagile("technical_assets: [").expect_err("malformed YAML");
+        assert!(
+            err.to_string().starts_with("YAML parsing error: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+}
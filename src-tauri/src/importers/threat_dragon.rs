@@ -0,0 +1,636 @@
+//! Parser for OWASP Threat Dragon v2 JSON models.
+//!
+//! A Threat Dragon model is a list of diagrams, each a flat list of AntV X6
+//! cells: actors, processes and stores become elements, flows become data
+//! flows, boundary boxes become rectangular trust boundaries and boundary
+//! curves become line boundaries. Threats live on the cell they were raised
+//! against and keep their status, severity and mitigation text. Each diagram
+//! becomes its own diagram, with the first as the main one.
+//!
+//! Threat Dragon's per-shape flags mostly have no counterpart here. A web
+//! application process becomes a `web_server`, an encrypted store carries
+//! `encryption: encrypted`, and an out-of-scope element is tagged
+//! [`OUT_OF_SCOPE_TAG`]; the rest are dropped. Flows with a loose end and
+//! shapes with no counterpart are left out and listed in the
+//! [`ImportReport`], as are threat types that are not STRIDE or CIA and
+//! severities or statuses that fall back, `NotApplicable` becoming accepted.
+//! Version 1 models (`diagramJson`) are rejected rather than half-read.
+
+use super::report::{ImportReport, ImportedModel};
+use super::tm7::{imported_diagram, MAIN_DIAGRAM_ID};
+use crate::models::layout::DEFAULT_DIAGRAM_NAME;
+use crate::models::{
+    BoundaryShape, DataFlow, Element, Metadata, Mitigation, MitigationStatus, Position, Severity,
+    Size, StrideCategory, Threat, ThreatModel, TrustBoundary,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Tag marking an element the source tool (Threat Dragon, pytm, Threagile)
+/// holds out of scope.
+pub const OUT_OF_SCOPE_TAG: &str = "out-of-scope";
+
+/// What an encrypted Threat Dragon store, or any imported element marked
+/// encrypted without naming a scheme, records as its `encryption`.
+pub(super) const ENCRYPTED: &str = "encrypted";
+
+#[derive(Debug, Error)]
+pub enum ThreatDragonError {
+    #[error("JSON parsing error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(
+        "Unsupported Threat Dragon model version {0:?}; open and save it in Threat Dragon 2 first"
+    )]
+    UnsupportedVersion(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct TdModel {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    summary: TdSummary,
+    #[serde(default)]
+    detail: TdDetail,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TdSummary {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    owner: String,
+    #[serde(default)]
+    description: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TdDetail {
+    #[serde(default)]
+    diagrams: Vec<TdDiagram>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TdDiagram {
+    #[serde(default)]
+    title: String,
+    /// The diagram's description.
+    #[serde(default)]
+    placeholder: String,
+    #[serde(default)]
+    cells: Vec<TdCell>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TdCell {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    shape: String,
+    position: Option<TdPoint>,
+    size: Option<TdSize>,
+    source: Option<TdEnd>,
+    target: Option<TdEnd>,
+    #[serde(default)]
+    vertices: Vec<TdPoint>,
+    #[serde(default)]
+    data: TdData,
+}
+
+#[derive(Debug, Deserialize)]
+struct TdPoint {
+    x: f64,
+    y: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TdSize {
+    width: f64,
+    height: f64,
+}
+
+/// A flow or boundary end: attached to a cell, or a free point.
+#[derive(Debug, Deserialize)]
+struct TdEnd {
+    cell: Option<String>,
+    x: Option<f64>,
+    y: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TdData {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    out_of_scope: bool,
+    #[serde(default)]
+    is_web_application: bool,
+    #[serde(default)]
+    is_encrypted: bool,
+    #[serde(default)]
+    protocol: String,
+    #[serde(default)]
+    threats: Vec<TdThreat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TdThreat {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default, rename = "type")]
+    threat_type: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    mitigation: String,
+}
+
+/// Parse a Threat Dragon v2 JSON string into a `ThreatModel`.
+pub fn parse_threat_dragon(json: &str) -> Result<ImportedModel, ThreatDragonError> {
+    let td: TdModel = serde_json::from_str(json)?;
+    if !td.version.starts_with("2.") {
+        return Err(ThreatDragonError::UnsupportedVersion(td.version));
+    }
+
+    let mut report = ImportReport::default();
+    let mut id_counter: HashMap<String, u32> = HashMap::new();
+    let mut diagram_counter: HashMap<String, u32> =
+        HashMap::from([(MAIN_DIAGRAM_ID.to_string(), 1)]);
+    let mut elements: Vec<Element> = Vec::new();
+    let mut boundaries: Vec<TrustBoundary> = Vec::new();
+    let mut flows: Vec<DataFlow> = Vec::new();
+    let mut diagrams = Vec::new();
+    let mut threats: Vec<Threat> = Vec::new();
+
+    for (index, diagram) in td.detail.diagrams.iter().enumerate() {
+        let membership =
+            (index > 0).then(|| report.unique_id(&diagram.title, "diagram", &mut diagram_counter));
+        let name = if !diagram.title.is_empty() {
+            diagram.title.clone()
+        } else if index == 0 {
+            DEFAULT_DIAGRAM_NAME.to_string()
+        } else {
+            format!("Diagram {}", index + 1)
+        };
+        let mut imported = imported_diagram(
+            membership.as_deref().unwrap_or(MAIN_DIAGRAM_ID),
+            name.clone(),
+        );
+        imported.description =
+            (!diagram.placeholder.is_empty()).then(|| diagram.placeholder.clone());
+        diagrams.push(imported);
+
+        // Cell ids are only unique within their diagram.
+        let mut cell_to_id: HashMap<&str, String> = HashMap::new();
+        let mut centers: Vec<(String, Position)> = Vec::new();
+        let first_boundary = boundaries.len();
+
+        for cell in &diagram.cells {
+            let item = format!("{name}.cells.{}", cell.id);
+            let element_type = match cell.shape.as_str() {
+                "actor" => "external_entity",
+                "process" if cell.data.is_web_application => "web_server",
+                "process" => "process",
+                "store" => "data_store",
+                "td-text-block" => "text",
+                "trust-boundary-box" | "trust-boundary-curve" => {
+                    boundaries.push(boundary_from_cell(
+                        cell,
+                        membership.clone(),
+                        &mut id_counter,
+                        &mut report,
+                    ));
+                    continue;
+                }
+                "flow" => continue,
+                shape => {
+                    report.skip(item, format!("shape {shape:?} has no counterpart"));
+                    continue;
+                }
+            };
+            let id = report.unique_id(&cell.data.name, element_type, &mut id_counter);
+            cell_to_id.insert(&cell.id, id.clone());
+            if let Some(position) = &cell.position {
+                let (width, height) = cell
+                    .size
+                    .as_ref()
+                    .map_or((100.0, 100.0), |s| (s.width, s.height));
+                centers.push((
+                    id.clone(),
+                    Position {
+                        x: position.x + width / 2.0,
+                        y: position.y + height / 2.0,
+                    },
+                ));
+            }
+            for threat in &cell.data.threats {
+                threats.push(convert_threat(
+                    threat,
+                    Some(id.clone()),
+                    None,
+                    threats.len(),
+                    &item,
+                    &mut report,
+                ));
+            }
+            elements.push(Element {
+                id,
+                element_type: element_type.to_string(),
+                name: cell.data.name.clone(),
+                trust_zone: String::new(),
+                layer: None,
+                group: None,
+                diagram: membership.clone(),
+                subtype: None,
+                icon: None,
+                description: cell.data.description.clone(),
+                technologies: Vec::new(),
+                tags: if cell.data.out_of_scope {
+                    vec![OUT_OF_SCOPE_TAG.to_string()]
+                } else {
+                    Vec::new()
+                },
+                stores: None,
+                encryption: (element_type == "data_store" && cell.data.is_encrypted)
+                    .then(|| ENCRYPTED.to_string()),
+                operations: Vec::new(),
+                position: cell.position.as_ref().map(|p| Position { x: p.x, y: p.y }),
+                fill_color: None,
+                stroke_color: None,
+                fill_opacity: None,
+                stroke_opacity: None,
+                font_size: None,
+                font_weight: None,
+            });
+        }
+
+        // A box holds the elements whose centre it covers.
+        for boundary in &mut boundaries[first_boundary..] {
+            let (Some(p), Some(s)) = (&boundary.position, &boundary.size) else {
+                continue;
+            };
+            boundary.contains = centers
+                .iter()
+                .filter(|(_, c)| {
+                    c.x >= p.x && c.x <= p.x + s.width && c.y >= p.y && c.y <= p.y + s.height
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+        }
+
+        for cell in diagram.cells.iter().filter(|c| c.shape == "flow") {
+            let endpoint = |end: &Option<TdEnd>| {
+                end.as_ref()
+                    .and_then(|e| e.cell.as_deref())
+                    .and_then(|cell| cell_to_id.get(cell))
+                    .cloned()
+            };
+            let item = format!("{name}.cells.{}", cell.id);
+            let (Some(from), Some(to)) = (endpoint(&cell.source), endpoint(&cell.target)) else {
+                report.skip(
+                    item,
+                    "flow does not join two imported elements; its threats are dropped with it",
+                );
+                continue;
+            };
+            let number = flows.len() as u32 + 1;
+            let id = format!("flow-{number}");
+            for threat in &cell.data.threats {
+                threats.push(convert_threat(
+                    threat,
+                    None,
+                    Some(id.clone()),
+                    threats.len(),
+                    &item,
+                    &mut report,
+                ));
+            }
+            flows.push(DataFlow {
+                id,
+                flow_number: Some(number),
+                name: cell.data.name.clone(),
+                from,
+                to,
+                protocol: cell.data.protocol.clone(),
+                data: Vec::new(),
+                authenticated: false,
+                label_offset: None,
+                source_handle: None,
+                target_handle: None,
+                stroke_color: None,
+                stroke_opacity: None,
+            });
+        }
+    }
+
+    if diagrams.is_empty() {
+        diagrams.push(imported_diagram(
+            MAIN_DIAGRAM_ID,
+            DEFAULT_DIAGRAM_NAME.to_string(),
+        ));
+    }
+
+    let today = chrono::Local::now().date_naive();
+    let model = ThreatModel {
+        version: "1.0".to_string(),
+        metadata: Metadata {
+            title: if td.summary.title.is_empty() {
+                "Imported Threat Model".to_string()
+            } else {
+                td.summary.title
+            },
+            author: td.summary.owner,
+            created: today,
+            modified: today,
+            description: td.summary.description,
+            created_by: None,
+            modified_by: None,
+            last_edit_timestamp: None,
+            threat_analysis_enabled: None,
+            settings: None,
+        },
+        layers: Vec::new(),
+        groups: Vec::new(),
+        elements,
+        data_flows: flows,
+        relationships: Vec::new(),
+        trust_boundaries: boundaries,
+        threats,
+        diagrams,
+    };
+    Ok(ImportedModel::new(model, report))
+}
+
+/// A boundary box keeps its rectangle; a boundary curve keeps its ends and
+/// bend points. Membership of a box is filled in once the diagram's elements
+/// are known.
+fn boundary_from_cell(
+    cell: &TdCell,
+    diagram: Option<String>,
+    id_counter: &mut HashMap<String, u32>,
+    report: &mut ImportReport,
+) -> TrustBoundary {
+    let id = report.unique_id(&cell.data.name, "boundary", id_counter);
+    let mut boundary = TrustBoundary {
+        id,
+        name: cell.data.name.clone(),
+        contains: Vec::new(),
+        diagram,
+        shape: None,
+        points: Vec::new(),
+        position: None,
+        size: None,
+        fill_color: None,
+        stroke_color: None,
+        fill_opacity: None,
+        stroke_opacity: None,
+    };
+    if cell.shape == "trust-boundary-curve" {
+        let end = |end: &Option<TdEnd>| match end {
+            Some(TdEnd {
+                x: Some(x),
+                y: Some(y),
+                ..
+            }) => Some(Position { x: *x, y: *y }),
+            _ => None,
+        };
+        boundary.shape = Some(BoundaryShape::Line);
+        boundary.points = end(&cell.source)
+            .into_iter()
+            .chain(cell.vertices.iter().map(|p| Position { x: p.x, y: p.y }))
+            .chain(end(&cell.target))
+            .collect();
+    } else {
+        boundary.position = cell.position.as_ref().map(|p| Position { x: p.x, y: p.y });
+        boundary.size = cell.size.as_ref().map(|s| Size {
+            width: s.width,
+            height: s.height,
+        });
+    }
+    boundary
+}
+
+fn convert_threat(
+    threat: &TdThreat,
+    element: Option<String>,
+    flow: Option<String>,
+    index: usize,
+    cell: &str,
+    report: &mut ImportReport,
+) -> Threat {
+    let item = format!("{cell}.threats.{}", threat.title);
+    let category = parse_threat_type(&threat.threat_type).unwrap_or_else(|| {
+        report.fall_back(
+            item.as_str(),
+            threat.threat_type.as_str(),
+            StrideCategory::Tampering.as_str(),
+            "threat type is neither STRIDE nor CIA",
+        );
+        StrideCategory::Tampering
+    });
+    Threat {
+        id: format!("threat-{}", index + 1),
+        title: threat.title.clone(),
+        category,
+        element,
+        flow,
+        severity: parse_severity(&threat.severity, &item, report),
+        description: threat.description.clone(),
+        mitigation: Some(Mitigation {
+            status: parse_status(&threat.status, &item, report),
+            description: threat.mitigation.clone(),
+        }),
+    }
+}
+
+/// Map a Threat Dragon threat type to a STRIDE category. The CIA model's
+/// types fold onto their STRIDE counterparts; other models' have none.
+fn parse_threat_type(threat_type: &str) -> Option<StrideCategory> {
+    match threat_type.to_ascii_lowercase().as_str() {
+        "spoofing" => Some(StrideCategory::Spoofing),
+        "tampering" | "integrity" => Some(StrideCategory::Tampering),
+        "repudiation" => Some(StrideCategory::Repudiation),
+        "information disclosure" | "confidentiality" => Some(StrideCategory::InformationDisclosure),
+        "denial of service" | "availability" => Some(StrideCategory::DenialOfService),
+        "elevation of privilege" => Some(StrideCategory::ElevationOfPrivilege),
+        _ => None,
+    }
+}
+
+/// Map a Threat Dragon severity; anything but its four levels is medium.
+fn parse_severity(severity: &str, item: &str, report: &mut ImportReport) -> Severity {
+    match severity.to_ascii_lowercase().as_str() {
+        "critical" => Severity::Critical,
+        "high" => Severity::High,
+        "medium" => Severity::Medium,
+        "low" => Severity::Low,
+        _ => {
+            report.fall_back(
+                format!("{item}.severity"),
+                severity,
+                Severity::Medium.as_str(),
+                "severity is not a Threat Dragon level",
+            );
+            Severity::Medium
+        }
+    }
+}
+
+/// Map a Threat Dragon status. `NotApplicable` has no counterpart and is
+/// taken as an accepted risk; an unknown status is not started.
+fn parse_status(status: &str, item: &str, report: &mut ImportReport) -> MitigationStatus {
+    match status {
+        "Open" => MitigationStatus::NotStarted,
+        "Mitigated" => MitigationStatus::Mitigated,
+        "NotApplicable" => {
+            report.fall_back(
+                format!("{item}.status"),
+                status,
+                MitigationStatus::Accepted.as_str(),
+                "not applicable is taken as an accepted risk",
+            );
+            MitigationStatus::Accepted
+        }
+        _ => {
+            report.fall_back(
+                format!("{item}.status"),
+                status,
+                MitigationStatus::NotStarted.as_str(),
+                "status is not a Threat Dragon status",
+            );
+            MitigationStatus::NotStarted
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importers::fixture_tests::assert_matches_pinned;
+
+    /// A Threat Dragon 2.2 model covering every cell shape the importer reads.
+    const FIXTURE_JSON: &str = include_str!("fixtures/threat-dragon-v2-sample.json");
+
+    /// The import result pinned for [`FIXTURE_JSON`], regenerated only when a
+    /// deliberate importer behavior change is being made.
+    const FIXTURE_EXPECTED_YAML: &str =
+        include_str!("fixtures/threat-dragon-v2-sample.expected.yaml");
+
+    #[test]
+    fn fixture_import_is_byte_identical_to_the_pinned_result() {
+        let model = parse_threat_dragon(FIXTURE_JSON)
+            .expect("fixture model should import")
+            .model;
+        assert_matches_pinned(
+            model,
+            FIXTURE_EXPECTED_YAML,
+            "threat-dragon-v2-sample.expected.yaml",
+        );
+    }
+
+    #[test]
+    fn cell_references_resolve_within_their_own_diagram() {
+        // The second diagram's flow names a cell id from the first; cell ids
+        // are only unique per diagram, so it has no source there.
+        let json = FIXTURE_JSON.replace(
+            r#""source": { "cell": "6a7b8c9d-1e2f-4a3b-8c4d-5e6f7a8b9cee" }"#,
+            r#""source": { "cell": "4f1c3e2a-8d6b-4a1f-b2c7-9e0d3a5f6b22" }"#,
+        );
+        let imported = parse_threat_dragon(&json).expect("fixture model should import");
+        let flows: Vec<&str> = imported
+            .model
+            .data_flows
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(flows, ["Browse & Order", "Write order"]);
+        let skipped: Vec<&str> = imported
+            .report
+            .skipped
+            .iter()
+            .map(|s| s.item.as_str())
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                "Storefront.cells.3d4e5f6a-8b9c-4d0e-9f1a-2b3c4d5e6fbb",
+                "Fulfilment.cells.7b8c9d0e-2f3a-4b4c-9d5e-6f7a8b9c0dff"
+            ]
+        );
+    }
+
+    #[test]
+    fn threat_types_outside_stride_and_cia_are_reported() {
+        let json = FIXTURE_JSON.replace(r#""type": "Tampering""#, r#""type": "Linkability""#);
+        let imported = parse_threat_dragon(&json).expect("fixture model should import");
+        let fallbacks: Vec<_> = imported
+            .report
+            .fallbacks
+            .iter()
+            .map(|f| (f.item.as_str(), f.source.as_str(), f.mapped_to.as_str()))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            [
+                (
+                    "Storefront.cells.0a1b2c3d-5e6f-4a7b-8c9d-9e0f1a2b3c88.threats.\
+                     Session cookie theft over \"Browse & Order\"",
+                    "Linkability",
+                    "Tampering"
+                ),
+                (
+                    "Storefront.cells.a2d4e6f8-1b3c-4d5e-8f70-1a2b3c4d5e33.threats.\
+                     Order history readable by support staff.status",
+                    "NotApplicable",
+                    "accepted"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_severities_and_statuses_are_reported() {
+        let json = FIXTURE_JSON
+            .replace(r#""severity": "High""#, r#""severity": "info""#)
+            .replace(r#""severity": "Critical""#, r#""severity": """#)
+            .replace(r#""status": "Mitigated""#, r#""status": "Investigating""#);
+        let imported = parse_threat_dragon(&json).expect("fixture model should import");
+        let mut fallbacks: Vec<_> = imported
+            .report
+            .fallbacks
+            .iter()
+            .filter(|f| f.source != "NotApplicable")
+            .map(|f| (f.source.as_str(), f.mapped_to.as_str()))
+            .collect();
+        fallbacks.sort_unstable();
+        assert_eq!(
+            fallbacks,
+            [
+                ("", "medium"),
+                ("Investigating", "not_started"),
+                ("info", "medium")
+            ]
+        );
+    }
+
+    #[test]
+    fn version_1_models_are_rejected() {
+        let v1 = r#"{"summary": {"title": "Old"}, "detail": {"diagrams": [{"diagramJson": {"cells": []}}]}}"#;
+        let err = parse_threat_dragon(v1).expect_err("a v1 model must be rejected");
+        assert!(matches!(err, ThreatDragonError::UnsupportedVersion(ref v) if v.is_empty()));
+
+        let err = parse_threat_dragon("<ThreatModel/>").expect_err("XML is not a model");
+        assert!(
+            err.to_string().starts_with("JSON parsing error: "),
+            "import errors must stay user-safe and prefixed, got: {err}"
+        );
+    }
+}
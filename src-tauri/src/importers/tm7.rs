@@ -3,11 +3,17 @@
 //! TM7 files use .NET DataContract XML serialization with multiple namespaces.
 //! This parser uses `quick-xml` to walk the XML tree and extract elements,
 //! data flows, trust boundaries, and threats, converting them to our
-//! `ThreatModel` format.
+//! `ThreatModel` format. Each drawing surface becomes its own diagram.
+//!
+//! Out-of-scope stencils, connectors left without both ends and untitled
+//! threats are not imported; they go into the [`ImportReport`], as do
+//! stencil types and threat categories that had to be guessed.
 
+use super::report::{ImportReport, ImportedModel};
+use crate::models::layout::DEFAULT_DIAGRAM_NAME;
 use crate::models::{
-    DataFlow, Diagram, Element, Metadata, Mitigation, MitigationStatus, Position, Severity, Size,
-    StrideCategory, Threat, ThreatModel, TrustBoundary, Viewport,
+    BoundaryShape, DataFlow, Diagram, Element, Metadata, Mitigation, MitigationStatus, Position,
+    Severity, Size, StrideCategory, Threat, ThreatModel, TrustBoundary, Viewport,
 };
 use quick_xml::escape::resolve_predefined_entity;
 use quick_xml::events::{BytesRef, BytesStart, Event};
@@ -21,6 +27,17 @@ pub enum Tm7Error {
     Xml(#[from] quick_xml::Error),
 }
 
+/// Id of the diagram built from the first drawing surface.
+pub(super) const MAIN_DIAGRAM_ID: &str = "main-dfd";
+
+/// Intermediate representation of one TM7 drawing surface (a diagram page).
+#[derive(Debug, Default)]
+struct Tm7Surface {
+    name: String,
+    borders: Vec<Tm7Element>,
+    lines: Vec<Tm7Line>,
+}
+
 /// Intermediate representation of a TM7 stencil element.
 #[derive(Debug, Default)]
 struct Tm7Element {
@@ -48,6 +65,9 @@ struct Tm7Line {
     source_y: f64,
     target_x: f64,
     target_y: f64,
+    /// Curve handle; TMT omits it for lines that were never bent.
+    handle_x: Option<f64>,
+    handle_y: Option<f64>,
     /// e.g. Connector, LineBoundary
     line_type: String,
 }
@@ -75,7 +95,7 @@ struct Tm7Meta {
 }
 
 /// Parse a TM7 XML string into a `ThreatModel`.
-pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
+pub fn parse_tm7(xml: &str) -> Result<ImportedModel, Tm7Error> {
     // Reader-level trimming is deliberately left off: quick-xml 0.38 splits a
     // text run at every entity reference, so per-event trimming would eat the
     // spaces around `&amp;` and friends. `read_text_content` trims the
@@ -83,10 +103,8 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
     let mut reader = Reader::from_str(xml);
 
     let mut meta = Tm7Meta::default();
-    let mut border_elements: Vec<Tm7Element> = Vec::new();
-    let mut lines: Vec<Tm7Line> = Vec::new();
+    let mut surfaces: Vec<Tm7Surface> = Vec::new();
     let mut threats: Vec<Tm7Threat> = Vec::new();
-    let mut diagram_name = String::from("Level 0 DFD");
 
     let mut buf = Vec::new();
 
@@ -99,12 +117,7 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
                         meta = parse_meta_information(&mut reader)?;
                     }
                     "DrawingSurfaceModel" => {
-                        let (name, borders, surface_lines) = parse_drawing_surface(&mut reader)?;
-                        if !name.is_empty() {
-                            diagram_name = name;
-                        }
-                        border_elements.extend(borders);
-                        lines.extend(surface_lines);
+                        surfaces.push(parse_drawing_surface(&mut reader)?);
                     }
                     "ThreatInstances" => {
                         threats = parse_threat_instances(&mut reader)?;
@@ -119,141 +132,207 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
         buf.clear();
     }
 
+    let mut report = ImportReport::default();
+
     // Build GUID → kebab-id mapping for elements
     let mut guid_to_id: HashMap<String, String> = HashMap::new();
     let mut id_counter: HashMap<String, u32> = HashMap::new();
 
     let mut thf_elements: Vec<Element> = Vec::new();
     let mut thf_boundaries: Vec<TrustBoundary> = Vec::new();
+    let mut thf_flows: Vec<DataFlow> = Vec::new();
+    let mut thf_diagrams: Vec<Diagram> = Vec::new();
+    let mut flow_counter: u32 = 0;
 
-    for elem in &border_elements {
-        if elem.stencil_type == "BorderBoundary" {
-            let id = make_unique_id(&elem.name, "boundary", &mut id_counter);
-            guid_to_id.insert(elem.guid.clone(), id.clone());
-            thf_boundaries.push(TrustBoundary {
-                id,
-                name: elem.name.clone(),
-                contains: Vec::new(), // populated later by containment check
-                position: Some(Position {
-                    x: elem.left,
-                    y: elem.top,
-                }),
-                size: Some(Size {
-                    width: elem.width,
-                    height: elem.height,
-                }),
-                fill_color: None,
-                stroke_color: None,
-                fill_opacity: None,
-                stroke_opacity: None,
-            });
-        } else if !elem.out_of_scope {
-            let element_type = match elem.generic_type_id.as_str() {
-                "GE.P" => "process",
-                "GE.DS" => "data_store",
-                "GE.EI" => "external_entity",
-                _ => "process",
-            };
-            let id = make_unique_id(&elem.name, element_type, &mut id_counter);
-            guid_to_id.insert(elem.guid.clone(), id.clone());
-            thf_elements.push(Element {
-                id,
-                element_type: element_type.to_string(),
-                name: elem.name.clone(),
-                trust_zone: String::new(),
-                layer: None,
-                group: None,
-                subtype: None,
-                icon: None,
-                description: String::new(),
-                technologies: Vec::new(),
-                tags: Vec::new(),
-                stores: None,
-                encryption: None,
-                position: Some(Position {
-                    x: elem.left,
-                    y: elem.top,
-                }),
-                fill_color: None,
-                stroke_color: None,
-                fill_opacity: None,
-                stroke_opacity: None,
-                font_size: None,
-                font_weight: None,
-            });
+    // The first drawing surface becomes the main diagram, whose members carry
+    // no `diagram` reference; every further surface is a diagram of its own.
+    let mut diagram_counter: HashMap<String, u32> =
+        HashMap::from([(MAIN_DIAGRAM_ID.to_string(), 1)]);
+
+    for (index, surface) in surfaces.iter().enumerate() {
+        let membership = if index == 0 {
+            None
+        } else {
+            Some(report.unique_id(&surface.name, "diagram", &mut diagram_counter))
+        };
+        let diagram_name = if !surface.name.is_empty() {
+            surface.name.clone()
+        } else if index == 0 {
+            DEFAULT_DIAGRAM_NAME.to_string()
+        } else {
+            format!("Diagram {}", index + 1)
+        };
+        thf_diagrams.push(imported_diagram(
+            membership.as_deref().unwrap_or(MAIN_DIAGRAM_ID),
+            diagram_name.clone(),
+        ));
+        let item = |name: &str, guid: &str| {
+            format!(
+                "{diagram_name}.{}",
+                if name.is_empty() { guid } else { name }
+            )
+        };
+
+        let first_element = thf_elements.len();
+        let first_boundary = thf_boundaries.len();
+
+        for elem in &surface.borders {
+            if elem.stencil_type == "BorderBoundary" {
+                let id = report.unique_id(&elem.name, "boundary", &mut id_counter);
+                guid_to_id.insert(elem.guid.clone(), id.clone());
+                thf_boundaries.push(TrustBoundary {
+                    id,
+                    name: elem.name.clone(),
+                    contains: Vec::new(), // populated later by containment check
+                    diagram: membership.clone(),
+                    shape: None,
+                    points: Vec::new(),
+                    position: Some(Position {
+                        x: elem.left,
+                        y: elem.top,
+                    }),
+                    size: Some(Size {
+                        width: elem.width,
+                        height: elem.height,
+                    }),
+                    fill_color: None,
+                    stroke_color: None,
+                    fill_opacity: None,
+                    stroke_opacity: None,
+                });
+            } else if elem.out_of_scope {
+                report.skip(item(&elem.name, &elem.guid), "marked out of scope");
+            } else {
+                let element_type = match elem.generic_type_id.as_str() {
+                    "GE.P" => "process",
+                    "GE.DS" => "data_store",
+                    "GE.EI" => "external_entity",
+                    _ => {
+                        report.fall_back(
+                            item(&elem.name, &elem.guid),
+                            elem.generic_type_id.as_str(),
+                            "process",
+                            "stencil type not recognised",
+                        );
+                        "process"
+                    }
+                };
+                let id = report.unique_id(&elem.name, element_type, &mut id_counter);
+                guid_to_id.insert(elem.guid.clone(), id.clone());
+                thf_elements.push(Element {
+                    id,
+                    element_type: element_type.to_string(),
+                    name: elem.name.clone(),
+                    trust_zone: String::new(),
+                    layer: None,
+                    group: None,
+                    diagram: membership.clone(),
+                    subtype: None,
+                    icon: None,
+                    description: String::new(),
+                    technologies: Vec::new(),
+                    tags: Vec::new(),
+                    stores: None,
+                    encryption: None,
+                    operations: Vec::new(),
+                    position: Some(Position {
+                        x: elem.left,
+                        y: elem.top,
+                    }),
+                    fill_color: None,
+                    stroke_color: None,
+                    fill_opacity: None,
+                    stroke_opacity: None,
+                    font_size: None,
+                    font_weight: None,
+                });
+            }
         }
-    }
 
-    // Line boundaries → trust boundaries
-    for line in &lines {
-        if line.line_type == "LineBoundary" {
-            let id = make_unique_id(&line.name, "boundary", &mut id_counter);
-            guid_to_id.insert(line.guid.clone(), id.clone());
-            // Convert line boundary to a rectangular boundary using endpoint coordinates
-            let x = line.source_x.min(line.target_x);
-            let y = line.source_y.min(line.target_y);
-            let w = (line.target_x - line.source_x).abs().max(200.0);
-            let h = (line.target_y - line.source_y).abs().max(100.0);
-            thf_boundaries.push(TrustBoundary {
-                id,
-                name: line.name.clone(),
-                contains: Vec::new(),
-                position: Some(Position { x, y }),
-                size: Some(Size {
-                    width: w,
-                    height: h,
-                }),
-                fill_color: None,
-                stroke_color: None,
-                fill_opacity: None,
-                stroke_opacity: None,
-            });
+        // Line boundaries keep their geometry: the endpoints plus the curve
+        // handle TMT drags them by, when it wrote one. A line separates
+        // regions rather than enclosing them, so it has no members.
+        for line in &surface.lines {
+            if line.line_type == "LineBoundary" {
+                let id = report.unique_id(&line.name, "boundary", &mut id_counter);
+                guid_to_id.insert(line.guid.clone(), id.clone());
+                let mut points = vec![Position {
+                    x: line.source_x,
+                    y: line.source_y,
+                }];
+                if let (Some(x), Some(y)) = (line.handle_x, line.handle_y) {
+                    points.push(Position { x, y });
+                }
+                points.push(Position {
+                    x: line.target_x,
+                    y: line.target_y,
+                });
+                thf_boundaries.push(TrustBoundary {
+                    id,
+                    name: line.name.clone(),
+                    contains: Vec::new(),
+                    diagram: membership.clone(),
+                    shape: Some(BoundaryShape::Line),
+                    points,
+                    position: None,
+                    size: None,
+                    fill_color: None,
+                    stroke_color: None,
+                    fill_opacity: None,
+                    stroke_opacity: None,
+                });
+            }
         }
-    }
 
-    // Build a lookup from element ID to its TM7 dimensions for accurate containment
-    let elem_dimensions: HashMap<&str, (f64, f64)> = border_elements
-        .iter()
-        .filter(|e| e.stencil_type != "BorderBoundary")
-        .filter_map(|e| {
-            guid_to_id
-                .get(&e.guid)
-                .map(|id| (id.as_str(), (e.width, e.height)))
-        })
-        .collect();
-
-    // Determine containment: which elements are inside which boundaries
-    for boundary in &mut thf_boundaries {
-        let (bx, by, bw, bh) = match (&boundary.position, &boundary.size) {
-            (Some(p), Some(s)) => (p.x, p.y, s.width, s.height),
-            _ => continue,
-        };
+        // Build a lookup from element ID to its TM7 dimensions for accurate containment
+        let elem_dimensions: HashMap<&str, (f64, f64)> = surface
+            .borders
+            .iter()
+            .filter(|e| e.stencil_type != "BorderBoundary")
+            .filter_map(|e| {
+                guid_to_id
+                    .get(&e.guid)
+                    .map(|id| (id.as_str(), (e.width, e.height)))
+            })
+            .collect();
+
+        // Determine containment: which elements are inside which boundaries.
+        // Surfaces are separate canvases, so only this surface's items count.
+        for boundary in &mut thf_boundaries[first_boundary..] {
+            let (bx, by, bw, bh) = match (&boundary.position, &boundary.size) {
+                (Some(p), Some(s)) => (p.x, p.y, s.width, s.height),
+                _ => continue,
+            };
 
-        for elem in &thf_elements {
-            if let Some(ref pos) = elem.position {
-                // Use actual element dimensions from TM7, falling back to 100x100
-                let (ew, eh) = elem_dimensions
-                    .get(elem.id.as_str())
-                    .copied()
-                    .unwrap_or((100.0, 100.0));
-                let cx = pos.x + ew / 2.0;
-                let cy = pos.y + eh / 2.0;
-                if cx >= bx && cx <= bx + bw && cy >= by && cy <= by + bh {
-                    boundary.contains.push(elem.id.clone());
+            for elem in &thf_elements[first_element..] {
+                if let Some(ref pos) = elem.position {
+                    // Use actual element dimensions from TM7, falling back to 100x100
+                    let (ew, eh) = elem_dimensions
+                        .get(elem.id.as_str())
+                        .copied()
+                        .unwrap_or((100.0, 100.0));
+                    let cx = pos.x + ew / 2.0;
+                    let cy = pos.y + eh / 2.0;
+                    if cx >= bx && cx <= bx + bw && cy >= by && cy <= by + bh {
+                        boundary.contains.push(elem.id.clone());
+                    }
                 }
             }
         }
-    }
 
-    // Data flows
-    let mut thf_flows: Vec<DataFlow> = Vec::new();
-    let mut flow_counter: u32 = 0;
-    for line in &lines {
-        if line.line_type == "Connector" {
-            let from_id = guid_to_id.get(&line.source_guid).cloned();
-            let to_id = guid_to_id.get(&line.target_guid).cloned();
-            // Skip flows referencing out-of-scope or unknown elements
-            if let (Some(from), Some(to)) = (from_id, to_id) {
+        // Data flows
+        for line in &surface.lines {
+            if line.line_type == "Connector" {
+                let from_id = guid_to_id.get(&line.source_guid).cloned();
+                let to_id = guid_to_id.get(&line.target_guid).cloned();
+                // Skip flows referencing out-of-scope or unknown elements
+                let (Some(from), Some(to)) = (from_id, to_id) else {
+                    report.skip(
+                        item(&line.name, &line.guid),
+                        "connector does not join two imported elements",
+                    );
+                    continue;
+                };
                 flow_counter += 1;
                 let flow_id = format!("flow-{flow_counter}");
                 guid_to_id.insert(line.guid.clone(), flow_id.clone());
@@ -276,6 +355,13 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
         }
     }
 
+    if thf_diagrams.is_empty() {
+        thf_diagrams.push(imported_diagram(
+            MAIN_DIAGRAM_ID,
+            DEFAULT_DIAGRAM_NAME.to_string(),
+        ));
+    }
+
     // Threats
     let mut thf_threats: Vec<Threat> = Vec::new();
     for tm7_threat in &threats {
@@ -285,6 +371,10 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
             .cloned()
             .unwrap_or_default();
         if title.is_empty() {
+            report.skip(
+                format!("ThreatInstances.{}", tm7_threat.id),
+                "threat has no title",
+            );
             continue;
         }
 
@@ -294,6 +384,19 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
             .cloned()
             .unwrap_or_default();
         let category = parse_stride_category(&category_str, &tm7_threat.type_id);
+        if stride_category_named(&category_str).is_none() {
+            let reason = if type_id_category(&tm7_threat.type_id).is_some() {
+                format!("inferred from type id {}", tm7_threat.type_id)
+            } else {
+                "neither category nor type id recognised".to_string()
+            };
+            report.fall_back(
+                format!("ThreatInstances.{}", tm7_threat.id),
+                category_str.as_str(),
+                category.as_str(),
+                reason,
+            );
+        }
 
         let description = tm7_threat
             .properties
@@ -328,7 +431,7 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
         meta.name
     };
 
-    Ok(ThreatModel {
+    let model = ThreatModel {
         version: "1.0".to_string(),
         metadata: Metadata {
             title,
@@ -353,19 +456,9 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
         relationships: Vec::new(),
         trust_boundaries: thf_boundaries,
         threats: thf_threats,
-        diagrams: vec![Diagram {
-            id: "main-dfd".to_string(),
-            name: diagram_name,
-            kind: None,
-            description: None,
-            layout_file: None,
-            viewport: Some(Viewport {
-                x: 0.0,
-                y: 0.0,
-                zoom: 1.0,
-            }),
-        }],
-    })
+        diagrams: thf_diagrams,
+    };
+    Ok(ImportedModel::new(model, report))
 }
 
 // ---------------------------------------------------------------------------
@@ -373,7 +466,7 @@ pub fn parse_tm7(xml: &str) -> Result<ThreatModel, Tm7Error> {
 // ---------------------------------------------------------------------------
 
 /// Extract the local name (without namespace prefix) from an XML element.
-fn local_name(e: &BytesStart<'_>) -> String {
+pub(super) fn local_name(e: &BytesStart<'_>) -> String {
     let binding = e.local_name();
     let full = String::from_utf8_lossy(binding.as_ref());
     full.to_string()
@@ -402,7 +495,7 @@ fn resolve_general_ref(e: &BytesRef<'_>) -> Option<String> {
 /// Entity references are resolved back into the surrounding text, and the
 /// result is trimmed once so that the indentation TMT writes around element
 /// content does not leak into names, descriptions, and numbers.
-fn read_text_content(reader: &mut Reader<&[u8]>) -> Result<String, Tm7Error> {
+pub(super) fn read_text_content(reader: &mut Reader<&[u8]>) -> Result<String, Tm7Error> {
     let mut buf = Vec::new();
     let mut text = String::new();
     let mut depth: u32 = 1;
@@ -441,7 +534,7 @@ fn read_text_content(reader: &mut Reader<&[u8]>) -> Result<String, Tm7Error> {
 }
 
 /// Skip over an element and all its children until the matching end tag.
-fn skip_element(reader: &mut Reader<&[u8]>) -> Result<(), Tm7Error> {
+pub(super) fn skip_element(reader: &mut Reader<&[u8]>) -> Result<(), Tm7Error> {
     let mut buf = Vec::new();
     let mut depth: u32 = 1;
     loop {
@@ -529,14 +622,10 @@ fn parse_meta_information(reader: &mut Reader<&[u8]>) -> Result<Tm7Meta, Tm7Erro
     Ok(meta)
 }
 
-fn parse_drawing_surface(
-    reader: &mut Reader<&[u8]>,
-) -> Result<(String, Vec<Tm7Element>, Vec<Tm7Line>), Tm7Error> {
+fn parse_drawing_surface(reader: &mut Reader<&[u8]>) -> Result<Tm7Surface, Tm7Error> {
     let mut buf = Vec::new();
     let mut depth: u32 = 1;
-    let mut diagram_name = String::new();
-    let mut borders: Vec<Tm7Element> = Vec::new();
-    let mut lines: Vec<Tm7Line> = Vec::new();
+    let mut surface = Tm7Surface::default();
     let mut found_borders = false;
 
     loop {
@@ -547,16 +636,16 @@ fn parse_drawing_surface(
                 match name.as_str() {
                     "Borders" => {
                         found_borders = true;
-                        borders = parse_borders(reader)?;
+                        surface.borders = parse_borders(reader)?;
                         depth -= 1; // parse_borders consumed end tag
                     }
                     "Lines" => {
-                        lines = parse_lines(reader)?;
+                        surface.lines = parse_lines(reader)?;
                         depth -= 1;
                     }
                     // Properties before Borders is the diagram-level properties
                     "Properties" if !found_borders => {
-                        diagram_name = parse_properties_name(reader)?;
+                        surface.name = parse_properties_name(reader)?;
                         depth -= 1;
                     }
                     _ => {}
@@ -574,7 +663,7 @@ fn parse_drawing_surface(
         }
         buf.clear();
     }
-    Ok((diagram_name, borders, lines))
+    Ok(surface)
 }
 
 /// Parse the `<Borders>` section containing stencil elements and boundary boxes.
@@ -753,6 +842,14 @@ fn parse_line_value(reader: &mut Reader<&[u8]>, itype: &str) -> Result<Tm7Line,
                         line.target_y = read_text_content(reader)?.parse().unwrap_or(0.0);
                         depth -= 1;
                     }
+                    "HandleX" => {
+                        line.handle_x = read_text_content(reader)?.parse().ok();
+                        depth -= 1;
+                    }
+                    "HandleY" => {
+                        line.handle_y = read_text_content(reader)?.parse().ok();
+                        depth -= 1;
+                    }
                     "Properties" => {
                         line.name = parse_properties_name(reader)?;
                         depth -= 1;
@@ -1018,8 +1115,28 @@ fn parse_properties_name(reader: &mut Reader<&[u8]>) -> Result<String, Tm7Error>
 // Conversion helpers
 // ---------------------------------------------------------------------------
 
+/// A diagram for one imported drawing surface, shown at the origin.
+pub(super) fn imported_diagram(id: &str, name: String) -> Diagram {
+    Diagram {
+        id: id.to_string(),
+        name,
+        kind: None,
+        description: None,
+        layout_file: None,
+        viewport: Some(Viewport {
+            x: 0.0,
+            y: 0.0,
+            zoom: 1.0,
+        }),
+    }
+}
+
 /// Generate a unique kebab-case ID from a name.
-fn make_unique_id(name: &str, fallback: &str, counter: &mut HashMap<String, u32>) -> String {
+pub(super) fn make_unique_id(
+    name: &str,
+    fallback: &str,
+    counter: &mut HashMap<String, u32>,
+) -> String {
     let base = if name.is_empty() {
         fallback.to_string()
     } else {
@@ -1043,34 +1160,43 @@ fn make_unique_id(name: &str, fallback: &str, counter: &mut HashMap<String, u32>
 }
 
 /// Map a TM7 STRIDE category string or TypeId prefix to our `StrideCategory`.
-fn parse_stride_category(category: &str, type_id: &str) -> StrideCategory {
-    // Try the explicit category string first
+pub(super) fn parse_stride_category(category: &str, type_id: &str) -> StrideCategory {
+    // Try the explicit category string first, then infer from the TypeId
+    stride_category_named(category)
+        .or_else(|| type_id_category(type_id))
+        .unwrap_or(StrideCategory::Tampering) // safe default
+}
+
+/// The STRIDE category a TMT category string names.
+pub(super) fn stride_category_named(category: &str) -> Option<StrideCategory> {
     match category {
-        "Spoofing" => return StrideCategory::Spoofing,
-        "Tampering" => return StrideCategory::Tampering,
-        "Repudiation" => return StrideCategory::Repudiation,
-        "Information Disclosure" => return StrideCategory::InformationDisclosure,
-        "Denial Of Service" | "Denial of Service" => return StrideCategory::DenialOfService,
+        "Spoofing" => Some(StrideCategory::Spoofing),
+        "Tampering" => Some(StrideCategory::Tampering),
+        "Repudiation" => Some(StrideCategory::Repudiation),
+        "Information Disclosure" => Some(StrideCategory::InformationDisclosure),
+        "Denial Of Service" | "Denial of Service" => Some(StrideCategory::DenialOfService),
         "Elevation Of Privilege" | "Elevation of Privilege" => {
-            return StrideCategory::ElevationOfPrivilege;
+            Some(StrideCategory::ElevationOfPrivilege)
         }
-        _ => {}
+        _ => None,
     }
+}
 
-    // Fallback: infer from TypeId prefix (S1, T7, I23, etc.)
-    match type_id.chars().next() {
-        Some('S') => StrideCategory::Spoofing,
-        Some('T') => StrideCategory::Tampering,
-        Some('R') => StrideCategory::Repudiation,
-        Some('I') => StrideCategory::InformationDisclosure,
-        Some('D') => StrideCategory::DenialOfService,
-        Some('E') => StrideCategory::ElevationOfPrivilege,
-        _ => StrideCategory::Tampering, // safe default
+/// The STRIDE category a TypeId prefix (S1, T7, I23, etc.) stands for.
+fn type_id_category(type_id: &str) -> Option<StrideCategory> {
+    match type_id.chars().next()? {
+        'S' => Some(StrideCategory::Spoofing),
+        'T' => Some(StrideCategory::Tampering),
+        'R' => Some(StrideCategory::Repudiation),
+        'I' => Some(StrideCategory::InformationDisclosure),
+        'D' => Some(StrideCategory::DenialOfService),
+        'E' => Some(StrideCategory::ElevationOfPrivilege),
+        _ => None,
     }
 }
 
 /// Map a TM7 priority string to our `Severity`.
-fn parse_severity(priority: &str) -> Severity {
+pub(super) fn parse_severity(priority: &str) -> Severity {
     match priority {
         "Critical" => Severity::Critical,
         "High" => Severity::High,
@@ -1098,6 +1224,7 @@ fn parse_mitigation_status(state: &str, justification: &str) -> Mitigation {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::importers::fixture_tests::assert_matches_pinned;
 
     /// A Microsoft TMT 4.3 shaped export covering every branch the importer walks.
     const FIXTURE_TM7: &str = include_str!("fixtures/tmt-4.3-sample.tm7");
@@ -1106,28 +1233,19 @@ mod tests {
     /// deliberate importer behavior change is being made.
     const FIXTURE_EXPECTED_YAML: &str = include_str!("fixtures/tmt-4.3-sample.expected.yaml");
 
-    /// Import the fixture and serialize it, pinning the two clock-derived
-    /// metadata dates so the result is byte-comparable across runs.
-    fn import_fixture_as_yaml() -> String {
-        let mut model = parse_tm7(FIXTURE_TM7).expect("fixture .tm7 should import");
-        let pinned = chrono::NaiveDate::from_ymd_opt(2000, 1, 1).expect("literal date is valid");
-        model.metadata.created = pinned;
-        model.metadata.modified = pinned;
-        serde_yaml::to_string(&model).expect("imported model should serialize")
-    }
-
     #[test]
     fn fixture_import_is_byte_identical_to_the_pinned_result() {
-        assert_eq!(
-            import_fixture_as_yaml(),
-            FIXTURE_EXPECTED_YAML,
-            "TM7 import drifted from src/importers/fixtures/tmt-4.3-sample.expected.yaml"
-        );
+        let model = parse_tm7(FIXTURE_TM7)
+            .expect("fixture .tm7 should import")
+            .model;
+        assert_matches_pinned(model, FIXTURE_EXPECTED_YAML, "tmt-4.3-sample.expected.yaml");
     }
 
     #[test]
     fn fixture_import_resolves_xml_entities_in_text() {
-        let model = parse_tm7(FIXTURE_TM7).expect("fixture .tm7 should import");
+        let model = parse_tm7(FIXTURE_TM7)
+            .expect("fixture .tm7 should import")
+            .model;
 
         assert_eq!(model.metadata.title, "Payments & Billing Platform");
         assert_eq!(
@@ -1162,6 +1280,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fixture_import_reports_what_it_dropped_or_guessed() {
+        let imported = parse_tm7(FIXTURE_TM7).expect("fixture .tm7 should import");
+        let report = &imported.report;
+
+        let skipped: Vec<_> = report
+            .skipped
+            .iter()
+            .map(|s| (s.item.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                ("Level 0 & 1 DFD.Legacy Batch Job", "marked out of scope"),
+                (
+                    "Level 0 & 1 DFD.Nightly Export",
+                    "connector does not join two imported elements"
+                ),
+                ("ThreatInstances.4", "threat has no title"),
+            ]
+        );
+        let fallbacks: Vec<_> = report
+            .fallbacks
+            .iter()
+            .map(|f| (f.item.as_str(), f.source.as_str(), f.mapped_to.as_str()))
+            .collect();
+        assert_eq!(
+            fallbacks,
+            [
+                ("Level 0 & 1 DFD.Reporting Job", "GE.XX.UNKNOWN", "process"),
+                ("ThreatInstances.2", "", "Elevation of Privilege"),
+            ]
+        );
+        assert_eq!(report.renames, []);
+        assert_eq!(report.counts.elements, imported.model.elements.len());
+        assert_eq!(report.counts.threats, imported.model.threats.len());
+    }
+
     #[test]
     fn undeclared_entity_is_dropped_without_discarding_surrounding_text() {
         let xml = r#"<?xml version="1.0"?>
@@ -1176,7 +1332,9 @@ mod tests {
   <Version>4.3</Version>
 </ThreatModel>"#;
 
-        let model = parse_tm7(xml).expect("an undeclared entity must not fail the import");
+        let model = parse_tm7(xml)
+            .expect("an undeclared entity must not fail the import")
+            .model;
         assert_eq!(
             model.metadata.title, "Acme  Corp",
             "only the unresolvable reference is dropped, not the whole text run"
@@ -1213,7 +1371,9 @@ mod tests {
     fn truncated_tm7_on_an_element_boundary_yields_only_the_parsed_prefix() {
         let truncated = &FIXTURE_TM7[..fixture_lines_tag_offset()];
 
-        let model = parse_tm7(truncated).expect("boundary-truncated input currently parses");
+        let model = parse_tm7(truncated)
+            .expect("boundary-truncated input currently parses")
+            .model;
         assert_eq!(
             model.elements.len(),
             5,
@@ -1460,7 +1620,7 @@ mod tests {
 
     #[test]
     fn test_parse_minimal_tm7() {
-        let model = parse_tm7(MINIMAL_TM7).expect("Failed to parse TM7");
+        let model = parse_tm7(MINIMAL_TM7).expect("Failed to parse TM7").model;
 
         // Metadata
         assert_eq!(model.metadata.title, "Sample Web App");
@@ -1559,7 +1719,7 @@ mod tests {
   <Version>4.3</Version>
 </ThreatModel>"#;
 
-        let model = parse_tm7(xml).expect("Failed to parse empty TM7");
+        let model = parse_tm7(xml).expect("Failed to parse empty TM7").model;
         assert_eq!(model.metadata.title, "Imported Threat Model");
         assert!(model.elements.is_empty());
         assert!(model.data_flows.is_empty());
@@ -1697,7 +1857,7 @@ mod tests {
   <Version>4.3</Version>
 </ThreatModel>"#;
 
-        let model = parse_tm7(xml).expect("Failed to parse");
+        let model = parse_tm7(xml).expect("Failed to parse").model;
         assert_eq!(
             model.elements.len(),
             1,
@@ -1770,7 +1930,7 @@ mod tests {
   <Version>4.3</Version>
 </ThreatModel>"#;
 
-        let model = parse_tm7(xml).expect("Failed to parse");
+        let model = parse_tm7(xml).expect("Failed to parse").model;
         assert_eq!(model.threats.len(), 1);
         assert_eq!(model.threats[0].title, "Orphaned Threat");
         assert!(
@@ -1845,7 +2005,7 @@ mod tests {
   <Version>4.3</Version>
 </ThreatModel>"#;
 
-        let model = parse_tm7(xml).expect("Failed to parse");
+        let model = parse_tm7(xml).expect("Failed to parse").model;
         assert_eq!(model.elements.len(), 2);
         assert_eq!(model.elements[0].id, "web-app");
         assert_eq!(model.elements[1].id, "web-app-2");
@@ -1896,15 +2056,127 @@ mod tests {
   <Version>4.3</Version>
 </ThreatModel>"#;
 
-        let model = parse_tm7(xml).expect("Failed to parse");
+        let model = parse_tm7(xml).expect("Failed to parse").model;
         assert_eq!(model.trust_boundaries.len(), 1);
         assert_eq!(model.trust_boundaries[0].name, "Internet Boundary");
-        // Line boundary should be converted to a rectangle
-        assert!(model.trust_boundaries[0].position.is_some());
-        assert!(model.trust_boundaries[0].size.is_some());
-        let size = model.trust_boundaries[0].size.as_ref().unwrap();
-        assert!(size.width >= 200.0, "Width should be at least 200 (min)");
-        assert_eq!(size.height, 340.0, "Height should be |350 - 10|");
+        // The line keeps its own geometry instead of becoming a rectangle
+        let boundary = &model.trust_boundaries[0];
+        assert_eq!(boundary.shape, Some(BoundaryShape::Line));
+        assert_eq!(
+            boundary.points,
+            [
+                Position { x: 100.0, y: 10.0 },
+                Position { x: 100.0, y: 350.0 }
+            ]
+        );
+        assert!(boundary.position.is_none());
+        assert!(boundary.size.is_none());
+        assert!(boundary.contains.is_empty());
+    }
+
+    /// A drawing surface holding one process stencil and one border boundary
+    /// drawn around it.
+    fn surface_with_process(surface: &str, name: &str, process: &str, boundary: &str) -> String {
+        const ABSTRACTS: &str =
+            "http://schemas.datacontract.org/2004/07/ThreatModeling.Model.Abstracts";
+        const ARRAYS: &str = "http://schemas.microsoft.com/2003/10/Serialization/Arrays";
+        const KB: &str = "http://schemas.datacontract.org/2004/07/ThreatModeling.KnowledgeBase";
+        let props = |value: &str| {
+            format!(
+                r#"<Properties xmlns="{ABSTRACTS}" xmlns:a="{ARRAYS}"><a:anyType i:type="b:StringDisplayAttribute" xmlns:b="{KB}"><b:DisplayName>Name</b:DisplayName><b:Value i:type="c:string" xmlns:c="http://www.w3.org/2001/XMLSchema">{value}</b:Value></a:anyType></Properties>"#
+            )
+        };
+        let stencil = |guid: &str, kind: &str, type_id: &str, value: &str, left: u32, size: u32| {
+            format!(
+                r#"<a:KeyValueOfguidanyType><a:Key>{guid}</a:Key><a:Value i:type="{kind}"><GenericTypeId xmlns="{ABSTRACTS}">{type_id}</GenericTypeId><Guid xmlns="{ABSTRACTS}">{guid}</Guid>{}<TypeId xmlns="{ABSTRACTS}">{type_id}</TypeId><Height xmlns="{ABSTRACTS}">{size}</Height><Left xmlns="{ABSTRACTS}">{left}</Left><Top xmlns="{ABSTRACTS}">{left}</Top><Width xmlns="{ABSTRACTS}">{size}</Width></a:Value></a:KeyValueOfguidanyType>"#,
+                props(value)
+            )
+        };
+        format!(
+            r#"<DrawingSurfaceModel><GenericTypeId xmlns="{ABSTRACTS}">DRAWINGSURFACE</GenericTypeId><Guid xmlns="{ABSTRACTS}">{surface}</Guid>{}<TypeId xmlns="{ABSTRACTS}">DRAWINGSURFACE</TypeId><Borders xmlns:a="{ARRAYS}">{}{}</Borders><Lines xmlns:a="{ARRAYS}"/></DrawingSurfaceModel>"#,
+            props(name),
+            stencil(
+                &format!("{surface}-p"),
+                "StencilEllipse",
+                "GE.P",
+                process,
+                100,
+                100
+            ),
+            stencil(
+                &format!("{surface}-b"),
+                "BorderBoundary",
+                "GE.TB.B",
+                boundary,
+                0,
+                400
+            ),
+        )
+    }
+
+    #[test]
+    fn each_drawing_surface_becomes_its_own_diagram() {
+        let xml = format!(
+            r#"<?xml version="1.0"?>
+<ThreatModel xmlns="http://schemas.datacontract.org/2004/07/ThreatModeling.Model" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+  <DrawingSurfaceList>{}{}</DrawingSurfaceList>
+  <MetaInformation><ThreatModelName>Two Surfaces</ThreatModelName><Owner/><HighLevelSystemDescription/></MetaInformation>
+  <ThreatInstances/>
+  <Version>4.3</Version>
+</ThreatModel>"#,
+            surface_with_process("ds-1", "Context", "Web App", "DMZ"),
+            surface_with_process("ds-2", "Level 1: Web App", "Web App", "DMZ"),
+        );
+
+        let imported = parse_tm7(&xml).expect("Failed to parse");
+        let model = &imported.model;
+        let diagrams: Vec<_> = model
+            .diagrams
+            .iter()
+            .map(|d| (d.id.as_str(), d.name.as_str()))
+            .collect();
+        assert_eq!(
+            diagrams,
+            [
+                ("main-dfd", "Context"),
+                ("level-1-web-app", "Level 1: Web App")
+            ]
+        );
+
+        // The first surface keeps the implicit membership single-diagram
+        // files have always had; later surfaces name their diagram.
+        let elements: Vec<_> = model
+            .elements
+            .iter()
+            .map(|e| (e.id.as_str(), e.diagram.as_deref()))
+            .collect();
+        assert_eq!(
+            elements,
+            [("web-app", None), ("web-app-2", Some("level-1-web-app"))]
+        );
+        let boundaries: Vec<_> = model
+            .trust_boundaries
+            .iter()
+            .map(|b| (b.id.as_str(), b.diagram.as_deref(), b.contains.clone()))
+            .collect();
+        assert_eq!(
+            boundaries,
+            [
+                ("dmz", None, vec!["web-app".to_string()]),
+                (
+                    "dmz-2",
+                    Some("level-1-web-app"),
+                    vec!["web-app-2".to_string()]
+                ),
+            ]
+        );
+        let renames: Vec<_> = imported
+            .report
+            .renames
+            .iter()
+            .map(|r| (r.name.as_str(), r.id.as_str()))
+            .collect();
+        assert_eq!(renames, [("Web App", "web-app-2"), ("DMZ", "dmz-2")]);
     }
 
     #[test]
@@ -1978,7 +2250,7 @@ mod tests {
   <ThreatInstances/>
 </ThreatModel>"#;
 
-        let model = parse_tm7(xml).expect("Failed to parse self-loop TM7");
+        let model = parse_tm7(xml).expect("Failed to parse self-loop TM7").model;
         assert_eq!(model.data_flows.len(), 1);
         assert_eq!(
             model.data_flows[0].from, model.data_flows[0].to,
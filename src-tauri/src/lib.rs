@@ -1,18 +1,21 @@
 mod ai;
 mod commands;
 mod errors;
+mod exporters;
 mod file_io;
 mod importers;
 pub mod mcp;
 mod menu;
 pub mod models;
+pub mod report;
 mod stride;
 
 use commands::{
-    analyze_stride, cancel_ai_stream, check_for_update, confirm_quit, create_new_model,
-    delete_api_key, get_api_key_status, import_threat_model, install_update, open_layout,
-    open_threat_model, save_layout, save_threat_model, set_api_key, start_ai_stream,
-    write_text_file,
+    analyze_stride, analyze_stride_with_template, cancel_ai_stream, check_for_update, confirm_quit,
+    create_new_model, delete_api_key, export_threat_model, get_api_key_status, import_mitigations,
+    import_threat_model, install_update, open_layout, open_threat_model, save_layout,
+    save_threat_model, set_api_key, start_ai_stream, threat_model_stamp, unwatch_threat_model,
+    watch_threat_model, write_text_file,
 };
 use tauri::{AppHandle, Emitter, Manager};
 
@@ -59,6 +62,9 @@ pub fn run() {
             // Track live AI relay streams so each can be cancelled independently
             app.manage(ai::providers::AiStreamRegistry::default());
 
+            // Watch open models so edits made by the MCP server reach the canvas
+            app.manage(file_io::FileWatchRegistry::default());
+
             // Check if the app was launched with a .thf file argument (file association)
             let args: Vec<String> = std::env::args().collect();
             if args.len() > 1 {
@@ -82,9 +88,13 @@ pub fn run() {
             create_new_model,
             open_threat_model,
             save_threat_model,
+            threat_model_stamp,
+            watch_threat_model,
+            unwatch_threat_model,
             open_layout,
             save_layout,
             analyze_stride,
+            analyze_stride_with_template,
             set_api_key,
             get_api_key_status,
             delete_api_key,
@@ -92,6 +102,8 @@ pub fn run() {
             cancel_ai_stream,
             write_text_file,
             import_threat_model,
+            import_mitigations,
+            export_threat_model,
             check_for_update,
             install_update,
             confirm_quit,
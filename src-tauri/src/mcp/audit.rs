@@ -0,0 +1,165 @@
+//! Append-only JSONL audit log of model changes made through MCP.
+//!
+//! Every call to a mutating tool that reaches a model (successful or not,
+//! dry runs excepted) appends one line: when it ran, which tool with which
+//! arguments, the client that called it (`clientInfo` from `initialize`),
+//! the outcome, the file's SHA-256 before and after, and the ids of the
+//! items it changed. Reviewers read it to vet AI-made edits before merging
+//! the model.
+//!
+//! By default `api.thf` logs to `api.audit.jsonl` beside it. With
+//! `--audit-dir`, logs go to the same root-relative path under that
+//! directory instead, so model directories stay clean.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use rmcp::model::Implementation;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::diff::{Change, ChangeOp};
+
+/// Replaces the `.thf` extension of a model to name its log.
+pub const AUDIT_EXTENSION: &str = "audit.jsonl";
+
+/// Where audit entries are written.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    /// Configured log directory; `None` logs beside each model.
+    dir: Option<PathBuf>,
+}
+
+impl AuditLog {
+    /// Log into `dir`, mirroring each model's path below the workspace root.
+    pub fn in_directory(dir: PathBuf) -> Self {
+        Self { dir: Some(dir) }
+    }
+
+    /// The log file for the model at `model`, a path below `root`.
+    pub fn path_for(&self, root: &Path, model: &Path) -> PathBuf {
+        let log = model.with_extension(AUDIT_EXTENSION);
+        match &self.dir {
+            Some(dir) => dir.join(log.strip_prefix(root).unwrap_or(&log)),
+            None => log,
+        }
+    }
+
+    /// Append `entry` as one line.
+    pub fn append(&self, root: &Path, model: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+        let path = self.path_for(root, model);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        // One write per entry, so lines from concurrent servers never interleave.
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?
+            .write_all(&line)
+    }
+}
+
+/// One logged tool call.
+#[derive(Debug, Serialize)]
+pub struct AuditEntry {
+    /// RFC 3339, UTC.
+    pub timestamp: String,
+    pub tool: String,
+    pub arguments: Value,
+    /// The calling client's `clientInfo`, if it sent one.
+    pub client: Option<Implementation>,
+    /// Model name, relative to the workspace root.
+    pub model: String,
+    pub outcome: Outcome,
+    /// The tool's summary on success, the error message otherwise.
+    pub result: String,
+    /// SHA-256 of the file before the call.
+    pub before_sha256: String,
+    /// SHA-256 of the file after a successful call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_sha256: Option<String>,
+    pub changes: Vec<ChangedItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Ok,
+    Error,
+}
+
+/// The identity of one changed item; the log keeps ids, not contents.
+#[derive(Debug, Serialize)]
+pub struct ChangedItem {
+    pub section: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub op: ChangeOp,
+}
+
+impl From<&Change> for ChangedItem {
+    fn from(change: &Change) -> Self {
+        Self {
+            section: change.section.clone(),
+            id: change.id.clone(),
+            op: change.op,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(tool: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: "2026-01-01T00:00:00.000Z".to_string(),
+            tool: tool.to_string(),
+            arguments: serde_json::json!({ "id": "api" }),
+            client: None,
+            model: "api.thf".to_string(),
+            outcome: Outcome::Ok,
+            result: "Deleted element: api".to_string(),
+            before_sha256: "before".to_string(),
+            after_sha256: Some("after".to_string()),
+            changes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn logs_beside_the_model_or_under_the_configured_directory() {
+        let root = Path::new("/work");
+        let model = Path::new("/work/billing/model.thf");
+        assert_eq!(
+            AuditLog::default().path_for(root, model),
+            Path::new("/work/billing/model.audit.jsonl")
+        );
+        assert_eq!(
+            AuditLog::in_directory(PathBuf::from("/logs")).path_for(root, model),
+            Path::new("/logs/billing/model.audit.jsonl")
+        );
+    }
+
+    #[test]
+    fn appends_one_json_line_per_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = dir.path().join("api.thf");
+        let log = AuditLog::default();
+        log.append(dir.path(), &model, &entry("delete_element"))
+            .unwrap();
+        log.append(dir.path(), &model, &entry("undo")).unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join("api.audit.jsonl")).unwrap();
+        let tools: Vec<String> = content
+            .lines()
+            .map(|line| {
+                let value: Value = serde_json::from_str(line).unwrap();
+                value["tool"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(tools, ["delete_element", "undo"]);
+    }
+}
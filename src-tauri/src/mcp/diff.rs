@@ -0,0 +1,216 @@
+//! Structural diff between two versions of a threat model, as reported by
+//! dry-run tool calls.
+//!
+//! Works on the serialized form, so every field the `.thf` file carries is
+//! covered without a hand-written comparison per type. Top-level arrays of
+//! objects with an `id` (elements, data flows, boundaries, threats, ...) are
+//! matched by id; everything else is compared field by field.
+
+use serde_json::{json, Map, Value};
+
+use crate::models::ThreatModel;
+
+/// One change between two models.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Change {
+    /// Top-level section, e.g. `elements` or `metadata`.
+    pub section: String,
+    /// Id of the changed item, for id-keyed sections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub op: ChangeOp,
+    /// The whole item before (removed) or after (added), or the changed
+    /// fields as `{ field: { before, after } }` (changed).
+    pub detail: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOp {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Changes that turn `before` into `after`, in section order, then in the
+/// order items appear (removed items last within a section).
+pub fn diff_models(before: &ThreatModel, after: &ThreatModel) -> Vec<Change> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+
+    let mut changes = Vec::new();
+    let mut sections: Vec<&String> = after.keys().collect();
+    sections.extend(before.keys().filter(|k| !after.contains_key(*k)));
+
+    for section in sections {
+        let old = before.get(section).unwrap_or(&Value::Null);
+        let new = after.get(section).unwrap_or(&Value::Null);
+        if old == new {
+            continue;
+        }
+        match (id_keyed(old), id_keyed(new)) {
+            (Some(old_items), Some(new_items)) => {
+                diff_items(section, &old_items, &new_items, &mut changes);
+            }
+            _ => changes.push(Change {
+                section: section.clone(),
+                id: None,
+                op: ChangeOp::Changed,
+                detail: field_changes(old, new),
+            }),
+        }
+    }
+    changes
+}
+
+/// `(id, item)` pairs if `value` is an array whose items all have string ids.
+/// An absent section counts as an empty one.
+fn id_keyed(value: &Value) -> Option<Vec<(&str, &Value)>> {
+    match value {
+        Value::Null => Some(Vec::new()),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.get("id").and_then(Value::as_str).map(|id| (id, item)))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn diff_items(
+    section: &str,
+    old: &[(&str, &Value)],
+    new: &[(&str, &Value)],
+    changes: &mut Vec<Change>,
+) {
+    fn find<'v>(items: &[(&str, &'v Value)], id: &str) -> Option<&'v Value> {
+        items
+            .iter()
+            .find(|(candidate, _)| *candidate == id)
+            .map(|(_, item)| *item)
+    }
+    for (id, item) in new {
+        let change = match find(old, id) {
+            None => Some((ChangeOp::Added, (*item).clone())),
+            Some(previous) if previous != *item => {
+                Some((ChangeOp::Changed, field_changes(previous, item)))
+            }
+            Some(_) => None,
+        };
+        if let Some((op, detail)) = change {
+            changes.push(Change {
+                section: section.to_string(),
+                id: Some(id.to_string()),
+                op,
+                detail,
+            });
+        }
+    }
+    for (id, item) in old {
+        if find(new, id).is_none() {
+            changes.push(Change {
+                section: section.to_string(),
+                id: Some(id.to_string()),
+                op: ChangeOp::Removed,
+                detail: (*item).clone(),
+            });
+        }
+    }
+}
+
+/// `{ field: { before, after } }` for the differing fields of two objects, or
+/// a single `{ before, after }` pair for anything else.
+fn field_changes(old: &Value, new: &Value) -> Value {
+    let (Value::Object(old), Value::Object(new)) = (old, new) else {
+        return json!({ "before": old, "after": new });
+    };
+    let mut fields = Map::new();
+    let keys = new
+        .keys()
+        .chain(old.keys().filter(|k| !new.contains_key(*k)));
+    for key in keys {
+        let before = old.get(key).unwrap_or(&Value::Null);
+        let after = new.get(key).unwrap_or(&Value::Null);
+        if before != after {
+            fields.insert(key.clone(), json!({ "before": before, "after": after }));
+        }
+    }
+    Value::Object(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TrustBoundary;
+
+    fn boundary(id: &str, name: &str) -> TrustBoundary {
+        TrustBoundary {
+            id: id.to_string(),
+            name: name.to_string(),
+            contains: Vec::new(),
+            diagram: None,
+            shape: None,
+            points: Vec::new(),
+            position: None,
+            size: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+        }
+    }
+
+    #[test]
+    fn identical_models_have_no_changes() {
+        let model = ThreatModel::new("Same", "Tester");
+        assert!(diff_models(&model, &model.clone()).is_empty());
+    }
+
+    #[test]
+    fn reports_added_changed_and_removed_items_by_id() {
+        let mut before = ThreatModel::new("Diff", "Tester");
+        before.trust_boundaries.push(boundary("dmz", "DMZ"));
+        before.trust_boundaries.push(boundary("old", "Old"));
+
+        let mut after = before.clone();
+        after.trust_boundaries.retain(|b| b.id != "old");
+        after.trust_boundaries[0].name = "Perimeter".to_string();
+        after.trust_boundaries.push(boundary("new", "New"));
+
+        let changes = diff_models(&before, &after);
+        let summary: Vec<(&str, Option<&str>, ChangeOp)> = changes
+            .iter()
+            .map(|c| (c.section.as_str(), c.id.as_deref(), c.op))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("trust_boundaries", Some("dmz"), ChangeOp::Changed),
+                ("trust_boundaries", Some("new"), ChangeOp::Added),
+                ("trust_boundaries", Some("old"), ChangeOp::Removed),
+            ]
+        );
+        assert_eq!(
+            changes[0].detail,
+            json!({ "name": { "before": "DMZ", "after": "Perimeter" } })
+        );
+    }
+
+    #[test]
+    fn reports_metadata_edits_field_by_field() {
+        let before = ThreatModel::new("Old title", "Tester");
+        let mut after = before.clone();
+        after.metadata.title = "New title".to_string();
+
+        let changes = diff_models(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].section, "metadata");
+        assert_eq!(changes[0].id, None);
+        assert_eq!(
+            changes[0].detail,
+            json!({ "title": { "before": "Old title", "after": "New title" } })
+        );
+    }
+}
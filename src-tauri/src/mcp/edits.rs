@@ -0,0 +1,673 @@
+//! Model edits behind the MCP mutating tools.
+//!
+//! Each edit validates its request against the model as it stands and
+//! applies it in memory, returning the summary the tool reports. The server
+//! owns locking, saving, dry runs, and history, so an edit runs the same way
+//! as a single tool call or as one step of an `apply_changes` batch.
+
+use rmcp::model::ErrorData;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::server::mcp_err;
+use crate::models::{
+    DataFlow, Element, Mitigation, MitigationStatus, Severity, StrideCategory, Threat, ThreatModel,
+    TrustBoundary,
+};
+
+// ───── Tool input schemas ─────
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(super) struct GetModelRequest {}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(super) struct GetReportRequest {
+    #[schemars(
+        description = "Report template: html, markdown or text for a built-in template, or the file name of a template in .threatforge/reports/ beside the model. Omit for the standard Markdown report"
+    )]
+    pub(super) template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(super) struct OpenModelRequest {
+    #[schemars(
+        description = "Model name as returned by list_models (a path relative to the workspace root)"
+    )]
+    pub(super) model: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub(super) struct AddElementRequest {
+    #[schemars(description = "Element type: process, data_store, external_entity, or text")]
+    pub(super) element_type: String,
+    #[schemars(description = "Human-readable element name")]
+    pub(super) name: String,
+    #[schemars(description = "Trust zone (e.g. internal, dmz, external)")]
+    pub(super) trust_zone: Option<String>,
+    #[schemars(description = "Description of the element")]
+    pub(super) description: Option<String>,
+    #[schemars(description = "Technologies used (e.g. [\"nginx\", \"TLS\"])")]
+    pub(super) technologies: Option<Vec<String>>,
+    #[schemars(description = "Return the changes this call would make without saving them")]
+    pub(super) dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub(super) struct UpdateElementRequest {
+    #[schemars(description = "ID of the element to update")]
+    pub(super) id: String,
+    #[schemars(description = "New name")]
+    pub(super) name: Option<String>,
+    #[schemars(description = "New type")]
+    pub(super) element_type: Option<String>,
+    #[schemars(description = "New trust zone")]
+    pub(super) trust_zone: Option<String>,
+    #[schemars(description = "New description")]
+    pub(super) description: Option<String>,
+    #[schemars(description = "Return the changes this call would make without saving them")]
+    pub(super) dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub(super) struct DeleteByIdRequest {
+    #[schemars(description = "ID of the item to delete")]
+    pub(super) id: String,
+    #[schemars(description = "Return the changes this call would make without saving them")]
+    pub(super) dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub(super) struct AddDataFlowRequest {
+    #[schemars(description = "Source element ID")]
+    pub(super) from: String,
+    #[schemars(description = "Target element ID")]
+    pub(super) to: String,
+    #[schemars(description = "Flow name")]
+    pub(super) name: Option<String>,
+    #[schemars(description = "Protocol (e.g. HTTPS/TLS-1.3)")]
+    pub(super) protocol: Option<String>,
+    #[schemars(description = "Data items carried")]
+    pub(super) data: Option<Vec<String>>,
+    #[schemars(description = "Whether the flow is authenticated")]
+    pub(super) authenticated: Option<bool>,
+    #[schemars(description = "Return the changes this call would make without saving them")]
+    pub(super) dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub(super) struct AddTrustBoundaryRequest {
+    #[schemars(description = "Boundary name")]
+    pub(super) name: String,
+    #[schemars(description = "Element IDs contained in this boundary")]
+    pub(super) contains: Option<Vec<String>>,
+    #[schemars(description = "Return the changes this call would make without saving them")]
+    pub(super) dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub(super) struct AddThreatRequest {
+    #[schemars(description = "Threat title")]
+    pub(super) title: String,
+    #[schemars(
+        description = "STRIDE category: Spoofing, Tampering, Repudiation, Information Disclosure, Denial of Service, Elevation of Privilege"
+    )]
+    pub(super) category: String,
+    #[schemars(description = "Severity: critical, high, medium, low, info")]
+    pub(super) severity: String,
+    #[schemars(description = "Detailed threat description")]
+    pub(super) description: String,
+    #[schemars(description = "Target element ID")]
+    pub(super) element: Option<String>,
+    #[schemars(description = "Target data flow ID")]
+    pub(super) flow: Option<String>,
+    #[schemars(description = "Return the changes this call would make without saving them")]
+    pub(super) dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub(super) struct ApplyChangesRequest {
+    #[schemars(
+        description = "Ordered operations, each an object with `op` naming a mutating tool (add_element, update_element, delete_element, add_data_flow, update_data_flow, delete_data_flow, add_trust_boundary, update_trust_boundary, delete_trust_boundary, add_threat, update_threat, delete_threat) plus that tool's arguments"
+    )]
+    pub(super) operations: Vec<serde_json::Value>,
+    #[schemars(description = "Return the changes the batch would make without saving them")]
+    pub(super) dry_run: Option<bool>,
+}
+
+// ───── Batch-only operations ─────
+//
+// Flows, boundaries, and threats have no update tool of their own; these are
+// reachable through `apply_changes`.
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UpdateDataFlowRequest {
+    pub(super) id: String,
+    pub(super) name: Option<String>,
+    pub(super) protocol: Option<String>,
+    pub(super) data: Option<Vec<String>>,
+    pub(super) authenticated: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UpdateTrustBoundaryRequest {
+    pub(super) id: String,
+    pub(super) name: Option<String>,
+    pub(super) contains: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UpdateThreatRequest {
+    pub(super) id: String,
+    pub(super) title: Option<String>,
+    pub(super) category: Option<String>,
+    pub(super) severity: Option<String>,
+    pub(super) description: Option<String>,
+    pub(super) element: Option<String>,
+    pub(super) flow: Option<String>,
+    /// `not_started`, `in_progress`, `mitigated`, `accepted`, or `transferred`.
+    pub(super) mitigation_status: Option<String>,
+    pub(super) mitigation_description: Option<String>,
+}
+
+// ───── Edits ─────
+
+/// Valid element types accepted by the MCP server.
+const VALID_ELEMENT_TYPES: &[&str] = &["process", "data_store", "external_entity", "text"];
+
+fn next_flow_number(model: &ThreatModel) -> u32 {
+    model
+        .data_flows
+        .iter()
+        .filter_map(|f| f.flow_number)
+        .max()
+        .and_then(|n| n.checked_add(1))
+        .unwrap_or(1)
+}
+
+/// Generate a unique element ID, appending a suffix on collision.
+fn unique_element_id(model: &ThreatModel, base: &str) -> String {
+    let base_id = crate::models::generate_element_id(base);
+    if !model.elements.iter().any(|e| e.id == base_id) {
+        return base_id;
+    }
+    let mut counter = 2;
+    loop {
+        let candidate = format!("{base_id}-{counter}");
+        if !model.elements.iter().any(|e| e.id == candidate) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+/// Generate a unique boundary ID, appending a suffix on collision.
+fn unique_boundary_id(model: &ThreatModel, base: &str) -> String {
+    let base_id = crate::models::generate_element_id(base);
+    if !model.trust_boundaries.iter().any(|b| b.id == base_id) {
+        return base_id;
+    }
+    let mut counter = 2;
+    loop {
+        let candidate = format!("{base_id}-{counter}");
+        if !model.trust_boundaries.iter().any(|b| b.id == candidate) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+fn check_element_type(element_type: &str) -> Result<(), ErrorData> {
+    if VALID_ELEMENT_TYPES.contains(&element_type) {
+        return Ok(());
+    }
+    Err(mcp_err(format!(
+        "Invalid element type: {}. Valid types: {}",
+        element_type,
+        VALID_ELEMENT_TYPES.join(", ")
+    )))
+}
+
+fn parse_category(category: &str) -> Result<StrideCategory, ErrorData> {
+    serde_json::from_value(serde_json::Value::String(category.to_string())).map_err(|_| {
+        mcp_err(format!(
+            "Invalid STRIDE category: {category}. Use: Spoofing, Tampering, Repudiation, Information Disclosure, Denial of Service, Elevation of Privilege"
+        ))
+    })
+}
+
+fn parse_severity(severity: &str) -> Result<Severity, ErrorData> {
+    serde_json::from_value(serde_json::Value::String(severity.to_string())).map_err(|_| {
+        mcp_err(format!(
+            "Invalid severity: {severity}. Use: critical, high, medium, low, info"
+        ))
+    })
+}
+
+fn parse_mitigation_status(status: &str) -> Result<MitigationStatus, ErrorData> {
+    serde_json::from_value(serde_json::Value::String(status.to_string())).map_err(|_| {
+        mcp_err(format!(
+            "Invalid mitigation status: {status}. Use: not_started, in_progress, mitigated, accepted, transferred"
+        ))
+    })
+}
+
+fn check_element_exists(model: &ThreatModel, id: &str, what: &str) -> Result<(), ErrorData> {
+    if model.elements.iter().any(|e| e.id == id) {
+        Ok(())
+    } else {
+        Err(mcp_err(format!("{what} not found: {id}")))
+    }
+}
+
+fn check_threat_targets(
+    model: &ThreatModel,
+    element: Option<&str>,
+    flow: Option<&str>,
+) -> Result<(), ErrorData> {
+    if let Some(eid) = element {
+        check_element_exists(model, eid, "Target element")?;
+    }
+    if let Some(fid) = flow {
+        if !model.data_flows.iter().any(|f| f.id == fid) {
+            return Err(mcp_err(format!("Target data flow not found: {fid}")));
+        }
+    }
+    Ok(())
+}
+
+/// Ids of threats whose element or data flow no longer exists, e.g. after
+/// their target was deleted.
+pub(super) fn orphaned_threats(model: &ThreatModel) -> Vec<&str> {
+    model
+        .threats
+        .iter()
+        .filter(|t| {
+            t.element
+                .as_deref()
+                .is_some_and(|eid| !model.elements.iter().any(|e| e.id == eid))
+                || t.flow
+                    .as_deref()
+                    .is_some_and(|fid| !model.data_flows.iter().any(|f| f.id == fid))
+        })
+        .map(|t| t.id.as_str())
+        .collect()
+}
+
+pub(super) fn add_element(
+    model: &mut ThreatModel,
+    req: AddElementRequest,
+) -> Result<String, ErrorData> {
+    check_element_type(&req.element_type)?;
+    let id = unique_element_id(model, &req.name);
+    model.elements.push(Element {
+        id: id.clone(),
+        element_type: req.element_type,
+        name: req.name,
+        trust_zone: req.trust_zone.unwrap_or_default(),
+        layer: None,
+        group: None,
+        diagram: None,
+        subtype: None,
+        icon: None,
+        description: req.description.unwrap_or_default(),
+        technologies: req.technologies.unwrap_or_default(),
+        tags: Vec::new(),
+        stores: None,
+        encryption: None,
+        operations: Vec::new(),
+        position: None,
+        fill_color: None,
+        stroke_color: None,
+        fill_opacity: None,
+        stroke_opacity: None,
+        font_size: None,
+        font_weight: None,
+    });
+    Ok(format!("Added element: {id}"))
+}
+
+pub(super) fn update_element(
+    model: &mut ThreatModel,
+    req: UpdateElementRequest,
+) -> Result<String, ErrorData> {
+    if let Some(ref t) = req.element_type {
+        check_element_type(t)?;
+    }
+    let el = model
+        .elements
+        .iter_mut()
+        .find(|e| e.id == req.id)
+        .ok_or_else(|| mcp_err(format!("Element not found: {}", req.id)))?;
+    if let Some(name) = req.name {
+        el.name = name;
+    }
+    if let Some(t) = req.element_type {
+        el.element_type = t;
+    }
+    if let Some(tz) = req.trust_zone {
+        el.trust_zone = tz;
+    }
+    if let Some(desc) = req.description {
+        el.description = desc;
+    }
+    Ok(format!("Updated element: {}", req.id))
+}
+
+pub(super) fn delete_element(
+    model: &mut ThreatModel,
+    req: DeleteByIdRequest,
+) -> Result<String, ErrorData> {
+    check_element_exists(model, &req.id, "Element")?;
+    model.elements.retain(|e| e.id != req.id);
+    // Cascade: remove connected flows
+    model
+        .data_flows
+        .retain(|f| f.from != req.id && f.to != req.id);
+    // Cascade: remove from trust boundary contains lists
+    for b in &mut model.trust_boundaries {
+        b.contains.retain(|c| c != &req.id);
+    }
+    Ok(format!("Deleted element: {}", req.id))
+}
+
+pub(super) fn add_data_flow(
+    model: &mut ThreatModel,
+    req: AddDataFlowRequest,
+) -> Result<String, ErrorData> {
+    check_element_exists(model, &req.from, "Source element")?;
+    check_element_exists(model, &req.to, "Target element")?;
+    let flow_number = next_flow_number(model);
+    let id = format!("flow-{flow_number}");
+    model.data_flows.push(DataFlow {
+        id: id.clone(),
+        flow_number: Some(flow_number),
+        name: req.name.unwrap_or_default(),
+        from: req.from,
+        to: req.to,
+        protocol: req.protocol.unwrap_or_default(),
+        data: req.data.unwrap_or_default(),
+        authenticated: req.authenticated.unwrap_or(false),
+        label_offset: None,
+        source_handle: None,
+        target_handle: None,
+        stroke_color: None,
+        stroke_opacity: None,
+    });
+    Ok(format!("Added data flow: {id}"))
+}
+
+pub(super) fn update_data_flow(
+    model: &mut ThreatModel,
+    req: UpdateDataFlowRequest,
+) -> Result<String, ErrorData> {
+    let flow = model
+        .data_flows
+        .iter_mut()
+        .find(|f| f.id == req.id)
+        .ok_or_else(|| mcp_err(format!("Data flow not found: {}", req.id)))?;
+    if let Some(name) = req.name {
+        flow.name = name;
+    }
+    if let Some(protocol) = req.protocol {
+        flow.protocol = protocol;
+    }
+    if let Some(data) = req.data {
+        flow.data = data;
+    }
+    if let Some(authenticated) = req.authenticated {
+        flow.authenticated = authenticated;
+    }
+    Ok(format!("Updated data flow: {}", req.id))
+}
+
+pub(super) fn delete_data_flow(
+    model: &mut ThreatModel,
+    req: DeleteByIdRequest,
+) -> Result<String, ErrorData> {
+    if !model.data_flows.iter().any(|f| f.id == req.id) {
+        return Err(mcp_err(format!("Data flow not found: {}", req.id)));
+    }
+    model.data_flows.retain(|f| f.id != req.id);
+    Ok(format!("Deleted data flow: {}", req.id))
+}
+
+pub(super) fn add_trust_boundary(
+    model: &mut ThreatModel,
+    req: AddTrustBoundaryRequest,
+) -> Result<String, ErrorData> {
+    for eid in req.contains.iter().flatten() {
+        if !model.elements.iter().any(|e| e.id == *eid) {
+            return Err(mcp_err(format!(
+                "Element not found in contains list: {eid}"
+            )));
+        }
+    }
+    let id = unique_boundary_id(model, &req.name);
+    model.trust_boundaries.push(TrustBoundary {
+        id: id.clone(),
+        name: req.name,
+        contains: req.contains.unwrap_or_default(),
+        diagram: None,
+        shape: None,
+        points: Vec::new(),
+        position: None,
+        size: None,
+        fill_color: None,
+        stroke_color: None,
+        fill_opacity: None,
+        stroke_opacity: None,
+    });
+    Ok(format!("Added trust boundary: {id}"))
+}
+
+pub(super) fn update_trust_boundary(
+    model: &mut ThreatModel,
+    req: UpdateTrustBoundaryRequest,
+) -> Result<String, ErrorData> {
+    for eid in req.contains.iter().flatten() {
+        if !model.elements.iter().any(|e| e.id == *eid) {
+            return Err(mcp_err(format!(
+                "Element not found in contains list: {eid}"
+            )));
+        }
+    }
+    let boundary = model
+        .trust_boundaries
+        .iter_mut()
+        .find(|b| b.id == req.id)
+        .ok_or_else(|| mcp_err(format!("Trust boundary not found: {}", req.id)))?;
+    if let Some(name) = req.name {
+        boundary.name = name;
+    }
+    if let Some(contains) = req.contains {
+        boundary.contains = contains;
+    }
+    Ok(format!("Updated trust boundary: {}", req.id))
+}
+
+pub(super) fn delete_trust_boundary(
+    model: &mut ThreatModel,
+    req: DeleteByIdRequest,
+) -> Result<String, ErrorData> {
+    if !model.trust_boundaries.iter().any(|b| b.id == req.id) {
+        return Err(mcp_err(format!("Trust boundary not found: {}", req.id)));
+    }
+    model.trust_boundaries.retain(|b| b.id != req.id);
+    Ok(format!("Deleted trust boundary: {}", req.id))
+}
+
+pub(super) fn add_threat(
+    model: &mut ThreatModel,
+    req: AddThreatRequest,
+) -> Result<String, ErrorData> {
+    let category = parse_category(&req.category)?;
+    let severity = parse_severity(&req.severity)?;
+    check_threat_targets(model, req.element.as_deref(), req.flow.as_deref())?;
+    let id = crate::models::generate_threat_id();
+    model.threats.push(Threat {
+        id: id.clone(),
+        title: req.title,
+        category,
+        element: req.element,
+        flow: req.flow,
+        severity,
+        description: req.description,
+        mitigation: None,
+    });
+    Ok(format!("Added threat: {id}"))
+}
+
+pub(super) fn update_threat(
+    model: &mut ThreatModel,
+    req: UpdateThreatRequest,
+) -> Result<String, ErrorData> {
+    let category = req.category.as_deref().map(parse_category).transpose()?;
+    let severity = req.severity.as_deref().map(parse_severity).transpose()?;
+    let status = req
+        .mitigation_status
+        .as_deref()
+        .map(parse_mitigation_status)
+        .transpose()?;
+    check_threat_targets(model, req.element.as_deref(), req.flow.as_deref())?;
+    let threat = model
+        .threats
+        .iter_mut()
+        .find(|t| t.id == req.id)
+        .ok_or_else(|| mcp_err(format!("Threat not found: {}", req.id)))?;
+    if let Some(title) = req.title {
+        threat.title = title;
+    }
+    if let Some(category) = category {
+        threat.category = category;
+    }
+    if let Some(severity) = severity {
+        threat.severity = severity;
+    }
+    if let Some(description) = req.description {
+        threat.description = description;
+    }
+    if let Some(element) = req.element {
+        threat.element = Some(element);
+    }
+    if let Some(flow) = req.flow {
+        threat.flow = Some(flow);
+    }
+    if status.is_some() || req.mitigation_description.is_some() {
+        let mitigation = threat.mitigation.get_or_insert(Mitigation {
+            status: MitigationStatus::NotStarted,
+            description: String::new(),
+        });
+        if let Some(status) = status {
+            mitigation.status = status;
+        }
+        if let Some(description) = req.mitigation_description {
+            mitigation.description = description;
+        }
+    }
+    Ok(format!("Updated threat: {}", req.id))
+}
+
+pub(super) fn delete_threat(
+    model: &mut ThreatModel,
+    req: DeleteByIdRequest,
+) -> Result<String, ErrorData> {
+    if !model.threats.iter().any(|t| t.id == req.id) {
+        return Err(mcp_err(format!("Threat not found: {}", req.id)));
+    }
+    model.threats.retain(|t| t.id != req.id);
+    Ok(format!("Deleted threat: {}", req.id))
+}
+
+/// Apply one `apply_changes` operation: `{ "op": <tool name>, ...arguments }`.
+/// A `dry_run` inside an operation is ignored; the batch's own flag decides.
+pub(super) fn apply_operation(
+    model: &mut ThreatModel,
+    operation: serde_json::Value,
+) -> Result<String, ErrorData> {
+    let serde_json::Value::Object(mut args) = operation else {
+        return Err(mcp_err("Each operation must be a JSON object"));
+    };
+    let op = match args.remove("op") {
+        Some(serde_json::Value::String(op)) => op,
+        _ => return Err(mcp_err("Each operation needs an `op` string")),
+    };
+    let args = serde_json::Value::Object(args);
+
+    fn parse<T: serde::de::DeserializeOwned>(
+        op: &str,
+        args: serde_json::Value,
+    ) -> Result<T, ErrorData> {
+        serde_json::from_value(args)
+            .map_err(|e| mcp_err(format!("Invalid arguments for {op}: {e}")))
+    }
+
+    match op.as_str() {
+        "add_element" => add_element(model, parse(&op, args)?),
+        "update_element" => update_element(model, parse(&op, args)?),
+        "delete_element" => delete_element(model, parse(&op, args)?),
+        "add_data_flow" => add_data_flow(model, parse(&op, args)?),
+        "update_data_flow" => update_data_flow(model, parse(&op, args)?),
+        "delete_data_flow" => delete_data_flow(model, parse(&op, args)?),
+        "add_trust_boundary" => add_trust_boundary(model, parse(&op, args)?),
+        "update_trust_boundary" => update_trust_boundary(model, parse(&op, args)?),
+        "delete_trust_boundary" => delete_trust_boundary(model, parse(&op, args)?),
+        "add_threat" => add_threat(model, parse(&op, args)?),
+        "update_threat" => update_threat(model, parse(&op, args)?),
+        "delete_threat" => delete_threat(model, parse(&op, args)?),
+        _ => Err(mcp_err(format!("Unknown operation: {op}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn operations_see_the_effects_of_earlier_ones() {
+        let mut model = ThreatModel::new("Batch", "Tester");
+        for op in [
+            json!({ "op": "add_element", "element_type": "process", "name": "API" }),
+            json!({ "op": "add_element", "element_type": "data_store", "name": "DB" }),
+            json!({ "op": "add_data_flow", "from": "api", "to": "db" }),
+            json!({ "op": "update_data_flow", "id": "flow-1", "protocol": "TLS" }),
+        ] {
+            apply_operation(&mut model, op).unwrap();
+        }
+        assert_eq!(model.data_flows[0].protocol, "TLS");
+    }
+
+    #[test]
+    fn update_threat_creates_a_mitigation_when_needed() {
+        let mut model = ThreatModel::new("Batch", "Tester");
+        apply_operation(
+            &mut model,
+            json!({ "op": "add_threat", "title": "T", "category": "Spoofing", "severity": "high", "description": "" }),
+        )
+        .unwrap();
+        let id = model.threats[0].id.clone();
+        apply_operation(
+            &mut model,
+            json!({ "op": "update_threat", "id": id, "mitigation_status": "in_progress" }),
+        )
+        .unwrap();
+        let mitigation = model.threats[0].mitigation.as_ref().unwrap();
+        assert_eq!(mitigation.status, MitigationStatus::InProgress);
+    }
+
+    #[test]
+    fn rejects_unknown_operations_and_bad_arguments() {
+        let mut model = ThreatModel::new("Batch", "Tester");
+        assert!(apply_operation(&mut model, json!({ "op": "drop_table" })).is_err());
+        assert!(apply_operation(&mut model, json!({ "element_type": "process" })).is_err());
+        assert!(apply_operation(&mut model, json!({ "op": "add_element", "name": 3 })).is_err());
+        assert!(apply_operation(
+            &mut model,
+            json!({ "op": "update_data_flow", "id": "x", "nope": 1 })
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! Streamable HTTP transport for the MCP server.
+//!
+//! Opt-in alternative to stdio (`threatforge-mcp --http <port>`) so several
+//! assistants can share one server and one model. It only binds to loopback
+//! addresses and every request must carry a bearer token. The server's own
+//! token (generated per process, or set through the environment) is only
+//! good for `initialize`. The response that opens a session carries a fresh
+//! token for it in the `Mcp-Session-Token` header, and every later request
+//! must present that token alongside its `Mcp-Session-Id`, so a client cannot
+//! reach into another client's session. A request that sends an `Origin`
+//! header (i.e. one made by a browser page) is refused unless that origin is
+//! explicitly allowed, which closes off DNS rebinding from arbitrary websites.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use rand::Rng;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use super::server::{lock, ThreatForgeServer};
+
+/// The path the MCP endpoint is mounted at.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Environment variable that overrides the generated bearer token, for
+/// clients that need a token known before the server starts.
+pub const TOKEN_ENV: &str = "THREATFORGE_MCP_TOKEN";
+
+/// Response header on `initialize` carrying the new session's bearer token.
+pub const SESSION_TOKEN_HEADER: &str = "mcp-session-token";
+
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+/// How the HTTP transport is exposed.
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    /// Must be a loopback address; [`bind`] refuses anything else.
+    pub addr: SocketAddr,
+    /// Bearer token an `initialize` request must present. Requests within a
+    /// session present that session's own token instead.
+    pub token: String,
+    /// Exact `Origin` values (e.g. `http://localhost:5173`) that browsers may
+    /// call from. Requests without an `Origin` header are not affected.
+    pub allowed_origins: Vec<String>,
+}
+
+impl HttpOptions {
+    /// `127.0.0.1:<port>` with a freshly generated token and no browser
+    /// origins allowed. Port `0` picks a free port.
+    pub fn localhost(port: u16) -> Self {
+        Self {
+            addr: SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+            token: generate_token(),
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+/// A random 256-bit token, hex encoded.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Bind the listener, refusing non-loopback addresses so the model is never
+/// reachable from another machine.
+pub async fn bind(options: &HttpOptions) -> io::Result<TcpListener> {
+    if !options.addr.ip().is_loopback() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "refusing to bind the MCP server to non-loopback address {}",
+                options.addr
+            ),
+        ));
+    }
+    TcpListener::bind(options.addr).await
+}
+
+/// The MCP endpoint behind the token and origin checks. Each HTTP session
+/// gets its own [`ThreatForgeServer::session`] over the shared model; when
+/// the session is closed or expires, its handler is dropped and with it the
+/// session's subscription watches.
+pub fn router(
+    server: ThreatForgeServer,
+    options: HttpOptions,
+    shutdown: CancellationToken,
+) -> Router {
+    let mut config = StreamableHttpServerConfig::default();
+    config.cancellation_token = shutdown;
+    let service = StreamableHttpService::new(
+        move || Ok(server.session()),
+        LocalSessionManager::default().into(),
+        config,
+    );
+    Router::new()
+        .nest_service(MCP_PATH, service)
+        .layer(middleware::from_fn_with_state(
+            Arc::new(Gate::new(options)),
+            guard,
+        ))
+}
+
+/// Serve until `shutdown` is cancelled.
+pub async fn serve(
+    server: ThreatForgeServer,
+    listener: TcpListener,
+    options: HttpOptions,
+    shutdown: CancellationToken,
+) -> io::Result<()> {
+    let app = router(server, options, shutdown.clone());
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move { shutdown.cancelled().await })
+        .await
+}
+
+/// The server's options plus the token issued to each open session, keyed by
+/// `Mcp-Session-Id`.
+struct Gate {
+    options: HttpOptions,
+    sessions: Mutex<HashMap<String, String>>,
+}
+
+/// What an authorized request was admitted to.
+#[derive(Debug, PartialEq)]
+enum Admission {
+    /// No session yet; only `initialize` gets anywhere from here.
+    New,
+    /// The named session, whose token the request presented.
+    Session(String),
+}
+
+async fn guard(State(gate): State<Arc<Gate>>, request: Request, next: Next) -> Response {
+    let admission = match gate.authorize(request.headers()) {
+        Ok(a) => a,
+        Err(status) => return status.into_response(),
+    };
+    let closing = request.method() == Method::DELETE;
+    let mut response = next.run(request).await;
+
+    match admission {
+        Admission::New => {
+            let opened = response
+                .headers()
+                .get(SESSION_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned);
+            if let Some(id) = opened {
+                let token = generate_token();
+                if let Ok(value) = HeaderValue::from_str(&token) {
+                    response.headers_mut().insert(SESSION_TOKEN_HEADER, value);
+                    lock(&gate.sessions).insert(id, token);
+                }
+            }
+        }
+        Admission::Session(id) => {
+            if closing && response.status().is_success() {
+                lock(&gate.sessions).remove(&id);
+            }
+        }
+    }
+    response
+}
+
+impl Gate {
+    fn new(options: HttpOptions) -> Self {
+        Self {
+            options,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The origin check runs first so a browser page never learns whether its
+    /// token guess was right. A request naming a session must present that
+    /// session's token; the server token only opens new sessions. An unknown
+    /// session id is refused the same way as a wrong token, so session ids
+    /// cannot be probed.
+    fn authorize(&self, headers: &HeaderMap) -> Result<Admission, StatusCode> {
+        if let Some(origin) = headers.get(header::ORIGIN) {
+            let allowed = origin
+                .to_str()
+                .is_ok_and(|o| self.options.allowed_origins.iter().any(|a| a == o));
+            if !allowed {
+                return Err(StatusCode::FORBIDDEN);
+            }
+        }
+
+        let presented = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        let Some(session) = headers.get(SESSION_ID_HEADER) else {
+            return if constant_time_eq(presented.as_bytes(), self.options.token.as_bytes()) {
+                Ok(Admission::New)
+            } else {
+                Err(StatusCode::UNAUTHORIZED)
+            };
+        };
+
+        let session = session.to_str().map_err(|_| StatusCode::UNAUTHORIZED)?;
+        match lock(&self.sessions).get(session) {
+            Some(token) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => {
+                Ok(Admission::Session(session.to_string()))
+            }
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+}
+
+/// Compare without short-circuiting on the first differing byte, so response
+/// timing does not leak how much of a guessed token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate() -> Gate {
+        Gate::new(HttpOptions {
+            allowed_origins: vec!["http://localhost:5173".to_string()],
+            ..HttpOptions::localhost(0)
+        })
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(
+                header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        map
+    }
+
+    #[test]
+    fn requires_the_exact_bearer_token() {
+        let gate = gate();
+        let bearer = format!("Bearer {}", gate.options.token);
+
+        assert_eq!(
+            gate.authorize(&headers(&[("authorization", &bearer)])),
+            Ok(Admission::New)
+        );
+        assert_eq!(
+            gate.authorize(&HeaderMap::new()),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            gate.authorize(&headers(&[("authorization", &format!("{bearer}0"))])),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+
+    #[test]
+    fn refuses_origins_outside_the_allowlist_even_with_the_token() {
+        let gate = gate();
+        let bearer = format!("Bearer {}", gate.options.token);
+
+        assert_eq!(
+            gate.authorize(&headers(&[
+                ("authorization", &bearer),
+                ("origin", "http://localhost:5173"),
+            ])),
+            Ok(Admission::New)
+        );
+        assert_eq!(
+            gate.authorize(&headers(&[
+                ("authorization", &bearer),
+                ("origin", "https://evil.example"),
+            ])),
+            Err(StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[test]
+    fn session_requests_need_that_sessions_token() {
+        let gate = gate();
+        lock(&gate.sessions).insert("a".to_string(), "token-a".to_string());
+        lock(&gate.sessions).insert("b".to_string(), "token-b".to_string());
+        let server_bearer = format!("Bearer {}", gate.options.token);
+
+        assert_eq!(
+            gate.authorize(&headers(&[
+                ("authorization", "Bearer token-a"),
+                ("mcp-session-id", "a"),
+            ])),
+            Ok(Admission::Session("a".to_string()))
+        );
+        for (bearer, session) in [
+            ("Bearer token-a", "b"),
+            (server_bearer.as_str(), "a"),
+            ("Bearer token-a", "unknown"),
+        ] {
+            assert_eq!(
+                gate.authorize(&headers(&[
+                    ("authorization", bearer),
+                    ("mcp-session-id", session),
+                ])),
+                Err(StatusCode::UNAUTHORIZED),
+                "{bearer} must not open session {session}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_to_bind_a_non_loopback_address() {
+        let options = HttpOptions {
+            addr: SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            ..HttpOptions::localhost(0)
+        };
+        let err = bind(&options).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn generated_tokens_are_long_and_distinct() {
+        let a = generate_token();
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, generate_token());
+    }
+}
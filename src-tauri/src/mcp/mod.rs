@@ -1 +1,9 @@
+pub mod audit;
+mod diff;
+mod edits;
+pub mod http;
+mod prompts;
+mod query;
+mod resources;
 pub mod server;
+mod workspace;
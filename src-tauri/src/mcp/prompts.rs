@@ -0,0 +1,591 @@
+//! MCP prompts — curated threat-modeling workflows filled in from the live
+//! model, so every assistant starts from the same vetted instructions.
+//!
+//! Like `resources.rs`, this module is transport-free: it describes the
+//! prompts and renders one against a `ThreatModel`. The handler in
+//! `server.rs` reloads the model and wraps the text in MCP messages.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::models::{DataFlow, Element, Severity, Threat, ThreatModel, TrustBoundary};
+use crate::stride;
+
+pub const REVIEW_STRIDE_COVERAGE: &str = "review_stride_coverage";
+pub const PROPOSE_MITIGATIONS: &str = "propose_mitigations";
+pub const DESCRIBE_TRUST_BOUNDARIES: &str = "describe_trust_boundaries";
+
+/// One argument of a prompt, as advertised by `prompts/list`.
+#[derive(Debug, PartialEq)]
+pub struct ArgumentSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+/// One entry of `prompts/list`.
+#[derive(Debug, PartialEq)]
+pub struct PromptSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub arguments: Vec<ArgumentSpec>,
+}
+
+/// A prompt filled in from the model: a one-line description and the user
+/// message text.
+#[derive(Debug, PartialEq)]
+pub struct RenderedPrompt {
+    pub description: String,
+    pub text: String,
+}
+
+/// Every prompt the server offers, in a stable order.
+pub fn list() -> Vec<PromptSpec> {
+    vec![
+        PromptSpec {
+            name: REVIEW_STRIDE_COVERAGE,
+            description: "Review STRIDE coverage for one element and its data flows",
+            arguments: vec![ArgumentSpec {
+                name: "element_id",
+                description: "ID of the element to review",
+                required: true,
+            }],
+        },
+        PromptSpec {
+            name: PROPOSE_MITIGATIONS,
+            description: "Propose mitigations for unmitigated threats of one severity",
+            arguments: vec![ArgumentSpec {
+                name: "severity",
+                description:
+                    "Severity to address: critical, high, medium, low, info (default: high)",
+                required: false,
+            }],
+        },
+        PromptSpec {
+            name: DESCRIBE_TRUST_BOUNDARIES,
+            description:
+                "Describe the architecture's trust boundaries and the flows that cross them",
+            arguments: vec![ArgumentSpec {
+                name: "boundary_id",
+                description: "ID of a single trust boundary to describe (default: all)",
+                required: false,
+            }],
+        },
+    ]
+}
+
+/// Render prompt `name` with `args`. The error is a client-facing message for
+/// an unknown prompt, a missing argument, or an id the model does not contain.
+pub fn render(
+    model: &ThreatModel,
+    name: &str,
+    args: &HashMap<String, String>,
+) -> Result<RenderedPrompt, String> {
+    match name {
+        REVIEW_STRIDE_COVERAGE => {
+            let id = args
+                .get("element_id")
+                .ok_or("Missing required argument: element_id")?;
+            let element = model
+                .elements
+                .iter()
+                .find(|e| &e.id == id)
+                .ok_or_else(|| format!("Element '{id}' not found"))?;
+            Ok(review_stride_coverage(model, element))
+        }
+        PROPOSE_MITIGATIONS => {
+            let severity = match args.get("severity") {
+                Some(s) => Severity::ALL
+                    .into_iter()
+                    .find(|v| v.as_str() == s)
+                    .ok_or_else(|| format!("Invalid severity '{s}'"))?,
+                None => Severity::High,
+            };
+            Ok(propose_mitigations(model, severity))
+        }
+        DESCRIBE_TRUST_BOUNDARIES => {
+            let boundaries: Vec<&TrustBoundary> = match args.get("boundary_id") {
+                Some(id) => vec![model
+                    .trust_boundaries
+                    .iter()
+                    .find(|b| &b.id == id)
+                    .ok_or_else(|| format!("Trust boundary '{id}' not found"))?],
+                None => model.trust_boundaries.iter().collect(),
+            };
+            Ok(describe_trust_boundaries(model, &boundaries))
+        }
+        _ => Err(format!("Unknown prompt: {name}")),
+    }
+}
+
+fn review_stride_coverage(model: &ThreatModel, element: &Element) -> RenderedPrompt {
+    let flows: Vec<&DataFlow> = model
+        .data_flows
+        .iter()
+        .filter(|f| f.from == element.id || f.to == element.id)
+        .collect();
+    let touches = |target: Option<&str>| {
+        target.is_some_and(|t| t == element.id || flows.iter().any(|f| f.id == t))
+    };
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "Review the STRIDE coverage of `{}` in the threat model \"{}\".\n",
+        element.id, model.metadata.title
+    );
+    let _ = writeln!(text, "## Element");
+    write_element(&mut text, model, element);
+
+    let _ = writeln!(text, "\n## Data flows");
+    if flows.is_empty() {
+        let _ = writeln!(text, "(none)");
+    }
+    for flow in &flows {
+        write_flow(&mut text, model, flow);
+    }
+
+    let _ = writeln!(text, "\n## Existing threats");
+    let existing: Vec<&Threat> = model
+        .threats
+        .iter()
+        .filter(|t| touches(t.element.as_deref()) || touches(t.flow.as_deref()))
+        .collect();
+    if existing.is_empty() {
+        let _ = writeln!(text, "(none)");
+    }
+    for threat in existing {
+        write_threat(&mut text, model, threat);
+    }
+
+    let _ = writeln!(text, "\n## Gaps flagged by the built-in STRIDE rules");
+    let gaps: Vec<Threat> = stride::analyze(model)
+        .into_iter()
+        .filter(|t| touches(t.element.as_deref()) || touches(t.flow.as_deref()))
+        .collect();
+    if gaps.is_empty() {
+        let _ = writeln!(text, "(none)");
+    }
+    for gap in &gaps {
+        let _ = writeln!(
+            text,
+            "- [{}] {} ({})",
+            category_name(gap),
+            gap.title,
+            gap.severity.as_str()
+        );
+    }
+
+    let _ = write!(
+        text,
+        "\nFor each STRIDE category (Spoofing, Tampering, Repudiation, Information Disclosure, \
+         Denial of Service, Elevation of Privilege), state whether it applies to this element or \
+         its flows and whether an existing threat covers it. For every uncovered category that \
+         applies, propose a threat with a title, severity, and description, and add it with the \
+         `add_threat` tool using the element or flow id above. Do not duplicate existing threats."
+    );
+
+    RenderedPrompt {
+        description: format!("STRIDE coverage review for {}", element.name),
+        text,
+    }
+}
+
+fn propose_mitigations(model: &ThreatModel, severity: Severity) -> RenderedPrompt {
+    let open: Vec<&Threat> = model
+        .threats
+        .iter()
+        .filter(|t| t.severity == severity && t.is_unmitigated())
+        .collect();
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "Propose mitigations for the unmitigated {} threats in the threat model \"{}\".\n",
+        severity.as_str(),
+        model.metadata.title
+    );
+    let _ = writeln!(text, "## Threats");
+    if open.is_empty() {
+        let _ = writeln!(
+            text,
+            "(none — every {} threat is addressed)",
+            severity.as_str()
+        );
+    }
+    for threat in &open {
+        write_threat(&mut text, model, threat);
+        if !threat.description.is_empty() {
+            let _ = writeln!(text, "  {}", threat.description);
+        }
+        if let Some(mitigation) = &threat.mitigation {
+            if !mitigation.description.is_empty() {
+                let _ = writeln!(text, "  Current plan: {}", mitigation.description);
+            }
+        }
+    }
+
+    let _ = write!(
+        text,
+        "\nFor each threat, propose one or more concrete mitigations that fit the technologies \
+         involved, say which STRIDE property each restores, and estimate the effort (low, \
+         medium, high). Prefer controls that address several threats at once and call those out. \
+         Reference threats by id."
+    );
+
+    RenderedPrompt {
+        description: format!(
+            "Mitigation proposals for {} unmitigated {} threat(s)",
+            open.len(),
+            severity.as_str()
+        ),
+        text,
+    }
+}
+
+fn describe_trust_boundaries(model: &ThreatModel, boundaries: &[&TrustBoundary]) -> RenderedPrompt {
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "Describe the trust boundaries of the threat model \"{}\".\n",
+        model.metadata.title
+    );
+
+    if boundaries.is_empty() {
+        let _ = writeln!(text, "The model has no trust boundaries yet.\n");
+    }
+    for boundary in boundaries {
+        let inside = |id: &str| boundary.contains.iter().any(|c| c == id);
+        let _ = writeln!(text, "## {} (`{}`)", boundary.name, boundary.id);
+        let _ = writeln!(text, "Contains:");
+        if boundary.contains.is_empty() {
+            let _ = writeln!(text, "(nothing)");
+        }
+        for id in &boundary.contains {
+            match model.elements.iter().find(|e| &e.id == id) {
+                Some(element) => write_element(&mut text, model, element),
+                None => {
+                    let _ = writeln!(text, "- `{id}` (missing element)");
+                }
+            }
+        }
+        let _ = writeln!(text, "Crossing flows:");
+        let crossing: Vec<&DataFlow> = model
+            .data_flows
+            .iter()
+            .filter(|f| inside(&f.from) != inside(&f.to))
+            .collect();
+        if crossing.is_empty() {
+            let _ = writeln!(text, "(none)");
+        }
+        for flow in crossing {
+            write_flow(&mut text, model, flow);
+        }
+        let _ = writeln!(text);
+    }
+
+    let unbounded: Vec<&Element> = model
+        .elements
+        .iter()
+        .filter(|e| e.element_type != "text")
+        .filter(|e| {
+            !model
+                .trust_boundaries
+                .iter()
+                .any(|b| b.contains.contains(&e.id))
+        })
+        .collect();
+    if !unbounded.is_empty() {
+        let _ = writeln!(text, "## Elements outside every boundary");
+        for element in unbounded {
+            write_element(&mut text, model, element);
+        }
+        let _ = writeln!(text);
+    }
+
+    let _ = write!(
+        text,
+        "Explain what each boundary separates and what it protects. For every crossing flow, \
+         say whether it is authenticated and protected in transit, and flag any that are not. \
+         Point out elements that look misplaced or should sit inside a boundary."
+    );
+
+    RenderedPrompt {
+        description: match boundaries {
+            [only] => format!("Trust boundary description for {}", only.name),
+            _ => format!("Description of {} trust boundaries", boundaries.len()),
+        },
+        text,
+    }
+}
+
+fn element_name<'a>(model: &'a ThreatModel, id: &'a str) -> &'a str {
+    model
+        .elements
+        .iter()
+        .find(|e| e.id == id)
+        .map(|e| e.name.as_str())
+        .unwrap_or(id)
+}
+
+fn category_name(threat: &Threat) -> String {
+    serde_json::to_value(&threat.category)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn write_element(text: &mut String, model: &ThreatModel, element: &Element) {
+    let _ = write!(
+        text,
+        "- `{}` {} ({})",
+        element.id, element.name, element.element_type
+    );
+    if !element.trust_zone.is_empty() {
+        let _ = write!(text, ", trust zone {}", element.trust_zone);
+    }
+    if !element.technologies.is_empty() {
+        let _ = write!(text, ", technologies: {}", element.technologies.join(", "));
+    }
+    if let Some(boundary) = model
+        .trust_boundaries
+        .iter()
+        .find(|b| b.contains.contains(&element.id))
+    {
+        let _ = write!(text, ", inside {}", boundary.name);
+    }
+    let _ = writeln!(text);
+    if !element.description.is_empty() {
+        let _ = writeln!(text, "  {}", element.description);
+    }
+}
+
+fn write_flow(text: &mut String, model: &ThreatModel, flow: &DataFlow) {
+    let _ = write!(
+        text,
+        "- `{}` {} → {}",
+        flow.id,
+        element_name(model, &flow.from),
+        element_name(model, &flow.to)
+    );
+    if !flow.protocol.is_empty() {
+        let _ = write!(text, " over {}", flow.protocol);
+    }
+    if !flow.data.is_empty() {
+        let _ = write!(text, ", carries {}", flow.data.join(", "));
+    }
+    let _ = writeln!(
+        text,
+        ", {}",
+        if flow.authenticated {
+            "authenticated"
+        } else {
+            "unauthenticated"
+        }
+    );
+}
+
+fn write_threat(text: &mut String, model: &ThreatModel, threat: &Threat) {
+    let target = threat
+        .element
+        .as_deref()
+        .map(|id| element_name(model, id).to_string())
+        .or_else(|| threat.flow.clone())
+        .unwrap_or_default();
+    let status = threat
+        .mitigation
+        .as_ref()
+        .and_then(|m| serde_json::to_value(&m.status).ok())
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| "none".to_string());
+    let _ = writeln!(
+        text,
+        "- `{}` [{}] {} ({}) on {} — mitigation: {}",
+        threat.id,
+        category_name(threat),
+        threat.title,
+        threat.severity.as_str(),
+        target,
+        status
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Mitigation, MitigationStatus, StrideCategory};
+
+    fn element(id: &str, element_type: &str, name: &str) -> Element {
+        Element {
+            id: id.to_string(),
+            element_type: element_type.to_string(),
+            name: name.to_string(),
+            trust_zone: String::new(),
+            layer: None,
+            group: None,
+            diagram: None,
+            subtype: None,
+            icon: None,
+            description: String::new(),
+            technologies: Vec::new(),
+            tags: Vec::new(),
+            stores: None,
+            encryption: None,
+            operations: Vec::new(),
+            position: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+            font_size: None,
+            font_weight: None,
+        }
+    }
+
+    fn sample_model() -> ThreatModel {
+        let mut model = ThreatModel::new("Prompts", "Tester");
+        model.elements.push(element("api", "process", "API"));
+        model.elements.push(element("db", "data_store", "Database"));
+        model.data_flows.push(DataFlow {
+            id: "flow-1".to_string(),
+            flow_number: Some(1),
+            name: "Query".to_string(),
+            from: "api".to_string(),
+            to: "db".to_string(),
+            protocol: "TLS".to_string(),
+            data: vec!["orders".to_string()],
+            authenticated: true,
+            label_offset: None,
+            source_handle: None,
+            target_handle: None,
+            stroke_color: None,
+            stroke_opacity: None,
+        });
+        model.trust_boundaries.push(TrustBoundary {
+            id: "internal".to_string(),
+            name: "Internal".to_string(),
+            contains: vec!["db".to_string()],
+            diagram: None,
+            shape: None,
+            points: Vec::new(),
+            position: None,
+            size: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+        });
+        for (id, severity, status) in [
+            ("threat-1", Severity::High, None),
+            (
+                "threat-2",
+                Severity::High,
+                Some(MitigationStatus::Mitigated),
+            ),
+        ] {
+            model.threats.push(Threat {
+                id: id.to_string(),
+                title: format!("Title {id}"),
+                category: StrideCategory::Spoofing,
+                element: Some("api".to_string()),
+                flow: None,
+                severity,
+                description: String::new(),
+                mitigation: status.map(|status| Mitigation {
+                    status,
+                    description: String::new(),
+                }),
+            });
+        }
+        model
+    }
+
+    fn args(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn every_listed_prompt_renders_with_its_required_arguments() {
+        let model = sample_model();
+        for spec in list() {
+            let mut given = HashMap::new();
+            if spec.arguments.iter().any(|a| a.required) {
+                given = args(&[("element_id", "api")]);
+            }
+            assert!(
+                render(&model, spec.name, &given).is_ok(),
+                "{} failed to render",
+                spec.name
+            );
+        }
+    }
+
+    #[test]
+    fn stride_review_lists_flows_existing_threats_and_rule_gaps() {
+        let model = sample_model();
+        let prompt = render(
+            &model,
+            REVIEW_STRIDE_COVERAGE,
+            &args(&[("element_id", "api")]),
+        )
+        .unwrap();
+        assert!(prompt.text.contains("`flow-1` API → Database over TLS"));
+        assert!(prompt.text.contains("`threat-1` [Spoofing] Title threat-1"));
+        // The built-in rules already suggest a tampering threat for a process.
+        assert!(prompt.text.contains("[Tampering] Tampering with API"));
+    }
+
+    #[test]
+    fn mitigation_prompt_only_includes_open_threats_of_the_requested_severity() {
+        let model = sample_model();
+        let prompt = render(&model, PROPOSE_MITIGATIONS, &HashMap::new()).unwrap();
+        assert!(prompt.text.contains("`threat-1`"));
+        assert!(!prompt.text.contains("`threat-2`"));
+
+        let low = render(&model, PROPOSE_MITIGATIONS, &args(&[("severity", "low")])).unwrap();
+        assert!(!low.text.contains("`threat-1`"));
+    }
+
+    #[test]
+    fn boundary_prompt_reports_crossing_flows_and_unbounded_elements() {
+        let model = sample_model();
+        let prompt = render(
+            &model,
+            DESCRIBE_TRUST_BOUNDARIES,
+            &args(&[("boundary_id", "internal")]),
+        )
+        .unwrap();
+        assert!(prompt.text.contains("Crossing flows:\n- `flow-1`"));
+        assert!(prompt
+            .text
+            .contains("## Elements outside every boundary\n- `api` API"));
+    }
+
+    #[test]
+    fn unknown_prompts_ids_and_missing_arguments_are_rejected() {
+        let model = sample_model();
+        assert!(render(&model, "nope", &HashMap::new()).is_err());
+        assert!(render(&model, REVIEW_STRIDE_COVERAGE, &HashMap::new()).is_err());
+        assert!(render(
+            &model,
+            REVIEW_STRIDE_COVERAGE,
+            &args(&[("element_id", "missing")])
+        )
+        .is_err());
+        assert!(render(
+            &model,
+            PROPOSE_MITIGATIONS,
+            &args(&[("severity", "urgent")])
+        )
+        .is_err());
+        assert!(render(
+            &model,
+            DESCRIBE_TRUST_BOUNDARIES,
+            &args(&[("boundary_id", "missing")])
+        )
+        .is_err());
+    }
+}
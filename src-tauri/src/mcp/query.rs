@@ -0,0 +1,580 @@
+//! Filtered, paginated reads behind the `query` and `get_element` tools.
+//!
+//! `list_elements` and `list_threats` return whole sections, which is too
+//! much for an assistant's context on large models. `query` narrows one
+//! section with filters, returns a page at a time, and projects each item to
+//! the fields asked for. Items are matched on their serialized form, so the
+//! filter values are the ones that appear in `.thf` files. A cursor carries
+//! the position after the last page and the version of the file it was read
+//! from, so a page is never taken from a model that changed in between.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::file_io::FileStamp;
+use crate::models::ThreatModel;
+
+/// Page size when the request gives none.
+const DEFAULT_LIMIT: usize = 50;
+/// Largest page a client may ask for.
+const MAX_LIMIT: usize = 500;
+
+/// How many hex digits of the file's SHA-256 a cursor carries: enough that
+/// an edit between two pages is caught, short enough to pass around.
+const CURSOR_VERSION_LEN: usize = 16;
+
+/// Sections `query` can search.
+const SECTIONS: &[&str] = &["elements", "data_flows", "trust_boundaries", "threats"];
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(super) struct QueryRequest {
+    #[schemars(
+        description = "Section to search: elements, data_flows, trust_boundaries, or threats"
+    )]
+    pub(super) section: String,
+    #[schemars(
+        description = "Element type (process, data_store, external_entity, text). For data flows and threats, matches the connected or targeted element"
+    )]
+    pub(super) element_type: Option<String>,
+    #[schemars(
+        description = "Element tag. For data flows and threats, matches the connected or targeted element"
+    )]
+    pub(super) tag: Option<String>,
+    #[schemars(
+        description = "Trust zone. For data flows and threats, matches the connected or targeted element"
+    )]
+    pub(super) trust_zone: Option<String>,
+    #[schemars(description = "Threats only: STRIDE category, e.g. Spoofing or Denial of Service")]
+    pub(super) category: Option<String>,
+    #[schemars(description = "Threats only: critical, high, medium, low, or info")]
+    pub(super) severity: Option<String>,
+    #[schemars(
+        description = "Threats only: not_started, in_progress, mitigated, accepted, transferred, or none (no mitigation recorded)"
+    )]
+    pub(super) mitigation_status: Option<String>,
+    #[schemars(
+        description = "Case-insensitive text matched against ids, names, titles, and descriptions"
+    )]
+    pub(super) text: Option<String>,
+    #[schemars(
+        description = "Fields to return for each item (default: a short summary per section; id is always included)"
+    )]
+    pub(super) fields: Option<Vec<String>>,
+    #[schemars(description = "Maximum items per page (default 50, at most 500)")]
+    pub(super) limit: Option<u32>,
+    #[schemars(description = "next_cursor from a previous page of the same query")]
+    pub(super) cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(super) struct GetElementRequest {
+    #[schemars(description = "ID of the element")]
+    pub(super) id: String,
+}
+
+/// Fields returned when the request does not choose any.
+fn default_fields(section: &str) -> &'static [&'static str] {
+    match section {
+        "elements" => &["id", "type", "name", "trust_zone"],
+        "data_flows" => &["id", "name", "from", "to", "protocol"],
+        "trust_boundaries" => &["id", "name", "contains"],
+        _ => &["id", "title", "category", "severity", "element", "flow"],
+    }
+}
+
+/// Run a query and return `{ section, total, items, next_cursor }`, where
+/// `total` counts every match, not just this page. `stamp` is the file the
+/// model was just loaded from; a cursor issued for another version of it is
+/// refused, because its position may no longer point at the next item.
+pub(super) fn run(
+    model: &ThreatModel,
+    stamp: &FileStamp,
+    req: &QueryRequest,
+) -> Result<Value, String> {
+    let section = req.section.as_str();
+    if !SECTIONS.contains(&section) {
+        return Err(format!(
+            "Unknown section: {section}. Use one of: {}",
+            SECTIONS.join(", ")
+        ));
+    }
+    check_filters_apply(req)?;
+
+    let limit = match req.limit {
+        Some(0) => return Err("limit must be at least 1".to_string()),
+        Some(n) => (n as usize).min(MAX_LIMIT),
+        None => DEFAULT_LIMIT,
+    };
+    let version = cursor_version(stamp);
+    let offset = match &req.cursor {
+        Some(cursor) => parse_cursor(cursor, version)?,
+        None => 0,
+    };
+
+    let serialized = serde_json::to_value(model).map_err(|e| format!("JSON: {e}"))?;
+    let elements = serialized["elements"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let flows = serialized["data_flows"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let items = serialized[section].as_array().cloned().unwrap_or_default();
+    let element =
+        |id: Option<&str>| id.and_then(|id| elements.iter().find(|e| e["id"].as_str() == Some(id)));
+    let flow_ends = |id: Option<&str>| {
+        id.and_then(|id| flows.iter().find(|f| f["id"].as_str() == Some(id)))
+            .map(|f| [f["from"].as_str(), f["to"].as_str()])
+            .into_iter()
+            .flatten()
+    };
+
+    let matches: Vec<&Value> = items
+        .iter()
+        .filter(|item| {
+            let related: Vec<&Value> = match section {
+                "data_flows" => [item["from"].as_str(), item["to"].as_str()]
+                    .into_iter()
+                    .filter_map(&element)
+                    .collect(),
+                "threats" => std::iter::once(item["element"].as_str())
+                    .chain(flow_ends(item["flow"].as_str()))
+                    .filter_map(&element)
+                    .collect(),
+                _ => vec![*item],
+            };
+            matches_element_filters(req, &related) && matches_threat_filters(req, item)
+        })
+        .filter(|item| matches_text(req.text.as_deref(), item))
+        .collect();
+
+    let fields: Vec<&str> = match &req.fields {
+        Some(fields) => fields.iter().map(String::as_str).collect(),
+        None => default_fields(section).to_vec(),
+    };
+    let page: Vec<Value> = matches
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .map(|item| project(item, &fields))
+        .collect();
+    let next = offset + page.len();
+    let next_cursor = (next < matches.len()).then(|| format!("{next}@{version}"));
+
+    Ok(json!({
+        "section": section,
+        "total": matches.len(),
+        "items": page,
+        "next_cursor": next_cursor,
+    }))
+}
+
+fn cursor_version(stamp: &FileStamp) -> &str {
+    &stamp.sha256[..CURSOR_VERSION_LEN.min(stamp.sha256.len())]
+}
+
+/// The offset in a `<offset>@<version>` cursor, if it was issued for
+/// `version`.
+fn parse_cursor(cursor: &str, version: &str) -> Result<usize, String> {
+    let (offset, issued_for) = cursor
+        .split_once('@')
+        .ok_or_else(|| format!("Invalid cursor: {cursor}"))?;
+    let offset = offset
+        .parse::<usize>()
+        .map_err(|_| format!("Invalid cursor: {cursor}"))?;
+    if issued_for != version {
+        return Err(
+            "Stale cursor: the model changed since the previous page. Run the query again \
+             without a cursor"
+                .to_string(),
+        );
+    }
+    Ok(offset)
+}
+
+/// Refuse filters that cannot match anything in the chosen section, so a
+/// typo does not silently return an empty page.
+fn check_filters_apply(req: &QueryRequest) -> Result<(), String> {
+    let section = req.section.as_str();
+    let element_filters = [
+        ("element_type", req.element_type.is_some()),
+        ("tag", req.tag.is_some()),
+        ("trust_zone", req.trust_zone.is_some()),
+    ];
+    let threat_filters = [
+        ("category", req.category.is_some()),
+        ("severity", req.severity.is_some()),
+        ("mitigation_status", req.mitigation_status.is_some()),
+    ];
+    if section == "trust_boundaries" {
+        if let Some((name, _)) = element_filters.iter().find(|(_, set)| *set) {
+            return Err(format!("{name} does not apply to trust_boundaries"));
+        }
+    }
+    if section != "threats" {
+        if let Some((name, _)) = threat_filters.iter().find(|(_, set)| *set) {
+            return Err(format!("{name} applies only to threats"));
+        }
+    }
+    Ok(())
+}
+
+/// Whether any of `related` (the item itself, or the elements it connects
+/// to) satisfies every element filter. Without element filters, everything
+/// matches.
+fn matches_element_filters(req: &QueryRequest, related: &[&Value]) -> bool {
+    if req.element_type.is_none() && req.tag.is_none() && req.trust_zone.is_none() {
+        return true;
+    }
+    related.iter().any(|element| {
+        let element_type = req
+            .element_type
+            .as_deref()
+            .is_none_or(|t| element["type"].as_str() == Some(t));
+        let tag = req.tag.as_deref().is_none_or(|tag| {
+            element["tags"]
+                .as_array()
+                .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag)))
+        });
+        let trust_zone = req
+            .trust_zone
+            .as_deref()
+            .is_none_or(|z| element["trust_zone"].as_str() == Some(z));
+        element_type && tag && trust_zone
+    })
+}
+
+fn matches_threat_filters(req: &QueryRequest, threat: &Value) -> bool {
+    let category = req.category.as_deref().is_none_or(|c| {
+        threat["category"]
+            .as_str()
+            .is_some_and(|actual| actual.eq_ignore_ascii_case(c))
+    });
+    let severity = req.severity.as_deref().is_none_or(|s| {
+        threat["severity"]
+            .as_str()
+            .is_some_and(|actual| actual.eq_ignore_ascii_case(s))
+    });
+    let status = req.mitigation_status.as_deref().is_none_or(|s| {
+        match threat["mitigation"]["status"].as_str() {
+            Some(actual) => actual == s,
+            None => s == "none",
+        }
+    });
+    category && severity && status
+}
+
+fn matches_text(text: Option<&str>, item: &Value) -> bool {
+    let Some(text) = text else {
+        return true;
+    };
+    let needle = text.to_lowercase();
+    ["id", "name", "title", "description"].iter().any(|field| {
+        item[*field]
+            .as_str()
+            .is_some_and(|value| value.to_lowercase().contains(&needle))
+    })
+}
+
+/// `item` reduced to `id` plus `fields`. Fields the item does not carry are
+/// returned as `null`.
+fn project(item: &Value, fields: &[&str]) -> Value {
+    let mut projected = Map::new();
+    projected.insert("id".to_string(), item["id"].clone());
+    for field in fields {
+        projected.insert(field.to_string(), item[*field].clone());
+    }
+    Value::Object(projected)
+}
+
+/// One element with everything attached to it: the flows in and out of it,
+/// the boundaries that contain it, and the threats against it or its flows.
+pub(super) fn element_detail(model: &ThreatModel, id: &str) -> Option<Value> {
+    let element = model.elements.iter().find(|e| e.id == id)?;
+    let outgoing: Vec<_> = model.data_flows.iter().filter(|f| f.from == id).collect();
+    let incoming: Vec<_> = model.data_flows.iter().filter(|f| f.to == id).collect();
+    let boundaries: Vec<Value> = model
+        .trust_boundaries
+        .iter()
+        .filter(|b| b.contains.iter().any(|c| c == id))
+        .map(|b| json!({ "id": b.id, "name": b.name }))
+        .collect();
+    let threats: Vec<_> = model
+        .threats
+        .iter()
+        .filter(|t| {
+            t.element.as_deref() == Some(id)
+                || t.flow
+                    .as_deref()
+                    .is_some_and(|fid| outgoing.iter().chain(&incoming).any(|f| f.id == fid))
+        })
+        .collect();
+    Some(json!({
+        "element": element,
+        "flows": { "outgoing": outgoing, "incoming": incoming },
+        "boundaries": boundaries,
+        "threats": threats,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        DataFlow, Element, Mitigation, MitigationStatus, Severity, StrideCategory, Threat,
+        TrustBoundary,
+    };
+
+    fn element(id: &str, element_type: &str, trust_zone: &str, tags: &[&str]) -> Element {
+        Element {
+            id: id.to_string(),
+            element_type: element_type.to_string(),
+            name: id.to_uppercase(),
+            trust_zone: trust_zone.to_string(),
+            layer: None,
+            group: None,
+            diagram: None,
+            subtype: None,
+            icon: None,
+            description: String::new(),
+            technologies: Vec::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            stores: None,
+            encryption: None,
+            operations: Vec::new(),
+            position: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+            font_size: None,
+            font_weight: None,
+        }
+    }
+
+    fn threat(
+        id: &str,
+        element: &str,
+        severity: Severity,
+        status: Option<MitigationStatus>,
+    ) -> Threat {
+        Threat {
+            id: id.to_string(),
+            title: format!("Threat {id}"),
+            category: StrideCategory::Spoofing,
+            element: Some(element.to_string()),
+            flow: None,
+            severity,
+            description: String::new(),
+            mitigation: status.map(|status| Mitigation {
+                status,
+                description: String::new(),
+            }),
+        }
+    }
+
+    fn stamp() -> FileStamp {
+        FileStamp::from_contents(b"version one", None)
+    }
+
+    fn sample_model() -> ThreatModel {
+        let mut model = ThreatModel::new("Query", "Tester");
+        model.elements = vec![
+            element("api", "process", "dmz", &["public"]),
+            element("db", "data_store", "internal", &["pii"]),
+            element("user", "external_entity", "external", &[]),
+        ];
+        model.data_flows.push(DataFlow {
+            id: "flow-1".to_string(),
+            flow_number: Some(1),
+            name: "Queries".to_string(),
+            from: "api".to_string(),
+            to: "db".to_string(),
+            protocol: "SQL".to_string(),
+            data: Vec::new(),
+            authenticated: true,
+            label_offset: None,
+            source_handle: None,
+            target_handle: None,
+            stroke_color: None,
+            stroke_opacity: None,
+        });
+        model.trust_boundaries.push(TrustBoundary {
+            id: "perimeter".to_string(),
+            name: "Perimeter".to_string(),
+            contains: vec!["api".to_string()],
+            diagram: None,
+            shape: None,
+            points: Vec::new(),
+            position: None,
+            size: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+        });
+        for n in 0..5 {
+            let status = (n % 2 == 0).then_some(MitigationStatus::Mitigated);
+            model
+                .threats
+                .push(threat(&format!("t{n}"), "api", Severity::High, status));
+        }
+        model
+            .threats
+            .push(threat("t-db", "db", Severity::Critical, None));
+        model
+    }
+
+    fn ids(result: &Value) -> Vec<&str> {
+        result["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn filters_threats_by_their_element_and_their_own_fields() {
+        let model = sample_model();
+        let req = QueryRequest {
+            section: "threats".to_string(),
+            trust_zone: Some("dmz".to_string()),
+            mitigation_status: Some("none".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&run(&model, &stamp(), &req).unwrap()), ["t1", "t3"]);
+
+        let req = QueryRequest {
+            section: "threats".to_string(),
+            severity: Some("CRITICAL".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&run(&model, &stamp(), &req).unwrap()), ["t-db"]);
+    }
+
+    #[test]
+    fn threats_on_a_flow_match_the_elements_it_connects() {
+        let mut model = sample_model();
+        model.threats.push(Threat {
+            element: None,
+            flow: Some("flow-1".to_string()),
+            ..threat("t-flow", "", Severity::Low, None)
+        });
+        for (element_type, trust_zone) in [("process", "dmz"), ("data_store", "internal")] {
+            let req = QueryRequest {
+                section: "threats".to_string(),
+                element_type: Some(element_type.to_string()),
+                trust_zone: Some(trust_zone.to_string()),
+                severity: Some("low".to_string()),
+                ..Default::default()
+            };
+            assert_eq!(ids(&run(&model, &stamp(), &req).unwrap()), ["t-flow"]);
+        }
+
+        let req = QueryRequest {
+            section: "threats".to_string(),
+            tag: Some("pii".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&run(&model, &stamp(), &req).unwrap()),
+            ["t-db", "t-flow"]
+        );
+    }
+
+    #[test]
+    fn pages_through_results_with_cursors() {
+        let model = sample_model();
+        let mut req = QueryRequest {
+            section: "threats".to_string(),
+            limit: Some(4),
+            ..Default::default()
+        };
+        let first = run(&model, &stamp(), &req).unwrap();
+        assert_eq!(first["total"], 6);
+        assert_eq!(ids(&first), ["t0", "t1", "t2", "t3"]);
+
+        req.cursor = first["next_cursor"].as_str().map(str::to_string);
+        let second = run(&model, &stamp(), &req).unwrap();
+        assert_eq!(ids(&second), ["t4", "t-db"]);
+        assert_eq!(second["next_cursor"], Value::Null);
+    }
+
+    #[test]
+    fn refuses_a_cursor_from_another_version_of_the_file() {
+        let model = sample_model();
+        let mut req = QueryRequest {
+            section: "threats".to_string(),
+            limit: Some(4),
+            ..Default::default()
+        };
+        let first = run(&model, &stamp(), &req).unwrap();
+        req.cursor = first["next_cursor"].as_str().map(str::to_string);
+
+        let edited = FileStamp::from_contents(b"version two", None);
+        let err = run(&model, &edited, &req).unwrap_err();
+        assert!(err.starts_with("Stale cursor"), "{err}");
+    }
+
+    #[test]
+    fn projects_the_requested_fields() {
+        let model = sample_model();
+        let req = QueryRequest {
+            section: "elements".to_string(),
+            tag: Some("pii".to_string()),
+            fields: Some(vec!["tags".to_string()]),
+            ..Default::default()
+        };
+        let result = run(&model, &stamp(), &req).unwrap();
+        assert_eq!(result["items"], json!([{ "id": "db", "tags": ["pii"] }]));
+    }
+
+    #[test]
+    fn refuses_filters_that_do_not_apply_and_bad_cursors() {
+        let model = sample_model();
+        for req in [
+            QueryRequest {
+                section: "elements".to_string(),
+                severity: Some("high".to_string()),
+                ..Default::default()
+            },
+            QueryRequest {
+                section: "trust_boundaries".to_string(),
+                tag: Some("pii".to_string()),
+                ..Default::default()
+            },
+            QueryRequest {
+                section: "threats".to_string(),
+                cursor: Some("later".to_string()),
+                ..Default::default()
+            },
+            QueryRequest {
+                section: "layers".to_string(),
+                ..Default::default()
+            },
+        ] {
+            assert!(
+                run(&model, &stamp(), &req).is_err(),
+                "{req:?} should be refused"
+            );
+        }
+    }
+
+    #[test]
+    fn element_detail_gathers_flows_boundaries_and_threats() {
+        let model = sample_model();
+        let detail = element_detail(&model, "api").unwrap();
+        assert_eq!(detail["element"]["id"], "api");
+        assert_eq!(detail["flows"]["outgoing"][0]["id"], "flow-1");
+        assert_eq!(detail["flows"]["incoming"], json!([]));
+        assert_eq!(
+            detail["boundaries"],
+            json!([{ "id": "perimeter", "name": "Perimeter" }])
+        );
+        assert_eq!(detail["threats"].as_array().unwrap().len(), 5);
+        assert!(element_detail(&model, "missing").is_none());
+    }
+}
@@ -0,0 +1,266 @@
+//! MCP resources — read-only JSON views of the threat model addressed by
+//! stable `threatforge://` URIs, so an assistant can pull context without
+//! calling tools.
+//!
+//! This module is transport-free: it lists, parses, and renders URIs against
+//! a `ThreatModel`. The handler in `server.rs` owns reloading, subscriptions,
+//! and `resources/updated` notifications.
+
+use serde_json::{json, Value};
+
+use crate::models::{Severity, Threat, ThreatModel};
+
+pub const MODEL_URI: &str = "threatforge://model";
+pub const THREATS_BY_SEVERITY_URI: &str = "threatforge://summaries/threats-by-severity";
+pub const UNMITIGATED_THREATS_URI: &str = "threatforge://summaries/unmitigated-threats";
+pub const ELEMENT_URI_TEMPLATE: &str = "threatforge://elements/{id}";
+pub const THREAT_URI_TEMPLATE: &str = "threatforge://threats/{id}";
+
+const ELEMENT_URI_PREFIX: &str = "threatforge://elements/";
+const THREAT_URI_PREFIX: &str = "threatforge://threats/";
+
+/// A resource URI resolved to the part of the model it names.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResourceRef<'a> {
+    Model,
+    Element(&'a str),
+    Threat(&'a str),
+    ThreatsBySeverity,
+    UnmitigatedThreats,
+}
+
+/// One entry of `resources/list`.
+#[derive(Debug, PartialEq)]
+pub struct ResourceEntry {
+    pub uri: String,
+    pub name: String,
+    pub description: String,
+}
+
+/// Resolve a URI, or `None` if it is not a ThreatForge resource URI. The
+/// referenced id is not checked against the model here; [`render`] does that.
+pub fn parse_uri(uri: &str) -> Option<ResourceRef<'_>> {
+    match uri {
+        MODEL_URI => Some(ResourceRef::Model),
+        THREATS_BY_SEVERITY_URI => Some(ResourceRef::ThreatsBySeverity),
+        UNMITIGATED_THREATS_URI => Some(ResourceRef::UnmitigatedThreats),
+        _ => {
+            if let Some(id) = uri.strip_prefix(ELEMENT_URI_PREFIX) {
+                (!id.is_empty()).then_some(ResourceRef::Element(id))
+            } else if let Some(id) = uri.strip_prefix(THREAT_URI_PREFIX) {
+                (!id.is_empty()).then_some(ResourceRef::Threat(id))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+pub fn element_uri(id: &str) -> String {
+    format!("{ELEMENT_URI_PREFIX}{id}")
+}
+
+pub fn threat_uri(id: &str) -> String {
+    format!("{THREAT_URI_PREFIX}{id}")
+}
+
+/// Every concrete resource the model currently offers, in a stable order:
+/// the model, the summaries, then one entry per element and per threat in
+/// file order.
+pub fn list(model: &ThreatModel) -> Vec<ResourceEntry> {
+    let mut entries = vec![
+        ResourceEntry {
+            uri: MODEL_URI.to_string(),
+            name: model.metadata.title.clone(),
+            description: "The full threat model".to_string(),
+        },
+        ResourceEntry {
+            uri: THREATS_BY_SEVERITY_URI.to_string(),
+            name: "Threats by severity".to_string(),
+            description: "Threat ids and titles grouped from critical to info".to_string(),
+        },
+        ResourceEntry {
+            uri: UNMITIGATED_THREATS_URI.to_string(),
+            name: "Unmitigated threats".to_string(),
+            description: "Threats with no mitigation, or one not started or in progress"
+                .to_string(),
+        },
+    ];
+    entries.extend(model.elements.iter().map(|e| ResourceEntry {
+        uri: element_uri(&e.id),
+        name: e.name.clone(),
+        description: format!("Element `{}` ({})", e.id, e.element_type),
+    }));
+    entries.extend(model.threats.iter().map(|t| ResourceEntry {
+        uri: threat_uri(&t.id),
+        name: t.title.clone(),
+        description: format!("Threat `{}` ({})", t.id, t.severity.as_str()),
+    }));
+    entries
+}
+
+/// Render a resource as pretty JSON, or `None` if the URI is unknown or names
+/// an id the model does not contain.
+pub fn render(model: &ThreatModel, uri: &str) -> Option<String> {
+    let value = match parse_uri(uri)? {
+        ResourceRef::Model => serde_json::to_value(model).ok()?,
+        ResourceRef::Element(id) => {
+            let element = model.elements.iter().find(|e| e.id == id)?;
+            serde_json::to_value(element).ok()?
+        }
+        ResourceRef::Threat(id) => {
+            let threat = model.threats.iter().find(|t| t.id == id)?;
+            serde_json::to_value(threat).ok()?
+        }
+        ResourceRef::ThreatsBySeverity => threats_by_severity(model),
+        ResourceRef::UnmitigatedThreats => Value::Array(
+            model
+                .threats
+                .iter()
+                .filter(|t| t.is_unmitigated())
+                .map(threat_summary)
+                .collect(),
+        ),
+    };
+    serde_json::to_string_pretty(&value).ok()
+}
+
+fn threats_by_severity(model: &ThreatModel) -> Value {
+    let mut grouped = serde_json::Map::new();
+    for severity in Severity::ALL {
+        let threats: Vec<Value> = model
+            .threats
+            .iter()
+            .filter(|t| t.severity == severity)
+            .map(threat_summary)
+            .collect();
+        grouped.insert(severity.as_str().to_string(), Value::Array(threats));
+    }
+    Value::Object(grouped)
+}
+
+fn threat_summary(t: &Threat) -> Value {
+    json!({
+        "id": t.id,
+        "uri": threat_uri(&t.id),
+        "title": t.title,
+        "category": t.category,
+        "severity": t.severity,
+        "element": t.element,
+        "flow": t.flow,
+        "mitigation_status": t.mitigation.as_ref().map(|m| &m.status),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Element, Mitigation, MitigationStatus, StrideCategory};
+
+    fn sample_model() -> ThreatModel {
+        let mut model = ThreatModel::new("Resources", "Tester");
+        model.elements.push(Element {
+            id: "api".to_string(),
+            element_type: "process".to_string(),
+            name: "API".to_string(),
+            trust_zone: String::new(),
+            layer: None,
+            group: None,
+            diagram: None,
+            subtype: None,
+            icon: None,
+            description: String::new(),
+            technologies: Vec::new(),
+            tags: Vec::new(),
+            stores: None,
+            encryption: None,
+            operations: Vec::new(),
+            position: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+            font_size: None,
+            font_weight: None,
+        });
+        for (id, severity, status) in [
+            ("threat-1", Severity::High, None),
+            ("threat-2", Severity::Low, Some(MitigationStatus::Mitigated)),
+        ] {
+            model.threats.push(Threat {
+                id: id.to_string(),
+                title: format!("Title {id}"),
+                category: StrideCategory::Spoofing,
+                element: Some("api".to_string()),
+                flow: None,
+                severity,
+                description: String::new(),
+                mitigation: status.map(|status| Mitigation {
+                    status,
+                    description: String::new(),
+                }),
+            });
+        }
+        model
+    }
+
+    #[test]
+    fn parses_every_uri_shape_and_rejects_others() {
+        assert_eq!(parse_uri(MODEL_URI), Some(ResourceRef::Model));
+        assert_eq!(
+            parse_uri("threatforge://elements/api"),
+            Some(ResourceRef::Element("api"))
+        );
+        assert_eq!(
+            parse_uri("threatforge://threats/threat-1"),
+            Some(ResourceRef::Threat("threat-1"))
+        );
+        assert_eq!(parse_uri("threatforge://elements/"), None);
+        assert_eq!(parse_uri("file:///model.thf"), None);
+    }
+
+    #[test]
+    fn lists_model_summaries_elements_and_threats_in_order() {
+        let uris: Vec<String> = list(&sample_model()).into_iter().map(|e| e.uri).collect();
+        assert_eq!(
+            uris,
+            [
+                MODEL_URI,
+                THREATS_BY_SEVERITY_URI,
+                UNMITIGATED_THREATS_URI,
+                "threatforge://elements/api",
+                "threatforge://threats/threat-1",
+                "threatforge://threats/threat-2",
+            ]
+        );
+    }
+
+    #[test]
+    fn summaries_group_by_severity_and_keep_only_open_threats() {
+        let model = sample_model();
+
+        let by_severity: Value =
+            serde_json::from_str(&render(&model, THREATS_BY_SEVERITY_URI).unwrap()).unwrap();
+        assert_eq!(by_severity["high"][0]["id"], "threat-1");
+        assert_eq!(by_severity["low"][0]["id"], "threat-2");
+        assert_eq!(by_severity["critical"], json!([]));
+
+        let open: Value =
+            serde_json::from_str(&render(&model, UNMITIGATED_THREATS_URI).unwrap()).unwrap();
+        let ids: Vec<&str> = open
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["threat-1"]);
+    }
+
+    #[test]
+    fn unknown_ids_do_not_render() {
+        let model = sample_model();
+        assert!(render(&model, "threatforge://elements/api").is_some());
+        assert!(render(&model, "threatforge://elements/missing").is_none());
+        assert!(render(&model, "threatforge://threats/missing").is_none());
+    }
+}
@@ -1,26 +1,39 @@
 //! ThreatForge MCP Server — exposes threat model data and mutation tools
 //! to external AI assistants (Claude Code, VS Code Copilot, Cursor, etc.)
-//! via the Model Context Protocol over stdio.
+//! via the Model Context Protocol over stdio or streamable HTTP.
 
 use std::borrow::Cow;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
 
 use rmcp::handler::server::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::*;
-use rmcp::{tool, tool_handler, tool_router, ServerHandler};
-use schemars::JsonSchema;
-use serde::Deserialize;
+use rmcp::service::RequestContext;
+use rmcp::{tool, tool_handler, tool_router, Peer, RoleServer, ServerHandler};
 
-use crate::models::{
-    DataFlow, Element, Severity, StrideCategory, Threat, ThreatModel, TrustBoundary,
+use super::audit::{AuditEntry, AuditLog, ChangedItem, Outcome};
+use super::edits::{
+    self, AddDataFlowRequest, AddElementRequest, AddThreatRequest, AddTrustBoundaryRequest,
+    ApplyChangesRequest, DeleteByIdRequest, GetModelRequest, GetReportRequest, OpenModelRequest,
+    UpdateElementRequest,
 };
+use super::query::{self, GetElementRequest, QueryRequest};
+use super::workspace::{relative_name, History, ModelFile, Workspace};
+use super::{diff, prompts, resources};
+use crate::file_io::{validate_references, FileStamp, FileWatchRegistry};
+use crate::models::ThreatModel;
+use crate::report;
 
 /// JSON-RPC internal error code.
 const JSONRPC_INTERNAL_ERROR: i32 = -32603;
 
-fn mcp_err(msg: impl Into<String>) -> ErrorData {
+/// Implementation-defined JSON-RPC server error: the `.thf` file changed on
+/// disk between this server's reload and its save.
+const MODEL_CONFLICT_ERROR: i32 = -32001;
+
+pub(super) fn mcp_err(msg: impl Into<String>) -> ErrorData {
     ErrorData {
         code: ErrorCode(JSONRPC_INTERNAL_ERROR),
         message: Cow::from(msg.into()),
@@ -28,196 +41,462 @@ fn mcp_err(msg: impl Into<String>) -> ErrorData {
     }
 }
 
+/// A conflict the client can act on: nothing was written, and retrying the
+/// call re-reads the file so the other writer's edits are kept.
+pub(super) fn conflict_err() -> ErrorData {
+    ErrorData {
+        code: ErrorCode(MODEL_CONFLICT_ERROR),
+        message: Cow::from(
+            "The threat model was modified by another writer (for example the ThreatForge \
+             desktop app) while this change was being applied. Nothing was saved; retry the \
+             call to apply it on top of the latest version.",
+        ),
+        data: Some(serde_json::json!({ "conflict": true })),
+    }
+}
+
 fn text_result(s: impl Into<String>) -> CallToolResult {
     CallToolResult::success(vec![ContentBlock::text(s.into())])
 }
 
+/// Lock a mutex, recovering from poisoning. Used for per-session bookkeeping
+/// that a panicking holder cannot leave half-written.
+pub(super) fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
 /// Shared, file-backed threat model state.
 #[derive(Clone)]
 pub struct ThreatForgeServer {
-    file_path: PathBuf,
-    model: Arc<Mutex<ThreatModel>>,
+    workspace: Arc<Workspace>,
+    /// Root-relative name of the model this session works on, if one is open.
+    active: Arc<Mutex<Option<String>>>,
+    /// Subscribed resource URIs, each with the content last reported to the
+    /// client, so a file change only notifies the resources it altered.
+    subscriptions: Arc<Mutex<HashMap<String, Option<String>>>>,
+    /// Polls the active `.thf` file while at least one resource is subscribed.
+    watches: Arc<FileWatchRegistry>,
+    /// The watched file and the peer its changes are reported to, so
+    /// `open_model` can move the watch to the newly opened file.
+    watched: Arc<Mutex<Option<WatchedFile>>>,
+    /// This session's undo history for each model file it edited, by path.
+    history: Arc<Mutex<HashMap<PathBuf, History>>>,
+    /// Set by `--read-only`: mutating tools are unregistered and nothing is saved.
+    read_only: bool,
+    /// Where mutating calls are logged.
+    audit_log: Arc<AuditLog>,
+    /// The connected client's `clientInfo`, recorded in the audit log.
+    client: Arc<Mutex<Option<Implementation>>>,
     tool_router: ToolRouter<Self>,
 }
 
+/// The file a session's subscription watch polls and the peer it reports to.
+struct WatchedFile {
+    path: PathBuf,
+    peer: Peer<RoleServer>,
+}
+
+/// A mutating tool call as the audit log records it.
+struct ToolCall {
+    tool: &'static str,
+    arguments: serde_json::Value,
+}
+
+impl ToolCall {
+    fn new(tool: &'static str, request: &impl serde::Serialize) -> Self {
+        Self {
+            tool,
+            arguments: serde_json::to_value(request).unwrap_or_default(),
+        }
+    }
+}
+
+/// Tools that change the model. `--read-only` removes them from the router.
+const MUTATING_TOOLS: &[&str] = &[
+    "apply_changes",
+    "undo",
+    "redo",
+    "add_element",
+    "update_element",
+    "delete_element",
+    "add_data_flow",
+    "delete_data_flow",
+    "add_trust_boundary",
+    "delete_trust_boundary",
+    "add_threat",
+    "delete_threat",
+];
+
 impl ThreatForgeServer {
     /// Create a new server instance for the given `.thf` file.
     pub fn new(file_path: PathBuf) -> Result<Self, String> {
-        let content = std::fs::read_to_string(&file_path)
-            .map_err(|e| format!("Failed to read {}: {e}", file_path.display()))?;
-        let model: ThreatModel = serde_yaml::from_str(&content)
-            .map_err(|e| format!("Failed to parse {}: {e}", file_path.display()))?;
-        Ok(Self {
-            file_path,
-            model: Arc::new(Mutex::new(model)),
+        Ok(Self::with_workspace(Workspace::single_file(&file_path)?))
+    }
+
+    /// Create a server over every `.thf` file below `root`. A session starts
+    /// on the only model if there is exactly one; otherwise the client picks
+    /// one with `open_model`.
+    pub fn workspace(root: PathBuf) -> Result<Self, String> {
+        Ok(Self::with_workspace(Workspace::directory(&root)?))
+    }
+
+    fn with_workspace(workspace: Workspace) -> Self {
+        let active = workspace.default_model();
+        Self {
+            workspace: Arc::new(workspace),
+            active: Arc::new(Mutex::new(active)),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            watches: Arc::new(FileWatchRegistry::default()),
+            watched: Arc::new(Mutex::new(None)),
+            history: Arc::new(Mutex::new(HashMap::new())),
+            read_only: false,
+            audit_log: Arc::new(AuditLog::default()),
+            client: Arc::new(Mutex::new(None)),
             tool_router: Self::tool_router(),
-        })
+        }
     }
 
-    /// Acquire the model lock, recovering from mutex poisoning.
-    fn lock_model(&self) -> Result<std::sync::MutexGuard<'_, ThreatModel>, ErrorData> {
-        match self.model.lock() {
-            Ok(guard) => Ok(guard),
-            Err(poisoned) => {
-                // Recover the inner data; the panic that caused poisoning is gone.
-                Ok(poisoned.into_inner())
-            }
+    /// Disable every mutating tool: they are no longer listed, calls to them
+    /// fail as unknown tools, and no save is ever attempted.
+    pub fn read_only(mut self) -> Self {
+        for name in MUTATING_TOOLS {
+            self.tool_router.remove_route(name);
         }
+        self.read_only = true;
+        self
     }
 
-    /// Persist the current model state to disk.
-    fn save_locked(&self, model: &std::sync::MutexGuard<'_, ThreatModel>) -> Result<(), ErrorData> {
-        let yaml =
-            serde_yaml::to_string(&**model).map_err(|e| mcp_err(format!("Serialize: {e}")))?;
-        std::fs::write(&self.file_path, yaml)
-            .map_err(|_| mcp_err("Failed to write threat model file"))?;
-        Ok(())
+    /// Write audit logs under `dir` instead of beside each model.
+    pub fn audit_dir(mut self, dir: PathBuf) -> Self {
+        self.audit_log = Arc::new(AuditLog::in_directory(dir));
+        self
     }
 
-    /// Reload the model from disk into the given locked guard.
-    fn reload_locked(
-        &self,
-        model: &mut std::sync::MutexGuard<'_, ThreatModel>,
-    ) -> Result<(), ErrorData> {
-        let content = std::fs::read_to_string(&self.file_path)
-            .map_err(|_| mcp_err("Failed to read threat model file"))?;
-        let fresh: ThreatModel =
-            serde_yaml::from_str(&content).map_err(|e| mcp_err(format!("Parse error: {e}")))?;
-        **model = fresh;
-        Ok(())
+    /// A handle for one more client session over the same workspace.
+    ///
+    /// Models are shared, so every session sees the others' edits; the open
+    /// model and resource subscriptions are per session, because
+    /// `resources/updated` notifications go to the peer that subscribed, and
+    /// so is undo history, so `undo` only ever reverts this session's edits.
+    pub fn session(&self) -> Self {
+        Self {
+            workspace: self.workspace.clone(),
+            active: Arc::new(Mutex::new(lock(&self.active).clone())),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            watches: Arc::new(FileWatchRegistry::default()),
+            watched: Arc::new(Mutex::new(None)),
+            history: Arc::new(Mutex::new(HashMap::new())),
+            read_only: self.read_only,
+            audit_log: self.audit_log.clone(),
+            client: Arc::new(Mutex::new(None)),
+            tool_router: self.tool_router.clone(),
+        }
     }
 
-    fn next_flow_number(model: &ThreatModel) -> u32 {
-        model
-            .data_flows
-            .iter()
-            .filter_map(|f| f.flow_number)
-            .max()
-            .and_then(|n| n.checked_add(1))
-            .unwrap_or(1)
+    /// The model this session works on.
+    fn current(&self) -> Result<Arc<ModelFile>, ErrorData> {
+        let name = lock(&self.active).clone().ok_or_else(|| {
+            mcp_err("No model is open. Call list_models, then open_model with one of the names")
+        })?;
+        Ok(self.workspace.open(&name)?.1)
     }
 
-    /// Valid element types accepted by the MCP server.
-    const VALID_ELEMENT_TYPES: &[&str] = &["process", "data_store", "external_entity", "text"];
+    /// Apply an edit to the freshly reloaded model, save it, and record the
+    /// previous version for `undo`. A dry run instead reports the changes
+    /// the edit would make. Either way, a failed or previewed edit leaves the
+    /// model as it is on disk. Everything but a dry run is audited.
+    fn edit(
+        &self,
+        call: ToolCall,
+        dry_run: Option<bool>,
+        apply: impl FnOnce(&mut ThreatModel) -> Result<String, ErrorData>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let dry_run = dry_run.unwrap_or(false);
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        let before = (*model).clone();
+        let before_stamp = file.lock_stamp().clone();
+        let summary = match apply(&mut model) {
+            Ok(summary) => summary,
+            Err(e) => {
+                *model = before;
+                if !dry_run {
+                    self.audit(&file, call, &before_stamp, Err(&e), &[]);
+                }
+                return Err(e);
+            }
+        };
 
-    /// Generate a unique element ID, appending a suffix on collision.
-    fn unique_element_id(model: &ThreatModel, base: &str) -> String {
-        let base_id = crate::models::generate_element_id(base);
-        if !model.elements.iter().any(|e| e.id == base_id) {
-            return base_id;
+        let changes = diff::diff_models(&before, &model);
+        if dry_run {
+            *model = before;
+            let report = serde_json::json!({
+                "dry_run": true,
+                "summary": summary,
+                "changes": changes,
+            });
+            let json =
+                serde_json::to_string_pretty(&report).map_err(|e| mcp_err(format!("JSON: {e}")))?;
+            return Ok(text_result(json));
         }
-        let mut counter = 2;
-        loop {
-            let candidate = format!("{base_id}-{counter}");
-            if !model.elements.iter().any(|e| e.id == candidate) {
-                return candidate;
-            }
-            counter += 1;
+        let saved = if self.read_only {
+            Err(mcp_err(
+                "The server is read-only; the model cannot be changed",
+            ))
+        } else {
+            file.save_locked(&model)
+        };
+        if let Err(e) = saved {
+            *model = before;
+            self.audit(&file, call, &before_stamp, Err(&e), &[]);
+            return Err(e);
         }
+        self.audit(&file, call, &before_stamp, Ok(&summary), &changes);
+        let mut history = lock(&self.history);
+        file.record_edit(
+            history.entry(file.path().to_path_buf()).or_default(),
+            before,
+        );
+        Ok(text_result(summary))
     }
 
-    /// Generate a unique boundary ID, appending a suffix on collision.
-    fn unique_boundary_id(model: &ThreatModel, base: &str) -> String {
-        let base_id = crate::models::generate_element_id(base);
-        if !model.trust_boundaries.iter().any(|b| b.id == base_id) {
-            return base_id;
-        }
-        let mut counter = 2;
-        loop {
-            let candidate = format!("{base_id}-{counter}");
-            if !model.trust_boundaries.iter().any(|b| b.id == candidate) {
-                return candidate;
+    /// Step the open model back (`undo`) or forward through its MCP history.
+    fn step_history(&self, undo: bool) -> Result<CallToolResult, ErrorData> {
+        let call = ToolCall {
+            tool: if undo { "undo" } else { "redo" },
+            arguments: serde_json::json!({}),
+        };
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        let before = (*model).clone();
+        let before_stamp = file.lock_stamp().clone();
+        let stepped = {
+            let mut histories = lock(&self.history);
+            let history = histories.entry(file.path().to_path_buf()).or_default();
+            if undo {
+                file.undo_locked(&mut model, history)
+            } else {
+                file.redo_locked(&mut model, history)
             }
-            counter += 1;
-        }
+        };
+        let remaining = match stepped {
+            Ok(remaining) => remaining,
+            Err(e) => {
+                self.audit(&file, call, &before_stamp, Err(&e), &[]);
+                return Err(e);
+            }
+        };
+        let summary = if undo {
+            "Undid the last change"
+        } else {
+            "Redid the last undone change"
+        };
+        let changes = diff::diff_models(&before, &model);
+        self.audit(&file, call, &before_stamp, Ok(summary), &changes);
+        let report = serde_json::json!({
+            "summary": summary,
+            "changes": changes,
+            "remaining": remaining,
+        });
+        let json =
+            serde_json::to_string_pretty(&report).map_err(|e| mcp_err(format!("JSON: {e}")))?;
+        Ok(text_result(json))
     }
-}
-
-// ───── Tool input schemas ─────
-
-#[derive(Debug, Deserialize, JsonSchema)]
-struct GetModelRequest {}
-
-#[derive(Debug, Deserialize, JsonSchema)]
-struct AddElementRequest {
-    #[schemars(description = "Element type: process, data_store, external_entity, or text")]
-    element_type: String,
-    #[schemars(description = "Human-readable element name")]
-    name: String,
-    #[schemars(description = "Trust zone (e.g. internal, dmz, external)")]
-    trust_zone: Option<String>,
-    #[schemars(description = "Description of the element")]
-    description: Option<String>,
-    #[schemars(description = "Technologies used (e.g. [\"nginx\", \"TLS\"])")]
-    technologies: Option<Vec<String>>,
-}
 
-#[derive(Debug, Deserialize, JsonSchema)]
-struct UpdateElementRequest {
-    #[schemars(description = "ID of the element to update")]
-    id: String,
-    #[schemars(description = "New name")]
-    name: Option<String>,
-    #[schemars(description = "New type")]
-    element_type: Option<String>,
-    #[schemars(description = "New trust zone")]
-    trust_zone: Option<String>,
-    #[schemars(description = "New description")]
-    description: Option<String>,
-}
+    /// Append a mutating call to the model's audit log. Call with the model
+    /// lock held, so entries are in the order the changes were applied.
+    ///
+    /// The change itself has already happened (or failed), so a log that
+    /// cannot be written is reported on stderr rather than failing the call.
+    fn audit(
+        &self,
+        file: &ModelFile,
+        call: ToolCall,
+        before: &FileStamp,
+        outcome: Result<&str, &ErrorData>,
+        changes: &[diff::Change],
+    ) {
+        let root = self.workspace.root();
+        let entry = AuditEntry {
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            tool: call.tool.to_string(),
+            arguments: call.arguments,
+            client: lock(&self.client).clone(),
+            model: relative_name(root, file.path()),
+            outcome: if outcome.is_ok() {
+                Outcome::Ok
+            } else {
+                Outcome::Error
+            },
+            result: match outcome {
+                Ok(summary) => summary.to_string(),
+                Err(e) => e.message.to_string(),
+            },
+            before_sha256: before.sha256.clone(),
+            after_sha256: outcome.is_ok().then(|| file.lock_stamp().sha256.clone()),
+            changes: changes.iter().map(ChangedItem::from).collect(),
+        };
+        if let Err(e) = self.audit_log.append(root, file.path(), &entry) {
+            eprintln!("Failed to write the MCP audit log: {e}");
+        }
+    }
 
-#[derive(Debug, Deserialize, JsonSchema)]
-struct DeleteByIdRequest {
-    #[schemars(description = "ID of the item to delete")]
-    id: String,
-}
+    /// Start (or move) the subscription watch to `path`, reporting to `peer`.
+    ///
+    /// Without a baseline the first poll always fires, which is what a moved
+    /// watch wants: the subscribed resources now render a different model.
+    fn watch_for_subscribers(&self, path: &Path, peer: Peer<RoleServer>, baseline: bool) {
+        let previous = lock(&self.watched).replace(WatchedFile {
+            path: path.to_path_buf(),
+            peer: peer.clone(),
+        });
+        if let Some(old) = previous {
+            self.watches.unwatch(&old.path);
+        }
+        let baseline = baseline
+            .then(|| self.current().ok().map(|f| f.lock_stamp().clone()))
+            .flatten();
+        // The watch thread owns this closure, so it must not hold the session:
+        // a clone would keep `watches` alive and the watch would never stop.
+        let notifier = Arc::new(SubscriptionNotifier {
+            workspace: self.workspace.clone(),
+            active: Arc::downgrade(&self.active),
+            subscriptions: Arc::downgrade(&self.subscriptions),
+        });
+        let runtime = tokio::runtime::Handle::current();
+        self.watches.watch(path, baseline, move |_, _| {
+            let notifier = notifier.clone();
+            let peer = peer.clone();
+            runtime.spawn(async move { notifier.notify_changed_resources(&peer).await });
+        });
+    }
 
-#[derive(Debug, Deserialize, JsonSchema)]
-struct AddDataFlowRequest {
-    #[schemars(description = "Source element ID")]
-    from: String,
-    #[schemars(description = "Target element ID")]
-    to: String,
-    #[schemars(description = "Flow name")]
-    name: Option<String>,
-    #[schemars(description = "Protocol (e.g. HTTPS/TLS-1.3)")]
-    protocol: Option<String>,
-    #[schemars(description = "Data items carried")]
-    data: Option<Vec<String>>,
-    #[schemars(description = "Whether the flow is authenticated")]
-    authenticated: Option<bool>,
+    /// Reload the file and render `uri`, or fail with "resource not found".
+    fn read_resource_text(&self, uri: &str) -> Result<String, ErrorData> {
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        resources::render(&model, uri)
+            .ok_or_else(|| ErrorData::resource_not_found(format!("Unknown resource: {uri}"), None))
+    }
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
-struct AddTrustBoundaryRequest {
-    #[schemars(description = "Boundary name")]
-    name: String,
-    #[schemars(description = "Element IDs contained in this boundary")]
-    contains: Option<Vec<String>>,
+/// What a subscription watch needs to report changes. The session state is
+/// held weakly, so a session that has ended (its transport closed, or its
+/// HTTP session expired) drops its watches instead of being kept alive by them.
+struct SubscriptionNotifier {
+    workspace: Arc<Workspace>,
+    active: Weak<Mutex<Option<String>>>,
+    subscriptions: Weak<Mutex<HashMap<String, Option<String>>>>,
 }
 
-#[derive(Debug, Deserialize, JsonSchema)]
-struct AddThreatRequest {
-    #[schemars(description = "Threat title")]
-    title: String,
-    #[schemars(
-        description = "STRIDE category: Spoofing, Tampering, Repudiation, Information Disclosure, Denial of Service, Elevation of Privilege"
-    )]
-    category: String,
-    #[schemars(description = "Severity: critical, high, medium, low, info")]
-    severity: String,
-    #[schemars(description = "Detailed threat description")]
-    description: String,
-    #[schemars(description = "Target element ID")]
-    element: Option<String>,
-    #[schemars(description = "Target data flow ID")]
-    flow: Option<String>,
+impl SubscriptionNotifier {
+    /// Re-render every subscribed resource and notify the client about the
+    /// ones whose content changed (including ones that no longer exist).
+    async fn notify_changed_resources(&self, peer: &Peer<RoleServer>) {
+        let (Some(active), Some(subscriptions)) =
+            (self.active.upgrade(), self.subscriptions.upgrade())
+        else {
+            return;
+        };
+        let changed: Vec<String> = {
+            let Some(name) = lock(&active).clone() else {
+                return;
+            };
+            let Ok((_, file)) = self.workspace.open(&name) else {
+                return;
+            };
+            let mut model = file.lock_model();
+            if file.reload_locked(&mut model).is_err() {
+                // Mid-write or briefly invalid; the next change is reported.
+                return;
+            }
+            let mut subscriptions = lock(&subscriptions);
+            subscriptions
+                .iter_mut()
+                .filter_map(|(uri, last)| {
+                    let current = resources::render(&model, uri);
+                    if *last == current {
+                        return None;
+                    }
+                    *last = current;
+                    Some(uri.clone())
+                })
+                .collect()
+        };
+        for uri in changed {
+            if peer
+                .notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+                .await
+                .is_err()
+            {
+                eprintln!("Failed to deliver resources/updated notification");
+            }
+        }
+    }
 }
 
 // ───── Tool implementations ─────
 
 #[tool_router]
 impl ThreatForgeServer {
+    #[tool(
+        description = "List the threat models in the workspace with their titles. The open model is marked."
+    )]
+    async fn list_models(
+        &self,
+        Parameters(_req): Parameters<GetModelRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let active = lock(&self.active).clone();
+        let summary: Vec<serde_json::Value> = self
+            .workspace
+            .model_names()
+            .into_iter()
+            .map(|name| {
+                let title = self.workspace.open(&name).ok().and_then(|(_, file)| {
+                    let mut model = file.lock_model();
+                    file.reload_locked(&mut model).ok()?;
+                    Some(model.metadata.title.clone())
+                });
+                serde_json::json!({
+                    "model": name,
+                    "title": title,
+                    "open": active.as_deref() == Some(name.as_str()),
+                })
+            })
+            .collect();
+        let json =
+            serde_json::to_string_pretty(&summary).map_err(|e| mcp_err(format!("JSON: {e}")))?;
+        Ok(text_result(json))
+    }
+
+    #[tool(
+        description = "Open a threat model from the workspace. Subsequent tools, resources, and prompts in this session use it."
+    )]
+    async fn open_model(
+        &self,
+        Parameters(req): Parameters<OpenModelRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let (name, file) = self.workspace.open(&req.model)?;
+        {
+            let mut model = file.lock_model();
+            file.reload_locked(&mut model)?;
+        }
+        *lock(&self.active) = Some(name.clone());
+
+        // Subscribed resources now describe a different file.
+        let watched_peer = lock(&self.watched).as_ref().map(|w| w.peer.clone());
+        if let Some(peer) = watched_peer {
+            self.watch_for_subscribers(file.path(), peer, false);
+        }
+        Ok(text_result(format!("Opened model: {name}")))
+    }
+
     #[tool(
         description = "Get the full threat model as JSON. Returns metadata, elements, data flows, trust boundaries, and threats."
     )]
@@ -225,20 +504,40 @@ impl ThreatForgeServer {
         &self,
         Parameters(_req): Parameters<GetModelRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
         let json =
             serde_json::to_string_pretty(&*model).map_err(|e| mcp_err(format!("JSON: {e}")))?;
         Ok(text_result(json))
     }
 
+    #[tool(
+        description = "Render the threat model as a Markdown report: summary with threats by severity, STRIDE category and mitigation status, the element, trust boundary and data flow inventory, and the threat register grouped by element and STRIDE category. Pass template to render through a built-in (html, markdown, text) or project report template instead."
+    )]
+    async fn get_report(
+        &self,
+        Parameters(req): Parameters<GetReportRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        let text = match req.template.as_deref() {
+            None => report::render_markdown(&model),
+            Some(template) => report::render_template(&model, Some(file.path()), template)
+                .map_err(|e| ErrorData::invalid_params(e, None))?,
+        };
+        Ok(text_result(text))
+    }
+
     #[tool(description = "List all elements in the threat model with their IDs, types, and names.")]
     async fn list_elements(
         &self,
         Parameters(_req): Parameters<GetModelRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
         let summary: Vec<serde_json::Value> = model
             .elements
             .iter()
@@ -261,8 +560,9 @@ impl ThreatForgeServer {
         &self,
         Parameters(_req): Parameters<GetModelRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
         let summary: Vec<serde_json::Value> = model
             .threats
             .iter()
@@ -282,6 +582,41 @@ impl ThreatForgeServer {
         Ok(text_result(json))
     }
 
+    #[tool(
+        description = "Search one section (elements, data_flows, trust_boundaries, threats) with filters on type, tag, trust zone, STRIDE category, severity, mitigation status, and text. Returns one page of items with the chosen fields and a next_cursor for the following page."
+    )]
+    async fn query(
+        &self,
+        Parameters(req): Parameters<QueryRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        let stamp = file.lock_stamp().clone();
+        let result =
+            query::run(&model, &stamp, &req).map_err(|e| ErrorData::invalid_params(e, None))?;
+        let json =
+            serde_json::to_string_pretty(&result).map_err(|e| mcp_err(format!("JSON: {e}")))?;
+        Ok(text_result(json))
+    }
+
+    #[tool(
+        description = "Get one element with its incoming and outgoing data flows, the trust boundaries containing it, and the threats against it or its flows."
+    )]
+    async fn get_element(
+        &self,
+        Parameters(req): Parameters<GetElementRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        let detail = query::element_detail(&model, &req.id)
+            .ok_or_else(|| mcp_err(format!("Element not found: {}", req.id)))?;
+        let json =
+            serde_json::to_string_pretty(&detail).map_err(|e| mcp_err(format!("JSON: {e}")))?;
+        Ok(text_result(json))
+    }
+
     #[tool(
         description = "Add a new DFD element (process, data_store, external_entity, or text) to the threat model."
     )]
@@ -289,42 +624,9 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<AddElementRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        // Validate element type before acquiring lock
-        if !Self::VALID_ELEMENT_TYPES.contains(&req.element_type.as_str()) {
-            return Err(mcp_err(format!(
-                "Invalid element type: {}. Valid types: {}",
-                req.element_type,
-                Self::VALID_ELEMENT_TYPES.join(", ")
-            )));
-        }
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        let id = Self::unique_element_id(&model, &req.name);
-        let element = Element {
-            id: id.clone(),
-            element_type: req.element_type,
-            name: req.name,
-            trust_zone: req.trust_zone.unwrap_or_default(),
-            layer: None,
-            group: None,
-            subtype: None,
-            icon: None,
-            description: req.description.unwrap_or_default(),
-            technologies: req.technologies.unwrap_or_default(),
-            tags: Vec::new(),
-            stores: None,
-            encryption: None,
-            position: None,
-            fill_color: None,
-            stroke_color: None,
-            fill_opacity: None,
-            stroke_opacity: None,
-            font_size: None,
-            font_weight: None,
-        };
-        model.elements.push(element);
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Added element: {id}")))
+        self.edit(ToolCall::new("add_element", &req), req.dry_run, |model| {
+            edits::add_element(model, req)
+        })
     }
 
     #[tool(description = "Update an existing element's name, type, trust zone, or description.")]
@@ -332,36 +634,11 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<UpdateElementRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        if let Some(ref t) = req.element_type {
-            if !Self::VALID_ELEMENT_TYPES.contains(&t.as_str()) {
-                return Err(mcp_err(format!(
-                    "Invalid element type: {}. Valid types: {}",
-                    t,
-                    Self::VALID_ELEMENT_TYPES.join(", ")
-                )));
-            }
-        }
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        let el = model
-            .elements
-            .iter_mut()
-            .find(|e| e.id == req.id)
-            .ok_or_else(|| mcp_err(format!("Element not found: {}", req.id)))?;
-        if let Some(name) = req.name {
-            el.name = name;
-        }
-        if let Some(t) = req.element_type {
-            el.element_type = t;
-        }
-        if let Some(tz) = req.trust_zone {
-            el.trust_zone = tz;
-        }
-        if let Some(desc) = req.description {
-            el.description = desc;
-        }
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Updated element: {}", req.id)))
+        self.edit(
+            ToolCall::new("update_element", &req),
+            req.dry_run,
+            |model| edits::update_element(model, req),
+        )
     }
 
     #[tool(description = "Delete an element and its connected data flows.")]
@@ -369,22 +646,11 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<DeleteByIdRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        if !model.elements.iter().any(|e| e.id == req.id) {
-            return Err(mcp_err(format!("Element not found: {}", req.id)));
-        }
-        model.elements.retain(|e| e.id != req.id);
-        // Cascade: remove connected flows
-        model
-            .data_flows
-            .retain(|f| f.from != req.id && f.to != req.id);
-        // Cascade: remove from trust boundary contains lists
-        for b in &mut model.trust_boundaries {
-            b.contains.retain(|c| c != &req.id);
-        }
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Deleted element: {}", req.id)))
+        self.edit(
+            ToolCall::new("delete_element", &req),
+            req.dry_run,
+            |model| edits::delete_element(model, req),
+        )
     }
 
     #[tool(description = "Add a data flow between two elements.")]
@@ -392,35 +658,9 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<AddDataFlowRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        // Validate element references exist
-        if !model.elements.iter().any(|e| e.id == req.from) {
-            return Err(mcp_err(format!("Source element not found: {}", req.from)));
-        }
-        if !model.elements.iter().any(|e| e.id == req.to) {
-            return Err(mcp_err(format!("Target element not found: {}", req.to)));
-        }
-        let flow_number = Self::next_flow_number(&model);
-        let id = format!("flow-{flow_number}");
-        let flow = DataFlow {
-            id: id.clone(),
-            flow_number: Some(flow_number),
-            name: req.name.unwrap_or_default(),
-            from: req.from,
-            to: req.to,
-            protocol: req.protocol.unwrap_or_default(),
-            data: req.data.unwrap_or_default(),
-            authenticated: req.authenticated.unwrap_or(false),
-            label_offset: None,
-            source_handle: None,
-            target_handle: None,
-            stroke_color: None,
-            stroke_opacity: None,
-        };
-        model.data_flows.push(flow);
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Added data flow: {id}")))
+        self.edit(ToolCall::new("add_data_flow", &req), req.dry_run, |model| {
+            edits::add_data_flow(model, req)
+        })
     }
 
     #[tool(description = "Delete a data flow by ID.")]
@@ -428,14 +668,11 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<DeleteByIdRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        if !model.data_flows.iter().any(|f| f.id == req.id) {
-            return Err(mcp_err(format!("Data flow not found: {}", req.id)));
-        }
-        model.data_flows.retain(|f| f.id != req.id);
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Deleted data flow: {}", req.id)))
+        self.edit(
+            ToolCall::new("delete_data_flow", &req),
+            req.dry_run,
+            |model| edits::delete_data_flow(model, req),
+        )
     }
 
     #[tool(description = "Add a trust boundary grouping elements.")]
@@ -443,33 +680,11 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<AddTrustBoundaryRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        // Validate contained element references
-        if let Some(ref contains) = req.contains {
-            for eid in contains {
-                if !model.elements.iter().any(|e| e.id == *eid) {
-                    return Err(mcp_err(format!(
-                        "Element not found in contains list: {eid}"
-                    )));
-                }
-            }
-        }
-        let id = Self::unique_boundary_id(&model, &req.name);
-        let boundary = TrustBoundary {
-            id: id.clone(),
-            name: req.name,
-            contains: req.contains.unwrap_or_default(),
-            position: None,
-            size: None,
-            fill_color: None,
-            stroke_color: None,
-            fill_opacity: None,
-            stroke_opacity: None,
-        };
-        model.trust_boundaries.push(boundary);
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Added trust boundary: {id}")))
+        self.edit(
+            ToolCall::new("add_trust_boundary", &req),
+            req.dry_run,
+            |model| edits::add_trust_boundary(model, req),
+        )
     }
 
     #[tool(description = "Delete a trust boundary by ID.")]
@@ -477,14 +692,11 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<DeleteByIdRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        if !model.trust_boundaries.iter().any(|b| b.id == req.id) {
-            return Err(mcp_err(format!("Trust boundary not found: {}", req.id)));
-        }
-        model.trust_boundaries.retain(|b| b.id != req.id);
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Deleted trust boundary: {}", req.id)))
+        self.edit(
+            ToolCall::new("delete_trust_boundary", &req),
+            req.dry_run,
+            |model| edits::delete_trust_boundary(model, req),
+        )
     }
 
     #[tool(description = "Add a STRIDE threat to the threat model.")]
@@ -492,52 +704,9 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<AddThreatRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        // Validate enum values before acquiring lock
-        let category: StrideCategory = serde_json::from_value(serde_json::Value::String(
-            req.category.clone(),
-        ))
-        .map_err(|_| {
-            mcp_err(format!(
-                "Invalid STRIDE category: {}. Use: Spoofing, Tampering, Repudiation, Information Disclosure, Denial of Service, Elevation of Privilege",
-                req.category
-            ))
-        })?;
-        let severity: Severity = serde_json::from_value(serde_json::Value::String(
-            req.severity.clone(),
-        ))
-        .map_err(|_| {
-            mcp_err(format!(
-                "Invalid severity: {}. Use: critical, high, medium, low, info",
-                req.severity
-            ))
-        })?;
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        // Validate optional element/flow references
-        if let Some(ref eid) = req.element {
-            if !model.elements.iter().any(|e| e.id == *eid) {
-                return Err(mcp_err(format!("Target element not found: {eid}")));
-            }
-        }
-        if let Some(ref fid) = req.flow {
-            if !model.data_flows.iter().any(|f| f.id == *fid) {
-                return Err(mcp_err(format!("Target data flow not found: {fid}")));
-            }
-        }
-        let id = crate::models::generate_threat_id();
-        let threat = Threat {
-            id: id.clone(),
-            title: req.title,
-            category,
-            element: req.element,
-            flow: req.flow,
-            severity,
-            description: req.description,
-            mitigation: None,
-        };
-        model.threats.push(threat);
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Added threat: {id}")))
+        self.edit(ToolCall::new("add_threat", &req), req.dry_run, |model| {
+            edits::add_threat(model, req)
+        })
     }
 
     #[tool(description = "Delete a threat by ID.")]
@@ -545,39 +714,267 @@ impl ThreatForgeServer {
         &self,
         Parameters(req): Parameters<DeleteByIdRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        let mut model = self.lock_model()?;
-        self.reload_locked(&mut model)?;
-        if !model.threats.iter().any(|t| t.id == req.id) {
-            return Err(mcp_err(format!("Threat not found: {}", req.id)));
+        self.edit(ToolCall::new("delete_threat", &req), req.dry_run, |model| {
+            edits::delete_threat(model, req)
+        })
+    }
+
+    #[tool(
+        description = "Apply an ordered batch of add/update/delete operations as one change. The result is validated once at the end; if any operation or the validation fails, nothing is saved."
+    )]
+    async fn apply_changes(
+        &self,
+        Parameters(req): Parameters<ApplyChangesRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if req.operations.is_empty() {
+            return Err(ErrorData::invalid_params("No operations given", None));
         }
-        model.threats.retain(|t| t.id != req.id);
-        self.save_locked(&model)?;
-        Ok(text_result(format!("Deleted threat: {}", req.id)))
+        self.edit(ToolCall::new("apply_changes", &req), req.dry_run, |model| {
+            let mut summaries = Vec::with_capacity(req.operations.len());
+            for (index, operation) in req.operations.into_iter().enumerate() {
+                let op = operation
+                    .get("op")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("?")
+                    .to_string();
+                let summary = edits::apply_operation(model, operation).map_err(|e| {
+                    mcp_err(format!(
+                        "operations[{index}] ({op}) failed: {}. Nothing was saved.",
+                        e.message
+                    ))
+                })?;
+                summaries.push(summary);
+            }
+            let orphaned = edits::orphaned_threats(model);
+            if !orphaned.is_empty() {
+                return Err(mcp_err(format!(
+                    "The batch would leave threats {} without their element or data flow. \
+                     Delete or retarget them in the batch. Nothing was saved.",
+                    orphaned.join(", ")
+                )));
+            }
+            validate_references(model).map_err(|e| {
+                mcp_err(format!(
+                    "The batch would leave the model invalid: {e}. Nothing was saved."
+                ))
+            })?;
+            Ok(summaries.join("\n"))
+        })
+    }
+
+    #[tool(
+        description = "Undo the last change made through this server to the open model (a batch counts as one change)."
+    )]
+    async fn undo(
+        &self,
+        Parameters(_req): Parameters<GetModelRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.step_history(true)
+    }
+
+    #[tool(description = "Redo the last change undone with undo.")]
+    async fn redo(
+        &self,
+        Parameters(_req): Parameters<GetModelRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.step_history(false)
     }
 }
 
 #[tool_handler(router = self.tool_router)]
 impl ServerHandler for ThreatForgeServer {
     fn get_info(&self) -> ServerInfo {
+        let mut instructions = String::from(
+            "ThreatForge MCP Server — read and modify threat models (.thf files). \
+             Tools: list_models, open_model, get_model, get_report, list_elements, list_threats, \
+             query, get_element, add_element, \
+             update_element, delete_element, add_data_flow, delete_data_flow, add_trust_boundary, \
+             delete_trust_boundary, add_threat, delete_threat, apply_changes, undo, redo. \
+             apply_changes runs a batch of edits atomically; mutating tools accept dry_run to \
+             preview their changes. \
+             Resources: threatforge://model, threatforge://elements/{id}, \
+             threatforge://threats/{id}, threatforge://summaries/threats-by-severity, \
+             threatforge://summaries/unmitigated-threats (subscribable). \
+             Prompts: review_stride_coverage, propose_mitigations, describe_trust_boundaries.",
+        );
+        if self.read_only {
+            instructions.push_str(
+                " This server is read-only: the add_*, update_*, delete_*, apply_changes, undo, and \
+                 redo tools are disabled.",
+            );
+        }
+
         // `ServerInfo` and `Implementation` are `#[non_exhaustive]`, so they are
         // built through their constructors rather than struct literals.
         //
         // rmcp echoes back any protocol version it recognises, so the version
         // set here is the fallback for clients that request an unknown one — it
         // is not a ceiling on what the server will negotiate.
-        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
-            .with_protocol_version(ProtocolVersion::V_2024_11_05)
-            .with_server_info(
-                Implementation::new("threatforge-mcp", env!("CARGO_PKG_VERSION"))
-                    .with_description("ThreatForge MCP Server for threat model manipulation")
-                    .with_website_url("https://github.com/exit-zero-labs/threat-forge"),
-            )
-            .with_instructions(
-                "ThreatForge MCP Server — read and modify threat models (.thf files). \
-                 Tools: get_model, list_elements, list_threats, add_element, update_element, \
-                 delete_element, add_data_flow, delete_data_flow, add_trust_boundary, \
-                 delete_trust_boundary, add_threat, delete_threat.",
-            )
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_prompts()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_tools()
+                .build(),
+        )
+        .with_protocol_version(ProtocolVersion::V_2024_11_05)
+        .with_server_info(
+            Implementation::new("threatforge-mcp", env!("CARGO_PKG_VERSION"))
+                .with_description("ThreatForge MCP Server for threat model manipulation")
+                .with_website_url("https://github.com/exit-zero-labs/threat-forge"),
+        )
+        .with_instructions(instructions)
+    }
+
+    async fn initialize(
+        &self,
+        request: InitializeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, ErrorData> {
+        *lock(&self.client) = Some(request.client_info.clone());
+        if context.peer.peer_info().is_none() {
+            context.peer.set_peer_info(request);
+        }
+        Ok(self.get_info())
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        let listed = prompts::list()
+            .into_iter()
+            .map(|spec| {
+                let arguments = spec
+                    .arguments
+                    .into_iter()
+                    .map(|arg| {
+                        let mut argument = PromptArgument::new(arg.name);
+                        argument.description = Some(arg.description.to_string());
+                        argument.required = Some(arg.required);
+                        argument
+                    })
+                    .collect();
+                Prompt::new(spec.name, Some(spec.description), Some(arguments))
+            })
+            .collect();
+        Ok(ListPromptsResult::with_all_items(listed))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        // Prompt arguments are strings on the wire; anything else is ignored.
+        let args: HashMap<String, String> = request
+            .arguments
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(k, v)| v.as_str().map(|s| (k, s.to_string())))
+            .collect();
+
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        let rendered = prompts::render(&model, &request.name, &args)
+            .map_err(|msg| ErrorData::invalid_params(msg, None))?;
+        Ok(
+            GetPromptResult::new(vec![PromptMessage::new_text(Role::User, rendered.text)])
+                .with_description(rendered.description),
+        )
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let file = self.current()?;
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model)?;
+        let listed = resources::list(&model)
+            .into_iter()
+            .map(|entry| {
+                Resource::new(entry.uri, entry.name)
+                    .with_description(entry.description)
+                    .with_mime_type("application/json")
+            })
+            .collect();
+        Ok(ListResourcesResult::with_all_items(listed))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        let templates = [
+            (
+                resources::ELEMENT_URI_TEMPLATE,
+                "Element",
+                "One element by id",
+            ),
+            (resources::THREAT_URI_TEMPLATE, "Threat", "One threat by id"),
+        ]
+        .into_iter()
+        .map(|(uri_template, name, description)| {
+            ResourceTemplate::new(uri_template, name)
+                .with_description(description)
+                .with_mime_type("application/json")
+        })
+        .collect();
+        Ok(ListResourceTemplatesResult::with_all_items(templates))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let text = self.read_resource_text(&request.uri)?;
+        Ok(ReadResourceResult::new(vec![ResourceContents::text(
+            text,
+            request.uri,
+        )]))
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let current = self.read_resource_text(&request.uri)?;
+        let first = {
+            let mut subscriptions = lock(&self.subscriptions);
+            subscriptions.insert(request.uri, Some(current));
+            subscriptions.len() == 1
+        };
+        if first {
+            let file = self.current()?;
+            self.watch_for_subscribers(file.path(), context.peer.clone(), true);
+        }
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let now_empty = {
+            let mut subscriptions = lock(&self.subscriptions);
+            subscriptions.remove(&request.uri);
+            subscriptions.is_empty()
+        };
+        if now_empty {
+            if let Some(watched) = lock(&self.watched).take() {
+                self.watches.unwatch(&watched.path);
+            }
+        }
+        Ok(())
     }
 }
 
@@ -587,18 +984,26 @@ mod tests {
 
     /// The tool names external assistants bind to. Renaming or dropping one is a
     /// breaking change for every already-configured MCP client.
-    const EXPECTED_TOOL_NAMES: [&str; 12] = [
+    const EXPECTED_TOOL_NAMES: [&str; 20] = [
         "add_data_flow",
         "add_element",
         "add_threat",
         "add_trust_boundary",
+        "apply_changes",
         "delete_data_flow",
         "delete_element",
         "delete_threat",
         "delete_trust_boundary",
+        "get_element",
         "get_model",
+        "get_report",
         "list_elements",
+        "list_models",
         "list_threats",
+        "open_model",
+        "query",
+        "redo",
+        "undo",
         "update_element",
     ];
 
@@ -657,6 +1062,15 @@ mod tests {
             info.capabilities.tools.is_some(),
             "the tools capability must stay enabled"
         );
+        let resources = info
+            .capabilities
+            .resources
+            .expect("the resources capability is advertised");
+        assert_eq!(resources.subscribe, Some(true));
+        assert!(
+            info.capabilities.prompts.is_some(),
+            "the prompts capability is advertised"
+        );
         assert_eq!(info.server_info.name, "threatforge-mcp");
         assert_eq!(info.server_info.version, env!("CARGO_PKG_VERSION"));
 
@@ -668,4 +1082,524 @@ mod tests {
             );
         }
     }
+
+    fn write_fixture_model(dir: &std::path::Path) -> PathBuf {
+        let path = dir.join("model.thf");
+        let model = ThreatModel::new("Fixture", "Tester");
+        std::fs::write(
+            &path,
+            serde_yaml::to_string(&model).expect("model should serialize"),
+        )
+        .expect("fixture .thf should be written");
+        path
+    }
+
+    #[test]
+    fn save_refuses_with_a_conflict_when_the_file_changed_after_reload() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+
+        let file = server.current().expect("the only model is open");
+        let mut model = file.lock_model();
+        file.reload_locked(&mut model).expect("reload");
+        model.metadata.title = "From MCP".to_string();
+
+        // The desktop app saves between this server's reload and its save.
+        let mut desktop = ThreatModel::new("From desktop", "Tester");
+        desktop.metadata.description = "desktop edit".to_string();
+        std::fs::write(&path, serde_yaml::to_string(&desktop).expect("serialize"))
+            .expect("desktop write");
+
+        let err = file
+            .save_locked(&model)
+            .expect_err("a stale save must be refused");
+        assert_eq!(err.code, ErrorCode(MODEL_CONFLICT_ERROR));
+
+        let on_disk = std::fs::read_to_string(&path).expect("read back");
+        assert!(
+            on_disk.contains("desktop edit"),
+            "the desktop edit must survive the refused save"
+        );
+
+        // Retrying re-reads the file and goes through.
+        file.reload_locked(&mut model).expect("reload");
+        model.metadata.title = "From MCP".to_string();
+        file.save_locked(&model).expect("retry should save");
+        let on_disk = std::fs::read_to_string(&path).expect("read back");
+        assert!(on_disk.contains("From MCP") && on_disk.contains("desktop edit"));
+    }
+
+    #[tokio::test]
+    async fn workspace_sessions_open_models_by_name_and_stay_inside_the_root() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let api = dir.path().join("api");
+        let billing = dir.path().join("billing");
+        std::fs::create_dir_all(&api).expect("mkdir");
+        std::fs::create_dir_all(&billing).expect("mkdir");
+        write_fixture_model(&api);
+        write_fixture_model(&billing);
+
+        let server =
+            ThreatForgeServer::workspace(dir.path().to_path_buf()).expect("workspace should open");
+        let err = server
+            .get_model(Parameters(GetModelRequest {}))
+            .await
+            .expect_err("no model is open while several exist");
+        assert!(err.message.contains("open_model"));
+
+        let other_session = server.session();
+        let escape = server
+            .open_model(Parameters(OpenModelRequest {
+                model: "../outside.thf".to_string(),
+            }))
+            .await;
+        assert!(escape.is_err());
+
+        server
+            .open_model(Parameters(OpenModelRequest {
+                model: "billing/model.thf".to_string(),
+            }))
+            .await
+            .expect("open billing");
+        server
+            .add_element(Parameters(AddElementRequest {
+                element_type: "process".to_string(),
+                name: "Ledger".to_string(),
+                trust_zone: None,
+                description: None,
+                technologies: None,
+                dry_run: None,
+            }))
+            .await
+            .expect("add to billing");
+
+        let billing_yaml =
+            std::fs::read_to_string(billing.join("model.thf")).expect("read billing");
+        let api_yaml = std::fs::read_to_string(api.join("model.thf")).expect("read api");
+        assert!(billing_yaml.contains("id: ledger"));
+        assert!(!api_yaml.contains("ledger"));
+
+        // The open model is per session.
+        assert!(other_session.current().is_err());
+    }
+
+    #[tokio::test]
+    async fn ending_a_session_stops_its_subscription_watch() {
+        use rmcp::ServiceExt;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path).expect("server should load");
+        let session = server.session();
+        let watches = Arc::downgrade(&session.watches);
+
+        let (client, transport) = tokio::io::duplex(64 * 1024);
+        let running = tokio::spawn(async move {
+            let service = session.serve(transport).await.expect("initialize");
+            service.waiting().await
+        });
+
+        let (reader, mut writer) = tokio::io::split(client);
+        let mut lines = BufReader::new(reader).lines();
+        for message in [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"resources/subscribe","params":{"uri":"threatforge://model"}}"#,
+        ] {
+            writer.write_all(message.as_bytes()).await.expect("write");
+            writer.write_all(b"\n").await.expect("write");
+        }
+        loop {
+            let line = lines.next_line().await.expect("read").expect("open");
+            let response: serde_json::Value = serde_json::from_str(&line).expect("JSON-RPC");
+            if response["id"] == 2 {
+                assert!(response.get("error").is_none(), "subscribe failed: {line}");
+                break;
+            }
+        }
+        assert!(
+            watches.upgrade().is_some(),
+            "the session is still connected"
+        );
+
+        // The client disconnects; the session and every watch it started go with it.
+        drop(writer);
+        drop(lines);
+        running.await.expect("join").expect("session ends");
+        assert!(
+            watches.upgrade().is_none(),
+            "the subscription watch kept the ended session alive"
+        );
+    }
+
+    fn add_element_request(name: &str, dry_run: Option<bool>) -> AddElementRequest {
+        AddElementRequest {
+            element_type: "process".to_string(),
+            name: name.to_string(),
+            trust_zone: None,
+            description: None,
+            technologies: None,
+            dry_run,
+        }
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_the_diff_without_saving() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let before = std::fs::read_to_string(&path).expect("read");
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+
+        let result = server
+            .add_element(Parameters(add_element_request("Web API", Some(true))))
+            .await
+            .expect("dry run");
+        let text = result.content[0]
+            .as_text()
+            .expect("text result")
+            .text
+            .clone();
+        let report: serde_json::Value = serde_json::from_str(&text).expect("JSON report");
+        assert_eq!(report["dry_run"], true);
+        assert_eq!(report["changes"][0]["section"], "elements");
+        assert_eq!(report["changes"][0]["id"], "web-api");
+        assert_eq!(report["changes"][0]["op"], "added");
+
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), before);
+        let model = server.get_model(Parameters(GetModelRequest {})).await;
+        assert!(!format!("{model:?}").contains("web-api"));
+    }
+
+    #[tokio::test]
+    async fn read_only_servers_do_not_offer_or_run_mutating_tools() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path)
+            .expect("server should load")
+            .read_only();
+
+        let names: Vec<String> = server
+            .session()
+            .tool_router
+            .list_all()
+            .into_iter()
+            .map(|t| t.name.to_string())
+            .collect();
+        assert!(names.contains(&"get_model".to_string()));
+        for name in MUTATING_TOOLS {
+            assert!(!names.contains(&name.to_string()), "{name} is still listed");
+        }
+        assert!(server
+            .get_info()
+            .instructions
+            .expect("instructions")
+            .contains("read-only"));
+
+        // Even a direct call cannot save.
+        let err = server
+            .add_element(Parameters(add_element_request("Web API", None)))
+            .await
+            .expect_err("read-only servers refuse to save");
+        assert!(err.message.contains("read-only"));
+    }
+
+    fn apply_changes_request(operations: serde_json::Value) -> ApplyChangesRequest {
+        ApplyChangesRequest {
+            operations: serde_json::from_value(operations).expect("operation list"),
+            dry_run: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_changes_saves_all_operations_or_none() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+
+        server
+            .apply_changes(Parameters(apply_changes_request(serde_json::json!([
+                { "op": "add_element", "element_type": "process", "name": "API" },
+                { "op": "add_element", "element_type": "data_store", "name": "DB" },
+                { "op": "add_data_flow", "from": "api", "to": "db", "protocol": "SQL" },
+            ]))))
+            .await
+            .expect("valid batch");
+        let saved = std::fs::read_to_string(&path).expect("read");
+        assert!(saved.contains("id: api") && saved.contains("id: db"));
+        assert!(saved.contains("flow-1"));
+
+        // The second operation fails, so the first is not saved either.
+        let err = server
+            .apply_changes(Parameters(apply_changes_request(serde_json::json!([
+                { "op": "add_element", "element_type": "process", "name": "Worker" },
+                { "op": "add_data_flow", "from": "worker", "to": "missing" },
+            ]))))
+            .await
+            .expect_err("the batch must fail");
+        assert!(err.message.contains("operations[1] (add_data_flow)"));
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), saved);
+        let file = server.current().expect("open");
+        let model = file.lock_model();
+        assert!(!model.elements.iter().any(|e| e.id == "worker"));
+    }
+
+    #[tokio::test]
+    async fn apply_changes_refuses_to_orphan_threats_and_names_them() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+        server
+            .apply_changes(Parameters(apply_changes_request(serde_json::json!([
+                { "op": "add_element", "element_type": "process", "name": "API" },
+                { "op": "add_element", "element_type": "data_store", "name": "DB" },
+                { "op": "add_data_flow", "from": "api", "to": "db" },
+                { "op": "add_threat", "title": "On DB", "category": "Tampering", "severity": "high", "description": "", "element": "db" },
+                { "op": "add_threat", "title": "On flow", "category": "Tampering", "severity": "high", "description": "", "flow": "flow-1" },
+            ]))))
+            .await
+            .expect("batch");
+        let saved = std::fs::read_to_string(&path).expect("read");
+        let threat_ids: Vec<String> = {
+            let file = server.current().expect("open");
+            let model = file.lock_model();
+            model
+                .threats
+                .iter()
+                .filter(|t| t.title.starts_with("On "))
+                .map(|t| t.id.clone())
+                .collect()
+        };
+
+        let err = server
+            .apply_changes(Parameters(apply_changes_request(serde_json::json!([
+                { "op": "delete_element", "id": "db" },
+            ]))))
+            .await
+            .expect_err("the batch would orphan threats");
+        for id in &threat_ids {
+            assert!(
+                err.message.contains(id.as_str()),
+                "{} should name {id}",
+                err.message
+            );
+        }
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), saved);
+
+        // Deleting the threats explicitly in the same batch lets it through.
+        let mut operations: Vec<serde_json::Value> = threat_ids
+            .iter()
+            .map(|id| serde_json::json!({ "op": "delete_threat", "id": id }))
+            .collect();
+        operations.push(serde_json::json!({ "op": "delete_element", "id": "db" }));
+        server
+            .apply_changes(Parameters(apply_changes_request(serde_json::Value::Array(
+                operations,
+            ))))
+            .await
+            .expect("threats deleted first");
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_step_through_mcp_edits() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let original = std::fs::read_to_string(&path).expect("read");
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+
+        server
+            .apply_changes(Parameters(apply_changes_request(serde_json::json!([
+                { "op": "add_element", "element_type": "process", "name": "API" },
+                { "op": "add_element", "element_type": "process", "name": "Worker" },
+            ]))))
+            .await
+            .expect("batch");
+        let after_batch = std::fs::read_to_string(&path).expect("read");
+
+        // A batch is undone as a whole.
+        server
+            .undo(Parameters(GetModelRequest {}))
+            .await
+            .expect("undo");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), original);
+        assert!(server.undo(Parameters(GetModelRequest {})).await.is_err());
+
+        server
+            .redo(Parameters(GetModelRequest {}))
+            .await
+            .expect("redo");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), after_batch);
+
+        // A new edit clears the redo stack.
+        server
+            .undo(Parameters(GetModelRequest {}))
+            .await
+            .expect("undo");
+        server
+            .add_element(Parameters(add_element_request("Queue", None)))
+            .await
+            .expect("add");
+        assert!(server.redo(Parameters(GetModelRequest {})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn undo_refuses_after_the_file_changed_elsewhere() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+        server
+            .add_element(Parameters(add_element_request("API", None)))
+            .await
+            .expect("add");
+
+        let mut desktop = ThreatModel::new("From desktop", "Tester");
+        desktop.metadata.description = "desktop edit".to_string();
+        std::fs::write(&path, serde_yaml::to_string(&desktop).expect("serialize"))
+            .expect("desktop write");
+
+        let err = server
+            .undo(Parameters(GetModelRequest {}))
+            .await
+            .expect_err("undo would discard the desktop edit");
+        assert!(err.message.contains("outside this server"));
+        assert!(std::fs::read_to_string(&path)
+            .expect("read")
+            .contains("desktop edit"));
+    }
+
+    #[tokio::test]
+    async fn undo_only_steps_through_the_sessions_own_edits() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let first = ThreatForgeServer::new(path.clone()).expect("server should load");
+        let second = first.session();
+
+        first
+            .add_element(Parameters(add_element_request("API", None)))
+            .await
+            .expect("add");
+        let after_first = std::fs::read_to_string(&path).expect("read");
+
+        let err = second
+            .undo(Parameters(GetModelRequest {}))
+            .await
+            .expect_err("the other session's edit is not in this history");
+        assert!(err.message.contains("Nothing to undo"), "{}", err.message);
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), after_first);
+
+        // Once the second session edits, the first can no longer step back
+        // without discarding that work.
+        second
+            .add_element(Parameters(add_element_request("Worker", None)))
+            .await
+            .expect("add");
+        let err = first
+            .undo(Parameters(GetModelRequest {}))
+            .await
+            .expect_err("undo would discard the second session's edit");
+        assert!(err.message.contains("another session"), "{}", err.message);
+
+        second
+            .undo(Parameters(GetModelRequest {}))
+            .await
+            .expect("the second session undoes its own edit");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), after_first);
+    }
+
+    fn audit_entries(path: &Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .expect("audit log")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("JSON line"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn mutating_calls_are_audited_with_client_and_outcome() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+        *lock(&server.client) = Some(Implementation::new("reviewer-bot", "1.2.3"));
+
+        server
+            .add_element(Parameters(add_element_request("Web API", None)))
+            .await
+            .expect("add");
+        server
+            .add_element(Parameters(add_element_request("Preview", Some(true))))
+            .await
+            .expect("dry run");
+        server
+            .delete_element(Parameters(DeleteByIdRequest {
+                id: "missing".to_string(),
+                dry_run: None,
+            }))
+            .await
+            .expect_err("unknown element");
+
+        let entries = audit_entries(&path.with_extension("audit.jsonl"));
+        assert_eq!(entries.len(), 2, "dry runs are not audited");
+
+        let added = &entries[0];
+        assert_eq!(added["tool"], "add_element");
+        assert_eq!(added["arguments"]["name"], "Web API");
+        assert_eq!(added["client"]["name"], "reviewer-bot");
+        assert_eq!(added["model"], "model.thf");
+        assert_eq!(added["outcome"], "ok");
+        assert_eq!(added["result"], "Added element: web-api");
+        assert_eq!(
+            added["changes"],
+            serde_json::json!([{ "section": "elements", "id": "web-api", "op": "added" }])
+        );
+        assert_ne!(added["before_sha256"], added["after_sha256"]);
+
+        let failed = &entries[1];
+        assert_eq!(failed["tool"], "delete_element");
+        assert_eq!(failed["outcome"], "error");
+        assert!(failed.get("after_sha256").is_none());
+        assert_eq!(failed["before_sha256"], added["after_sha256"]);
+    }
+
+    #[tokio::test]
+    async fn failed_dry_runs_are_not_audited() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path.clone()).expect("server should load");
+
+        server
+            .delete_element(Parameters(DeleteByIdRequest {
+                id: "missing".to_string(),
+                dry_run: Some(true),
+            }))
+            .await
+            .expect_err("unknown element");
+
+        assert!(!path.with_extension("audit.jsonl").exists());
+    }
+
+    #[tokio::test]
+    async fn audit_dir_keeps_logs_out_of_the_model_directory() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let logs = tempfile::tempdir().expect("log dir");
+        let path = write_fixture_model(dir.path());
+        let server = ThreatForgeServer::new(path.clone())
+            .expect("server should load")
+            .audit_dir(logs.path().to_path_buf());
+
+        server
+            .add_element(Parameters(add_element_request("Web API", None)))
+            .await
+            .expect("add");
+        server
+            .undo(Parameters(GetModelRequest {}))
+            .await
+            .expect("undo");
+
+        assert!(!path.with_extension("audit.jsonl").exists());
+        let entries = audit_entries(&logs.path().join("model.audit.jsonl"));
+        let tools: Vec<&str> = entries.iter().filter_map(|e| e["tool"].as_str()).collect();
+        assert_eq!(tools, ["add_element", "undo"]);
+        assert_eq!(entries[1]["changes"][0]["op"], "removed");
+    }
 }
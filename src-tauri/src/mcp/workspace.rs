@@ -0,0 +1,484 @@
+//! The `.thf` files an MCP server may touch.
+//!
+//! A server either serves a single file (`threatforge-mcp model.thf`) or a
+//! workspace directory (`threatforge-mcp services/`), where every `.thf` below
+//! the root is a model addressed by its root-relative path. Each file keeps its
+//! own lock and disk stamp, shared by every session that opens it, and every
+//! path is resolved and checked against the root before it is read or written.
+//! Undo history is not shared: each session keeps a [`History`] per file, so
+//! one client can never step back through another's edits.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use rmcp::model::ErrorData;
+
+use super::server::{conflict_err, mcp_err};
+use crate::errors::ThreatForgeError;
+use crate::file_io::{ensure_unchanged, modified_ms, FileStamp};
+use crate::models::ThreatModel;
+
+/// Directories never searched for models: hidden ones (`.git`, `.venv`) and
+/// the usual dependency and build output trees.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+
+/// How many MCP edits `undo` can step back through, per model and session.
+pub const HISTORY_LIMIT: usize = 50;
+
+/// A model version `undo` or `redo` can return to.
+struct Snapshot {
+    model: ThreatModel,
+    /// Stamp of the file right after the edit this snapshot reverses. If the
+    /// file no longer matches it, someone else wrote in between and stepping
+    /// back would discard their work.
+    saved: FileStamp,
+}
+
+/// Undo and redo stacks of one session's MCP edits to one file. A new edit
+/// clears redo.
+#[derive(Default)]
+pub struct History {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+/// One file-backed threat model.
+pub struct ModelFile {
+    path: PathBuf,
+    model: Mutex<ThreatModel>,
+    /// Stamp of the file as this server last read or wrote it. Only touched
+    /// while the model lock is held, so the two never disagree.
+    disk_stamp: Mutex<FileStamp>,
+}
+
+impl ModelFile {
+    fn load(path: PathBuf) -> Result<Self, String> {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let model: ThreatModel = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+        let stamp = FileStamp::from_contents(content.as_bytes(), modified_ms(&path));
+        Ok(Self {
+            path,
+            model: Mutex::new(model),
+            disk_stamp: Mutex::new(stamp),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Acquire the model lock, recovering from mutex poisoning.
+    pub fn lock_model(&self) -> MutexGuard<'_, ThreatModel> {
+        match self.model.lock() {
+            Ok(guard) => guard,
+            // Recover the inner data; the panic that caused poisoning is gone.
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Lock the disk stamp, recovering from mutex poisoning like [`Self::lock_model`].
+    pub fn lock_stamp(&self) -> MutexGuard<'_, FileStamp> {
+        match self.disk_stamp.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Persist the current model state to disk.
+    ///
+    /// Refuses with a conflict error if the file no longer matches the stamp
+    /// taken by the last reload, so an edit made by the desktop app in the
+    /// meantime is never overwritten.
+    pub fn save_locked(&self, model: &MutexGuard<'_, ThreatModel>) -> Result<(), ErrorData> {
+        let yaml =
+            serde_yaml::to_string(&**model).map_err(|e| mcp_err(format!("Serialize: {e}")))?;
+        let mut stamp = self.lock_stamp();
+        match ensure_unchanged(&self.path, &stamp) {
+            Ok(()) => {}
+            Err(ThreatForgeError::ConcurrentModification { .. }) => return Err(conflict_err()),
+            Err(_) => return Err(mcp_err("Failed to read threat model file")),
+        }
+        std::fs::write(&self.path, &yaml)
+            .map_err(|_| mcp_err("Failed to write threat model file"))?;
+        *stamp = FileStamp::from_contents(yaml.as_bytes(), modified_ms(&self.path));
+        Ok(())
+    }
+
+    /// Reload the model from disk into the given locked guard.
+    pub fn reload_locked(&self, model: &mut MutexGuard<'_, ThreatModel>) -> Result<(), ErrorData> {
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|_| mcp_err("Failed to read threat model file"))?;
+        let fresh: ThreatModel =
+            serde_yaml::from_str(&content).map_err(|e| mcp_err(format!("Parse error: {e}")))?;
+        **model = fresh;
+        *self.lock_stamp() = FileStamp::from_contents(content.as_bytes(), modified_ms(&self.path));
+        Ok(())
+    }
+
+    /// Remember `before` in the editing session's `history` as the version
+    /// the edit just saved replaced. Call with the model lock still held
+    /// after [`Self::save_locked`].
+    pub fn record_edit(&self, history: &mut History, before: ThreatModel) {
+        let saved = self.lock_stamp().clone();
+        history.redo.clear();
+        history.undo.push_back(Snapshot {
+            model: before,
+            saved,
+        });
+        if history.undo.len() > HISTORY_LIMIT {
+            history.undo.pop_front();
+        }
+    }
+
+    /// Revert the last edit recorded in `history`. Returns how many edits
+    /// remain to undo.
+    pub fn undo_locked(
+        &self,
+        model: &mut MutexGuard<'_, ThreatModel>,
+        history: &mut History,
+    ) -> Result<usize, ErrorData> {
+        self.step_locked(model, history, true)
+    }
+
+    /// Re-apply the last edit undone from `history`. Returns how many edits
+    /// remain to redo.
+    pub fn redo_locked(
+        &self,
+        model: &mut MutexGuard<'_, ThreatModel>,
+        history: &mut History,
+    ) -> Result<usize, ErrorData> {
+        self.step_locked(model, history, false)
+    }
+
+    /// Swap the model on disk with the newest snapshot on one stack, pushing
+    /// the version it replaces onto the other.
+    fn step_locked(
+        &self,
+        model: &mut MutexGuard<'_, ThreatModel>,
+        history: &mut History,
+        undo: bool,
+    ) -> Result<usize, ErrorData> {
+        let action = if undo { "undo" } else { "redo" };
+        self.reload_locked(model)?;
+        let target = if undo {
+            history.undo.pop_back()
+        } else {
+            history.redo.pop()
+        };
+        let Some(target) = target else {
+            return Err(mcp_err(format!("Nothing to {action}")));
+        };
+        if !self.lock_stamp().same_contents(&target.saved) {
+            // The history describes a file that no longer exists.
+            *history = History::default();
+            return Err(mcp_err(format!(
+                "Cannot {action}: the model was changed by another session or outside this \
+                 server since your last MCP edit. The undo history has been cleared."
+            )));
+        }
+
+        let current = std::mem::replace(&mut **model, target.model);
+        if let Err(e) = self.save_locked(model) {
+            let target = Snapshot {
+                model: std::mem::replace(&mut **model, current),
+                saved: target.saved,
+            };
+            if undo {
+                history.undo.push_back(target);
+            } else {
+                history.redo.push(target);
+            }
+            return Err(e);
+        }
+
+        let reversed = Snapshot {
+            model: current,
+            saved: self.lock_stamp().clone(),
+        };
+        if undo {
+            history.redo.push(reversed);
+            Ok(history.undo.len())
+        } else {
+            history.undo.push_back(reversed);
+            Ok(history.redo.len())
+        }
+    }
+}
+
+/// The set of models a server may open, confined to one root directory.
+pub struct Workspace {
+    /// Canonical root; every resolved model path must stay below it.
+    root: PathBuf,
+    /// In single-file mode, the one model name that may be opened.
+    single: Option<String>,
+    /// Loaded models by name, shared by every session.
+    files: Mutex<HashMap<String, Arc<ModelFile>>>,
+}
+
+impl Workspace {
+    /// A workspace holding exactly `path`. The file is loaded up front so a
+    /// missing or invalid model fails at startup.
+    pub fn single_file(path: &Path) -> Result<Self, String> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let root = canonical
+            .parent()
+            .ok_or_else(|| format!("{} has no parent directory", path.display()))?
+            .to_path_buf();
+        let name = relative_name(&root, &canonical);
+        let file = Arc::new(ModelFile::load(canonical)?);
+        Ok(Self {
+            root,
+            single: Some(name.clone()),
+            files: Mutex::new(HashMap::from([(name, file)])),
+        })
+    }
+
+    /// A workspace over every `.thf` below `root`. Models are loaded on first
+    /// open.
+    pub fn directory(root: &Path) -> Result<Self, String> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| format!("Failed to open workspace {}: {e}", root.display()))?;
+        if !root.is_dir() {
+            return Err(format!("{} is not a directory", root.display()));
+        }
+        Ok(Self {
+            root,
+            single: None,
+            files: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn lock_files(&self) -> MutexGuard<'_, HashMap<String, Arc<ModelFile>>> {
+        match self.files.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// The canonical workspace root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Names of every model, sorted. Directories are rescanned on each call so
+    /// files added after startup show up.
+    pub fn model_names(&self) -> Vec<String> {
+        if let Some(name) = &self.single {
+            return vec![name.clone()];
+        }
+        let mut names = Vec::new();
+        discover(&self.root, &self.root, &mut names);
+        names.sort();
+        names
+    }
+
+    /// The model a new session starts on: the only one there is, if any.
+    pub fn default_model(&self) -> Option<String> {
+        match self.model_names().as_slice() {
+            [only] => Some(only.clone()),
+            _ => None,
+        }
+    }
+
+    /// Open `name` (a root-relative path), loading it on first use.
+    pub fn open(&self, name: &str) -> Result<(String, Arc<ModelFile>), ErrorData> {
+        let (name, path) = self.resolve(name)?;
+        let mut files = self.lock_files();
+        if let Some(file) = files.get(&name) {
+            return Ok((name, file.clone()));
+        }
+        let file = Arc::new(ModelFile::load(path).map_err(mcp_err)?);
+        files.insert(name.clone(), file.clone());
+        Ok((name, file))
+    }
+
+    /// Map a client-supplied name to its canonical name and path, refusing
+    /// anything that is not a `.thf` file inside the root.
+    fn resolve(&self, name: &str) -> Result<(String, PathBuf), ErrorData> {
+        let relative = Path::new(name);
+        let plain = relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if name.is_empty() || !plain {
+            return Err(mcp_err(format!(
+                "Invalid model name: {name}. Use a path relative to the workspace root, as returned by list_models"
+            )));
+        }
+        if relative.extension().and_then(|e| e.to_str()) != Some("thf") {
+            return Err(mcp_err(format!("Not a .thf file: {name}")));
+        }
+
+        // Canonicalizing resolves symlinks, so a link pointing out of the
+        // workspace is caught by the prefix check below.
+        let path = self
+            .root
+            .join(relative)
+            .canonicalize()
+            .map_err(|_| mcp_err(format!("Model not found: {name}")))?;
+        if !path.starts_with(&self.root) {
+            return Err(mcp_err(format!("Model is outside the workspace: {name}")));
+        }
+        // A `.thf` link may point at any other file in the root.
+        if path.extension().and_then(|e| e.to_str()) != Some("thf") {
+            return Err(mcp_err(format!("Not a .thf file: {name}")));
+        }
+        let canonical_name = relative_name(&self.root, &path);
+        if self
+            .single
+            .as_ref()
+            .is_some_and(|only| *only != canonical_name)
+        {
+            return Err(mcp_err(format!("Model not found: {name}")));
+        }
+        Ok((canonical_name, path))
+    }
+}
+
+/// `path` relative to `root`, with `/` separators on every platform.
+pub(super) fn relative_name(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Collect `.thf` files below `dir`. Symlinked directories are not followed,
+/// so discovery cannot wander out of the root.
+fn discover(root: &Path, dir: &Path, names: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name.starts_with('.') {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&file_name.as_ref()) {
+                discover(root, &path, names);
+            }
+        } else if file_type.is_file() && path.extension().and_then(|e| e.to_str()) == Some("thf") {
+            names.push(relative_name(root, &path));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_model(path: &Path, title: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let model = ThreatModel::new(title, "Tester");
+        std::fs::write(path, serde_yaml::to_string(&model).unwrap()).unwrap();
+    }
+
+    fn sample_workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        write_model(&dir.path().join("billing/model.thf"), "Billing");
+        write_model(&dir.path().join("api.thf"), "API");
+        write_model(&dir.path().join(".cache/stale.thf"), "Hidden");
+        write_model(&dir.path().join("node_modules/pkg/dep.thf"), "Dependency");
+        std::fs::write(dir.path().join("notes.yaml"), "not a model").unwrap();
+        dir
+    }
+
+    #[test]
+    fn discovers_nested_models_and_skips_hidden_and_dependency_dirs() {
+        let dir = sample_workspace();
+        let workspace = Workspace::directory(dir.path()).unwrap();
+        assert_eq!(workspace.model_names(), ["api.thf", "billing/model.thf"]);
+        assert_eq!(workspace.default_model(), None);
+    }
+
+    #[test]
+    fn opens_each_model_once_and_shares_it() {
+        let dir = sample_workspace();
+        let workspace = Workspace::directory(dir.path()).unwrap();
+
+        let (name, first) = workspace.open("billing/model.thf").unwrap();
+        assert_eq!(name, "billing/model.thf");
+        assert_eq!(first.lock_model().metadata.title, "Billing");
+
+        let (_, again) = workspace.open("billing/model.thf").unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+    }
+
+    #[test]
+    fn refuses_names_that_escape_the_root_or_are_not_models() {
+        let dir = sample_workspace();
+        let outside = TempDir::new().unwrap();
+        write_model(&outside.path().join("secret.thf"), "Secret");
+        let workspace = Workspace::directory(dir.path()).unwrap();
+
+        for name in [
+            "",
+            "../secret.thf",
+            "billing/../../secret.thf",
+            "./api.thf",
+            "notes.yaml",
+            "missing.thf",
+        ] {
+            assert!(workspace.open(name).is_err(), "{name:?} should be refused");
+        }
+        let absolute = outside.path().join("secret.thf");
+        assert!(workspace.open(&absolute.to_string_lossy()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks_that_point_outside_the_root() {
+        let dir = sample_workspace();
+        let outside = TempDir::new().unwrap();
+        write_model(&outside.path().join("secret.thf"), "Secret");
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.thf"),
+            dir.path().join("link.thf"),
+        )
+        .unwrap();
+
+        let workspace = Workspace::directory(dir.path()).unwrap();
+        let Err(err) = workspace.open("link.thf") else {
+            panic!("a symlink out of the workspace must be refused");
+        };
+        assert!(err.message.contains("outside the workspace"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_thf_symlinks_to_other_files_in_the_root() {
+        let dir = sample_workspace();
+        std::os::unix::fs::symlink(dir.path().join("notes.yaml"), dir.path().join("notes.thf"))
+            .unwrap();
+
+        let workspace = Workspace::directory(dir.path()).unwrap();
+        let Err(err) = workspace.open("notes.thf") else {
+            panic!("a .thf link to a non-.thf file must be refused");
+        };
+        assert!(err.message.contains("Not a .thf file"));
+    }
+
+    #[test]
+    fn single_file_mode_exposes_only_that_file() {
+        let dir = sample_workspace();
+        let workspace = Workspace::single_file(&dir.path().join("api.thf")).unwrap();
+        assert_eq!(workspace.model_names(), ["api.thf"]);
+        assert_eq!(workspace.default_model().as_deref(), Some("api.thf"));
+        assert!(workspace.open("api.thf").is_ok());
+        assert!(workspace.open("billing/model.thf").is_err());
+    }
+}
@@ -68,8 +68,71 @@ pub fn build_menu(app: &App) -> Result<Menu<Wry>, Box<dyn std::error::Error>> {
         true,
         Some("CmdOrCtrl+Shift+E"),
     )?;
+    let file_export_tm7 = MenuItem::with_id(
+        handle,
+        "file-export-tm7",
+        "Export as Threat Modeling Tool (.tm7)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
+    let file_export_threat_dragon = MenuItem::with_id(
+        handle,
+        "file-export-threatdragon",
+        "Export as OWASP Threat Dragon (.json)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
+    let file_export_otm = MenuItem::with_id(
+        handle,
+        "file-export-otm",
+        "Export as Open Threat Model (.otm)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
+    let file_export_markdown = MenuItem::with_id(
+        handle,
+        "file-export-markdown",
+        "Export as Markdown Report (.md)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
+    let file_export_csv = MenuItem::with_id(
+        handle,
+        "file-export-csv",
+        "Export Threat Register (.csv)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
+    let file_export_xlsx = MenuItem::with_id(
+        handle,
+        "file-export-xlsx",
+        "Export Threat Register (.xlsx)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
+    let file_export_xlsx_architecture = MenuItem::with_id(
+        handle,
+        "file-export-xlsx-architecture",
+        "Export Threat Register with Elements and Flows (.xlsx)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
+    let file_export_sarif = MenuItem::with_id(
+        handle,
+        "file-export-sarif",
+        "Export Open Threats as SARIF (.sarif)\u{2026}",
+        true,
+        None::<&str>,
+    )?;
     let file_import =
         MenuItem::with_id(handle, "file-import", "Import\u{2026}", true, None::<&str>)?;
+    let file_import_mitigations = MenuItem::with_id(
+        handle,
+        "file-import-mitigations",
+        "Import Mitigations from CSV\u{2026}",
+        true,
+        None::<&str>,
+    )?;
     let file_close = MenuItem::with_id(
         handle,
         "file-close",
@@ -91,10 +154,19 @@ pub fn build_menu(app: &App) -> Result<Menu<Wry>, Box<dyn std::error::Error>> {
             &file_new,
             &file_open,
             &file_import,
+            &file_import_mitigations,
             &PredefinedMenuItem::separator(handle)?,
             &file_save,
             &file_save_as,
             &file_export_html,
+            &file_export_markdown,
+            &file_export_tm7,
+            &file_export_threat_dragon,
+            &file_export_otm,
+            &file_export_csv,
+            &file_export_xlsx,
+            &file_export_xlsx_architecture,
+            &file_export_sarif,
             &PredefinedMenuItem::separator(handle)?,
             &file_close,
             #[cfg(any(target_os = "windows", target_os = "linux"))]
@@ -0,0 +1,71 @@
+//! Placement rules shared by everything that draws a model: the format
+//! exporters and the report's diagram renderer. Keeping them in one place
+//! means an unplaced element lands in the same spot in every output.
+
+use super::{BoundaryShape, Position, Size, ThreatModel, TrustBoundary};
+
+/// Name of a drawing surface whose diagram has none, as TMT names its first.
+pub const DEFAULT_DIAGRAM_NAME: &str = "Level 0 DFD";
+
+/// Grid spacing for elements that were never placed on a canvas.
+const GRID_SPACING: f64 = 200.0;
+const GRID_COLUMNS: usize = 5;
+/// Offset of the grid from the surface origin.
+const GRID_MARGIN: f64 = 40.0;
+
+/// Padding around the members of a boundary that has no geometry of its own.
+pub const BOUNDARY_PADDING: f64 = 40.0;
+
+/// The drawing surface for an item's `diagram` reference; unset or unknown
+/// references fall back to the first diagram, as the schema defines.
+pub fn surface_index(model: &ThreatModel, diagram: Option<&str>) -> usize {
+    diagram
+        .and_then(|id| model.diagrams.iter().position(|d| d.id == id))
+        .unwrap_or(0)
+}
+
+/// The vertices of a line boundary, or `None` for a rectangle. A line with
+/// fewer than two points cannot be drawn and is written as a rectangle.
+pub fn line_points(boundary: &TrustBoundary) -> Option<&[Position]> {
+    (boundary.shape == Some(BoundaryShape::Line) && boundary.points.len() >= 2)
+        .then_some(boundary.points.as_slice())
+}
+
+/// Where the `index`th unplaced element goes.
+pub fn grid_origin(index: usize) -> Position {
+    Position {
+        x: GRID_MARGIN + (index % GRID_COLUMNS) as f64 * GRID_SPACING,
+        y: GRID_MARGIN + (index / GRID_COLUMNS) as f64 * GRID_SPACING,
+    }
+}
+
+/// The box drawn around a boundary's members, each an origin and size, when
+/// the boundary has no geometry of its own; `None` when it has no members.
+pub fn boundary_bounds(
+    members: impl IntoIterator<Item = (Position, Size)>,
+) -> Option<(Position, Size)> {
+    let (min_x, min_y, max_x, max_y) = members.into_iter().fold(None, |bounds, (p, s)| {
+        let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ));
+        Some((
+            f64::min(min_x, p.x),
+            f64::min(min_y, p.y),
+            f64::max(max_x, p.x + s.width),
+            f64::max(max_y, p.y + s.height),
+        ))
+    })?;
+    Some((
+        Position {
+            x: min_x - BOUNDARY_PADDING,
+            y: min_y - BOUNDARY_PADDING,
+        },
+        Size {
+            width: max_x - min_x + 2.0 * BOUNDARY_PADDING,
+            height: max_y - min_y + 2.0 * BOUNDARY_PADDING,
+        },
+    ))
+}
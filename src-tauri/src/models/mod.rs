@@ -1,3 +1,4 @@
+pub mod layout;
 mod threat_model;
 
 pub use threat_model::*;
@@ -122,6 +122,10 @@ pub struct Element {
     /// Membership lives on the element, not as a `contains` list on the group.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub group: Option<String>,
+    /// Optional diagram this element is drawn on (references `diagrams[].id`). Absent means the
+    /// first diagram, so single-diagram models never carry it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagram: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -138,6 +142,10 @@ pub struct Element {
     pub stores: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub encryption: Option<String>,
+    /// Operations an API element exposes, e.g. as imported from an OpenAPI document. STRIDE
+    /// analysis checks each one for authentication, rate limiting and sensitive responses.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operations: Vec<ApiOperation>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub position: Option<Position>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -154,6 +162,29 @@ pub struct Element {
     pub font_weight: Option<String>,
 }
 
+/// One operation of an API element: a method on a path.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiOperation {
+    /// HTTP method, upper case (e.g. "GET").
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Whether every caller must authenticate. An operation any of whose security alternatives
+    /// is empty accepts anonymous callers and is not authenticated.
+    #[serde(default)]
+    pub authenticated: bool,
+    /// Whether the operation declares a rate limit.
+    #[serde(default)]
+    pub rate_limited: bool,
+    /// Parameter and request body field names; nested fields are dotted (`address.city`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub request_fields: Vec<String>,
+    /// Field names of successful responses, dotted like `request_fields`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response_fields: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DataFlow {
     pub id: String,
@@ -187,6 +218,16 @@ pub struct TrustBoundary {
     pub name: String,
     #[serde(default)]
     pub contains: Vec<String>,
+    /// Optional diagram this boundary is drawn on (references `diagrams[].id`). Absent means the
+    /// first diagram.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagram: Option<String>,
+    /// Absent means a rectangle drawn from `position` and `size`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<BoundaryShape>,
+    /// Vertices of a `line` boundary, in canvas coordinates. Unused by rectangles.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub points: Vec<Position>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub position: Option<Position>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -201,6 +242,18 @@ pub struct TrustBoundary {
     pub stroke_opacity: Option<f64>,
 }
 
+/// How a trust boundary is drawn.
+///
+/// A rectangle encloses the elements in its `contains` list. A line (as drawn in Microsoft's
+/// Threat Modeling Tool) separates two regions: it has no members, and a flow crosses it by
+/// crossing the line.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryShape {
+    Rectangle,
+    Line,
+}
+
 /// A horizontal architecture layer (e.g. presentation, application, data).
 ///
 /// Array order is display order; there is deliberately no `order` integer, which would create
@@ -288,6 +341,30 @@ pub enum StrideCategory {
     ElevationOfPrivilege,
 }
 
+impl StrideCategory {
+    /// Every category, in STRIDE order.
+    pub const ALL: [StrideCategory; 6] = [
+        StrideCategory::Spoofing,
+        StrideCategory::Tampering,
+        StrideCategory::Repudiation,
+        StrideCategory::InformationDisclosure,
+        StrideCategory::DenialOfService,
+        StrideCategory::ElevationOfPrivilege,
+    ];
+
+    /// The serialized name, e.g. `Information Disclosure`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StrideCategory::Spoofing => "Spoofing",
+            StrideCategory::Tampering => "Tampering",
+            StrideCategory::Repudiation => "Repudiation",
+            StrideCategory::InformationDisclosure => "Information Disclosure",
+            StrideCategory::DenialOfService => "Denial of Service",
+            StrideCategory::ElevationOfPrivilege => "Elevation of Privilege",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
@@ -298,6 +375,39 @@ pub enum Severity {
     Info,
 }
 
+impl Severity {
+    /// Every severity, most severe first.
+    pub const ALL: [Severity; 5] = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+        Severity::Info,
+    ];
+
+    /// The serialized name, e.g. `high`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+            Severity::Info => "info",
+        }
+    }
+
+    /// The display name, e.g. `High`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Critical => "Critical",
+            Severity::High => "High",
+            Severity::Medium => "Medium",
+            Severity::Low => "Low",
+            Severity::Info => "Info",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum MitigationStatus {
@@ -308,6 +418,30 @@ pub enum MitigationStatus {
     Transferred,
 }
 
+impl MitigationStatus {
+    /// The serialized name, e.g. `in_progress`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MitigationStatus::NotStarted => "not_started",
+            MitigationStatus::InProgress => "in_progress",
+            MitigationStatus::Mitigated => "mitigated",
+            MitigationStatus::Accepted => "accepted",
+            MitigationStatus::Transferred => "transferred",
+        }
+    }
+
+    /// The display name, e.g. `In progress`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            MitigationStatus::NotStarted => "Not started",
+            MitigationStatus::InProgress => "In progress",
+            MitigationStatus::Mitigated => "Mitigated",
+            MitigationStatus::Accepted => "Accepted",
+            MitigationStatus::Transferred => "Transferred",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Mitigation {
     pub status: MitigationStatus,
@@ -331,6 +465,21 @@ pub struct Threat {
     pub mitigation: Option<Mitigation>,
 }
 
+impl Threat {
+    /// Whether the threat still needs work: no mitigation recorded, or one that is not started or
+    /// in progress. Accepted and transferred risks count as handled.
+    pub fn is_unmitigated(&self) -> bool {
+        !matches!(
+            self.mitigation.as_ref().map(|m| &m.status),
+            Some(
+                MitigationStatus::Mitigated
+                    | MitigationStatus::Accepted
+                    | MitigationStatus::Transferred
+            )
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Diagram {
     pub id: String,
@@ -515,6 +664,57 @@ diagrams:
         assert_eq!(id.len(), 15); // "threat-" (7) + 8 hex chars
     }
 
+    #[test]
+    fn test_threat_is_unmitigated() {
+        let mut threat = Threat {
+            id: "threat-1".to_string(),
+            title: "T".to_string(),
+            category: StrideCategory::Tampering,
+            element: None,
+            flow: None,
+            severity: Severity::High,
+            description: String::new(),
+            mitigation: None,
+        };
+        assert!(threat.is_unmitigated(), "no mitigation recorded");
+
+        for (status, open) in [
+            (MitigationStatus::NotStarted, true),
+            (MitigationStatus::InProgress, true),
+            (MitigationStatus::Mitigated, false),
+            (MitigationStatus::Accepted, false),
+            (MitigationStatus::Transferred, false),
+        ] {
+            threat.mitigation = Some(Mitigation {
+                status: status.clone(),
+                description: String::new(),
+            });
+            assert_eq!(threat.is_unmitigated(), open, "{status:?}");
+        }
+    }
+
+    #[test]
+    fn test_severity_as_str_matches_serialized_name() {
+        for severity in Severity::ALL {
+            let yaml = serde_yaml::to_string(&severity).expect("serialize");
+            assert_eq!(yaml.trim(), severity.as_str());
+        }
+    }
+
+    #[test]
+    fn test_mitigation_status_as_str_matches_serialized_name() {
+        for status in [
+            MitigationStatus::NotStarted,
+            MitigationStatus::InProgress,
+            MitigationStatus::Mitigated,
+            MitigationStatus::Accepted,
+            MitigationStatus::Transferred,
+        ] {
+            let yaml = serde_yaml::to_string(&status).expect("serialize");
+            assert_eq!(yaml.trim(), status.as_str());
+        }
+    }
+
     #[test]
     fn test_inline_position_round_trip() {
         let yaml = r##"
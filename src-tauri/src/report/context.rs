@@ -0,0 +1,282 @@
+//! The data a report template renders.
+//!
+//! Templates see one [`ReportContext`], serialized to template values. Its
+//! top-level variables are:
+//!
+//! - `model`: the threat model exactly as in the `.thf` file (`metadata`,
+//!   `elements`, `data_flows`, `trust_boundaries`, `threats`, `diagrams`).
+//! - `stats`: counts for the summary, with threats broken down by severity
+//!   and by STRIDE category.
+//! - `risk_matrix`: threats counted by severity against STRIDE category.
+//! - `threats_by_status`: threats grouped by mitigation status.
+//! - `threats`: every threat with its severity and status labels and the name
+//!   of the element or flow it is raised against.
+//!
+//! Lists follow file order, or the fixed severity, STRIDE and status orders,
+//! so a template renders the same model to the same text.
+
+use serde::Serialize;
+
+use super::{architecture_elements, element_names, share, status_label, STATUSES};
+use crate::models::{MitigationStatus, Severity, StrideCategory, Threat, ThreatModel};
+
+/// Everything a report template can refer to.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportContext<'a> {
+    pub model: &'a ThreatModel,
+    pub stats: Stats,
+    pub risk_matrix: RiskMatrix,
+    pub threats_by_status: Vec<StatusGroup<'a>>,
+    pub threats: Vec<ThreatEntry<'a>>,
+}
+
+/// Counts for a report summary. Text annotations are not counted as elements.
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub elements: usize,
+    pub data_flows: usize,
+    pub trust_boundaries: usize,
+    pub threats: usize,
+    pub unmitigated: usize,
+    /// One entry per severity, most severe first.
+    pub by_severity: Vec<SeverityCount>,
+    /// One entry per STRIDE category, in STRIDE order.
+    pub by_category: Vec<CategoryCount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeverityCount {
+    /// As written in the file, e.g. `critical`.
+    pub severity: Severity,
+    /// For display, e.g. `Critical`.
+    pub label: &'static str,
+    pub threats: usize,
+    pub unmitigated: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryCount {
+    pub category: StrideCategory,
+    pub threats: usize,
+    pub unmitigated: usize,
+}
+
+/// Threats by severity (rows, most severe first) and STRIDE category
+/// (columns, in STRIDE order).
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskMatrix {
+    /// The column headings.
+    pub categories: Vec<StrideCategory>,
+    pub rows: Vec<RiskRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskRow {
+    pub severity: Severity,
+    pub label: &'static str,
+    /// One cell per entry of `categories`.
+    pub cells: Vec<RiskCell>,
+    /// The row's cells added up.
+    pub total: RiskCell,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RiskCell {
+    pub threats: usize,
+    pub unmitigated: usize,
+}
+
+/// The threats with one mitigation status. Every status is listed, including
+/// those no threat has.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusGroup<'a> {
+    /// As written in the file, e.g. `in_progress`; none when no mitigation is
+    /// recorded.
+    pub status: Option<MitigationStatus>,
+    pub label: &'static str,
+    /// Percentage of all threats, rounded to a whole number.
+    pub share: usize,
+    pub threats: Vec<ThreatEntry<'a>>,
+}
+
+/// A threat as the model records it, plus what a report shows beside it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreatEntry<'a> {
+    #[serde(flatten)]
+    pub threat: &'a Threat,
+    pub severity_label: &'static str,
+    pub status: Option<&'a MitigationStatus>,
+    pub status_label: &'static str,
+    pub unmitigated: bool,
+    /// Name of the element the threat is raised against, if it exists.
+    pub element_name: Option<&'a str>,
+    /// Name of the flow the threat is raised against, if it exists.
+    pub flow_name: Option<&'a str>,
+}
+
+impl<'a> ReportContext<'a> {
+    pub fn new(model: &'a ThreatModel) -> Self {
+        let names = element_names(model);
+        let threats: Vec<ThreatEntry<'a>> = model
+            .threats
+            .iter()
+            .map(|threat| {
+                let status = threat.mitigation.as_ref().map(|m| &m.status);
+                ThreatEntry {
+                    threat,
+                    severity_label: threat.severity.label(),
+                    status,
+                    status_label: status_label(status),
+                    unmitigated: threat.is_unmitigated(),
+                    element_name: threat
+                        .element
+                        .as_deref()
+                        .and_then(|id| names.get(id).copied()),
+                    flow_name: threat.flow.as_deref().and_then(|id| {
+                        model
+                            .data_flows
+                            .iter()
+                            .find(|f| f.id == id)
+                            .map(|f| f.name.as_str())
+                    }),
+                }
+            })
+            .collect();
+
+        Self {
+            model,
+            stats: stats(model),
+            risk_matrix: risk_matrix(&model.threats),
+            threats_by_status: STATUSES
+                .iter()
+                .map(|status| {
+                    let in_status: Vec<ThreatEntry<'a>> = threats
+                        .iter()
+                        .filter(|t| t.status == status.as_ref())
+                        .cloned()
+                        .collect();
+                    StatusGroup {
+                        status: status.clone(),
+                        label: status_label(status.as_ref()),
+                        share: share(in_status.len(), threats.len(), 100),
+                        threats: in_status,
+                    }
+                })
+                .collect(),
+            threats,
+        }
+    }
+}
+
+fn stats(model: &ThreatModel) -> Stats {
+    let threats = &model.threats;
+    Stats {
+        elements: architecture_elements(model).count(),
+        data_flows: model.data_flows.len(),
+        trust_boundaries: model.trust_boundaries.len(),
+        threats: threats.len(),
+        unmitigated: threats.iter().filter(|t| t.is_unmitigated()).count(),
+        by_severity: Severity::ALL
+            .iter()
+            .map(|severity| {
+                let cell = count(threats.iter().filter(|t| &t.severity == severity));
+                SeverityCount {
+                    severity: severity.clone(),
+                    label: severity.label(),
+                    threats: cell.threats,
+                    unmitigated: cell.unmitigated,
+                }
+            })
+            .collect(),
+        by_category: StrideCategory::ALL
+            .iter()
+            .map(|category| {
+                let cell = count(threats.iter().filter(|t| &t.category == category));
+                CategoryCount {
+                    category: category.clone(),
+                    threats: cell.threats,
+                    unmitigated: cell.unmitigated,
+                }
+            })
+            .collect(),
+    }
+}
+
+fn risk_matrix(threats: &[Threat]) -> RiskMatrix {
+    RiskMatrix {
+        categories: StrideCategory::ALL.to_vec(),
+        rows: Severity::ALL
+            .iter()
+            .map(|severity| {
+                let in_row = threats.iter().filter(|t| &t.severity == severity);
+                RiskRow {
+                    severity: severity.clone(),
+                    label: severity.label(),
+                    cells: StrideCategory::ALL
+                        .iter()
+                        .map(|category| count(in_row.clone().filter(|t| &t.category == category)))
+                        .collect(),
+                    total: count(in_row),
+                }
+            })
+            .collect(),
+    }
+}
+
+fn count<'t>(threats: impl Iterator<Item = &'t Threat>) -> RiskCell {
+    threats.fold(RiskCell::default(), |cell, threat| RiskCell {
+        threats: cell.threats + 1,
+        unmitigated: cell.unmitigated + usize::from(threat.is_unmitigated()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_THF: &str = include_str!("fixtures/report-sample.thf");
+
+    fn fixture() -> ThreatModel {
+        serde_yaml::from_str(FIXTURE_THF).expect("fixture should parse")
+    }
+
+    #[test]
+    fn risk_matrix_counts_threats_by_severity_and_category() {
+        let model = fixture();
+        let matrix = ReportContext::new(&model).risk_matrix;
+        let critical = &matrix.rows[0];
+        assert_eq!(critical.label, "Critical");
+        // Tampering (flow-1, mitigated) and Information Disclosure (accepted).
+        let cells: Vec<(usize, usize)> = critical
+            .cells
+            .iter()
+            .map(|c| (c.threats, c.unmitigated))
+            .collect();
+        assert_eq!(cells, [(0, 0), (1, 0), (0, 0), (1, 0), (0, 0), (0, 0)]);
+        assert_eq!((critical.total.threats, critical.total.unmitigated), (2, 0));
+        let total: usize = matrix.rows.iter().map(|r| r.total.threats).sum();
+        assert_eq!(total, model.threats.len());
+    }
+
+    #[test]
+    fn threats_are_grouped_by_status_with_their_targets_named() {
+        let model = fixture();
+        let context = ReportContext::new(&model);
+        assert_eq!(context.stats.elements, 3);
+        assert_eq!(context.stats.unmitigated, 4);
+
+        let none = &context.threats_by_status[0];
+        assert_eq!(none.label, "None recorded");
+        assert_eq!(none.share, 29);
+        let ids: Vec<&str> = none.threats.iter().map(|t| t.threat.id.as_str()).collect();
+        assert_eq!(ids, ["threat-5", "threat-7"]);
+        assert_eq!(none.threats[0].flow_name, Some("Record payment"));
+        assert_eq!(none.threats[1].element_name, None);
+
+        let value = serde_json::to_value(&context.threats[0]).unwrap();
+        assert_eq!(value["id"], "threat-1");
+        assert_eq!(value["element_name"], "Payments API");
+        assert_eq!(value["status"], "in_progress");
+        assert_eq!(value["mitigation"]["status"], "in_progress");
+    }
+}
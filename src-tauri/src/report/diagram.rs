@@ -0,0 +1,1423 @@
+//! Diagrams drawn as SVG or PNG from a model's inline layout, without the
+//! webview, so CI jobs can put them in reports and wiki pages.
+//!
+//! One diagram is drawn at a time: the elements, trust boundaries and groups
+//! placed on it, and the data flows between its elements. Elements keep the
+//! canvas look — a rounded box, rectangle, store or hexagon by type, with an
+//! icon for the type, the trust zone, the technologies and a count of the
+//! threats raised against the element. Flows are curved as on the canvas and
+//! labelled with their number, name, protocol and data, moved by their
+//! `label_offset`. Groups are drawn as solid boxes behind the elements,
+//! boundaries dashed.
+//!
+//! Elements carry no size, so a box is sized from its text as the canvas
+//! sizes it; a custom `icon` is drawn as its type's icon. Elements with no
+//! position are lined up below the rest, and boundaries and groups with no
+//! geometry of their own are drawn around their members. Relationships are
+//! not drawn. The SVG depends only on the model; the PNG is rasterized with
+//! the fonts installed on the machine.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use resvg::{tiny_skia, usvg};
+
+use crate::models::layout::{
+    boundary_bounds, grid_origin, line_points, surface_index, DEFAULT_DIAGRAM_NAME,
+};
+use crate::models::{DataFlow, Element, Group, Position, Size, ThreatModel, TrustBoundary};
+use crate::stride::{stride_category_for_type, ComponentStrideCategory};
+
+/// Space around the drawing.
+const MARGIN: f64 = 24.0;
+/// Space between the placed elements and those lined up below them.
+const UNPLACED_GAP: f64 = 80.0;
+/// Space around the members of a group drawn around them.
+const GROUP_PADDING: f64 = 24.0;
+/// Room above a group's members for its name.
+const GROUP_LABEL_HEIGHT: f64 = 20.0;
+
+const ELEMENT_MIN_WIDTH: f64 = 140.0;
+const ELEMENT_PADDING_X: f64 = 16.0;
+const ELEMENT_PADDING_Y: f64 = 12.0;
+const ICON_SIZE: f64 = 16.0;
+const ICON_GAP: f64 = 6.0;
+/// How far a self-loop arcs out of its element.
+const SELF_LOOP_OFFSET: f64 = 70.0;
+
+const FONT_FAMILY: &str =
+    "Inter, 'Segoe UI', Helvetica, Arial, 'DejaVu Sans', 'Liberation Sans', sans-serif";
+const BACKGROUND: &str = "#ffffff";
+const CARD: &str = "#ffffff";
+const BORDER: &str = "#cbd5e1";
+const FOREGROUND: &str = "#0f172a";
+const MUTED: &str = "#64748b";
+const CHIP: &str = "#f1f5f9";
+const EDGE: &str = "#94a3b8";
+const SIGNAL: &str = "#0d9488";
+const EMBER: &str = "#ea580c";
+
+/// Services drawn as hexagons on the canvas: the networking and platform
+/// components.
+const HEXAGON_TYPES: [&str; 10] = [
+    "load_balancer",
+    "cdn",
+    "dns",
+    "proxy",
+    "container",
+    "vpn_gateway",
+    "service_mesh",
+    "kubernetes",
+    "serverless_platform",
+    "ci_cd_pipeline",
+];
+
+/// Draw a diagram of `model` as SVG. `diagram` is a diagram id or name; the
+/// first diagram is drawn when it is `None`.
+pub fn render_svg(model: &ThreatModel, diagram: Option<&str>) -> Result<String, String> {
+    let surface = select_diagram(model, diagram)?;
+    Ok(Scene::new(model, surface).to_svg())
+}
+
+/// Draw a diagram of `model` as PNG, `scale` pixels per canvas unit.
+pub fn render_png(
+    model: &ThreatModel,
+    diagram: Option<&str>,
+    scale: f32,
+) -> Result<Vec<u8>, String> {
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(format!("PNG scale must be a positive number, not {scale}"));
+    }
+    let svg = render_svg(model, diagram)?;
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(&svg, &options)
+        .map_err(|e| format!("Failed to read the drawn diagram: {e}"))?;
+    let too_large = || "Diagram is too large to rasterize".to_string();
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(too_large)?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).ok_or_else(too_large)?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|e| format!("Failed to encode PNG: {e}"))
+}
+
+/// The index of the diagram named by id or name, the first when unnamed.
+fn select_diagram(model: &ThreatModel, diagram: Option<&str>) -> Result<usize, String> {
+    let Some(key) = diagram else {
+        return Ok(0);
+    };
+    if let Some(index) = model.diagrams.iter().position(|d| d.id == key) {
+        return Ok(index);
+    }
+    if let Some(index) = model.diagrams.iter().position(|d| d.name == key) {
+        return Ok(index);
+    }
+    if model.diagrams.is_empty() {
+        return Err(format!(
+            "Unknown diagram '{key}': the model has no diagrams"
+        ));
+    }
+    let available: Vec<String> = model
+        .diagrams
+        .iter()
+        .map(|d| format!("{} ({})", d.id, d.name))
+        .collect();
+    Err(format!(
+        "Unknown diagram '{key}'. Diagrams: {}",
+        available.join(", ")
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Layout
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Rect {
+    fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    /// The smallest rectangle holding all of `rects`, grown by `padding`.
+    fn around(rects: impl IntoIterator<Item = Rect>, padding: f64) -> Option<Rect> {
+        let mut bounds = Bounds::default();
+        for rect in rects {
+            bounds.include_rect(&rect);
+        }
+        bounds.rect().map(|r| Rect {
+            x: r.x - padding,
+            y: r.y - padding,
+            width: r.width + 2.0 * padding,
+            height: r.height + 2.0 * padding,
+        })
+    }
+}
+
+/// The extent of everything drawn.
+#[derive(Debug, Default)]
+struct Bounds(Option<(f64, f64, f64, f64)>);
+
+impl Bounds {
+    fn include(&mut self, x: f64, y: f64) {
+        self.0 = Some(match self.0 {
+            None => (x, y, x, y),
+            Some((min_x, min_y, max_x, max_y)) => {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            }
+        });
+    }
+
+    fn include_rect(&mut self, rect: &Rect) {
+        self.include(rect.x, rect.y);
+        self.include(rect.right(), rect.bottom());
+    }
+
+    fn rect(&self) -> Option<Rect> {
+        self.0.map(|(min_x, min_y, max_x, max_y)| Rect {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Rounded,
+    Rect,
+    Store,
+    Hexagon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+/// An element placed on the diagram.
+struct Node<'a> {
+    element: &'a Element,
+    rect: Rect,
+}
+
+impl Node<'_> {
+    fn is_text(&self) -> bool {
+        self.element.element_type == "text"
+    }
+
+    /// The point a flow leaves or enters by: `fraction` of the way along
+    /// `side`, from the top or left.
+    fn handle(&self, side: Side, fraction: f64) -> (f64, f64) {
+        let r = &self.rect;
+        match side {
+            Side::Top => (r.x + r.width * fraction, r.y),
+            Side::Bottom => (r.x + r.width * fraction, r.bottom()),
+            Side::Left => (r.x, r.y + r.height * fraction),
+            Side::Right => (r.right(), r.y + r.height * fraction),
+        }
+    }
+}
+
+/// A flow's curve and where its label sits.
+struct FlowGeometry {
+    path: String,
+    label: (f64, f64),
+}
+
+/// A flow's label chip, as lines of text.
+struct FlowLabel<'a> {
+    number: Option<u32>,
+    authenticated: bool,
+    lines: Vec<(&'a str, f64, &'static str)>,
+}
+
+impl FlowLabel<'_> {
+    const HEADER_HEIGHT: f64 = 18.0;
+    const PADDING_X: f64 = 8.0;
+    const PADDING_Y: f64 = 4.0;
+
+    fn has_header(&self) -> bool {
+        self.number.is_some() || self.authenticated
+    }
+
+    fn size(&self) -> (f64, f64) {
+        let header_width = self.number.map_or(0.0, |n| badge_width(&n.to_string()))
+            + if self.authenticated { 16.0 } else { 0.0 };
+        let width = self
+            .lines
+            .iter()
+            .map(|(text, size, _)| text_width(text, *size))
+            .fold(header_width, f64::max);
+        let height = if self.has_header() {
+            Self::HEADER_HEIGHT
+        } else {
+            0.0
+        } + self
+            .lines
+            .iter()
+            .map(|(_, size, _)| line_height(*size))
+            .sum::<f64>();
+        (
+            width + 2.0 * Self::PADDING_X,
+            height + 2.0 * Self::PADDING_Y,
+        )
+    }
+}
+
+struct Scene<'a> {
+    model: &'a ThreatModel,
+    title: &'a str,
+    nodes: Vec<Node<'a>>,
+    groups: Vec<(&'a Group, Rect)>,
+    boundaries: Vec<(&'a TrustBoundary, BoundaryGeometry<'a>)>,
+    flows: Vec<(&'a DataFlow, FlowGeometry)>,
+}
+
+enum BoundaryGeometry<'a> {
+    Box(Rect),
+    Line(&'a [Position]),
+}
+
+impl<'a> Scene<'a> {
+    fn new(model: &'a ThreatModel, surface: usize) -> Self {
+        let on_surface = |diagram: Option<&str>| surface_index(model, diagram) == surface;
+        let elements: Vec<&Element> = model
+            .elements
+            .iter()
+            .filter(|e| on_surface(e.diagram.as_deref()))
+            .collect();
+
+        let mut nodes: Vec<Node> = elements
+            .iter()
+            .filter_map(|element| {
+                let position = element.position.as_ref()?;
+                Some(Node {
+                    element,
+                    rect: element_rect(element, position),
+                })
+            })
+            .collect();
+        let below = Rect::around(nodes.iter().map(|n| n.rect), 0.0)
+            .map_or(0.0, |r| r.bottom() + UNPLACED_GAP);
+        for (index, element) in elements.iter().filter(|e| e.position.is_none()).enumerate() {
+            let origin = grid_origin(index);
+            let position = Position {
+                x: origin.x,
+                y: origin.y + below,
+            };
+            nodes.push(Node {
+                element,
+                rect: element_rect(element, &position),
+            });
+        }
+        let by_id: HashMap<&str, &Node> =
+            nodes.iter().map(|n| (n.element.id.as_str(), n)).collect();
+
+        let boundaries = model
+            .trust_boundaries
+            .iter()
+            .filter(|b| on_surface(b.diagram.as_deref()))
+            .filter_map(|boundary| {
+                if let Some(points) = line_points(boundary) {
+                    return Some((boundary, BoundaryGeometry::Line(points)));
+                }
+                let rect = match (&boundary.position, &boundary.size) {
+                    (Some(p), Some(s)) => Rect {
+                        x: p.x,
+                        y: p.y,
+                        width: s.width,
+                        height: s.height,
+                    },
+                    _ => {
+                        let members = boundary.contains.iter().filter_map(|id| {
+                            let rect = by_id.get(id.as_str())?.rect;
+                            let size = Size {
+                                width: rect.width,
+                                height: rect.height,
+                            };
+                            Some((
+                                Position {
+                                    x: rect.x,
+                                    y: rect.y,
+                                },
+                                size,
+                            ))
+                        });
+                        let (p, s) = boundary_bounds(members)?;
+                        Rect {
+                            x: p.x,
+                            y: p.y,
+                            width: s.width,
+                            height: s.height,
+                        }
+                    }
+                };
+                Some((boundary, BoundaryGeometry::Box(rect)))
+            })
+            .collect();
+
+        let groups = group_boxes(model, &nodes, surface);
+
+        let flows = model
+            .data_flows
+            .iter()
+            .filter_map(|flow| {
+                let source = by_id.get(flow.from.as_str())?;
+                let target = by_id.get(flow.to.as_str())?;
+                Some((flow, flow_geometry(flow, source, target)))
+            })
+            .collect();
+
+        let title = match model.diagrams.get(surface) {
+            Some(d) if !d.name.is_empty() => d.name.as_str(),
+            _ => DEFAULT_DIAGRAM_NAME,
+        };
+
+        Scene {
+            model,
+            title,
+            nodes,
+            groups,
+            boundaries,
+            flows,
+        }
+    }
+
+    fn to_svg(&self) -> String {
+        let mut markers: Vec<&str> = Vec::new();
+        let mut flows = String::new();
+        let mut labels = String::new();
+        let mut bounds = Bounds::default();
+
+        for (flow, geometry) in &self.flows {
+            let (color, opacity) = match valid_color(flow.stroke_color.as_deref()) {
+                Some(color) => (color, flow.stroke_opacity.unwrap_or(1.0)),
+                None => (EDGE, 1.0),
+            };
+            let marker = markers.iter().position(|c| *c == color).unwrap_or_else(|| {
+                markers.push(color);
+                markers.len() - 1
+            });
+            let _ = writeln!(
+                flows,
+                r#"<path d="{}" fill="none" stroke="{color}"{} stroke-width="2" marker-end="url(#arrow-{marker})"/>"#,
+                geometry.path,
+                opacity_attr("stroke-opacity", opacity),
+            );
+            bounds.include(geometry.label.0, geometry.label.1);
+            if let Some(rect) = write_flow_label(&mut labels, flow, geometry.label) {
+                bounds.include_rect(&rect);
+            }
+        }
+
+        let mut groups = String::new();
+        for (group, rect) in &self.groups {
+            bounds.include_rect(rect);
+            write_group(&mut groups, group, rect);
+        }
+
+        let mut boundaries = String::new();
+        for (boundary, geometry) in &self.boundaries {
+            match geometry {
+                BoundaryGeometry::Box(rect) => bounds.include_rect(rect),
+                BoundaryGeometry::Line(points) => {
+                    points.iter().for_each(|p| bounds.include(p.x, p.y));
+                }
+            }
+            write_boundary(&mut boundaries, boundary, geometry);
+        }
+
+        let mut elements = String::new();
+        for node in &self.nodes {
+            bounds.include_rect(&node.rect);
+            if node.is_text() {
+                write_annotation(&mut elements, node);
+            } else {
+                let threats = self
+                    .model
+                    .threats
+                    .iter()
+                    .filter(|t| t.element.as_deref() == Some(node.element.id.as_str()))
+                    .count();
+                write_element(&mut elements, node, threats);
+            }
+        }
+
+        let frame = bounds.rect().unwrap_or(Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+        });
+        let frame = Rect {
+            x: (frame.x - MARGIN).floor(),
+            y: (frame.y - MARGIN).floor(),
+            width: (frame.width + 2.0 * MARGIN).ceil(),
+            height: (frame.height + 2.0 * MARGIN).ceil(),
+        };
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{x} {y} {w} {h}" font-family="{FONT_FAMILY}">"#,
+            x = num(frame.x),
+            y = num(frame.y),
+            w = num(frame.width),
+            h = num(frame.height),
+        );
+        let _ = writeln!(
+            svg,
+            "<title>{}: {}</title>",
+            escape(&self.model.metadata.title),
+            escape(self.title)
+        );
+        svg.push_str("<defs>\n");
+        for (index, color) in markers.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                r#"<marker id="arrow-{index}" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="5" markerHeight="5" orient="auto-start-reverse"><path d="M0 0L10 5L0 10z" fill="{color}"/></marker>"#
+            );
+        }
+        svg.push_str("</defs>\n");
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{BACKGROUND}"/>"#,
+            num(frame.x),
+            num(frame.y),
+            num(frame.width),
+            num(frame.height),
+        );
+        for (class, content) in [
+            ("groups", groups),
+            ("trust-boundaries", boundaries),
+            ("data-flows", flows),
+            ("elements", elements),
+            ("flow-labels", labels),
+        ] {
+            if !content.is_empty() {
+                let _ = write!(svg, "<g class=\"{class}\">\n{content}</g>\n");
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Where an element is drawn, sized from its text as the canvas sizes it.
+fn element_rect(element: &Element, position: &Position) -> Rect {
+    let (width, height) = if element.element_type == "text" {
+        let size = element.font_size.unwrap_or(14.0);
+        (
+            text_width(&element.name, size) + 2.0 * 8.0,
+            line_height(size) + 2.0 * 4.0,
+        )
+    } else {
+        let label = ICON_SIZE + ICON_GAP + text_width(&element.name, 14.0);
+        let zone = text_width(&element.trust_zone, 10.0);
+        let content = label.max(zone).max(technologies_width(element));
+        let padding = match shape_for(&element.element_type) {
+            Shape::Hexagon => 2.0 * ELEMENT_PADDING_X,
+            _ => ELEMENT_PADDING_X,
+        };
+        let mut height = 2.0 * ELEMENT_PADDING_Y + 20.0;
+        if !element.trust_zone.is_empty() {
+            height += 14.0;
+        }
+        if !element.technologies.is_empty() {
+            height += 18.0;
+        }
+        ((content + 2.0 * padding).max(ELEMENT_MIN_WIDTH), height)
+    };
+    Rect {
+        x: position.x,
+        y: position.y,
+        width,
+        height,
+    }
+}
+
+fn technologies_width(element: &Element) -> f64 {
+    let chips: f64 = element
+        .technologies
+        .iter()
+        .map(|t| text_width(t, 9.0) + 8.0)
+        .sum();
+    chips + 2.0 * element.technologies.len().saturating_sub(1) as f64
+}
+
+/// The groups to draw, outermost first, with their boxes. A group without
+/// geometry of its own is drawn around its members on this diagram, nested
+/// groups included; one with geometry is drawn where it has members, or on
+/// the first diagram when it has none.
+fn group_boxes<'a>(
+    model: &'a ThreatModel,
+    nodes: &[Node],
+    surface: usize,
+) -> Vec<(&'a Group, Rect)> {
+    fn members<'g>(
+        model: &'g ThreatModel,
+        group: &str,
+        seen: &mut HashSet<&'g str>,
+    ) -> Vec<&'g str> {
+        let mut ids: Vec<&str> = model
+            .elements
+            .iter()
+            .filter(|e| e.group.as_deref() == Some(group))
+            .map(|e| e.id.as_str())
+            .collect();
+        for child in model
+            .groups
+            .iter()
+            .filter(|g| g.parent.as_deref() == Some(group))
+        {
+            if seen.insert(child.id.as_str()) {
+                ids.extend(members(model, &child.id, seen));
+            }
+        }
+        ids
+    }
+
+    fn depth(model: &ThreatModel, group: &Group) -> usize {
+        let mut depth = 0;
+        let mut parent = group.parent.as_deref();
+        while let Some(id) = parent.filter(|_| depth <= model.groups.len()) {
+            depth += 1;
+            parent = model
+                .groups
+                .iter()
+                .find(|g| g.id == id)
+                .and_then(|g| g.parent.as_deref());
+        }
+        depth
+    }
+
+    let by_id: HashMap<&str, Rect> = nodes
+        .iter()
+        .map(|n| (n.element.id.as_str(), n.rect))
+        .collect();
+    let mut boxes: Vec<(usize, &Group, Rect)> = Vec::new();
+    for group in &model.groups {
+        let ids = members(model, &group.id, &mut HashSet::from([group.id.as_str()]));
+        let here: Vec<Rect> = ids.iter().filter_map(|id| by_id.get(id).copied()).collect();
+        let rect = match (&group.position, &group.size) {
+            (Some(p), Some(s)) if !here.is_empty() || (ids.is_empty() && surface == 0) => Rect {
+                x: p.x,
+                y: p.y,
+                width: s.width,
+                height: s.height,
+            },
+            (Some(_), Some(_)) => continue,
+            _ => {
+                let Some(around) = Rect::around(here, GROUP_PADDING) else {
+                    continue;
+                };
+                Rect {
+                    y: around.y - GROUP_LABEL_HEIGHT,
+                    height: around.height + GROUP_LABEL_HEIGHT,
+                    ..around
+                }
+            }
+        };
+        boxes.push((depth(model, group), group, rect));
+    }
+    boxes.sort_by_key(|(depth, _, _)| *depth);
+    boxes.into_iter().map(|(_, g, r)| (g, r)).collect()
+}
+
+/// The curve of a flow, as the canvas draws it: a bezier between the sides
+/// the flow's handles name, or the sides facing each other, bent through its
+/// label when the label was moved.
+fn flow_geometry(flow: &DataFlow, source: &Node, target: &Node) -> FlowGeometry {
+    if flow.from == flow.to {
+        let (x, y) = source.handle(Side::Right, 0.5);
+        let o = SELF_LOOP_OFFSET;
+        return FlowGeometry {
+            path: format!(
+                "M{} {}C{} {} {} {} {} {}",
+                num(x),
+                num(y),
+                num(x + o),
+                num(y - o),
+                num(x + o),
+                num(y - o),
+                num(x),
+                num(y)
+            ),
+            label: (x + o * 0.8, y - o * 0.8),
+        };
+    }
+
+    let (source_center, target_center) = (source.rect.center(), target.rect.center());
+    let dx = target_center.0 - source_center.0;
+    let dy = target_center.1 - source_center.1;
+    let (facing_source, facing_target) = if dx.abs() >= dy.abs() {
+        if dx >= 0.0 {
+            (Side::Right, Side::Left)
+        } else {
+            (Side::Left, Side::Right)
+        }
+    } else if dy >= 0.0 {
+        (Side::Bottom, Side::Top)
+    } else {
+        (Side::Top, Side::Bottom)
+    };
+    let (source_side, source_fraction) = flow
+        .source_handle
+        .as_deref()
+        .and_then(parse_handle)
+        .unwrap_or((facing_source, 0.5));
+    let (target_side, target_fraction) = flow
+        .target_handle
+        .as_deref()
+        .and_then(parse_handle)
+        .unwrap_or((facing_target, 0.5));
+    let (sx, sy) = source.handle(source_side, source_fraction);
+    let (tx, ty) = target.handle(target_side, target_fraction);
+
+    let (c1x, c1y) = control_point(source_side, sx, sy, tx, ty);
+    let (c2x, c2y) = control_point(target_side, tx, ty, sx, sy);
+    let default_label = (
+        sx * 0.125 + c1x * 0.375 + c2x * 0.375 + tx * 0.125,
+        sy * 0.125 + c1y * 0.375 + c2y * 0.375 + ty * 0.125,
+    );
+
+    let offset = flow
+        .label_offset
+        .as_ref()
+        .filter(|o| o.x != 0.0 || o.y != 0.0);
+    match offset {
+        Some(offset) if has_label(flow) => {
+            let (lx, ly) = (default_label.0 + offset.x, default_label.1 + offset.y);
+            // A quadratic curve through the label: its control point is
+            // 2W - (S + T) / 2.
+            let (cx, cy) = (2.0 * lx - (sx + tx) / 2.0, 2.0 * ly - (sy + ty) / 2.0);
+            FlowGeometry {
+                path: format!(
+                    "M{} {}Q{} {} {} {}",
+                    num(sx),
+                    num(sy),
+                    num(cx),
+                    num(cy),
+                    num(tx),
+                    num(ty)
+                ),
+                label: (lx, ly),
+            }
+        }
+        _ => FlowGeometry {
+            path: format!(
+                "M{} {}C{} {} {} {} {} {}",
+                num(sx),
+                num(sy),
+                num(c1x),
+                num(c1y),
+                num(c2x),
+                num(c2y),
+                num(tx),
+                num(ty)
+            ),
+            label: default_label,
+        },
+    }
+}
+
+/// A handle id such as `top-source` or `bottom-left-target`: the side it
+/// sits on and how far along it.
+fn parse_handle(handle: &str) -> Option<(Side, f64)> {
+    let position = handle
+        .strip_suffix("-source")
+        .or_else(|| handle.strip_suffix("-target"))
+        .unwrap_or(handle);
+    Some(match position {
+        "top" => (Side::Top, 0.5),
+        "top-left" => (Side::Top, 0.15),
+        "top-right" => (Side::Top, 0.85),
+        "bottom" => (Side::Bottom, 0.5),
+        "bottom-left" => (Side::Bottom, 0.15),
+        "bottom-right" => (Side::Bottom, 0.85),
+        "left" => (Side::Left, 0.5),
+        "right" => (Side::Right, 0.5),
+        _ => return None,
+    })
+}
+
+/// The control point of a bezier end leaving `side` at (x1, y1) towards
+/// (x2, y2), as React Flow places it.
+fn control_point(side: Side, x1: f64, y1: f64, x2: f64, y2: f64) -> (f64, f64) {
+    let offset = |distance: f64| {
+        if distance >= 0.0 {
+            0.5 * distance
+        } else {
+            0.25 * 25.0 * (-distance).sqrt()
+        }
+    };
+    match side {
+        Side::Left => (x1 - offset(x1 - x2), y1),
+        Side::Right => (x1 + offset(x2 - x1), y1),
+        Side::Top => (x1, y1 - offset(y1 - y2)),
+        Side::Bottom => (x1, y1 + offset(y2 - y1)),
+    }
+}
+
+fn has_label(flow: &DataFlow) -> bool {
+    flow.flow_number.is_some()
+        || flow.authenticated
+        || !flow.name.is_empty()
+        || !flow.protocol.is_empty()
+        || !flow.data.is_empty()
+}
+
+// ---------------------------------------------------------------------------
+// Drawing
+// ---------------------------------------------------------------------------
+
+fn write_group(out: &mut String, group: &Group, rect: &Rect) {
+    let fill = valid_color(group.fill_color.as_deref());
+    let stroke = valid_color(group.stroke_color.as_deref());
+    let _ = writeln!(
+        out,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" rx="10" fill="{}"{} stroke="{}"{} stroke-width="1.5"/>"#,
+        num(rect.x),
+        num(rect.y),
+        num(rect.width),
+        num(rect.height),
+        fill.unwrap_or(CHIP),
+        opacity_attr(
+            "fill-opacity",
+            group
+                .fill_opacity
+                .unwrap_or(if fill.is_some() { 0.1 } else { 0.6 })
+        ),
+        stroke.unwrap_or(BORDER),
+        opacity_attr("stroke-opacity", group.stroke_opacity.unwrap_or(1.0)),
+    );
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="12" font-weight="600" fill="{MUTED}">{}</text>"#,
+        num(rect.x + 10.0),
+        num(rect.y + 18.0),
+        escape(&group.name),
+    );
+}
+
+fn write_boundary(out: &mut String, boundary: &TrustBoundary, geometry: &BoundaryGeometry) {
+    let fill = valid_color(boundary.fill_color.as_deref());
+    let stroke = valid_color(boundary.stroke_color.as_deref()).unwrap_or(MUTED);
+    let stroke_opacity = opacity_attr(
+        "stroke-opacity",
+        boundary
+            .stroke_opacity
+            .unwrap_or(if boundary.stroke_color.is_some() {
+                0.6
+            } else {
+                0.5
+            }),
+    );
+    let (label_x, label_y) = match geometry {
+        BoundaryGeometry::Box(rect) => {
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="8" fill="{}"{} stroke="{stroke}"{stroke_opacity} stroke-width="2" stroke-dasharray="6 4"/>"#,
+                num(rect.x),
+                num(rect.y),
+                num(rect.width),
+                num(rect.height),
+                fill.unwrap_or(MUTED),
+                opacity_attr("fill-opacity", boundary.fill_opacity.unwrap_or(0.05)),
+            );
+            (rect.x + 8.0, rect.y + 20.0)
+        }
+        BoundaryGeometry::Line(points) => {
+            let vertices: Vec<String> = points
+                .iter()
+                .map(|p| format!("{},{}", num(p.x), num(p.y)))
+                .collect();
+            let _ = writeln!(
+                out,
+                r#"<polyline points="{}" fill="none" stroke="{stroke}"{stroke_opacity} stroke-width="2" stroke-dasharray="6 4"/>"#,
+                vertices.join(" "),
+            );
+            (points[0].x + 4.0, points[0].y + 14.0)
+        }
+    };
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="12" font-weight="600" letter-spacing="0.6" fill="{MUTED}">{}</text>"#,
+        num(label_x),
+        num(label_y),
+        escape(&boundary.name.to_uppercase()),
+    );
+}
+
+fn write_element(out: &mut String, node: &Node, threats: usize) {
+    let element = node.element;
+    let rect = &node.rect;
+    let shape = shape_for(&element.element_type);
+    let fill = match valid_color(element.fill_color.as_deref()) {
+        Some(color) => format!(
+            "fill=\"{color}\"{}",
+            opacity_attr("fill-opacity", element.fill_opacity.unwrap_or(0.15))
+        ),
+        None => format!("fill=\"{CARD}\""),
+    };
+    let stroke = match valid_color(element.stroke_color.as_deref()) {
+        Some(color) => format!(
+            "stroke=\"{color}\"{}",
+            opacity_attr("stroke-opacity", element.stroke_opacity.unwrap_or(1.0))
+        ),
+        None => format!("stroke=\"{BORDER}\""),
+    };
+    let (x, y, w, h) = (rect.x, rect.y, rect.width, rect.height);
+
+    out.push_str("<g>\n");
+    match shape {
+        Shape::Rounded | Shape::Rect => {
+            let radius = if shape == Shape::Rounded { 16.0 } else { 2.0 };
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {fill} {stroke} stroke-width="2"/>"#,
+                num(x),
+                num(y),
+                num(w),
+                num(h),
+                num(radius),
+            );
+        }
+        Shape::Store => {
+            // A store has rules above and below, as in DFD notation.
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {fill}/>"#,
+                num(x),
+                num(y),
+                num(w),
+                num(h),
+            );
+            let _ = writeln!(
+                out,
+                r#"<path d="M{x0} {y0}H{x1}M{x0} {y1}H{x1}" fill="none" {stroke} stroke-width="2"/>"#,
+                x0 = num(x),
+                x1 = num(x + w),
+                y0 = num(y + 1.0),
+                y1 = num(y + h - 1.0),
+            );
+        }
+        Shape::Hexagon => {
+            let points = [
+                (0.25, 0.0),
+                (0.75, 0.0),
+                (1.0, 0.5),
+                (0.75, 1.0),
+                (0.25, 1.0),
+                (0.0, 0.5),
+            ]
+            .iter()
+            .map(|(fx, fy)| format!("{},{}", num(x + w * fx), num(y + h * fy)))
+            .collect::<Vec<_>>()
+            .join(" ");
+            let _ = writeln!(
+                out,
+                r#"<polygon points="{points}" {fill} {stroke} stroke-width="2"/>"#
+            );
+        }
+    }
+
+    let has_zone = !element.trust_zone.is_empty();
+    let has_technologies = !element.technologies.is_empty();
+    let content_height =
+        20.0 + if has_zone { 14.0 } else { 0.0 } + if has_technologies { 18.0 } else { 0.0 };
+    let center_x = x + w / 2.0;
+    let mut line_y = y + (h - content_height) / 2.0 + 10.0;
+
+    let label_width = ICON_SIZE + ICON_GAP + text_width(&element.name, 14.0);
+    let icon_x = center_x - label_width / 2.0;
+    write_glyph(
+        out,
+        glyph_for(&element.element_type),
+        icon_x,
+        line_y - ICON_SIZE / 2.0,
+        1.0,
+    );
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="14" font-weight="500" fill="{FOREGROUND}">{}</text>"#,
+        num(icon_x + ICON_SIZE + ICON_GAP),
+        num(line_y + 5.0),
+        escape(&element.name),
+    );
+    line_y += 10.0;
+
+    if has_zone {
+        let _ = writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-size="10" text-anchor="middle" fill="{MUTED}">{}</text>"#,
+            num(center_x),
+            num(line_y + 11.0),
+            escape(&element.trust_zone),
+        );
+        line_y += 14.0;
+    }
+    if has_technologies {
+        let mut chip_x = center_x - technologies_width(element) / 2.0;
+        let chip_y = line_y + 4.0;
+        for technology in &element.technologies {
+            let chip_width = text_width(technology, 9.0) + 8.0;
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="13" rx="3" fill="{CHIP}"/>"#,
+                num(chip_x),
+                num(chip_y),
+                num(chip_width),
+            );
+            let _ = writeln!(
+                out,
+                r#"<text x="{}" y="{}" font-size="9" text-anchor="middle" fill="{MUTED}">{}</text>"#,
+                num(chip_x + chip_width / 2.0),
+                num(chip_y + 9.5),
+                escape(technology),
+            );
+            chip_x += chip_width + 2.0;
+        }
+    }
+
+    if threats > 0 {
+        let count = threats.to_string();
+        let badge = badge_width(&count);
+        let (bx, by) = (x + w - badge + 6.0, y - 6.0);
+        let _ = writeln!(
+            out,
+            r#"<rect x="{}" y="{}" width="{}" height="16" rx="8" fill="{EMBER}"/>"#,
+            num(bx),
+            num(by),
+            num(badge),
+        );
+        let _ = writeln!(
+            out,
+            r##"<text x="{}" y="{}" font-size="9" font-weight="700" text-anchor="middle" fill="#ffffff">{count}</text>"##,
+            num(bx + badge / 2.0),
+            num(by + 11.5),
+        );
+    }
+    out.push_str("</g>\n");
+}
+
+fn write_annotation(out: &mut String, node: &Node) {
+    let element = node.element;
+    let size = element.font_size.unwrap_or(14.0);
+    let weight = match element.font_weight.as_deref() {
+        Some("bold") => "700",
+        Some(weight) if weight.chars().all(|c| c.is_ascii_digit()) && !weight.is_empty() => weight,
+        _ => "400",
+    };
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-size="{}" font-weight="{weight}" fill="{FOREGROUND}">{}</text>"#,
+        num(node.rect.x + 8.0),
+        num(node.rect.y + 4.0 + size),
+        num(size),
+        escape(&element.name),
+    );
+}
+
+/// Draw a flow's label chip centred on `at`, returning where it went.
+fn write_flow_label(out: &mut String, flow: &DataFlow, at: (f64, f64)) -> Option<Rect> {
+    if !has_label(flow) {
+        return None;
+    }
+    let data = flow.data.join(", ");
+    let mut lines = Vec::new();
+    if !flow.name.is_empty() {
+        lines.push((flow.name.as_str(), 11.0, FOREGROUND));
+    }
+    if !flow.protocol.is_empty() {
+        lines.push((flow.protocol.as_str(), 9.0, MUTED));
+    }
+    if !data.is_empty() {
+        lines.push((data.as_str(), 9.0, MUTED));
+    }
+    let label = FlowLabel {
+        number: flow.flow_number,
+        authenticated: flow.authenticated,
+        lines,
+    };
+    let (width, height) = label.size();
+    let rect = Rect {
+        x: at.0 - width / 2.0,
+        y: at.1 - height / 2.0,
+        width,
+        height,
+    };
+
+    out.push_str("<g>\n");
+    let _ = writeln!(
+        out,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" rx="6" fill="{CARD}" stroke="{BORDER}"/>"#,
+        num(rect.x),
+        num(rect.y),
+        num(rect.width),
+        num(rect.height),
+    );
+    let mut y = rect.y + FlowLabel::PADDING_Y;
+    if label.has_header() {
+        let header_width = label.number.map_or(0.0, |n| badge_width(&n.to_string()))
+            + if label.authenticated { 16.0 } else { 0.0 };
+        let mut x = at.0 - header_width / 2.0;
+        if let Some(number) = label.number {
+            let number = number.to_string();
+            let badge = badge_width(&number);
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="16" rx="8" fill="{CHIP}" stroke="{BORDER}" stroke-width="0.5"/>"#,
+                num(x),
+                num(y + 1.0),
+                num(badge),
+            );
+            let _ = writeln!(
+                out,
+                r#"<text x="{}" y="{}" font-size="9" font-weight="600" text-anchor="middle" fill="{MUTED}">{number}</text>"#,
+                num(x + badge / 2.0),
+                num(y + 12.5),
+            );
+            x += badge + 4.0;
+        }
+        if label.authenticated {
+            write_glyph(out, Glyph::Lock, x, y + 3.0, 0.75);
+        }
+        y += FlowLabel::HEADER_HEIGHT;
+    }
+    for (text, size, color) in &label.lines {
+        let height = line_height(*size);
+        let weight = if *size > 10.0 {
+            r#" font-weight="500""#
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-size="{}"{weight} text-anchor="middle" fill="{color}">{}</text>"#,
+            num(at.0),
+            num(y + height * 0.75),
+            num(*size),
+            escape(text),
+        );
+        y += height;
+    }
+    out.push_str("</g>\n");
+    Some(rect)
+}
+
+// ---------------------------------------------------------------------------
+// Element types
+// ---------------------------------------------------------------------------
+
+fn shape_for(element_type: &str) -> Shape {
+    match stride_category_for_type(element_type) {
+        ComponentStrideCategory::Store => Shape::Store,
+        ComponentStrideCategory::Actor => Shape::Rect,
+        _ if HEXAGON_TYPES.contains(&element_type) => Shape::Hexagon,
+        _ => Shape::Rounded,
+    }
+}
+
+/// A 16-unit line icon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Glyph {
+    Database,
+    User,
+    Monitor,
+    Phone,
+    Terminal,
+    Chip,
+    Globe,
+    Shield,
+    Key,
+    Envelope,
+    Bolt,
+    Gear,
+    Cube,
+    Network,
+    Folder,
+    Lock,
+}
+
+fn glyph_for(element_type: &str) -> Glyph {
+    match element_type {
+        "external_entity" | "identity_provider" | "auth_provider" => Glyph::User,
+        "web_browser" | "desktop_app" => Glyph::Monitor,
+        "mobile_app" => Glyph::Phone,
+        "cli_tool" | "api_client" => Glyph::Terminal,
+        "iot_device" => Glyph::Chip,
+        "web_server" | "cdn" | "dns" => Glyph::Globe,
+        "firewall" | "waf" | "vpn_gateway" | "certificate_authority" | "siem" => Glyph::Shield,
+        "secret_manager" | "key_management" => Glyph::Key,
+        "message_queue" | "event_bus" | "stream_processor" | "webhook" => Glyph::Envelope,
+        "serverless_function" | "serverless_platform" => Glyph::Bolt,
+        "background_worker" | "ci_cd_pipeline" | "process" => Glyph::Gear,
+        "generic" | "microservice" | "container" | "kubernetes" | "container_registry" => {
+            Glyph::Cube
+        }
+        "api_gateway" | "load_balancer" | "proxy" | "service_mesh" | "api_endpoint" => {
+            Glyph::Network
+        }
+        "object_storage" | "file_storage" | "backup_service" => Glyph::Folder,
+        other => match stride_category_for_type(other) {
+            ComponentStrideCategory::Store => Glyph::Database,
+            ComponentStrideCategory::Actor => Glyph::User,
+            _ => Glyph::Cube,
+        },
+    }
+}
+
+fn glyph_shapes(glyph: Glyph) -> &'static str {
+    match glyph {
+        Glyph::Database => {
+            r#"<ellipse cx="8" cy="3.5" rx="5.5" ry="2"/><path d="M2.5 3.5v9c0 1.1 2.5 2 5.5 2s5.5-.9 5.5-2v-9M2.5 8c0 1.1 2.5 2 5.5 2s5.5-.9 5.5-2"/>"#
+        }
+        Glyph::User => {
+            r#"<circle cx="8" cy="5" r="3"/><path d="M2.5 14.5c0-3 2.5-5 5.5-5s5.5 2 5.5 5"/>"#
+        }
+        Glyph::Monitor => {
+            r#"<rect x="1.5" y="2" width="13" height="9" rx="1"/><path d="M5.5 14.5h5M8 11v3.5"/>"#
+        }
+        Glyph::Phone => {
+            r#"<rect x="4" y="1.5" width="8" height="13" rx="1.5"/><path d="M7 12.5h2"/>"#
+        }
+        Glyph::Terminal => {
+            r#"<rect x="1.5" y="2.5" width="13" height="11" rx="1.5"/><path d="M4.5 6l2 2-2 2M8.5 10.5h3"/>"#
+        }
+        Glyph::Chip => {
+            r#"<rect x="4" y="4" width="8" height="8" rx="1"/><path d="M6.5 1.5V4M9.5 1.5V4M6.5 12v2.5M9.5 12v2.5M1.5 6.5H4M1.5 9.5H4M12 6.5h2.5M12 9.5h2.5"/>"#
+        }
+        Glyph::Globe => {
+            r#"<circle cx="8" cy="8" r="6.5"/><path d="M1.5 8h13M8 1.5c2 2 2.8 4.2 2.8 6.5S10 12.5 8 14.5C6 12.5 5.2 10.3 5.2 8S6 3.5 8 1.5"/>"#
+        }
+        Glyph::Shield => {
+            r#"<path d="M8 1.5l5.5 2v4.5c0 3.2-2.3 5.5-5.5 6.5-3.2-1-5.5-3.3-5.5-6.5V3.5z"/>"#
+        }
+        Glyph::Key => r#"<circle cx="5" cy="11" r="3"/><path d="M7.2 8.8L14 2M11.5 4.5l2 2"/>"#,
+        Glyph::Envelope => {
+            r#"<rect x="1.5" y="3.5" width="13" height="9" rx="1"/><path d="M1.5 4.5L8 9l6.5-4.5"/>"#
+        }
+        Glyph::Bolt => r#"<path d="M9 1.5L3 9h5l-1 5.5L13 7H8z"/>"#,
+        Glyph::Gear => {
+            r#"<circle cx="8" cy="8" r="2.5"/><path d="M8 1.5v2M8 12.5v2M1.5 8h2M12.5 8h2M3.4 3.4l1.4 1.4M11.2 11.2l1.4 1.4M3.4 12.6l1.4-1.4M11.2 4.8l1.4-1.4"/>"#
+        }
+        Glyph::Cube => r#"<path d="M8 1.5l6 3v7l-6 3-6-3v-7zM2 4.5l6 3 6-3M8 7.5v7"/>"#,
+        Glyph::Network => {
+            r#"<circle cx="8" cy="3" r="1.75"/><circle cx="3" cy="13" r="1.75"/><circle cx="13" cy="13" r="1.75"/><path d="M8 4.75V8L4 11.5M8 8l4 3.5"/>"#
+        }
+        Glyph::Folder => {
+            r#"<path d="M1.5 4v8.5a1 1 0 0 0 1 1h11a1 1 0 0 0 1-1V6a1 1 0 0 0-1-1H8L6.5 3h-4a1 1 0 0 0-1 1z"/>"#
+        }
+        Glyph::Lock => {
+            r#"<rect x="3" y="7" width="10" height="7" rx="1"/><path d="M5 7V5a3 3 0 0 1 6 0v2"/>"#
+        }
+    }
+}
+
+fn write_glyph(out: &mut String, glyph: Glyph, x: f64, y: f64, scale: f64) {
+    let color = if glyph == Glyph::Lock { SIGNAL } else { MUTED };
+    let scale = if scale == 1.0 {
+        String::new()
+    } else {
+        format!(" scale({})", num(scale))
+    };
+    let _ = writeln!(
+        out,
+        r#"<g transform="translate({} {}){scale}" fill="none" stroke="{color}" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round">{}</g>"#,
+        num(x),
+        num(y),
+        glyph_shapes(glyph),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Text and numbers
+// ---------------------------------------------------------------------------
+
+/// Approximate width of `text` at `size`; there are no font metrics to
+/// measure with, and the SVG must not depend on the installed fonts.
+fn text_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * size * 0.56
+}
+
+fn line_height(size: f64) -> f64 {
+    (size * 1.35).round()
+}
+
+/// Width of a round count badge, which grows with the digits.
+fn badge_width(text: &str) -> f64 {
+    (text_width(text, 9.0) + 8.0).max(16.0)
+}
+
+/// `#rrggbb` colours only; anything else falls back to the default, as on
+/// the canvas.
+fn valid_color(color: Option<&str>) -> Option<&str> {
+    color.filter(|c| {
+        c.len() == 7 && c.starts_with('#') && c[1..].chars().all(|ch| ch.is_ascii_hexdigit())
+    })
+}
+
+fn opacity_attr(name: &str, opacity: f64) -> String {
+    if opacity >= 1.0 {
+        String::new()
+    } else {
+        format!(" {name}=\"{}\"", num(opacity.max(0.0)))
+    }
+}
+
+/// A coordinate rounded to two decimals, without trailing zeros.
+fn num(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded == 0.0 {
+        "0".to_string()
+    } else {
+        format!("{rounded}")
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_THF: &str = include_str!("fixtures/diagram-sample.thf");
+    /// The first diagram of [`FIXTURE_THF`], regenerated only when the
+    /// drawing is deliberately changed.
+    const EXPECTED_SVG: &str = include_str!("fixtures/diagram-sample.expected.svg");
+
+    fn fixture() -> ThreatModel {
+        serde_yaml::from_str(FIXTURE_THF).expect("fixture should parse")
+    }
+
+    #[test]
+    fn first_diagram_is_byte_identical_to_the_pinned_drawing() {
+        let svg = render_svg(&fixture(), None).unwrap();
+        assert_eq!(svg, EXPECTED_SVG);
+        assert_eq!(render_svg(&fixture(), Some("main")).unwrap(), svg);
+    }
+
+    #[test]
+    fn a_diagram_shows_only_its_own_elements_and_flows() {
+        let model = fixture();
+        let main = render_svg(&model, None).unwrap();
+        assert!(main.contains("Storefront &lt;web&gt;"));
+        assert!(main.contains(">Save order<"));
+        // The flow to the fulfilment diagram has no end on this one.
+        assert!(!main.contains("Cross-diagram"));
+        assert!(!main.contains("Fulfilment worker"));
+
+        let fulfilment = render_svg(&model, Some("Fulfilment")).unwrap();
+        assert!(fulfilment.contains("<title>Storefront: Fulfilment</title>"));
+        assert!(fulfilment.contains(">Pick list<"));
+        assert!(!fulfilment.contains("Shopper"));
+        assert!(!fulfilment.contains("DATA CENTRE"));
+    }
+
+    #[test]
+    fn unplaced_elements_are_lined_up_below_the_placed_ones() {
+        let model = fixture();
+        let worker = Node {
+            element: &model.elements[5],
+            rect: element_rect(&model.elements[5], &Position { x: 80.0, y: 80.0 }),
+        };
+        let scene = Scene::new(&model, 1);
+        let warehouse = scene
+            .nodes
+            .iter()
+            .find(|n| n.element.id == "warehouse")
+            .unwrap();
+        assert!(warehouse.rect.y >= worker.rect.bottom() + UNPLACED_GAP);
+    }
+
+    #[test]
+    fn flows_leave_by_their_handles_or_the_facing_sides() {
+        let model = fixture();
+        let scene = Scene::new(&model, 0);
+        let node = |id: &str| scene.nodes.iter().find(|n| n.element.id == id).unwrap();
+        let path = |id: &str| {
+            let (_, geometry) = scene.flows.iter().find(|(f, _)| f.id == id).unwrap();
+            geometry.path.clone()
+        };
+
+        // f1 runs left to right: shopper's right side to the web server's left.
+        let (sx, sy) = node("shopper").handle(Side::Right, 0.5);
+        assert!(path("f1").starts_with(&format!("M{} {}", num(sx), num(sy))));
+        // f3 names its handles: top of the load balancer, bottom of the web server.
+        let (sx, sy) = node("lb").handle(Side::Top, 0.5);
+        let (tx, ty) = node("web").handle(Side::Bottom, 0.5);
+        assert!(path("f3").starts_with(&format!("M{} {}", num(sx), num(sy))));
+        assert!(path("f3").ends_with(&format!("{} {}", num(tx), num(ty))));
+        // f2's label was dragged, so the curve bends through it.
+        assert!(path("f2").contains('Q'));
+    }
+
+    #[test]
+    fn unknown_diagrams_are_refused_with_the_available_ones() {
+        let error = render_svg(&fixture(), Some("nope")).unwrap_err();
+        assert_eq!(
+            error,
+            "Unknown diagram 'nope'. Diagrams: main (Level 0), fulfilment (Fulfilment)"
+        );
+    }
+
+    #[test]
+    fn png_is_rasterized_at_the_requested_scale() {
+        let model = fixture();
+        let svg = render_svg(&model, None).unwrap();
+        let width: u32 = svg
+            .split("width=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .and_then(|w| w.parse().ok())
+            .unwrap();
+
+        let png = render_png(&model, None, 2.0).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let png_width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        assert_eq!(png_width, width * 2);
+        assert!(render_png(&model, None, 0.0).is_err());
+    }
+}
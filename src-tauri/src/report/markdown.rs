@@ -0,0 +1,345 @@
+//! Markdown threat report.
+//!
+//! The report opens with the model's metadata and a summary: counts, threats
+//! by severity and by STRIDE category, and a mitigation status chart drawn
+//! as a table of bars. An inventory of elements, trust boundaries and data
+//! flows follows, then the threat register, grouped by the element or flow
+//! each threat is raised against and within that by STRIDE category.
+//!
+//! The output is meant to be committed beside the `.thf` it comes from, so it
+//! depends on nothing but the model: there is no generation timestamp,
+//! sections follow file order, and threats within a category are ordered by
+//! severity and then file order. Text annotations are left out.
+
+use std::fmt::Write;
+
+use super::{architecture_elements, element_names, severity_rank, share, status_label, STATUSES};
+use crate::models::{Severity, StrideCategory, Threat, ThreatModel};
+
+/// Characters in a full bar of the mitigation status chart.
+const BAR_WIDTH: usize = 20;
+
+/// Shown in a table cell that has no value.
+const EMPTY_CELL: &str = "—";
+
+/// Render `model` as a Markdown report.
+pub fn render_markdown(model: &ThreatModel) -> String {
+    let mut text = String::new();
+    write_heading(&mut text, model);
+    write_summary(&mut text, model);
+    write_architecture(&mut text, model);
+    write_register(&mut text, model);
+    let end = text.trim_end().len();
+    text.truncate(end);
+    text.push('\n');
+    text
+}
+
+fn write_heading(text: &mut String, model: &ThreatModel) {
+    let metadata = &model.metadata;
+    let _ = writeln!(text, "# {}\n", metadata.title.trim());
+    if !metadata.description.trim().is_empty() {
+        let _ = writeln!(text, "{}\n", metadata.description.trim());
+    }
+    if !metadata.author.trim().is_empty() {
+        let _ = writeln!(text, "- **Author:** {}", metadata.author.trim());
+    }
+    let _ = writeln!(text, "- **Created:** {}", metadata.created);
+    let _ = writeln!(text, "- **Last modified:** {}", metadata.modified);
+    let _ = writeln!(text, "- **Schema version:** {}\n", model.version);
+}
+
+fn write_summary(text: &mut String, model: &ThreatModel) {
+    let threats = &model.threats;
+    let open = threats.iter().filter(|t| t.is_unmitigated()).count();
+    let _ = writeln!(text, "## Summary\n");
+    let _ = writeln!(
+        text,
+        "{}, {} and {}. {}, {} of them unmitigated.\n",
+        plural(architecture_elements(model).count(), "element", "elements"),
+        plural(model.data_flows.len(), "data flow", "data flows"),
+        plural(
+            model.trust_boundaries.len(),
+            "trust boundary",
+            "trust boundaries"
+        ),
+        plural(threats.len(), "threat", "threats"),
+        open,
+    );
+    if threats.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(text, "### Threats by severity\n");
+    let rows = Severity::ALL.iter().map(|severity| {
+        let matching = threats.iter().filter(|t| &t.severity == severity);
+        [
+            severity.label().to_string(),
+            matching.clone().count().to_string(),
+            matching.filter(|t| t.is_unmitigated()).count().to_string(),
+        ]
+    });
+    write_table(text, ["Severity", "Threats", "Unmitigated"], rows);
+
+    let _ = writeln!(text, "### Threats by STRIDE category\n");
+    let rows = StrideCategory::ALL.iter().map(|category| {
+        let matching = threats.iter().filter(|t| &t.category == category);
+        [
+            category.as_str().to_string(),
+            matching.clone().count().to_string(),
+            matching.filter(|t| t.is_unmitigated()).count().to_string(),
+        ]
+    });
+    write_table(text, ["Category", "Threats", "Unmitigated"], rows);
+
+    let _ = writeln!(text, "### Mitigation status\n");
+    let rows = STATUSES.iter().map(|status| {
+        let count = threats
+            .iter()
+            .filter(|t| t.mitigation.as_ref().map(|m| &m.status) == status.as_ref())
+            .count();
+        [
+            status_label(status.as_ref()).to_string(),
+            count.to_string(),
+            format!("{}%", share(count, threats.len(), 100)),
+            format!(
+                "`{}{}`",
+                "█".repeat(share(count, threats.len(), BAR_WIDTH)),
+                "░".repeat(BAR_WIDTH - share(count, threats.len(), BAR_WIDTH))
+            ),
+        ]
+    });
+    write_table(text, ["Status", "Threats", "Share", "Chart"], rows);
+}
+
+fn write_architecture(text: &mut String, model: &ThreatModel) {
+    let names = element_names(model);
+    let name = |id: &str| names.get(id).copied().unwrap_or(id).to_string();
+    let _ = writeln!(text, "## Architecture\n");
+
+    let _ = writeln!(text, "### Elements\n");
+    let rows = architecture_elements(model).map(|element| {
+        [
+            format!("`{}`", element.id),
+            cell(&element.name),
+            element.element_type.clone(),
+            cell(&element.trust_zone),
+            cell(&element.technologies.join(", ")),
+        ]
+    });
+    write_table(
+        text,
+        ["ID", "Name", "Type", "Trust zone", "Technologies"],
+        rows,
+    );
+
+    let _ = writeln!(text, "### Trust boundaries\n");
+    let rows = model.trust_boundaries.iter().map(|boundary| {
+        let contains: Vec<String> = boundary.contains.iter().map(|id| name(id)).collect();
+        [
+            format!("`{}`", boundary.id),
+            cell(&boundary.name),
+            cell(&contains.join(", ")),
+        ]
+    });
+    write_table(text, ["ID", "Name", "Contains"], rows);
+
+    let _ = writeln!(text, "### Data flows\n");
+    let rows = model.data_flows.iter().map(|flow| {
+        [
+            flow.flow_number
+                .map_or_else(|| EMPTY_CELL.to_string(), |n| n.to_string()),
+            format!("`{}`", flow.id),
+            cell(&flow.name),
+            cell(&name(&flow.from)),
+            cell(&name(&flow.to)),
+            cell(&flow.protocol),
+            cell(&flow.data.join(", ")),
+            if flow.authenticated { "yes" } else { "no" }.to_string(),
+        ]
+    });
+    write_table(
+        text,
+        [
+            "#",
+            "ID",
+            "Name",
+            "From",
+            "To",
+            "Protocol",
+            "Data",
+            "Authenticated",
+        ],
+        rows,
+    );
+}
+
+fn write_register(text: &mut String, model: &ThreatModel) {
+    let _ = writeln!(text, "## Threat register\n");
+    if model.threats.is_empty() {
+        let _ = writeln!(text, "No threats recorded.");
+        return;
+    }
+    let names = element_names(model);
+    // A threat raised against both an element and one of its flows is listed
+    // once, under the element.
+    let on_element = |t: &Threat| {
+        t.element
+            .as_ref()
+            .is_some_and(|id| model.elements.iter().any(|e| &e.id == id))
+    };
+    let on_flow = |t: &Threat| {
+        t.flow
+            .as_ref()
+            .is_some_and(|id| model.data_flows.iter().any(|f| &f.id == id))
+    };
+
+    for element in &model.elements {
+        let threats: Vec<&Threat> = model
+            .threats
+            .iter()
+            .filter(|t| t.element.as_deref() == Some(element.id.as_str()))
+            .collect();
+        let heading = format!("{} (`{}`)", element.name.trim(), element.id);
+        write_target(text, &heading, &threats);
+    }
+    for flow in &model.data_flows {
+        let threats: Vec<&Threat> = model
+            .threats
+            .iter()
+            .filter(|t| t.flow.as_deref() == Some(flow.id.as_str()) && !on_element(t))
+            .collect();
+        let from = names.get(flow.from.as_str()).copied().unwrap_or(&flow.from);
+        let to = names.get(flow.to.as_str()).copied().unwrap_or(&flow.to);
+        let heading = format!(
+            "{} (`{}`, {} → {})",
+            flow.name.trim(),
+            flow.id,
+            from.trim(),
+            to.trim()
+        );
+        write_target(text, &heading, &threats);
+    }
+    let unassigned: Vec<&Threat> = model
+        .threats
+        .iter()
+        .filter(|t| !on_element(t) && !on_flow(t))
+        .collect();
+    write_target(text, "Not assigned to an element or flow", &unassigned);
+}
+
+/// The threats raised against one element or flow, by STRIDE category.
+fn write_target(text: &mut String, heading: &str, threats: &[&Threat]) {
+    if threats.is_empty() {
+        return;
+    }
+    let _ = writeln!(text, "### {heading}\n");
+    for category in &StrideCategory::ALL {
+        let mut in_category: Vec<&Threat> = threats
+            .iter()
+            .copied()
+            .filter(|t| &t.category == category)
+            .collect();
+        if in_category.is_empty() {
+            continue;
+        }
+        in_category.sort_by_key(|t| severity_rank(&t.severity));
+        let _ = writeln!(text, "#### {}\n", category.as_str());
+        let rows = in_category.iter().map(|threat| {
+            let mitigation = threat.mitigation.as_ref();
+            let title = if threat.description.trim().is_empty() {
+                format!("**{}**", cell(&threat.title))
+            } else {
+                format!(
+                    "**{}**<br>{}",
+                    cell(&threat.title),
+                    cell(&threat.description)
+                )
+            };
+            [
+                format!("`{}`", threat.id),
+                title,
+                threat.severity.label().to_string(),
+                status_label(mitigation.map(|m| &m.status)).to_string(),
+                cell(mitigation.map_or("", |m| m.description.as_str())),
+            ]
+        });
+        write_table(
+            text,
+            ["ID", "Threat", "Severity", "Status", "Mitigation"],
+            rows,
+        );
+    }
+}
+
+/// A table followed by a blank line; a table without rows is left out.
+fn write_table<const N: usize>(
+    text: &mut String,
+    headers: [&str; N],
+    rows: impl Iterator<Item = [String; N]>,
+) {
+    let mut rows = rows.peekable();
+    if rows.peek().is_none() {
+        let _ = writeln!(text, "None.\n");
+        return;
+    }
+    let _ = writeln!(text, "| {} |", headers.join(" | "));
+    let _ = writeln!(text, "|{}", " --- |".repeat(N));
+    for row in rows {
+        let _ = writeln!(text, "| {} |", row.join(" | "));
+    }
+    let _ = writeln!(text);
+}
+
+/// Free text made safe for a table cell: pipes escaped, line breaks kept as
+/// `<br>`, and an empty value shown as a dash.
+fn cell(value: &str) -> String {
+    let value = value.trim();
+    if value.is_empty() {
+        return EMPTY_CELL.to_string();
+    }
+    value
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("<br>")
+        .replace('|', "\\|")
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{count} {}", if count == 1 { one } else { many })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A model with threats on elements, on flows and on neither, every
+    /// mitigation status, and text that needs escaping in a table.
+    const FIXTURE_THF: &str = include_str!("fixtures/report-sample.thf");
+
+    /// The report pinned for [`FIXTURE_THF`], regenerated only when the
+    /// report layout is deliberately changed.
+    const FIXTURE_EXPECTED_MD: &str = include_str!("fixtures/report-sample.expected.md");
+
+    #[test]
+    fn fixture_report_is_byte_identical_to_the_pinned_result() {
+        let model: ThreatModel = serde_yaml::from_str(FIXTURE_THF).expect("fixture should parse");
+        assert_eq!(
+            render_markdown(&model),
+            FIXTURE_EXPECTED_MD,
+            "Markdown report drifted from src/report/fixtures/report-sample.expected.md"
+        );
+    }
+
+    #[test]
+    fn a_model_without_threats_says_so_and_draws_no_charts() {
+        let report = render_markdown(&ThreatModel::new("Empty", ""));
+        assert!(report.contains(
+            "0 elements, 0 data flows and 0 trust boundaries. 0 threats, 0 of them unmitigated."
+        ));
+        assert!(!report.contains("### Mitigation status"));
+        assert!(!report.contains("**Author:**"));
+        assert!(report.ends_with("## Threat register\n\nNo threats recorded.\n"));
+    }
+}
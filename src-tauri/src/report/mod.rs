@@ -0,0 +1,129 @@
+//! Threat reports rendered from a model, for reviewers and stakeholders who
+//! will not open the canvas.
+//!
+//! The renderers take a `ThreatModel` and return text, with no I/O of their
+//! own, so the export command, the MCP server and the `threatforge-report`
+//! command line all produce the same report from the same model. Besides the
+//! fixed Markdown report, a model can be rendered through a report template:
+//! one of the built-in HTML, Markdown and plain-text templates, or one kept in
+//! the project beside the model (see [`template`]). The open threats can also
+//! be written as a SARIF log for code-scanning dashboards, and each diagram
+//! drawn as SVG or PNG for reports and wikis that cannot embed the canvas.
+
+mod context;
+mod diagram;
+mod markdown;
+pub mod template;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::exporters::sarif;
+use crate::models::{Element, MitigationStatus, Severity, ThreatModel};
+
+pub use context::ReportContext;
+pub use diagram::{render_png, render_svg};
+pub use markdown::render_markdown;
+pub use template::{render_template, render_template_file};
+
+/// Every mitigation status a threat can be in, no mitigation recorded first,
+/// in the order reports list them.
+const STATUSES: [Option<MitigationStatus>; 6] = [
+    None,
+    Some(MitigationStatus::NotStarted),
+    Some(MitigationStatus::InProgress),
+    Some(MitigationStatus::Mitigated),
+    Some(MitigationStatus::Accepted),
+    Some(MitigationStatus::Transferred),
+];
+
+/// Read a `.thf` file to report on, validated the way the app opens it.
+pub fn read_model(path: &Path) -> Result<ThreatModel, String> {
+    crate::file_io::read_threat_model(path).map_err(|e| e.to_string())
+}
+
+/// The open threats of `model` as a SARIF log pointing at lines of the `.thf`
+/// file at `model_path`. With `rules`, a Threat Modeling Tool template, the
+/// threats its rules generated are reported under their ids.
+///
+/// Fails when the file's threats differ from the model's, e.g. because the
+/// model has unsaved changes: the lines would point at the wrong threats.
+pub fn render_sarif(
+    model: &ThreatModel,
+    model_path: &Path,
+    rules: Option<&Path>,
+) -> Result<String, String> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+    };
+    let source = read(model_path)?;
+    let saved = serde_yaml::from_str::<ThreatModel>(&source).ok();
+    if !saved.is_some_and(|saved| saved.threats == model.threats) {
+        return Err(format!(
+            "{} does not match the model being exported; save it first so the SARIF results point at the right lines",
+            model_path.display()
+        ));
+    }
+    let pack = match rules {
+        Some(path) => Some(
+            crate::importers::tb7::parse_tb7(&read(path)?)
+                .map_err(|e| e.to_string())?
+                .pack,
+        ),
+        None => None,
+    };
+    let uri = sarif::artifact_uri(model_path);
+    Ok(sarif::write_sarif(model, &uri, &source, pack.as_ref()))
+}
+
+/// Elements that are part of the architecture, leaving out text annotations.
+fn architecture_elements(model: &ThreatModel) -> impl Iterator<Item = &Element> {
+    model.elements.iter().filter(|e| e.element_type != "text")
+}
+
+fn element_names(model: &ThreatModel) -> HashMap<&str, &str> {
+    model
+        .elements
+        .iter()
+        .map(|e| (e.id.as_str(), e.name.as_str()))
+        .collect()
+}
+
+/// `count` out of `total` in units of `scale`, rounded to the nearest unit.
+fn share(count: usize, total: usize, scale: usize) -> usize {
+    if total == 0 {
+        return 0;
+    }
+    (count * scale + total / 2) / total
+}
+
+fn severity_rank(severity: &Severity) -> usize {
+    Severity::ALL
+        .iter()
+        .position(|s| s == severity)
+        .unwrap_or(Severity::ALL.len())
+}
+
+/// A threat's mitigation status as the report shows it, including the lack of one.
+fn status_label(status: Option<&MitigationStatus>) -> &'static str {
+    status.map_or("None recorded", MitigationStatus::label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sarif_is_refused_when_the_saved_threats_differ_from_the_model() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("model.thf");
+        std::fs::write(&path, include_str!("fixtures/report-sample.thf")).expect("write");
+        let mut model = read_model(&path).expect("fixture should load");
+
+        assert!(render_sarif(&model, &path, None).is_ok());
+
+        model.threats.remove(0);
+        let err = render_sarif(&model, &path, None).expect_err("unsaved changes");
+        assert!(err.contains("save it first"), "{err}");
+    }
+}
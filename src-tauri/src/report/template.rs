@@ -0,0 +1,228 @@
+//! Reports rendered through MiniJinja templates.
+//!
+//! Three templates are built in: `html`, `markdown` and `text`. A project can
+//! keep its own beside its models, in `.threatforge/reports/`, and refer to
+//! them by file name; templates there can `{% include %}` and
+//! `{% extends %}` one another. A template file elsewhere can be rendered by
+//! path from the command line. Every template renders a [`ReportContext`].
+//!
+//! Blocks are trimmed (`trim_blocks` and `lstrip_blocks`), so a tag on a line
+//! of its own leaves no blank line behind. Templates whose name ends in
+//! `.html` (optionally followed by `.j2`) have their output HTML-escaped.
+
+use std::path::{Path, PathBuf};
+
+use minijinja::{path_loader, Environment};
+
+use super::ReportContext;
+use crate::models::ThreatModel;
+
+/// Where a project keeps its report templates, relative to the directory of
+/// the model being reported on.
+pub const PROJECT_TEMPLATE_DIR: &str = ".threatforge/reports";
+
+/// A template shipped with ThreatForge.
+pub struct BuiltinTemplate {
+    /// Name a caller selects the template by.
+    pub name: &'static str,
+    /// Name the template is registered under; its extension decides escaping.
+    pub file_name: &'static str,
+    pub source: &'static str,
+}
+
+pub const BUILTIN_TEMPLATES: [BuiltinTemplate; 3] = [
+    BuiltinTemplate {
+        name: "html",
+        file_name: "report.html.j2",
+        source: include_str!("templates/report.html.j2"),
+    },
+    BuiltinTemplate {
+        name: "markdown",
+        file_name: "report.md.j2",
+        source: include_str!("templates/report.md.j2"),
+    },
+    BuiltinTemplate {
+        name: "text",
+        file_name: "report.txt.j2",
+        source: include_str!("templates/report.txt.j2"),
+    },
+];
+
+/// The project template directory for the model at `model_path`.
+pub fn project_template_dir(model_path: &Path) -> PathBuf {
+    model_path
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(PROJECT_TEMPLATE_DIR)
+}
+
+/// Names of the templates available to the model at `model_path`: the
+/// built-in ones, then the project's files in name order.
+pub fn list_templates(model_path: Option<&Path>) -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|t| t.name.to_string())
+        .collect();
+    let Some(dir) = model_path.map(project_template_dir) else {
+        return names;
+    };
+    let mut project: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    project.sort();
+    names.extend(project);
+    names
+}
+
+/// Render `model` through the template called `name`: a built-in template,
+/// or a file in the project template directory of the model at `model_path`.
+pub fn render_template(
+    model: &ThreatModel,
+    model_path: Option<&Path>,
+    name: &str,
+) -> Result<String, String> {
+    let mut env = environment();
+    if let Some(builtin) = BUILTIN_TEMPLATES.iter().find(|t| t.name == name) {
+        env.add_template(builtin.file_name, builtin.source)
+            .map_err(|e| template_error(name, &e))?;
+        return render(&env, builtin.file_name, model);
+    }
+
+    if let Some(path) = model_path {
+        env.set_loader(path_loader(project_template_dir(path)));
+    }
+    match env.get_template(name) {
+        Ok(_) => render(&env, name, model),
+        Err(e) if e.kind() == minijinja::ErrorKind::TemplateNotFound => Err(format!(
+            "Unknown report template '{name}'. Available templates: {}",
+            list_templates(model_path).join(", ")
+        )),
+        Err(e) => Err(template_error(name, &e)),
+    }
+}
+
+/// Render `model` through the template file at `path`. Templates it includes
+/// or extends are looked up in the same directory.
+pub fn render_template_file(model: &ThreatModel, path: &Path) -> Result<String, String> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Not a template file: {}", path.display()))?;
+    if !path.is_file() {
+        return Err(format!("Template file not found: {}", path.display()));
+    }
+    let mut env = environment();
+    env.set_loader(path_loader(path.parent().unwrap_or(Path::new(""))));
+    render(&env, name, model)
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+    env
+}
+
+fn render(env: &Environment, name: &str, model: &ThreatModel) -> Result<String, String> {
+    env.get_template(name)
+        .and_then(|template| template.render(ReportContext::new(model)))
+        .map_err(|e| template_error(name, &e))
+}
+
+fn template_error(name: &str, error: &minijinja::Error) -> String {
+    format!("Report template '{name}' failed: {error}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_THF: &str = include_str!("fixtures/report-sample.thf");
+
+    /// Output of each built-in template for [`FIXTURE_THF`], regenerated only
+    /// when a built-in template is deliberately changed.
+    const EXPECTED: [(&str, &str); 3] = [
+        ("html", include_str!("fixtures/report-sample.expected.html")),
+        (
+            "markdown",
+            include_str!("fixtures/report-sample.expected.template.md"),
+        ),
+        ("text", include_str!("fixtures/report-sample.expected.txt")),
+    ];
+
+    fn fixture() -> ThreatModel {
+        serde_yaml::from_str(FIXTURE_THF).expect("fixture should parse")
+    }
+
+    #[test]
+    fn builtin_templates_are_byte_identical_to_the_pinned_results() {
+        let model = fixture();
+        for (name, expected) in EXPECTED {
+            assert_eq!(
+                render_template(&model, None, name).unwrap(),
+                expected,
+                "built-in '{name}' report template drifted from its pinned result"
+            );
+        }
+    }
+
+    #[test]
+    fn html_template_escapes_model_text() {
+        let mut model = fixture();
+        model.threats[0].title = "<script>alert(1)</script>".to_string();
+        let html = render_template(&model, None, "html").unwrap();
+        assert!(html.contains("&lt;script&gt;alert(1)"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn project_templates_are_loaded_beside_the_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = dir.path().join(PROJECT_TEMPLATE_DIR);
+        std::fs::create_dir_all(&templates).unwrap();
+        std::fs::write(
+            templates.join("audit.md"),
+            "{% include \"header.md\" %}{{ stats.unmitigated }} open\n",
+        )
+        .unwrap();
+        std::fs::write(
+            templates.join("header.md"),
+            "# {{ model.metadata.title }}\n",
+        )
+        .unwrap();
+        let model_path = dir.path().join("payments.thf");
+
+        let report = render_template(&fixture(), Some(&model_path), "audit.md").unwrap();
+        assert_eq!(report, "# Payments Service\n4 open\n");
+        assert_eq!(
+            list_templates(Some(&model_path)),
+            ["html", "markdown", "text", "audit.md", "header.md"]
+        );
+    }
+
+    #[test]
+    fn unknown_templates_and_paths_outside_the_project_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("secret.md"), "{{ model }}").unwrap();
+        let model_path = dir.path().join(".threatforge/payments.thf");
+
+        let error = render_template(&fixture(), Some(&model_path), "../../secret.md").unwrap_err();
+        assert!(error.starts_with("Unknown report template '../../secret.md'"));
+        assert!(error.ends_with("Available templates: html, markdown, text"));
+    }
+
+    #[test]
+    fn template_errors_name_the_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.md");
+        std::fs::write(&path, "{% for threat in threats %}").unwrap();
+        let error = render_template_file(&fixture(), &path).unwrap_err();
+        assert!(error.starts_with("Report template 'broken.md' failed:"));
+    }
+}
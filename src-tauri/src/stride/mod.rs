@@ -1,12 +1,17 @@
 use std::collections::HashSet;
 
+mod rule_pack;
+
+pub use rule_pack::*;
+
 use crate::models::{
-    generate_threat_id, Severity, StrideCategory, Threat, ThreatModel, TrustBoundary,
+    generate_threat_id, ApiOperation, BoundaryShape, DataFlow, Element, Position, Severity,
+    StrideCategory, Threat, ThreatModel, TrustBoundary,
 };
 
 /// STRIDE category for threat matching — determined by component type.
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum ComponentStrideCategory {
+pub(crate) enum ComponentStrideCategory {
     Service,
     Store,
     Actor,
@@ -14,7 +19,7 @@ enum ComponentStrideCategory {
 }
 
 /// Map a component type string to its STRIDE category.
-fn stride_category_for_type(element_type: &str) -> ComponentStrideCategory {
+pub(crate) fn stride_category_for_type(element_type: &str) -> ComponentStrideCategory {
     match element_type {
         // Databases / stores
         "sql_database" | "nosql_database" | "cache" | "search_index" | "object_storage"
@@ -173,6 +178,96 @@ fn build_rules() -> Vec<ThreatRule> {
     ]
 }
 
+/// Field names that mark a response as carrying secrets or personal data. Matched against the
+/// last segment of a dotted field name, lower-cased with `_` and `-` removed, so `access_token`
+/// and `billingAddress.cardNumber` both match.
+const SENSITIVE_FIELD_MARKERS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "apikey",
+    "privatekey",
+    "ssn",
+    "socialsecurity",
+    "taxid",
+    "creditcard",
+    "cardnumber",
+    "cvv",
+    "iban",
+    "accountnumber",
+    "dateofbirth",
+    "email",
+    "phone",
+];
+
+/// The fields of `fields` whose name marks them as sensitive.
+fn sensitive_fields(fields: &[String]) -> Vec<&str> {
+    fields
+        .iter()
+        .filter(|field| {
+            let name = field.rsplit('.').next().unwrap_or(field);
+            let name: String = name
+                .chars()
+                .filter(|c| !matches!(c, '_' | '-'))
+                .flat_map(char::to_lowercase)
+                .collect();
+            SENSITIVE_FIELD_MARKERS
+                .iter()
+                .any(|marker| name.contains(marker))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Threats one API operation raises: anonymous access, no rate limit, and sensitive fields in
+/// its responses. Element rules are keyed by category, but an element carries one of these per
+/// operation, so they are told apart by title.
+fn operation_threats(element: &Element, operation: &ApiOperation) -> Vec<Threat> {
+    let name = &element.name;
+    let label = format!("{} {}", operation.method, operation.path);
+    let mut threats = Vec::new();
+    let mut push = |category, severity, title, description| {
+        threats.push(Threat {
+            id: generate_threat_id(),
+            title,
+            category,
+            element: Some(element.id.clone()),
+            flow: None,
+            severity,
+            description,
+            mitigation: None,
+        });
+    };
+
+    if !operation.authenticated {
+        push(
+            StrideCategory::Spoofing,
+            Severity::High,
+            format!("Unauthenticated operation {label} on {name}"),
+            format!("{label} accepts requests from anonymous callers, so anyone who can reach {name} can invoke it. Require authentication unless the operation is deliberately public."),
+        );
+    }
+    if !operation.rate_limited {
+        push(
+            StrideCategory::DenialOfService,
+            Severity::Medium,
+            format!("No rate limit on {label} of {name}"),
+            format!("{label} declares no rate limit, so a single caller can exhaust {name} or brute-force it. Enforce a per-client rate limit and document it in the API description."),
+        );
+    }
+    let sensitive = sensitive_fields(&operation.response_fields);
+    if !sensitive.is_empty() {
+        push(
+            StrideCategory::InformationDisclosure,
+            Severity::High,
+            format!("Sensitive fields returned by {label} on {name}"),
+            format!("{label} returns {}. Return only the fields callers need, and never return secrets or credentials.", sensitive.join(", ")),
+        );
+    }
+    threats
+}
+
 /// Look up element name by ID, falling back to the raw ID.
 fn element_name<'a>(model: &'a ThreatModel, element_id: &'a str) -> &'a str {
     model
@@ -205,11 +300,47 @@ fn element_boundary<'a>(
         .find(|b| b.contains.iter().any(|c| c == element_id))
 }
 
-/// Returns true if source and target are in different trust boundaries (or one is unbound).
-fn crosses_boundary(boundaries: &[TrustBoundary], source: &str, target: &str) -> bool {
-    let src_boundary = element_boundary(boundaries, source).map(|b| &b.id);
-    let tgt_boundary = element_boundary(boundaries, target).map(|b| &b.id);
-    src_boundary != tgt_boundary
+/// Returns true if source and target are in different trust boundaries (or one is unbound),
+/// or a line boundary runs between them.
+fn crosses_boundary(model: &ThreatModel, flow: &DataFlow) -> bool {
+    let boundaries = &model.trust_boundaries;
+    let src_boundary = element_boundary(boundaries, &flow.from).map(|b| &b.id);
+    let tgt_boundary = element_boundary(boundaries, &flow.to).map(|b| &b.id);
+    if src_boundary != tgt_boundary {
+        return true;
+    }
+    let endpoint = |id: &str| model.elements.iter().find(|e| e.id == id);
+    let (Some(source), Some(target)) = (endpoint(&flow.from), endpoint(&flow.to)) else {
+        return false;
+    };
+    boundaries
+        .iter()
+        .filter(|b| b.shape == Some(BoundaryShape::Line))
+        .any(|b| flow_crosses(b, source, target))
+}
+
+/// Whether a flow crosses a boundary: a rectangle holds exactly one of its
+/// endpoints, or a line intersects the straight path between them. A line
+/// is never crossed by a flow whose endpoints have no position.
+fn flow_crosses(boundary: &TrustBoundary, source: &Element, target: &Element) -> bool {
+    if boundary.shape == Some(BoundaryShape::Line) {
+        let (Some(from), Some(to)) = (&source.position, &target.position) else {
+            return false;
+        };
+        return boundary
+            .points
+            .windows(2)
+            .any(|segment| segments_intersect(from, to, &segment[0], &segment[1]));
+    }
+    let holds = |id: &str| boundary.contains.iter().any(|c| c == id);
+    holds(&source.id) != holds(&target.id)
+}
+
+fn segments_intersect(a: &Position, b: &Position, c: &Position, d: &Position) -> bool {
+    let orientation = |p: &Position, q: &Position, r: &Position| {
+        ((q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x)).signum()
+    };
+    orientation(a, b, c) != orientation(a, b, d) && orientation(c, d, a) != orientation(c, d, b)
 }
 
 /// Analyze a threat model using STRIDE-per-element methodology.
@@ -268,7 +399,7 @@ pub fn analyze(model: &ThreatModel) -> Vec<Threat> {
             }
 
             // For data flows, boost severity if the flow crosses a trust boundary
-            let severity = if crosses_boundary(&model.trust_boundaries, &flow.from, &flow.to) {
+            let severity = if crosses_boundary(model, flow) {
                 // Bump severity one level for cross-boundary flows
                 match &rule.severity {
                     Severity::Medium => Severity::High,
@@ -301,6 +432,27 @@ pub fn analyze(model: &ThreatModel) -> Vec<Threat> {
         }
     }
 
+    // API operation rules
+    let existing_titles: HashSet<(&str, &str)> = model
+        .threats
+        .iter()
+        .filter_map(|t| Some((t.element.as_deref()?, t.title.as_str())))
+        .collect();
+    for element in &model.elements {
+        if stride_category_for_type(&element.element_type) == ComponentStrideCategory::Annotation {
+            continue;
+        }
+        for operation in &element.operations {
+            suggestions.extend(
+                operation_threats(element, operation)
+                    .into_iter()
+                    .filter(|t| {
+                        !existing_titles.contains(&(element.id.as_str(), t.title.as_str()))
+                    }),
+            );
+        }
+    }
+
     suggestions
 }
 
@@ -335,6 +487,7 @@ mod tests {
                     trust_zone: "internal".to_string(),
                     layer: None,
                     group: None,
+                    diagram: None,
                     subtype: None,
                     icon: None,
                     description: String::new(),
@@ -342,6 +495,7 @@ mod tests {
                     tags: vec![],
                     stores: None,
                     encryption: None,
+                    operations: Vec::new(),
                     position: None,
                     fill_color: None,
                     stroke_color: None,
@@ -357,6 +511,7 @@ mod tests {
                     trust_zone: "internal".to_string(),
                     layer: None,
                     group: None,
+                    diagram: None,
                     subtype: None,
                     icon: None,
                     description: String::new(),
@@ -364,6 +519,7 @@ mod tests {
                     tags: vec![],
                     stores: None,
                     encryption: None,
+                    operations: Vec::new(),
                     position: None,
                     fill_color: None,
                     stroke_color: None,
@@ -379,6 +535,7 @@ mod tests {
                     trust_zone: "external".to_string(),
                     layer: None,
                     group: None,
+                    diagram: None,
                     subtype: None,
                     icon: None,
                     description: String::new(),
@@ -386,6 +543,7 @@ mod tests {
                     tags: vec![],
                     stores: None,
                     encryption: None,
+                    operations: Vec::new(),
                     position: None,
                     fill_color: None,
                     stroke_color: None,
@@ -415,6 +573,9 @@ mod tests {
                 id: "boundary-1".to_string(),
                 name: "Internal Network".to_string(),
                 contains: vec!["web-app".to_string(), "db".to_string()],
+                diagram: None,
+                shape: None,
+                points: Vec::new(),
                 position: None,
                 size: None,
                 fill_color: None,
@@ -593,6 +754,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_flow_across_a_line_boundary_boosts_severity() {
+        let mut model = sample_model();
+        // flow-1 runs from web-app to db, which share a boundary; a line drawn
+        // between them still separates them.
+        model.elements[0].position = Some(Position { x: 0.0, y: 0.0 });
+        let db = model.elements.iter_mut().find(|e| e.id == "db").unwrap();
+        db.position = Some(Position { x: 200.0, y: 0.0 });
+        model.trust_boundaries.push(TrustBoundary {
+            id: "line-1".to_string(),
+            name: "Line".to_string(),
+            contains: vec![],
+            diagram: None,
+            shape: Some(BoundaryShape::Line),
+            points: vec![
+                Position { x: 100.0, y: -50.0 },
+                Position { x: 100.0, y: 50.0 },
+            ],
+            position: None,
+            size: None,
+            fill_color: None,
+            stroke_color: None,
+            fill_opacity: None,
+            stroke_opacity: None,
+        });
+
+        let threats = analyze(&model);
+        let dos_threat = threats.iter().find(|t| {
+            t.flow.as_deref() == Some("flow-1") && t.category == StrideCategory::DenialOfService
+        });
+        assert_eq!(
+            dos_threat.expect("DoS threat for flow-1").severity,
+            Severity::High,
+            "A flow crossing a line boundary should be boosted from Medium to High"
+        );
+    }
+
+    #[test]
+    fn api_operations_raise_their_own_threats() {
+        let mut model = sample_model();
+        let operation = |path: &str, authenticated, rate_limited, response: &[&str]| ApiOperation {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            summary: None,
+            authenticated,
+            rate_limited,
+            request_fields: vec![],
+            response_fields: response.iter().map(|f| f.to_string()).collect(),
+        };
+        model.elements[0].operations = vec![
+            operation("/health", false, true, &["status"]),
+            operation("/me", true, false, &["profile.emailAddress", "apiKey"]),
+        ];
+        model.threats.push(Threat {
+            id: "existing-1".to_string(),
+            title: "No rate limit on GET /me of Web Application".to_string(),
+            category: StrideCategory::DenialOfService,
+            element: Some("web-app".to_string()),
+            flow: None,
+            severity: Severity::Medium,
+            description: String::new(),
+            mitigation: None,
+        });
+
+        let titles: Vec<String> = analyze(&model).into_iter().map(|t| t.title).collect();
+        let operation_titles: Vec<&str> = titles
+            .iter()
+            .map(String::as_str)
+            .filter(|t| t.contains(" GET /"))
+            .collect();
+        assert_eq!(
+            operation_titles,
+            vec![
+                "Unauthenticated operation GET /health on Web Application",
+                "Sensitive fields returned by GET /me on Web Application",
+            ]
+        );
+        let disclosure = analyze(&model)
+            .into_iter()
+            .find(|t| t.title.starts_with("Sensitive fields"))
+            .expect("sensitive response is flagged");
+        assert!(disclosure
+            .description
+            .starts_with("GET /me returns profile.emailAddress, apiKey."));
+    }
+
     #[test]
     fn test_stride_category_mapping() {
         // Services
@@ -695,6 +942,7 @@ mod tests {
             trust_zone: String::new(),
             layer: None,
             group: None,
+            diagram: None,
             subtype: None,
             icon: None,
             description: String::new(),
@@ -702,6 +950,7 @@ mod tests {
             tags: vec![],
             stores: None,
             encryption: None,
+            operations: Vec::new(),
             position: None,
             fill_color: None,
             stroke_color: None,
@@ -0,0 +1,484 @@
+//! STRIDE rules imported from a Microsoft Threat Modeling Tool template.
+//!
+//! TMT generates threats per interaction — a data flow with its source and
+//! target — by testing each threat type's include and exclude filters against
+//! the stencils involved. A [`RulePack`] carries those threat types and the
+//! stencil hierarchy they refer to, and [`analyze_with_rule_pack`] replays them
+//! over a model in place of the built-in STRIDE-per-element rules.
+
+use std::collections::HashSet;
+
+use super::{flow_crosses, stride_category_for_type, ComponentStrideCategory};
+use crate::models::{
+    BoundaryShape, DataFlow, Element, Severity, StrideCategory, Threat, ThreatModel, TrustBoundary,
+};
+
+// TMT's generic stencil ids, the roots every template stencil specialises.
+pub const GENERIC_PROCESS: &str = "GE.P";
+pub const GENERIC_DATA_STORE: &str = "GE.DS";
+pub const GENERIC_EXTERNAL_ENTITY: &str = "GE.EI";
+pub const GENERIC_DATA_FLOW: &str = "GE.DF";
+pub const GENERIC_BORDER_BOUNDARY: &str = "GE.TB.B";
+pub const GENERIC_LINE_BOUNDARY: &str = "GE.TB.L";
+
+/// A set of threat-generation rules and the stencils they are written against.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RulePack {
+    pub name: String,
+    pub stencils: Vec<PackStencil>,
+    pub rules: Vec<PackRule>,
+}
+
+/// A stencil type from the template's element hierarchy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackStencil {
+    /// TMT type id, e.g. `GE.P` or `SE.P.TMCore.WebApp`.
+    pub id: String,
+    pub name: String,
+    /// The stencil this one specialises; `None` for the generic roots.
+    pub parent: Option<String>,
+    /// The ThreatForge element type this stencil becomes. `None` for data
+    /// flows, trust boundaries and annotations.
+    pub element_type: Option<String>,
+}
+
+/// One TMT threat type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackRule {
+    pub id: String,
+    /// Title template; `{source.Name}`, `{target.Name}` and `{flow.Name}` are
+    /// filled in per interaction.
+    pub title: String,
+    pub description: String,
+    pub category: StrideCategory,
+    pub severity: Severity,
+    /// Interactions the rule applies to. A rule without one never fires,
+    /// as in TMT.
+    pub include: Option<Filter>,
+    /// Interactions excluded even when included.
+    pub exclude: Option<Filter>,
+}
+
+/// A generation filter expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    All(Vec<Filter>),
+    Any(Vec<Filter>),
+    Not(Box<Filter>),
+    /// `source is 'SE.P.TMCore.WebApp'`: the subject is that stencil or one of
+    /// its descendants.
+    Is {
+        subject: Subject,
+        stencil: String,
+    },
+    /// `flow crosses 'GE.TB.B'`: the flow crosses a boundary of that stencil.
+    Crosses {
+        stencil: String,
+    },
+    /// `flow.Protocol is 'HTTPS'`: a property comparison. Only properties the
+    /// model has (`Name`, and `Protocol` on flows) can ever match.
+    Property {
+        subject: Subject,
+        name: String,
+        value: String,
+    },
+}
+
+/// What a filter clause tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subject {
+    Source,
+    Target,
+    Flow,
+}
+
+impl RulePack {
+    fn stencil(&self, id: &str) -> Option<&PackStencil> {
+        self.stencils.iter().find(|s| s.id == id)
+    }
+
+    /// Whether stencil `id` is `ancestor` or specialises it. The walk is
+    /// bounded by the stencil count, so a cyclic hierarchy cannot hang it.
+    fn descends_from(&self, id: &str, ancestor: &str) -> bool {
+        let mut current = Some(id);
+        for _ in 0..=self.stencils.len() {
+            match current {
+                Some(c) if c == ancestor => return true,
+                Some(c) => current = self.stencil(c).and_then(|s| s.parent.as_deref()),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// The generic root a stencil specialises, e.g. `GE.P`.
+    fn root_of<'a>(&'a self, id: &'a str) -> &'a str {
+        let mut current = id;
+        for _ in 0..=self.stencils.len() {
+            match self.stencil(current).and_then(|s| s.parent.as_deref()) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// The stencils an element counts as: the one its `subtype` names, those
+    /// mapped to its element type, and the generic root of its STRIDE role.
+    ///
+    /// A stencil that only maps to its root's element type (a custom process
+    /// with no closer ThreatForge type) would otherwise claim every plain
+    /// process, so it matches through `subtype` alone.
+    fn element_stencils<'a>(&'a self, element: &'a Element) -> Vec<&'a str> {
+        let mut ids: Vec<&str> = self
+            .stencils
+            .iter()
+            .filter(|s| {
+                if s.element_type.as_deref() != Some(element.element_type.as_str()) {
+                    return false;
+                }
+                let root_type = self
+                    .stencil(self.root_of(&s.id))
+                    .and_then(|r| r.element_type.as_deref());
+                s.parent.is_none() || root_type != s.element_type.as_deref()
+            })
+            .map(|s| s.id.as_str())
+            .collect();
+        if let Some(subtype) = element.subtype.as_deref() {
+            ids.push(subtype);
+        }
+        ids.push(match stride_category_for_type(&element.element_type) {
+            ComponentStrideCategory::Store => GENERIC_DATA_STORE,
+            ComponentStrideCategory::Actor => GENERIC_EXTERNAL_ENTITY,
+            _ => GENERIC_PROCESS,
+        });
+        ids
+    }
+
+    fn element_is(&self, element: &Element, stencil: &str) -> bool {
+        self.element_stencils(element)
+            .iter()
+            .any(|id| self.descends_from(id, stencil))
+    }
+
+    /// A boundary is its generic shape's stencil, and any stencil whose name it
+    /// carries — TMT names a new boundary after its stencil.
+    fn boundary_is(&self, boundary: &TrustBoundary, stencil: &str) -> bool {
+        let generic = if boundary.shape == Some(BoundaryShape::Line) {
+            GENERIC_LINE_BOUNDARY
+        } else {
+            GENERIC_BORDER_BOUNDARY
+        };
+        self.descends_from(generic, stencil)
+            || self
+                .stencils
+                .iter()
+                .filter(|s| s.name == boundary.name)
+                .any(|s| self.descends_from(&s.id, stencil))
+    }
+}
+
+/// One data flow with its resolved endpoints.
+struct Interaction<'a> {
+    flow: &'a DataFlow,
+    source: &'a Element,
+    target: &'a Element,
+}
+
+impl Filter {
+    fn matches(&self, pack: &RulePack, model: &ThreatModel, interaction: &Interaction) -> bool {
+        match self {
+            Filter::All(clauses) => clauses.iter().all(|c| c.matches(pack, model, interaction)),
+            Filter::Any(clauses) => clauses.iter().any(|c| c.matches(pack, model, interaction)),
+            Filter::Not(clause) => !clause.matches(pack, model, interaction),
+            Filter::Is { subject, stencil } => match subject {
+                Subject::Source => pack.element_is(interaction.source, stencil),
+                Subject::Target => pack.element_is(interaction.target, stencil),
+                // Flows carry no stencil of their own; every flow is generic.
+                Subject::Flow => pack.descends_from(GENERIC_DATA_FLOW, stencil),
+            },
+            Filter::Crosses { stencil } => model
+                .trust_boundaries
+                .iter()
+                .filter(|b| pack.boundary_is(b, stencil))
+                .any(|b| flow_crosses(b, interaction.source, interaction.target)),
+            Filter::Property {
+                subject,
+                name,
+                value,
+            } => {
+                let actual = match (subject, name.to_ascii_lowercase().as_str()) {
+                    (Subject::Source, "name") => &interaction.source.name,
+                    (Subject::Target, "name") => &interaction.target.name,
+                    (Subject::Flow, "name") => &interaction.flow.name,
+                    (Subject::Flow, "protocol") => &interaction.flow.protocol,
+                    _ => return false,
+                };
+                actual.eq_ignore_ascii_case(value)
+            }
+        }
+    }
+}
+
+/// Fill a TMT title or description template for one interaction.
+impl<'a> Interaction<'a> {
+    /// The interaction along `flow`, when both its endpoints exist.
+    fn of(model: &'a ThreatModel, flow: &'a DataFlow) -> Option<Self> {
+        let endpoint = |id: &str| model.elements.iter().find(|e| e.id == id);
+        Some(Self {
+            flow,
+            source: endpoint(&flow.from)?,
+            target: endpoint(&flow.to)?,
+        })
+    }
+}
+
+impl PackRule {
+    /// Whether the rule applies to `interaction`: included and not excluded.
+    /// A rule without an include filter never fires, as in TMT.
+    fn fires(&self, pack: &RulePack, model: &ThreatModel, interaction: &Interaction) -> bool {
+        self.include
+            .as_ref()
+            .is_some_and(|f| f.matches(pack, model, interaction))
+            && !self
+                .exclude
+                .as_ref()
+                .is_some_and(|f| f.matches(pack, model, interaction))
+    }
+}
+
+fn expand(template: &str, interaction: &Interaction) -> String {
+    template
+        .replace("{source.Name}", &interaction.source.name)
+        .replace("{target.Name}", &interaction.target.name)
+        .replace("{flow.Name}", &interaction.flow.name)
+}
+
+/// Generate the threats a rule pack yields for a model.
+///
+/// Each rule is tested against every data flow whose endpoints exist. As with
+/// a TMT import, a threat records the flow and its source element. Threats the
+/// model already has — the same flow and title — are not suggested again.
+pub fn analyze_with_rule_pack(model: &ThreatModel, pack: &RulePack) -> Vec<Threat> {
+    let mut seen: HashSet<(String, String)> = model
+        .threats
+        .iter()
+        .filter_map(|t| Some((t.flow.clone()?, t.title.clone())))
+        .collect();
+    let mut suggestions = Vec::new();
+
+    for flow in &model.data_flows {
+        let Some(interaction) = Interaction::of(model, flow) else {
+            continue;
+        };
+        for rule in pack
+            .rules
+            .iter()
+            .filter(|r| r.fires(pack, model, &interaction))
+        {
+            let title = expand(&rule.title, &interaction);
+            if !seen.insert((flow.id.clone(), title.clone())) {
+                continue;
+            }
+            suggestions.push(Threat {
+                id: crate::models::generate_threat_id(),
+                title,
+                category: rule.category.clone(),
+                element: Some(interaction.source.id.clone()),
+                flow: Some(flow.id.clone()),
+                severity: rule.severity.clone(),
+                description: expand(&rule.description, &interaction),
+                mitigation: None,
+            });
+        }
+    }
+
+    suggestions
+}
+
+/// The rule of `pack` that generates `threat`: one that fires on the threat's
+/// flow with the threat's title. That is the pair [`analyze_with_rule_pack`]
+/// tells threats apart by, so a threat it generated finds its rule even after
+/// its severity, description or mitigation were edited.
+pub fn rule_for_threat<'p>(
+    pack: &'p RulePack,
+    model: &ThreatModel,
+    threat: &Threat,
+) -> Option<&'p PackRule> {
+    let flow = model
+        .data_flows
+        .iter()
+        .find(|f| threat.flow.as_deref() == Some(f.id.as_str()))?;
+    let interaction = Interaction::of(model, flow)?;
+    pack.rules.iter().find(|rule| {
+        rule.fires(pack, model, &interaction) && expand(&rule.title, &interaction) == threat.title
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = r#"
+version: "1.0"
+metadata:
+  title: Rule pack
+  author: Test
+  created: 2026-01-01
+  modified: 2026-01-01
+elements:
+  - { id: user, type: web_browser, name: User, position: { x: 0, y: 100 } }
+  - { id: app, type: process, name: App, position: { x: 200, y: 100 } }
+  - { id: db, type: sql_database, name: Orders, position: { x: 400, y: 100 } }
+data_flows:
+  - { id: f1, name: Login, from: user, to: app, protocol: HTTPS }
+  - { id: f2, name: Query, from: app, to: db, protocol: TCP }
+trust_boundaries:
+  - id: edge
+    name: Edge
+    shape: line
+    points: [{ x: 100, y: 0 }, { x: 100, y: 200 }]
+"#;
+
+    fn model() -> ThreatModel {
+        serde_yaml::from_str(MODEL).expect("test model should parse")
+    }
+
+    fn stencil(id: &str, parent: Option<&str>, element_type: Option<&str>) -> PackStencil {
+        PackStencil {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent: parent.map(str::to_string),
+            element_type: element_type.map(str::to_string),
+        }
+    }
+
+    fn pack(include: Filter) -> RulePack {
+        RulePack {
+            name: "Test".to_string(),
+            stencils: vec![
+                stencil(GENERIC_PROCESS, None, Some("process")),
+                stencil(GENERIC_DATA_STORE, None, Some("data_store")),
+                stencil(GENERIC_EXTERNAL_ENTITY, None, Some("external_entity")),
+                stencil(GENERIC_LINE_BOUNDARY, None, None),
+                stencil("SE.P.Custom", Some(GENERIC_PROCESS), Some("process")),
+                stencil("SE.DS.Sql", Some(GENERIC_DATA_STORE), Some("sql_database")),
+            ],
+            rules: vec![PackRule {
+                id: "R1".to_string(),
+                title: "{flow.Name} from {source.Name} to {target.Name}".to_string(),
+                description: String::new(),
+                category: StrideCategory::Tampering,
+                severity: Severity::High,
+                include: Some(include),
+                exclude: None,
+            }],
+        }
+    }
+
+    fn is(subject: Subject, stencil: &str) -> Filter {
+        Filter::Is {
+            subject,
+            stencil: stencil.to_string(),
+        }
+    }
+
+    fn flows(model: &ThreatModel, pack: &RulePack) -> Vec<String> {
+        analyze_with_rule_pack(model, pack)
+            .into_iter()
+            .map(|t| t.flow.unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn elements_match_their_type_role_and_subtype_stencils() {
+        let model = model();
+        assert_eq!(
+            flows(&model, &pack(is(Subject::Target, "SE.DS.Sql"))),
+            ["f2"]
+        );
+        assert_eq!(
+            flows(&model, &pack(is(Subject::Target, GENERIC_DATA_STORE))),
+            ["f2"]
+        );
+        assert_eq!(
+            flows(&model, &pack(is(Subject::Source, GENERIC_EXTERNAL_ENTITY))),
+            ["f1"]
+        );
+
+        // A stencil that only narrows to `process` claims no plain process...
+        let custom = pack(is(Subject::Target, "SE.P.Custom"));
+        assert!(flows(&model, &custom).is_empty());
+        // ...but does claim one whose subtype names it.
+        let mut model = model;
+        model.elements[1].subtype = Some("SE.P.Custom".to_string());
+        assert_eq!(flows(&model, &custom), ["f1"]);
+    }
+
+    #[test]
+    fn line_boundaries_are_crossed_by_the_flows_that_pass_them() {
+        let model = model();
+        let crosses = Filter::Crosses {
+            stencil: GENERIC_LINE_BOUNDARY.to_string(),
+        };
+        assert_eq!(flows(&model, &pack(crosses.clone())), ["f1"]);
+
+        let unencrypted = Filter::All(vec![
+            crosses,
+            Filter::Not(Box::new(Filter::Property {
+                subject: Subject::Flow,
+                name: "Protocol".to_string(),
+                value: "https".to_string(),
+            })),
+        ]);
+        assert!(flows(&model, &pack(unencrypted)).is_empty());
+    }
+
+    #[test]
+    fn unknown_properties_and_missing_includes_never_match() {
+        let model = model();
+        let unknown = Filter::Property {
+            subject: Subject::Target,
+            name: "b3ec5f0e".to_string(),
+            value: "Yes".to_string(),
+        };
+        assert!(flows(&model, &pack(unknown)).is_empty());
+
+        let mut no_include = pack(is(Subject::Source, GENERIC_PROCESS));
+        no_include.rules[0].include = None;
+        assert!(flows(&model, &no_include).is_empty());
+    }
+
+    #[test]
+    fn threats_already_in_the_model_are_not_suggested_again() {
+        let mut model = model();
+        let pack = pack(is(Subject::Source, GENERIC_PROCESS));
+        let suggested = analyze_with_rule_pack(&model, &pack);
+        assert_eq!(suggested.len(), 1);
+        assert_eq!(suggested[0].title, "Query from App to Orders");
+        assert_eq!(suggested[0].element.as_deref(), Some("app"));
+        assert_eq!(suggested[0].severity, Severity::High);
+
+        model.threats.extend(suggested);
+        assert!(analyze_with_rule_pack(&model, &pack).is_empty());
+    }
+
+    #[test]
+    fn generated_threats_are_traced_back_to_their_rule() {
+        let mut model = model();
+        let pack = pack(is(Subject::Source, GENERIC_PROCESS));
+        model.threats = analyze_with_rule_pack(&model, &pack);
+        model.threats[0].severity = Severity::Low;
+        let rule = rule_for_threat(&pack, &model, &model.threats[0]);
+        assert_eq!(rule.map(|r| r.id.as_str()), Some("R1"));
+
+        // A retitled threat, or one on a flow the rule skips, is not the rule's.
+        let mut retitled = model.threats[0].clone();
+        retitled.title = "Orders queried".to_string();
+        assert!(rule_for_threat(&pack, &model, &retitled).is_none());
+        let mut moved = model.threats[0].clone();
+        moved.flow = Some("f1".to_string());
+        moved.title = "Login from User to App".to_string();
+        assert!(rule_for_threat(&pack, &model, &moved).is_none());
+    }
+}
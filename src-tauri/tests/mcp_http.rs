@@ -0,0 +1,508 @@
+//! End-to-end check of the `threatforge-mcp --http` transport: the HTTP
+//! equivalents of `mcp_stdio.rs`, plus the token and origin checks and a
+//! model shared between two sessions.
+//!
+//! Like the stdio tests, this drives the real binary over a real socket.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde_json::{json, Value};
+
+/// Generous enough for a cold process start on a loaded CI runner, short enough
+/// that a hung server fails the test instead of the job.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+const TOKEN: &str = "test-token";
+
+const MODEL_THF: &str = "\
+version: '1.0'
+metadata:
+  title: MCP Fixture
+  author: Tester
+  created: 2026-01-01
+  modified: 2026-01-01
+  description: ''
+elements: []
+data_flows: []
+trust_boundaries: []
+threats: []
+diagrams:
+- id: main-dfd
+  name: Level 0 DFD
+";
+
+/// A running `threatforge-mcp --http 0` process.
+struct McpHttpServer {
+    child: Child,
+    url: String,
+}
+
+impl McpHttpServer {
+    fn spawn(model_path: &std::path::Path, extra_args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_threatforge-mcp"))
+            .args(["--http", "0"])
+            .args(extra_args)
+            .arg(model_path)
+            .env("THREATFORGE_MCP_TOKEN", TOKEN)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("threatforge-mcp should start");
+
+        // The first stderr line announces the bound URL.
+        let stderr = child.stderr.take().expect("stderr is piped");
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                let _ = tx.send(line);
+            }
+        });
+        let line = rx
+            .recv_timeout(RESPONSE_TIMEOUT)
+            .expect("server should announce its address");
+        let url = line
+            .strip_prefix("threatforge-mcp listening on ")
+            .unwrap_or_else(|| panic!("unexpected first stderr line: {line}"))
+            .to_string();
+
+        Self { child, url }
+    }
+
+    fn client(&self) -> McpClient {
+        McpClient {
+            http: reqwest::Client::new(),
+            url: self.url.clone(),
+            session: None,
+            token: TOKEN.to_string(),
+        }
+    }
+}
+
+impl Drop for McpHttpServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// One MCP session over streamable HTTP.
+struct McpClient {
+    http: reqwest::Client,
+    url: String,
+    session: Option<String>,
+    /// The server token until `initialize` hands out the session's own.
+    token: String,
+}
+
+impl McpClient {
+    fn post(&self, message: &Value) -> reqwest::RequestBuilder {
+        let mut request = self
+            .http
+            .post(&self.url)
+            .bearer_auth(&self.token)
+            .header("Accept", "application/json, text/event-stream")
+            .json(message);
+        if let Some(session) = &self.session {
+            request = request.header("Mcp-Session-Id", session);
+        }
+        request
+    }
+
+    async fn notify(&self, method: &str) {
+        let response = self
+            .post(&json!({ "jsonrpc": "2.0", "method": method }))
+            .send()
+            .await
+            .expect("notification should be sent");
+        assert!(
+            response.status().is_success(),
+            "`{method}` was refused: {}",
+            response.status()
+        );
+    }
+
+    /// Send a request and return its `result` payload.
+    async fn request(&mut self, id: u32, method: &str, params: Value) -> Value {
+        let response = self
+            .post(&json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .unwrap_or_else(|e| panic!("`{method}` request failed: {e}"));
+        assert!(
+            response.status().is_success(),
+            "`{method}` was refused: {}",
+            response.status()
+        );
+        if let Some(session) = response.headers().get("mcp-session-id") {
+            self.session = Some(session.to_str().expect("ascii session id").to_string());
+            let token = response
+                .headers()
+                .get("mcp-session-token")
+                .expect("a new session comes with its own token");
+            self.token = token.to_str().expect("ascii session token").to_string();
+        }
+
+        let is_sse = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        let message = if is_sse {
+            next_sse_message(response, |m| m["id"] == json!(id)).await
+        } else {
+            response.json().await.expect("JSON response body")
+        };
+        assert!(
+            message.get("error").is_none(),
+            "`{method}` returned an error: {message}"
+        );
+        message["result"].clone()
+    }
+
+    /// Open the standalone SSE stream that carries server-initiated messages.
+    async fn open_event_stream(&self) -> reqwest::Response {
+        let response = self
+            .http
+            .get(&self.url)
+            .bearer_auth(&self.token)
+            .header("Accept", "text/event-stream")
+            .header("Mcp-Session-Id", self.session.as_deref().expect("session"))
+            .send()
+            .await
+            .expect("event stream should open");
+        assert!(response.status().is_success(), "{}", response.status());
+        response
+    }
+}
+
+/// Read SSE `data:` payloads until one matches, failing on timeout.
+async fn next_sse_message(response: reqwest::Response, matches: impl Fn(&Value) -> bool) -> Value {
+    let read = async {
+        let mut body = response.bytes_stream();
+        let mut buffer = String::new();
+        while let Some(chunk) = body.next().await {
+            buffer.push_str(&String::from_utf8_lossy(&chunk.expect("SSE chunk")));
+            while let Some(end) = buffer.find('\n') {
+                let line = buffer[..end].trim_end_matches('\r').to_string();
+                buffer.drain(..=end);
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                if let Ok(message) = serde_json::from_str::<Value>(data.trim()) {
+                    if matches(&message) {
+                        return message;
+                    }
+                }
+            }
+        }
+        panic!("event stream ended before the expected message");
+    };
+    tokio::time::timeout(RESPONSE_TIMEOUT, read)
+        .await
+        .expect("timed out waiting for an SSE message")
+}
+
+fn initialize_params(protocol_version: &str) -> Value {
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": {},
+        "clientInfo": { "name": "threatforge-tests", "version": "0" },
+    })
+}
+
+fn write_model(dir: &tempfile::TempDir) -> std::path::PathBuf {
+    let model_path = dir.path().join("model.thf");
+    std::fs::write(&model_path, MODEL_THF).expect("fixture .thf should be written");
+    model_path
+}
+
+#[tokio::test]
+async fn mcp_server_handshakes_lists_its_tools_and_executes_one_over_http() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = write_model(&dir);
+    let server = McpHttpServer::spawn(&model_path, &[]);
+    let mut client = server.client();
+
+    let init = client
+        .request(1, "initialize", initialize_params("2024-11-05"))
+        .await;
+    assert_eq!(init["protocolVersion"], json!("2024-11-05"));
+    assert_eq!(init["serverInfo"]["name"], json!("threatforge-mcp"));
+    assert!(
+        client.session.is_some(),
+        "initialize must open an HTTP session"
+    );
+    client.notify("notifications/initialized").await;
+
+    let listed = client.request(2, "tools/list", json!({})).await;
+    let mut names: Vec<&str> = listed["tools"]
+        .as_array()
+        .expect("tools/list returns an array")
+        .iter()
+        .map(|t| t["name"].as_str().expect("each tool has a name"))
+        .collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        [
+            "add_data_flow",
+            "add_element",
+            "add_threat",
+            "add_trust_boundary",
+            "apply_changes",
+            "delete_data_flow",
+            "delete_element",
+            "delete_threat",
+            "delete_trust_boundary",
+            "get_element",
+            "get_model",
+            "get_report",
+            "list_elements",
+            "list_models",
+            "list_threats",
+            "open_model",
+            "query",
+            "redo",
+            "undo",
+            "update_element",
+        ]
+    );
+
+    let called = client
+        .request(
+            3,
+            "tools/call",
+            json!({
+                "name": "add_element",
+                "arguments": { "element_type": "process", "name": "Web API" },
+            }),
+        )
+        .await;
+    assert_eq!(
+        called["content"][0]["text"],
+        json!("Added element: web-api")
+    );
+
+    let saved = std::fs::read_to_string(&model_path).expect("model file should still be readable");
+    assert!(
+        saved.contains("id: web-api"),
+        "the tool call must be persisted to the .thf file, got:\n{saved}"
+    );
+}
+
+#[tokio::test]
+async fn mcp_server_negotiates_a_known_protocol_version_and_falls_back_otherwise_over_http() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = write_model(&dir);
+    let server = McpHttpServer::spawn(&model_path, &[]);
+
+    let init = server
+        .client()
+        .request(1, "initialize", initialize_params("2025-11-25"))
+        .await;
+    assert_eq!(init["protocolVersion"], json!("2025-11-25"));
+
+    let init = server
+        .client()
+        .request(1, "initialize", initialize_params("1999-01-01"))
+        .await;
+    assert_eq!(init["protocolVersion"], json!("2024-11-05"));
+}
+
+#[tokio::test]
+async fn mcp_server_publishes_resources_and_notifies_subscribers_of_changes_over_http() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = write_model(&dir);
+    let server = McpHttpServer::spawn(&model_path, &[]);
+    let mut client = server.client();
+
+    client
+        .request(1, "initialize", initialize_params("2024-11-05"))
+        .await;
+    client.notify("notifications/initialized").await;
+
+    let read = client
+        .request(2, "resources/read", json!({ "uri": "threatforge://model" }))
+        .await;
+    let text = read["contents"][0]["text"]
+        .as_str()
+        .expect("the model resource is text");
+    assert!(
+        text.contains("MCP Fixture"),
+        "unexpected model body: {text}"
+    );
+
+    let events = client.open_event_stream().await;
+    client
+        .request(
+            3,
+            "resources/subscribe",
+            json!({ "uri": "threatforge://model" }),
+        )
+        .await;
+    client
+        .request(
+            4,
+            "tools/call",
+            json!({
+                "name": "add_element",
+                "arguments": { "element_type": "process", "name": "Web API" },
+            }),
+        )
+        .await;
+
+    let updated = next_sse_message(events, |m| {
+        m["method"] == json!("notifications/resources/updated")
+    })
+    .await;
+    assert_eq!(updated["params"]["uri"], json!("threatforge://model"));
+}
+
+#[tokio::test]
+async fn mcp_server_shares_one_model_between_http_sessions() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = write_model(&dir);
+    let server = McpHttpServer::spawn(&model_path, &[]);
+
+    let mut first = server.client();
+    let mut second = server.client();
+    for client in [&mut first, &mut second] {
+        client
+            .request(1, "initialize", initialize_params("2024-11-05"))
+            .await;
+        client.notify("notifications/initialized").await;
+    }
+    assert_ne!(first.session, second.session);
+
+    first
+        .request(
+            2,
+            "tools/call",
+            json!({
+                "name": "add_element",
+                "arguments": { "element_type": "process", "name": "Web API" },
+            }),
+        )
+        .await;
+    let listed = second
+        .request(
+            2,
+            "tools/call",
+            json!({ "name": "list_elements", "arguments": {} }),
+        )
+        .await;
+    let text = listed["content"][0]["text"].as_str().expect("text result");
+    assert!(
+        text.contains("web-api"),
+        "second session should see the first one's element: {text}"
+    );
+}
+
+#[tokio::test]
+async fn mcp_server_rejects_missing_tokens_and_foreign_origins() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = write_model(&dir);
+    let server = McpHttpServer::spawn(&model_path, &["--allow-origin", "http://localhost:5173"]);
+    let http = reqwest::Client::new();
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": initialize_params("2024-11-05"),
+    });
+    let post = || {
+        http.post(&server.url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(&initialize)
+    };
+
+    let no_token = post().send().await.expect("request should be sent");
+    assert_eq!(no_token.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let wrong_token = post()
+        .bearer_auth("not-the-token")
+        .send()
+        .await
+        .expect("request should be sent");
+    assert_eq!(wrong_token.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let foreign = post()
+        .bearer_auth(TOKEN)
+        .header("Origin", "https://evil.example")
+        .send()
+        .await
+        .expect("request should be sent");
+    assert_eq!(foreign.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let allowed = post()
+        .bearer_auth(TOKEN)
+        .header("Origin", "http://localhost:5173")
+        .send()
+        .await
+        .expect("request should be sent");
+    assert!(allowed.status().is_success(), "{}", allowed.status());
+}
+
+#[tokio::test]
+async fn mcp_server_refuses_one_sessions_token_for_another_session() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = write_model(&dir);
+    let server = McpHttpServer::spawn(&model_path, &[]);
+
+    let mut a = server.client();
+    let mut b = server.client();
+    for client in [&mut a, &mut b] {
+        client
+            .request(1, "initialize", initialize_params("2024-11-05"))
+            .await;
+        client.notify("notifications/initialized").await;
+    }
+    assert_ne!(a.token, b.token, "each session gets its own token");
+    assert_ne!(a.token, TOKEN);
+
+    let list = json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": { "name": "list_elements", "arguments": {} },
+    });
+    let send_as = |token: &str, session: &str| {
+        a.http
+            .post(&a.url)
+            .bearer_auth(token)
+            .header("Accept", "application/json, text/event-stream")
+            .header("Mcp-Session-Id", session)
+            .json(&list)
+            .send()
+    };
+    let b_session = b.session.as_deref().expect("session");
+
+    let crossed = send_as(&a.token, b_session)
+        .await
+        .expect("request should be sent");
+    assert_eq!(crossed.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let server_token = send_as(TOKEN, b_session)
+        .await
+        .expect("request should be sent");
+    assert_eq!(
+        server_token.status(),
+        reqwest::StatusCode::UNAUTHORIZED,
+        "the server token only opens sessions"
+    );
+
+    let own = send_as(&b.token, b_session)
+        .await
+        .expect("request should be sent");
+    assert!(own.status().is_success(), "{}", own.status());
+}
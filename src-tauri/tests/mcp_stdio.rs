@@ -41,7 +41,12 @@ struct McpServer {
 
 impl McpServer {
     fn spawn(model_path: &std::path::Path) -> Self {
+        Self::spawn_with_args(model_path, &[])
+    }
+
+    fn spawn_with_args(model_path: &std::path::Path, extra_args: &[&str]) -> Self {
         let mut child = Command::new(env!("CARGO_BIN_EXE_threatforge-mcp"))
+            .args(extra_args)
             .arg(model_path)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
@@ -75,7 +80,22 @@ impl McpServer {
         self.stdin.flush().expect("request should be flushed");
     }
 
-    /// Send a request and return its `result` payload.
+    /// Receive the next JSON-RPC message, whatever it is.
+    fn next_message(&mut self, waiting_for: &str) -> (Value, String) {
+        let line = match self.stdout.recv_timeout(RESPONSE_TIMEOUT) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => panic!("timed out waiting for {waiting_for}"),
+            Err(RecvTimeoutError::Disconnected) => {
+                panic!("server exited before sending {waiting_for}")
+            }
+        };
+        let message: Value = serde_json::from_str(&line)
+            .unwrap_or_else(|e| panic!("{waiting_for} is not JSON ({e}): {line}"));
+        (message, line)
+    }
+
+    /// Send a request and return its `result` payload, skipping any
+    /// notifications that arrive first.
     fn request(&mut self, id: u32, method: &str, params: Value) -> Value {
         self.send(&json!({
             "jsonrpc": "2.0",
@@ -84,16 +104,13 @@ impl McpServer {
             "params": params,
         }));
 
-        let line = match self.stdout.recv_timeout(RESPONSE_TIMEOUT) {
-            Ok(line) => line,
-            Err(RecvTimeoutError::Timeout) => panic!("timed out waiting for a `{method}` response"),
-            Err(RecvTimeoutError::Disconnected) => {
-                panic!("server exited before answering `{method}`")
+        let waiting_for = format!("a `{method}` response");
+        let (response, line) = loop {
+            let (message, line) = self.next_message(&waiting_for);
+            if message.get("id").is_some() {
+                break (message, line);
             }
         };
-
-        let response: Value = serde_json::from_str(&line)
-            .unwrap_or_else(|e| panic!("`{method}` response is not JSON ({e}): {line}"));
         assert_eq!(response["id"], json!(id), "response id mismatch: {line}");
         assert!(
             response.get("error").is_none(),
@@ -151,13 +168,21 @@ fn mcp_server_handshakes_lists_its_tools_and_executes_one_over_stdio() {
             "add_element",
             "add_threat",
             "add_trust_boundary",
+            "apply_changes",
             "delete_data_flow",
             "delete_element",
             "delete_threat",
             "delete_trust_boundary",
+            "get_element",
             "get_model",
+            "get_report",
             "list_elements",
+            "list_models",
             "list_threats",
+            "open_model",
+            "query",
+            "redo",
+            "undo",
             "update_element",
         ]
     );
@@ -181,6 +206,14 @@ fn mcp_server_handshakes_lists_its_tools_and_executes_one_over_stdio() {
         saved.contains("id: web-api"),
         "the tool call must be persisted to the .thf file, got:\n{saved}"
     );
+
+    let audit = std::fs::read_to_string(model_path.with_extension("audit.jsonl"))
+        .expect("the change should be audited beside the model");
+    let entry: Value = serde_json::from_str(audit.lines().next().expect("one entry"))
+        .expect("audit lines are JSON");
+    assert_eq!(entry["tool"], "add_element");
+    assert_eq!(entry["client"]["name"], "threatforge-tests");
+    assert_eq!(entry["changes"][0]["id"], "web-api");
 }
 
 /// The server declares `2024-11-05` in `get_info`, but rmcp echoes back any
@@ -200,3 +233,147 @@ fn mcp_server_negotiates_a_known_protocol_version_and_falls_back_otherwise() {
     let init = unknown.request(1, "initialize", initialize_params("1999-01-01"));
     assert_eq!(init["protocolVersion"], json!("2024-11-05"));
 }
+
+#[test]
+fn mcp_server_publishes_resources_and_notifies_subscribers_of_changes() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = dir.path().join("model.thf");
+    std::fs::write(&model_path, MODEL_THF).expect("fixture .thf should be written");
+
+    let mut server = McpServer::spawn(&model_path);
+    let init = server.request(1, "initialize", initialize_params("2024-11-05"));
+    assert_eq!(
+        init["capabilities"]["resources"]["subscribe"],
+        json!(true),
+        "server must advertise subscribable resources: {init}"
+    );
+    server.send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }));
+
+    let listed = server.request(2, "resources/list", json!({}));
+    let uris: Vec<&str> = listed["resources"]
+        .as_array()
+        .expect("resources/list returns an array")
+        .iter()
+        .map(|r| r["uri"].as_str().expect("each resource has a uri"))
+        .collect();
+    assert_eq!(
+        uris,
+        [
+            "threatforge://model",
+            "threatforge://summaries/threats-by-severity",
+            "threatforge://summaries/unmitigated-threats",
+        ]
+    );
+
+    let read = server.request(3, "resources/read", json!({ "uri": "threatforge://model" }));
+    let text = read["contents"][0]["text"]
+        .as_str()
+        .expect("the model resource is text");
+    assert!(
+        text.contains("MCP Fixture"),
+        "unexpected model body: {text}"
+    );
+
+    server.request(
+        4,
+        "resources/subscribe",
+        json!({ "uri": "threatforge://model" }),
+    );
+    server.request(
+        5,
+        "tools/call",
+        json!({
+            "name": "add_element",
+            "arguments": { "element_type": "process", "name": "Web API" },
+        }),
+    );
+
+    loop {
+        let (message, line) = server.next_message("a resources/updated notification");
+        if message["method"] == json!("notifications/resources/updated") {
+            assert_eq!(
+                message["params"]["uri"],
+                json!("threatforge://model"),
+                "{line}"
+            );
+            break;
+        }
+    }
+}
+
+#[test]
+fn mcp_server_in_read_only_mode_lists_only_read_tools_and_refuses_writes() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = dir.path().join("model.thf");
+    std::fs::write(&model_path, MODEL_THF).expect("fixture .thf should be written");
+
+    let mut server = McpServer::spawn_with_args(&model_path, &["--read-only"]);
+    server.request(1, "initialize", initialize_params("2024-11-05"));
+    server.send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }));
+
+    let listed = server.request(2, "tools/list", json!({}));
+    let mut names: Vec<&str> = listed["tools"]
+        .as_array()
+        .expect("tools/list returns an array")
+        .iter()
+        .map(|t| t["name"].as_str().expect("each tool has a name"))
+        .collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        [
+            "get_element",
+            "get_model",
+            "get_report",
+            "list_elements",
+            "list_models",
+            "list_threats",
+            "open_model",
+            "query",
+        ]
+    );
+
+    server.send(&json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "method": "tools/call",
+        "params": {
+            "name": "add_element",
+            "arguments": { "element_type": "process", "name": "Web API" },
+        },
+    }));
+    let (response, line) = server.next_message("the refused add_element response");
+    assert!(
+        response.get("error").is_some(),
+        "write must be refused: {line}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&model_path).expect("model file should be readable"),
+        MODEL_THF
+    );
+}
+
+#[test]
+fn mcp_server_refuses_an_audit_dir_in_read_only_mode() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let model_path = dir.path().join("model.thf");
+    std::fs::write(&model_path, MODEL_THF).expect("fixture .thf should be written");
+    let audit_dir = dir.path().join("audit");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_threatforge-mcp"))
+        .arg("--read-only")
+        .arg("--audit-dir")
+        .arg(&audit_dir)
+        .arg(&model_path)
+        .stdin(Stdio::null())
+        .output()
+        .expect("threatforge-mcp should run");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--read-only"), "{stderr}");
+    assert!(
+        !audit_dir.exists(),
+        "a read-only server must not create the audit directory"
+    );
+}
@@ -45,6 +45,7 @@ vi.mock("@xyflow/react", () => ({
 	BackgroundVariant: { Dots: "dots" },
 	Controls: () => null,
 	MiniMap: () => null,
+	Panel: ({ children }: { children?: React.ReactNode }) => <div>{children}</div>,
 	SelectionMode: { Partial: "partial" },
 	Handle: () => null,
 	Position: { Top: "top", Right: "right", Bottom: "bottom", Left: "left" },
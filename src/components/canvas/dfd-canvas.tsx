@@ -23,6 +23,7 @@ import { useModelStore } from "@/stores/model-store";
 import { useSettingsStore } from "@/stores/settings-store";
 import { useUiStore } from "@/stores/ui-store";
 import { buildEdgeMenuItems, buildNodeMenuItems, CanvasContextMenu } from "./canvas-context-menu";
+import { DiagramSelector } from "./diagram-selector";
 import { DataFlowEdge } from "./edges/data-flow-edge";
 import { DfdElementNode } from "./nodes/dfd-element-node";
 import { TextAnnotationNode } from "./nodes/text-annotation-node";
@@ -514,6 +515,7 @@ export function DfdCanvas() {
 					size={1}
 					color={canvasColors.dotColor}
 				/>
+				<DiagramSelector />
 				<Controls className="!bg-card !border-border !shadow-md [&>button]:!bg-card [&>button]:!border-border [&>button]:!text-foreground [&>button:hover]:!bg-accent" />
 				{minimapVisible && (
 					<MiniMap
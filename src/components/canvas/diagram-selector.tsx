@@ -0,0 +1,40 @@
+import { Panel, useReactFlow } from "@xyflow/react";
+import { useCanvasStore } from "@/stores/canvas-store";
+import { resolveDiagramId } from "@/stores/canvas-store-factory";
+import { useModelStore } from "@/stores/model-store";
+
+/** Picks which of the model's diagrams the canvas draws. Hidden while there is only one. */
+export function DiagramSelector() {
+	const model = useModelStore((s) => s.model);
+	const activeDiagramId = useCanvasStore((s) => s.activeDiagramId);
+	const setActiveDiagram = useCanvasStore((s) => s.setActiveDiagram);
+	const { fitView } = useReactFlow();
+
+	if (!model || model.diagrams.length < 2) return null;
+
+	const selected = resolveDiagramId(model, activeDiagramId) ?? "";
+
+	return (
+		<Panel position="top-left">
+			<label className="flex items-center gap-2 rounded-md border border-border bg-card px-2 py-1 shadow-sm">
+				<span className="text-[10px] font-medium text-muted-foreground">Diagram</span>
+				<select
+					data-testid="diagram-selector"
+					value={selected}
+					onChange={(e) => {
+						setActiveDiagram(e.target.value);
+						// Diagrams are laid out independently; frame the one just drawn.
+						requestAnimationFrame(() => void fitView());
+					}}
+					className="rounded border border-border bg-background px-2 py-1 text-xs focus:border-primary focus:outline-none"
+				>
+					{model.diagrams.map((d) => (
+						<option key={d.id} value={d.id}>
+							{d.name || d.id}
+						</option>
+					))}
+				</select>
+			</label>
+		</Panel>
+	);
+}
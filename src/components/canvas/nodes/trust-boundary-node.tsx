@@ -75,6 +75,64 @@ export function TrustBoundaryNode({ id, data, selected }: NodeProps) {
 		useUiStore.getState().setRightPanelTab("properties");
 	}, [id]);
 
+	// Name label — clickable, selects boundary
+	const label = (
+		<button
+			type="button"
+			className="relative w-full p-2 bg-transparent border-none text-left"
+			style={{ pointerEvents: "auto" }}
+			onClick={selectBoundary}
+		>
+			{isEditing ? (
+				<input
+					ref={inputRef}
+					defaultValue={nodeData.boundaryName ?? nodeData.label}
+					autoFocus
+					className="bg-transparent text-xs font-semibold uppercase tracking-wider text-muted-foreground outline-none"
+					onBlur={(e) => commitLabel(e.target.value)}
+					onKeyDown={(e) => {
+						// Stop propagation so Backspace doesn't trigger canvas node deletion
+						e.stopPropagation();
+						if (e.key === "Enter") commitLabel(e.currentTarget.value);
+						if (e.key === "Escape") setIsEditing(false);
+					}}
+				/>
+			) : (
+				<div
+					className="text-xs font-semibold uppercase tracking-wider text-muted-foreground cursor-text"
+					onDoubleClick={() => setIsEditing(true)}
+				>
+					{nodeData.boundaryName ?? nodeData.label}
+				</div>
+			)}
+		</button>
+	);
+
+	const linePoints = nodeData.boundaryLinePoints;
+	if (linePoints) {
+		// A line boundary separates regions instead of enclosing them: no fill, no resize, and the
+		// node moves its vertices as a whole.
+		return (
+			<div className="relative h-full w-full" style={{ pointerEvents: "none" }}>
+				<svg className="absolute inset-0 h-full w-full overflow-visible" aria-hidden="true">
+					<polyline
+						points={linePoints.map((p) => `${p.x},${p.y}`).join(" ")}
+						fill="none"
+						strokeWidth={2}
+						strokeDasharray="6 4"
+						className={cn(
+							!strokeColor && (selected ? "stroke-tf-ember/60" : "stroke-muted-foreground/50"),
+						)}
+						style={strokeColor ? { stroke: hexToRgba(strokeColor, strokeOpacity) } : undefined}
+					/>
+				</svg>
+				<div className="absolute w-max" style={{ left: linePoints[0].x, top: linePoints[0].y }}>
+					{label}
+				</div>
+			</div>
+		);
+	}
+
 	return (
 		<>
 			<NodeResizeControl
@@ -147,36 +205,7 @@ export function TrustBoundaryNode({ id, data, selected }: NodeProps) {
 					onClick={selectBoundary}
 					aria-label="Select boundary"
 				/>
-				{/* Name label — clickable, selects boundary */}
-				<button
-					type="button"
-					className="relative w-full p-2 bg-transparent border-none text-left"
-					style={{ pointerEvents: "auto" }}
-					onClick={selectBoundary}
-				>
-					{isEditing ? (
-						<input
-							ref={inputRef}
-							defaultValue={nodeData.boundaryName ?? nodeData.label}
-							autoFocus
-							className="bg-transparent text-xs font-semibold uppercase tracking-wider text-muted-foreground outline-none"
-							onBlur={(e) => commitLabel(e.target.value)}
-							onKeyDown={(e) => {
-								// Stop propagation so Backspace doesn't trigger canvas node deletion
-								e.stopPropagation();
-								if (e.key === "Enter") commitLabel(e.currentTarget.value);
-								if (e.key === "Escape") setIsEditing(false);
-							}}
-						/>
-					) : (
-						<div
-							className="text-xs font-semibold uppercase tracking-wider text-muted-foreground cursor-text"
-							onDoubleClick={() => setIsEditing(true)}
-						>
-							{nodeData.boundaryName ?? nodeData.label}
-						</div>
-					)}
-				</button>
+				{label}
 			</div>
 		</>
 	);
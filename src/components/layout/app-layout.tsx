@@ -2,6 +2,7 @@ import { ReactFlowProvider } from "@xyflow/react";
 import { useCallback, useEffect } from "react";
 import { useAutosave } from "@/hooks/use-autosave";
 import { useCloseGuard } from "@/hooks/use-close-guard";
+import { useExternalChanges } from "@/hooks/use-external-changes";
 import { useKeyboardShortcuts } from "@/hooks/use-keyboard-shortcuts";
 import { useNativeMenu } from "@/hooks/use-native-menu";
 import { useOnboardingTriggers } from "@/hooks/use-onboarding-triggers";
@@ -44,6 +45,9 @@ export function AppLayout() {
 	useKeyboardShortcuts();
 	useNativeMenu();
 	useAutosave();
+	// Desktop: files changed on disk by another writer (such as the MCP server) are reloaded or
+	// resolved with the user rather than overwritten.
+	useExternalChanges();
 	// Browser workspace persistence: restore reads storage after first paint and marks
 	// availability; the autosave hook only writes once it reports available.
 	useWorkspaceRestore();
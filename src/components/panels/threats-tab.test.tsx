@@ -1,6 +1,6 @@
 import { fireEvent, render, screen } from "@testing-library/react";
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
-import { copyTextToClipboard } from "@/lib/platform";
+import { copyTextToClipboard, isTauri } from "@/lib/platform";
 import { createDocumentStores, setActiveStores } from "@/stores/document-stores";
 import { useModelStore } from "@/stores/model-store";
 import type { Threat, ThreatModel } from "@/types/threat-model";
@@ -11,10 +11,21 @@ vi.mock("@/lib/platform", async (importOriginal) => {
 	return {
 		...actual,
 		copyTextToClipboard: vi.fn(),
+		isTauri: vi.fn(() => false),
 	};
 });
 
+const stride = vi.hoisted(() => ({
+	analyze: vi.fn(),
+	analyzeWithTemplate: vi.fn(),
+}));
+
+vi.mock("@/lib/adapters/get-stride-adapter", () => ({
+	getStrideAdapter: () => Promise.resolve(stride),
+}));
+
 const mockedCopyTextToClipboard = vi.mocked(copyTextToClipboard);
+const mockedIsTauri = vi.mocked(isTauri);
 
 function makeModel(threats: Threat[]): ThreatModel {
 	return {
@@ -155,3 +166,38 @@ describe("ThreatsTab context menu", () => {
 		alertSpy.mockRestore();
 	});
 });
+
+describe("ThreatsTab template analysis", () => {
+	it("is only offered in the desktop app", () => {
+		useModelStore.setState({ model: makeModel([]) });
+		mockedIsTauri.mockReturnValue(false);
+
+		render(<ThreatsTab />);
+
+		expect(screen.queryByTestId("btn-stride-analyze-template")).not.toBeInTheDocument();
+	});
+
+	it("adds the threats a picked .tb7 template generates", async () => {
+		useModelStore.setState({ model: makeModel([]) });
+		mockedIsTauri.mockReturnValue(true);
+		const generated: Threat = {
+			id: "threat-tb7",
+			title: "Spoofing the API Gateway",
+			category: "Spoofing",
+			element: "api-gateway",
+			severity: "high",
+			description: "From the team's knowledge base.",
+		};
+		stride.analyzeWithTemplate.mockResolvedValue([generated]);
+
+		render(<ThreatsTab />);
+		fireEvent.click(screen.getByTestId("btn-stride-analyze-template"));
+
+		await vi.waitFor(() => {
+			expect(useModelStore.getState().model?.threats.map((t) => t.title)).toEqual([
+				"Spoofing the API Gateway",
+			]);
+		});
+		expect(stride.analyze).not.toHaveBeenCalled();
+	});
+});
@@ -10,7 +10,7 @@ import {
 } from "lucide-react";
 import { useState } from "react";
 import { CanvasContextMenu, type ContextMenuItem } from "@/components/canvas/canvas-context-menu";
-import { copyTextToClipboard } from "@/lib/platform";
+import { copyTextToClipboard, isTauri } from "@/lib/platform";
 import { getComponent } from "@/lib/registry/registry";
 import { serializeThreatYaml } from "@/lib/thf-yaml";
 import { cn } from "@/lib/utils";
@@ -50,6 +50,7 @@ export function ThreatsTab() {
 	const setSelectedThreat = useModelStore((s) => s.setSelectedThreat);
 	const isAnalyzing = useModelStore((s) => s.isAnalyzing);
 	const analyzeThreats = useModelStore((s) => s.analyzeThreats);
+	const analyzeThreatsWithTemplate = useModelStore((s) => s.analyzeThreatsWithTemplate);
 	const [filterByElement, setFilterByElement] = useState(false);
 
 	if (!model) {
@@ -93,6 +94,22 @@ export function ThreatsTab() {
 					</>
 				)}
 			</button>
+			{isTauri() && (
+				<button
+					type="button"
+					data-testid="btn-stride-analyze-template"
+					disabled={!hasElements || isAnalyzing}
+					onClick={() =>
+						void analyzeThreatsWithTemplate().catch((err) => {
+							const msg = err instanceof Error ? err.message : String(err);
+							window.alert(`Template analysis failed: ${msg}`);
+						})
+					}
+					className="text-xs text-muted-foreground transition-colors hover:text-foreground disabled:cursor-not-allowed disabled:opacity-50"
+				>
+					Run with a Threat Modeling Tool template (.tb7)…
+				</button>
+			)}
 
 			{/* Filter toggle when element is selected */}
 			{selectedElement && model.threats.length > 0 && (
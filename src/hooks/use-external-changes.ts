@@ -0,0 +1,40 @@
+import { useEffect } from "react";
+import { getFileAdapter } from "@/lib/adapters/get-file-adapter";
+import { useModelStore } from "@/stores/model-store";
+import { useFileOperations } from "./use-file-operations";
+
+/**
+ * Watches the active document's file for changes made by another program — typically an assistant
+ * saving through the MCP server — so they are reloaded or resolved instead of being silently
+ * overwritten by the next save. Switching documents moves the watch; a change made while a document
+ * was in the background is reported when it becomes active again. No-op in web builds, where the
+ * adapter has nothing to watch.
+ */
+export function useExternalChanges() {
+	const filePath = useModelStore((s) => s.filePath);
+	const { handleExternalChange } = useFileOperations();
+
+	useEffect(() => {
+		if (!filePath) return;
+
+		let stopWatching: (() => void) | undefined;
+		let cancelled = false;
+		void getFileAdapter()
+			.then((adapter) =>
+				adapter.watchThreatModel(filePath, () => void handleExternalChange(filePath)),
+			)
+			.then((stop) => {
+				if (cancelled) stop();
+				else stopWatching = stop;
+			})
+			.catch(() => {
+				// A file that cannot be watched (deleted, or on a path the backend refuses) still
+				// saves; a conflicting save is refused by the backend check either way.
+			});
+
+		return () => {
+			cancelled = true;
+			stopWatching?.();
+		};
+	}, [filePath, handleExternalChange]);
+}
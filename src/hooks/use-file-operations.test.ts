@@ -1,5 +1,6 @@
 import { act, renderHook } from "@testing-library/react";
 import { beforeEach, describe, expect, it, vi } from "vitest";
+import { ConcurrentModificationError, type ImportReport } from "@/lib/adapters/file-adapter";
 import { useCanvasStore } from "@/stores/canvas-store";
 import { useDocumentRegistry } from "@/stores/document-registry";
 import { createDocumentStores, setActiveStores } from "@/stores/document-stores";
@@ -12,11 +13,16 @@ const adapter = vi.hoisted(() => ({
 	createNewModel: vi.fn(),
 	openThreatModel: vi.fn(),
 	importThreatModel: vi.fn(),
+	importMitigations: vi.fn(),
 	saveThreatModel: vi.fn(),
+	readThreatModel: vi.fn(),
+	watchThreatModel: vi.fn(),
 	openLayout: vi.fn(),
 	saveLayout: vi.fn(),
 	exportAsHtml: vi.fn(),
+	exportModel: vi.fn(),
 	confirmDiscard: vi.fn(),
+	resolveConflict: vi.fn(),
 }));
 
 vi.mock("@/lib/adapters/get-file-adapter", () => ({
@@ -43,6 +49,23 @@ function makeModel(title: string): ThreatModel {
 	};
 }
 
+function makeReport(report: Partial<ImportReport> = {}): ImportReport {
+	return {
+		counts: {
+			elements: 0,
+			data_flows: 0,
+			trust_boundaries: 0,
+			groups: 0,
+			threats: 0,
+			diagrams: 1,
+		},
+		skipped: [],
+		fallbacks: [],
+		renames: [],
+		...report,
+	};
+}
+
 beforeEach(() => {
 	vi.clearAllMocks();
 	useDocumentRegistry.setState({
@@ -150,7 +173,10 @@ describe("useFileOperations lifecycle through the document registry", () => {
 		});
 		const firstId = useDocumentRegistry.getState().openDocumentIds[0];
 
-		adapter.importThreatModel.mockResolvedValue({ model: makeModel("Imported") });
+		adapter.importThreatModel.mockResolvedValue({
+			model: makeModel("Imported"),
+			report: makeReport(),
+		});
 		await act(async () => {
 			await result.current.importModel();
 		});
@@ -164,6 +190,175 @@ describe("useFileOperations lifecycle through the document registry", () => {
 		expect(adapter.confirmDiscard).not.toHaveBeenCalled();
 	});
 
+	it("Import alerts what a lossy format left out, mapped by fallback or renamed", async () => {
+		const alert = vi.spyOn(window, "alert").mockImplementation(() => {});
+		const { result } = renderHook(() => useFileOperations());
+
+		adapter.importThreatModel.mockResolvedValue({
+			model: makeModel("Threagile"),
+			report: makeReport({
+				counts: {
+					elements: 3,
+					data_flows: 1,
+					trust_boundaries: 1,
+					groups: 0,
+					threats: 2,
+					diagrams: 1,
+				},
+				skipped: [{ item: "abuse_cases (2)", reason: "abuse cases have no counterpart" }],
+				fallbacks: [
+					{
+						item: "technical_assets.audit-sink.technology",
+						source: "mainframe",
+						mapped_to: "data_store",
+						reason: "technology not recognised; typed by asset type",
+					},
+				],
+				renames: [{ name: "Web App", id: "web-app-2" }],
+			}),
+		});
+		await act(async () => {
+			await result.current.importModel();
+		});
+
+		expect(useModelStore.getState().model?.metadata.title).toBe("Threagile");
+		expect(alert).toHaveBeenCalledWith(
+			[
+				"Imported 3 elements, 1 data flow, 1 trust boundary, 0 groups, 2 threats.",
+				"Not carried over (1):",
+				"- abuse_cases (2): abuse cases have no counterpart",
+				"Mapped by fallback (1):",
+				'- technical_assets.audit-sink.technology: "mainframe" → data_store (technology not recognised; typed by asset type)',
+				"Renamed (1):",
+				"- Web App → web-app-2",
+			].join("\n"),
+		);
+		alert.mockRestore();
+	});
+
+	it("Import keeps long report sections short enough for an alert", async () => {
+		const alert = vi.spyOn(window, "alert").mockImplementation(() => {});
+		const { result } = renderHook(() => useFileOperations());
+
+		const skipped = Array.from({ length: 12 }, (_, i) => ({
+			item: `Deployment/job-${i}`,
+			reason: "kind has no counterpart in the model",
+		}));
+		adapter.importThreatModel.mockResolvedValue({
+			model: makeModel("Manifests"),
+			report: makeReport({ skipped }),
+		});
+		await act(async () => {
+			await result.current.importModel();
+		});
+
+		const summary = alert.mock.calls[0]?.[0] as string;
+		expect(summary).toContain("Not carried over (12):");
+		expect(summary).toContain("- Deployment/job-9: kind has no counterpart in the model");
+		expect(summary).not.toContain("job-10");
+		expect(summary.endsWith("- …and 2 more")).toBe(true);
+		alert.mockRestore();
+	});
+
+	it("Import raises no alert when nothing was lost", async () => {
+		const alert = vi.spyOn(window, "alert").mockImplementation(() => {});
+		const { result } = renderHook(() => useFileOperations());
+
+		adapter.importThreatModel.mockResolvedValue({
+			model: makeModel("Lossless"),
+			report: makeReport({ counts: { ...makeReport().counts, elements: 4 } }),
+		});
+		await act(async () => {
+			await result.current.importModel();
+		});
+
+		expect(alert).not.toHaveBeenCalled();
+		alert.mockRestore();
+	});
+
+	it("Import Mitigations updates the open document as one undoable edit and reports skipped rows", async () => {
+		const alert = vi.spyOn(window, "alert").mockImplementation(() => {});
+		const model = makeModel("Triaged");
+		model.threats = [
+			{ id: "t1", title: "Replay", category: "Spoofing", severity: "high", description: "" },
+			{ id: "t2", title: "Leak", category: "Information Disclosure", severity: "low", description: "" },
+		];
+		adapter.openThreatModel.mockResolvedValue({ model, path: "/triaged.thf" });
+		const { result } = renderHook(() => useFileOperations());
+		await act(async () => {
+			await result.current.openModel();
+		});
+
+		adapter.importMitigations.mockResolvedValue({
+			updates: [{ threat_id: "t2", mitigation: { status: "accepted", description: "Low value" } }],
+			unchanged: 1,
+			skipped: [{ item: "line 4", reason: "no threat with id 't9'" }],
+		});
+		await act(async () => {
+			await result.current.importMitigations();
+		});
+
+		const threats = useModelStore.getState().model?.threats ?? [];
+		expect(threats[0]?.mitigation).toBeUndefined();
+		expect(threats[1]?.mitigation).toEqual({ status: "accepted", description: "Low value" });
+		expect(useModelStore.getState().isDirty).toBe(true);
+		expect(useHistoryStore.getState().past).toHaveLength(1);
+		expect(alert).toHaveBeenCalledWith(
+			[
+				"Updated the mitigation of 1 threat; 1 unchanged.",
+				"Rows not applied (1):",
+				"- line 4: no threat with id 't9'",
+			].join("\n"),
+		);
+		alert.mockRestore();
+	});
+
+	it("SARIF export points at the saved file and asks for a save first", async () => {
+		const alert = vi.spyOn(window, "alert").mockImplementation(() => {});
+		adapter.createNewModel.mockResolvedValue(makeModel("Scratch"));
+		const { result } = renderHook(() => useFileOperations());
+		await act(async () => {
+			await result.current.newModel();
+		});
+		await act(async () => {
+			await result.current.exportModel("sarif");
+		});
+		expect(adapter.exportModel).not.toHaveBeenCalled();
+		expect(alert).toHaveBeenCalledWith(
+			"Save the threat model before exporting SARIF: its results point at the .thf file.",
+		);
+
+		const model = makeModel("Payments");
+		adapter.openThreatModel.mockResolvedValue({ model, path: "/repo/payments.thf" });
+		await act(async () => {
+			await result.current.openModel();
+		});
+		await act(async () => {
+			await result.current.exportModel("sarif");
+		});
+		expect(adapter.exportModel).toHaveBeenCalledWith(
+			model,
+			"sarif",
+			"payments",
+			"/repo/payments.thf",
+		);
+
+		// Unsaved edits would leave the results pointing at the wrong lines.
+		adapter.exportModel.mockClear();
+		alert.mockClear();
+		act(() => {
+			useModelStore.getState().markDirty();
+		});
+		await act(async () => {
+			await result.current.exportModel("sarif");
+		});
+		expect(adapter.exportModel).not.toHaveBeenCalled();
+		expect(alert).toHaveBeenCalledWith(
+			"Save the threat model before exporting SARIF: its results point at the .thf file.",
+		);
+		alert.mockRestore();
+	});
+
 	it("Close disposes the active document and returns to an empty scratch view", async () => {
 		adapter.createNewModel.mockResolvedValue(makeModel("Doc"));
 		const { result } = renderHook(() => useFileOperations());
@@ -249,6 +444,123 @@ describe("useFileOperations lifecycle through the document registry", () => {
 	});
 });
 
+describe("useFileOperations resolves files changed on disk by another writer", () => {
+	/** An active document saved at `/repo/shop.thf`, with one unsaved edit. */
+	async function openDirtyDocument() {
+		adapter.openThreatModel.mockResolvedValue({ model: makeModel("Mine"), path: "/repo/shop.thf" });
+		const { result } = renderHook(() => useFileOperations());
+		await act(async () => {
+			await result.current.openModel();
+		});
+		act(() => {
+			useCanvasStore.getState().addElement("web_server", { x: 0, y: 0 });
+		});
+		return result;
+	}
+
+	it("Save of a file changed on disk reloads it when the user chooses to", async () => {
+		const result = await openDirtyDocument();
+		adapter.saveThreatModel.mockRejectedValueOnce(
+			new ConcurrentModificationError("/repo/shop.thf"),
+		);
+		adapter.resolveConflict.mockResolvedValueOnce("reload");
+		adapter.readThreatModel.mockResolvedValueOnce(makeModel("Theirs"));
+
+		await act(async () => {
+			await result.current.saveModel();
+		});
+
+		expect(adapter.resolveConflict).toHaveBeenCalledWith("shop");
+		expect(adapter.readThreatModel).toHaveBeenCalledWith("/repo/shop.thf");
+		expect(adapter.saveThreatModel).toHaveBeenCalledTimes(1);
+		expect(useModelStore.getState().model?.metadata.title).toBe("Theirs");
+		expect(useModelStore.getState().isDirty).toBe(false);
+		expect(useHistoryStore.getState().past).toEqual([]);
+	});
+
+	it("Save of a file changed on disk overwrites it only when the user chooses to", async () => {
+		const result = await openDirtyDocument();
+		adapter.saveThreatModel
+			.mockRejectedValueOnce(new ConcurrentModificationError("/repo/shop.thf"))
+			.mockResolvedValueOnce("/repo/shop.thf");
+		adapter.resolveConflict.mockResolvedValueOnce("overwrite");
+
+		await act(async () => {
+			await result.current.saveModel();
+		});
+
+		expect(adapter.saveThreatModel).toHaveBeenLastCalledWith(
+			expect.objectContaining({ elements: [expect.objectContaining({ id: "comp-1" })] }),
+			"/repo/shop.thf",
+			{ overwrite: true },
+		);
+		expect(adapter.readThreatModel).not.toHaveBeenCalled();
+		expect(useModelStore.getState().isDirty).toBe(false);
+	});
+
+	it("Cancelling a conflicting save keeps the edits unsaved", async () => {
+		const result = await openDirtyDocument();
+		adapter.saveThreatModel.mockRejectedValueOnce(
+			new ConcurrentModificationError("/repo/shop.thf"),
+		);
+		adapter.resolveConflict.mockResolvedValueOnce("cancel");
+
+		await act(async () => {
+			await result.current.saveModel();
+		});
+
+		expect(adapter.saveThreatModel).toHaveBeenCalledTimes(1);
+		expect(useModelStore.getState().isDirty).toBe(true);
+		expect(useModelStore.getState().model?.metadata.title).toBe("Mine");
+	});
+
+	it("An external change reloads a document without edits and asks about one with edits", async () => {
+		adapter.openThreatModel.mockResolvedValue({ model: makeModel("Mine"), path: "/repo/shop.thf" });
+		const { result } = renderHook(() => useFileOperations());
+		await act(async () => {
+			await result.current.openModel();
+		});
+
+		adapter.readThreatModel.mockResolvedValueOnce(makeModel("Theirs"));
+		await act(async () => {
+			await result.current.handleExternalChange("/repo/shop.thf");
+		});
+		expect(adapter.resolveConflict).not.toHaveBeenCalled();
+		expect(useModelStore.getState().model?.metadata.title).toBe("Theirs");
+
+		act(() => {
+			useCanvasStore.getState().addElement("web_server", { x: 0, y: 0 });
+		});
+		adapter.resolveConflict.mockResolvedValueOnce("cancel");
+		await act(async () => {
+			await result.current.handleExternalChange("/repo/shop.thf");
+		});
+		expect(adapter.resolveConflict).toHaveBeenCalledWith("shop");
+		expect(useModelStore.getState().isDirty).toBe(true);
+
+		// A change to some other file is not this document's concern.
+		await act(async () => {
+			await result.current.handleExternalChange("/repo/other.thf");
+		});
+		expect(adapter.resolveConflict).toHaveBeenCalledTimes(1);
+	});
+
+	it("An external change keeps the edits unsaved when overwriting the file fails", async () => {
+		const result = await openDirtyDocument();
+		const alert = vi.spyOn(window, "alert").mockImplementation(() => {});
+		adapter.resolveConflict.mockResolvedValueOnce("overwrite");
+		adapter.saveThreatModel.mockRejectedValueOnce(new Error("disk full"));
+
+		await act(async () => {
+			await result.current.handleExternalChange("/repo/shop.thf");
+		});
+
+		expect(alert).toHaveBeenCalledWith("Save failed: disk full");
+		expect(useModelStore.getState().isDirty).toBe(true);
+		alert.mockRestore();
+	});
+});
+
 describe("useFileOperations surfaces open rejections without partial loads", () => {
 	it("alerts the rejection message and leaves the current document intact", async () => {
 		adapter.createNewModel.mockResolvedValue(makeModel("Kept"));
@@ -1,4 +1,11 @@
 import { useCallback } from "react";
+import {
+	ConcurrentModificationError,
+	type ExportFormat,
+	type ImportedModel,
+	type ImportReport,
+	type MitigationImport,
+} from "@/lib/adapters/file-adapter";
 import { getFileAdapter } from "@/lib/adapters/get-file-adapter";
 import { documentDisplayTitle } from "@/lib/document-display-title";
 import { generateHtmlReport } from "@/lib/export/export-html";
@@ -7,6 +14,7 @@ import { buildLayoutFromModel } from "@/lib/model-layout-utils";
 import { mergeWorkspaceTabs } from "@/lib/workspace-tabs";
 import { useCanvasStore } from "@/stores/canvas-store";
 import { nextActiveDocumentId, useDocumentRegistry } from "@/stores/document-registry";
+import { useHistoryStore } from "@/stores/history-store";
 import { useModelStore } from "@/stores/model-store";
 import { useSettingsStore } from "@/stores/settings-store";
 import { useWorkspaceStore } from "@/stores/workspace-store";
@@ -25,6 +33,57 @@ function sanitizeFilename(name: string): string {
 		.replace(/^-|-$/g, "");
 }
 
+/** Lines shown per report section before the rest is summarised, so the alert stays on screen. */
+const MAX_REPORT_LINES = 10;
+
+function plural(count: number, noun: string, nouns = `${noun}s`): string {
+	return `${count} ${count === 1 ? noun : nouns}`;
+}
+
+function reportSection(title: string, lines: string[]): string[] {
+	if (lines.length === 0) return [];
+	const shown = lines.slice(0, MAX_REPORT_LINES).map((line) => `- ${line}`);
+	const rest = lines.length - shown.length;
+	if (rest > 0) shown.push(`- …and ${rest} more`);
+	return [`${title} (${lines.length}):`, ...shown];
+}
+
+/**
+ * A summary of what an import left out, guessed or renamed, or null when it
+ * carried everything over as it was.
+ */
+function describeImportReport(report: ImportReport): string | null {
+	const { counts, skipped, fallbacks, renames } = report;
+	if (skipped.length === 0 && fallbacks.length === 0 && renames.length === 0) return null;
+	const imported = [
+		plural(counts.elements, "element"),
+		plural(counts.data_flows, "data flow"),
+		plural(counts.trust_boundaries, "trust boundary", "trust boundaries"),
+		plural(counts.groups, "group"),
+		plural(counts.threats, "threat"),
+	];
+	return [
+		`Imported ${imported.join(", ")}.`,
+		...reportSection("Not carried over", skipped.map(({ item, reason }) => `${item}: ${reason}`)),
+		...reportSection(
+			"Mapped by fallback",
+			fallbacks.map(
+				({ item, source, mapped_to, reason }) =>
+					`${item}: ${source ? `"${source}"` : "nothing"} → ${mapped_to} (${reason})`,
+			),
+		),
+		...reportSection("Renamed", renames.map(({ name, id }) => `${name} → ${id}`)),
+	].join("\n");
+}
+
+/** What a mitigation CSV changed and which of its rows were left out. */
+function describeMitigationImport({ updates, unchanged, skipped }: MitigationImport): string {
+	return [
+		`Updated the mitigation of ${plural(updates.length, "threat")}; ${unchanged} unchanged.`,
+		...reportSection("Rows not applied", skipped.map(({ item, reason }) => `${item}: ${reason}`)),
+	].join("\n");
+}
+
 /** Build an author string like "Jane Doe <jane@example.com>" from settings. */
 function getAuthorIdentity(): string {
 	const { authorName, authorEmail } = useSettingsStore.getState().settings;
@@ -51,6 +110,50 @@ function captureActiveCanvas(model: ThreatModel): ThreatModel {
 	return captureCanvasIntoModel(model, useCanvasStore.getState());
 }
 
+/**
+ * Replace the active document with the file at `path` as it is on disk now, after another program
+ * changed it. Its undo history described the discarded version, so it is cleared.
+ */
+async function reloadActiveDocument(path: string): Promise<void> {
+	const adapter = await getFileAdapter();
+	let reloaded: ThreatModel;
+	try {
+		reloaded = await adapter.readThreatModel(path);
+	} catch (err) {
+		const msg = err instanceof Error ? err.message : String(err);
+		window.alert(`Reload failed: ${msg}`);
+		return;
+	}
+	// Keep drawing the same diagram, at its saved viewport.
+	const { activeDiagramId } = useCanvasStore.getState();
+	useCanvasStore.setState({ pendingLayout: buildLayoutFromModel(reloaded, activeDiagramId) });
+	useModelStore.getState().setModel(reloaded, path);
+	useHistoryStore.getState().clear();
+	syncActiveFileSettings(reloaded);
+}
+
+/**
+ * Write `captured` to the active document's file. When the file changed on disk since it was
+ * loaded, the user chooses between reloading it, overwriting it, or leaving both as they are.
+ */
+async function saveActiveDocument(captured: ThreatModel, path: string | null): Promise<void> {
+	const adapter = await getFileAdapter();
+	let savedPath: string | null;
+	try {
+		savedPath = await adapter.saveThreatModel(captured, path);
+	} catch (err) {
+		if (!(err instanceof ConcurrentModificationError)) throw err;
+		const choice = await adapter.resolveConflict(documentDisplayTitle(captured, path));
+		if (choice === "reload") await reloadActiveDocument(err.path);
+		if (choice !== "overwrite") return;
+		savedPath = await adapter.saveThreatModel(captured, path, { overwrite: true });
+	}
+	if (!savedPath) return;
+
+	useModelStore.getState().setModel(captured, savedPath);
+	syncActiveFileSettings(captured);
+}
+
 /**
  * The D1 neighbour a close should activate, computed across the **full merged tab order** rather
  * than the registry alone (`#56`). After a reload the registry holds only the active document, so
@@ -71,6 +174,7 @@ export function useFileOperations() {
 	const model = useModelStore((s) => s.model);
 	const filePath = useModelStore((s) => s.filePath);
 	const setModel = useModelStore((s) => s.setModel);
+	const updateThreats = useModelStore((s) => s.updateThreats);
 
 	const newModel = useCallback(async () => {
 		const adapter = await getFileAdapter();
@@ -139,13 +243,8 @@ export function useFileOperations() {
 			},
 		});
 
-		const adapter = await getFileAdapter();
-		const savedPath = await adapter.saveThreatModel(captured, filePath);
-		if (!savedPath) return;
-
-		setModel(captured, savedPath);
-		syncActiveFileSettings(captured);
-	}, [model, filePath, setModel]);
+		await saveActiveDocument(captured, filePath);
+	}, [model, filePath]);
 
 	const saveModelAs = useCallback(async () => {
 		if (!model) return;
@@ -169,6 +268,42 @@ export function useFileOperations() {
 		syncActiveFileSettings(captured);
 	}, [model, setModel]);
 
+	/**
+	 * React to another program — typically an assistant using the MCP server — changing the active
+	 * document's file. A document without edits of its own simply reloads; otherwise the user
+	 * chooses between reloading, overwriting the file with their version, or deciding at the next
+	 * save.
+	 */
+	const handleExternalChange = useCallback(async (path: string) => {
+		const { model: current, filePath: currentPath, isDirty } = useModelStore.getState();
+		if (!current || currentPath !== path) return;
+		if (!isDirty) {
+			await reloadActiveDocument(path);
+			return;
+		}
+
+		const adapter = await getFileAdapter();
+		const choice = await adapter.resolveConflict(documentDisplayTitle(current, path));
+		if (choice === "reload") {
+			await reloadActiveDocument(path);
+		} else if (choice === "overwrite") {
+			const captured = captureActiveCanvas(current);
+			let savedPath: string | null;
+			try {
+				savedPath = await adapter.saveThreatModel(captured, path, { overwrite: true });
+			} catch (err) {
+				// The edits stay unsaved in the document, so nothing is lost by stopping here.
+				const msg = err instanceof Error ? err.message : String(err);
+				window.alert(`Save failed: ${msg}`);
+				return;
+			}
+			if (!savedPath) return;
+
+			useModelStore.getState().setModel(captured, savedPath);
+			syncActiveFileSettings(captured);
+		}
+	}, []);
+
 	/**
 	 * Close one document by id, guarding its *own* unsaved changes and naming it in the prompt
 	 * (`#54` D6). Used for any tab — the active one or a background one — from the close button, the
@@ -244,7 +379,7 @@ export function useFileOperations() {
 
 	const importModel = useCallback(async () => {
 		const adapter = await getFileAdapter();
-		let result: { model: ThreatModel } | null;
+		let result: ImportedModel | null;
 		try {
 			result = await adapter.importThreatModel();
 		} catch (err) {
@@ -254,7 +389,7 @@ export function useFileOperations() {
 		}
 		if (!result) return;
 
-		const { model: imported } = result;
+		const { model: imported, report } = result;
 
 		// Build layout from inline positions (imported models carry no sidecar reference).
 		const pendingLayout = buildLayoutFromModel(imported);
@@ -265,8 +400,35 @@ export function useFileOperations() {
 		useDocumentRegistry
 			.getState()
 			.createDocument({ model: imported, filePath: null, pendingLayout });
+
+		// Importers list what they left out, mapped by fallback or renamed rather than doing it silently.
+		const summary = describeImportReport(report);
+		if (summary) window.alert(summary);
 	}, []);
 
+	const importMitigations = useCallback(async () => {
+		if (!model) return;
+
+		const adapter = await getFileAdapter();
+		let result: MitigationImport | null;
+		try {
+			result = await adapter.importMitigations(model);
+		} catch (err) {
+			const msg = err instanceof Error ? err.message : String(err);
+			window.alert(`Import failed: ${msg}`);
+			return;
+		}
+		if (!result) return;
+
+		// One undoable edit for the whole spreadsheet, applied to the open document.
+		updateThreats(
+			Object.fromEntries(
+				result.updates.map(({ threat_id, mitigation }) => [threat_id, { mitigation }]),
+			),
+		);
+		window.alert(describeMitigationImport(result));
+	}, [model, updateThreats]);
+
 	const exportAsHtml = useCallback(async () => {
 		if (!model) return;
 
@@ -278,14 +440,40 @@ export function useFileOperations() {
 		await adapter.exportAsHtml(html, defaultName);
 	}, [model]);
 
+	const exportModel = useCallback(
+		async (format: ExportFormat) => {
+			if (!model) return;
+			// SARIF results point at lines of the saved file, so it must exist and hold this model.
+			if (format === "sarif" && (!filePath || useModelStore.getState().isDirty)) {
+				window.alert(
+					"Save the threat model before exporting SARIF: its results point at the .thf file.",
+				);
+				return;
+			}
+
+			const defaultName = sanitizeFilename(model.metadata.title) || "threat-model";
+			const adapter = await getFileAdapter();
+			try {
+				await adapter.exportModel(model, format, defaultName, filePath);
+			} catch (err) {
+				const msg = err instanceof Error ? err.message : String(err);
+				window.alert(`Export failed: ${msg}`);
+			}
+		},
+		[model, filePath],
+	);
+
 	return {
 		newModel,
 		openModel,
 		importModel,
+		importMitigations,
 		saveModel,
 		saveModelAs,
 		closeModel,
 		closeDocumentById,
 		exportAsHtml,
+		exportModel,
+		handleExternalChange,
 	};
 }
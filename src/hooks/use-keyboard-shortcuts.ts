@@ -126,7 +126,10 @@ export function useKeyboardShortcuts() {
 						// Redo (Cmd+Shift+Z)
 						const snapshot = useHistoryStore.getState().redo(currentModel);
 						if (snapshot) {
-							const layout = buildLayoutFromModel(snapshot);
+							const layout = buildLayoutFromModel(
+								snapshot,
+								useCanvasStore.getState().activeDiagramId,
+							);
 							if (layout) useCanvasStore.getState().setPendingLayout(layout);
 							useModelStore.getState().restoreSnapshot(snapshot);
 							useCanvasStore.getState().syncFromModel();
@@ -135,7 +138,10 @@ export function useKeyboardShortcuts() {
 						// Undo (Cmd+Z)
 						const snapshot = useHistoryStore.getState().undo(currentModel);
 						if (snapshot) {
-							const layout = buildLayoutFromModel(snapshot);
+							const layout = buildLayoutFromModel(
+								snapshot,
+								useCanvasStore.getState().activeDiagramId,
+							);
 							if (layout) useCanvasStore.getState().setPendingLayout(layout);
 							useModelStore.getState().restoreSnapshot(snapshot);
 							useCanvasStore.getState().syncFromModel();
@@ -150,7 +156,10 @@ export function useKeyboardShortcuts() {
 					if (!currentModel) break;
 					const snapshot = useHistoryStore.getState().redo(currentModel);
 					if (snapshot) {
-						const layout = buildLayoutFromModel(snapshot);
+						const layout = buildLayoutFromModel(
+							snapshot,
+							useCanvasStore.getState().activeDiagramId,
+						);
 						if (layout) useCanvasStore.getState().setPendingLayout(layout);
 						useModelStore.getState().restoreSnapshot(snapshot);
 						useCanvasStore.getState().syncFromModel();
@@ -1,4 +1,5 @@
 import { useEffect } from "react";
+import { getFileAdapter } from "@/lib/adapters/get-file-adapter";
 import { buildLayoutFromModel } from "@/lib/model-layout-utils";
 import { isTauri } from "@/lib/platform";
 import { useCanvasInstanceStore } from "@/stores/canvas-instance-store";
@@ -11,14 +12,12 @@ import { useUiStore } from "@/stores/ui-store";
 import { useFileOperations } from "./use-file-operations";
 
 /**
- * Open a .thf file by path (used for file association / open-with).
- * Calls the Rust open_threat_model command directly with the given path.
+ * Open a .thf file by path (used for file association / open-with). Reads through the file adapter
+ * so the next save is checked against the file as it was opened.
  */
 async function openFileByPath(filePath: string) {
-	const { invoke } = await import("@tauri-apps/api/core");
-	const model = await invoke<import("@/types/threat-model").ThreatModel>("open_threat_model", {
-		path: filePath,
-	});
+	const adapter = await getFileAdapter();
+	const model = await adapter.readThreatModel(filePath);
 
 	const pendingLayout = buildLayoutFromModel(model);
 	// Open in a new tab, matching the in-app Open (`#54` step 6): a file-association open adds a
@@ -33,8 +32,17 @@ async function openFileByPath(filePath: string) {
  * No-op in web builds where Tauri APIs are unavailable.
  */
 export function useNativeMenu() {
-	const { newModel, openModel, importModel, saveModel, saveModelAs, closeModel, exportAsHtml } =
-		useFileOperations();
+	const {
+		newModel,
+		openModel,
+		importModel,
+		importMitigations,
+		saveModel,
+		saveModelAs,
+		closeModel,
+		exportAsHtml,
+		exportModel,
+	} = useFileOperations();
 
 	useEffect(() => {
 		if (!isTauri()) return;
@@ -64,6 +72,9 @@ export function useNativeMenu() {
 					case "file-import":
 						void importModel();
 						break;
+					case "file-import-mitigations":
+						void importMitigations();
+						break;
 					case "file-save":
 						void saveModel();
 						break;
@@ -76,6 +87,30 @@ export function useNativeMenu() {
 					case "file-export-html":
 						void exportAsHtml();
 						break;
+					case "file-export-markdown":
+						void exportModel("markdown");
+						break;
+					case "file-export-tm7":
+						void exportModel("tm7");
+						break;
+					case "file-export-threatdragon":
+						void exportModel("threatdragon");
+						break;
+					case "file-export-otm":
+						void exportModel("otm");
+						break;
+					case "file-export-csv":
+						void exportModel("csv");
+						break;
+					case "file-export-xlsx":
+						void exportModel("xlsx");
+						break;
+					case "file-export-xlsx-architecture":
+						void exportModel("xlsx-architecture");
+						break;
+					case "file-export-sarif":
+						void exportModel("sarif");
+						break;
 
 					// Edit
 					case "edit-undo": {
@@ -83,7 +118,10 @@ export function useNativeMenu() {
 						if (!currentModel) break;
 						const snapshot = useHistoryStore.getState().undo(currentModel);
 						if (snapshot) {
-							const layout = buildLayoutFromModel(snapshot);
+							const layout = buildLayoutFromModel(
+								snapshot,
+								useCanvasStore.getState().activeDiagramId,
+							);
 							if (layout) useCanvasStore.getState().setPendingLayout(layout);
 							useModelStore.getState().restoreSnapshot(snapshot);
 							useCanvasStore.getState().syncFromModel();
@@ -95,7 +133,10 @@ export function useNativeMenu() {
 						if (!currentModel) break;
 						const snapshot = useHistoryStore.getState().redo(currentModel);
 						if (snapshot) {
-							const layout = buildLayoutFromModel(snapshot);
+							const layout = buildLayoutFromModel(
+								snapshot,
+								useCanvasStore.getState().activeDiagramId,
+							);
 							if (layout) useCanvasStore.getState().setPendingLayout(layout);
 							useModelStore.getState().restoreSnapshot(snapshot);
 							useCanvasStore.getState().syncFromModel();
@@ -189,5 +230,15 @@ export function useNativeMenu() {
 			unlistenMenu?.();
 			unlistenFile?.();
 		};
-	}, [newModel, openModel, importModel, saveModel, saveModelAs, closeModel, exportAsHtml]);
+	}, [
+		newModel,
+		openModel,
+		importModel,
+		importMitigations,
+		saveModel,
+		saveModelAs,
+		closeModel,
+		exportAsHtml,
+		exportModel,
+	]);
 }
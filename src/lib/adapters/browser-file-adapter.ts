@@ -1,7 +1,13 @@
 import { readThreatModelText } from "@/lib/thf-validation";
 import { serializeThreatModelYaml } from "@/lib/thf-yaml";
 import type { DiagramLayout, ThreatModel } from "@/types/threat-model";
-import type { FileAdapter } from "./file-adapter";
+import type {
+	ConflictResolution,
+	ExportFormat,
+	FileAdapter,
+	ImportedModel,
+	MitigationImport,
+} from "./file-adapter";
 
 function todayString(): string {
 	return new Date().toISOString().split("T")[0];
@@ -50,7 +56,12 @@ export class BrowserFileAdapter implements FileAdapter {
 		return { model: readThreatModelText(text), path: file.name };
 	}
 
-	async importThreatModel(): Promise<{ model: ThreatModel } | null> {
+	async importThreatModel(): Promise<ImportedModel | null> {
+		window.alert("Import is only available in the desktop app.");
+		return null;
+	}
+
+	async importMitigations(_model: ThreatModel): Promise<MitigationImport | null> {
 		window.alert("Import is only available in the desktop app.");
 		return null;
 	}
@@ -62,6 +73,16 @@ export class BrowserFileAdapter implements FileAdapter {
 		return filename;
 	}
 
+	async readThreatModel(_path: string): Promise<ThreatModel> {
+		// Browser "paths" are download names; there is no file to read back.
+		throw new Error("Reading a model by path is only available in the desktop app.");
+	}
+
+	async watchThreatModel(_path: string, _onChange: () => void): Promise<() => void> {
+		// Downloads are never written by anyone else, so there is nothing to watch.
+		return () => {};
+	}
+
 	async openLayout(_modelPath: string, _layoutFile: string): Promise<DiagramLayout | null> {
 		// Browser version doesn't persist layout
 		return null;
@@ -77,12 +98,26 @@ export class BrowserFileAdapter implements FileAdapter {
 		return defaultName;
 	}
 
+	async exportModel(
+		_model: ThreatModel,
+		_format: ExportFormat,
+		_defaultName: string,
+	): Promise<string | null> {
+		window.alert("Export is only available in the desktop app.");
+		return null;
+	}
+
 	async confirmDiscard(documentTitle?: string): Promise<boolean> {
 		const message = documentTitle
 			? `You have unsaved changes in "${documentTitle}". Discard them?`
 			: "You have unsaved changes. Discard them?";
 		return window.confirm(message);
 	}
+
+	async resolveConflict(_documentTitle: string): Promise<ConflictResolution> {
+		// Browser saves never conflict: nothing else writes a download.
+		return "cancel";
+	}
 }
 
 function pickFile(accept: string): Promise<File | null> {
@@ -6,4 +6,9 @@ export class BrowserStrideAdapter implements StrideAdapter {
 	async analyze(model: ThreatModel): Promise<Threat[]> {
 		return analyzeStride(model);
 	}
+
+	async analyzeWithTemplate(_model: ThreatModel): Promise<Threat[] | null> {
+		window.alert("Threat Modeling Tool templates are only available in the desktop app.");
+		return null;
+	}
 }
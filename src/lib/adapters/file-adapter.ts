@@ -1,4 +1,96 @@
-import type { DiagramLayout, ThreatModel } from "@/types/threat-model";
+import type { DiagramLayout, Mitigation, ThreatModel } from "@/types/threat-model";
+
+/**
+ * External formats a model can be exported to: Microsoft TMT `.tm7`, OWASP Threat Dragon JSON, Open
+ * Threat Model, the Markdown threat report, the threat register as CSV or XLSX (optionally with a
+ * sheet of elements and flows), and the open threats as a SARIF log for code-scanning dashboards.
+ */
+export type ExportFormat =
+	| "tm7"
+	| "threatdragon"
+	| "otm"
+	| "markdown"
+	| "csv"
+	| "xlsx"
+	| "xlsx-architecture"
+	| "sarif";
+
+/** A source construct an import could not carry over, and why. Mirrors `importers::report::SkippedItem`. */
+export interface SkippedImportItem {
+	item: string;
+	reason: string;
+}
+
+/** A source value the importer did not recognise and mapped by a fallback. Mirrors `importers::report::FallbackMapping`. */
+export interface ImportFallback {
+	item: string;
+	/** The value as the source has it; empty when it has none. */
+	source: string;
+	mapped_to: string;
+	reason: string;
+}
+
+/** A name imported under a suffixed id because its id was taken. Mirrors `importers::report::Rename`. */
+export interface ImportRename {
+	name: string;
+	id: string;
+}
+
+/** How many of each section an imported model holds. Mirrors `importers::report::ImportCounts`. */
+export interface ImportCounts {
+	elements: number;
+	data_flows: number;
+	trust_boundaries: number;
+	groups: number;
+	threats: number;
+	diagrams: number;
+}
+
+/** How an import went. Mirrors `importers::report::ImportReport`; the lists are empty for a lossless import. */
+export interface ImportReport {
+	counts: ImportCounts;
+	skipped: SkippedImportItem[];
+	fallbacks: ImportFallback[];
+	renames: ImportRename[];
+}
+
+/** An imported model together with its import report. */
+export interface ImportedModel {
+	model: ThreatModel;
+	report: ImportReport;
+}
+
+/** The new mitigation of one threat. Mirrors `importers::mitigations::MitigationUpdate`. */
+export interface MitigationUpdate {
+	threat_id: string;
+	mitigation: Mitigation;
+}
+
+/** What a triaged threat register CSV changes. Mirrors `importers::mitigations::MitigationImport`. */
+export interface MitigationImport {
+	updates: MitigationUpdate[];
+	/** Rows that matched a threat but left its mitigation as it was. */
+	unchanged: number;
+	/** Rows left out, by CSV line, with the reason. */
+	skipped: SkippedImportItem[];
+}
+
+/**
+ * A save refused because the file changed on disk after it was loaded or last saved — typically an
+ * assistant saving through the MCP server. The caller reloads or explicitly overwrites.
+ */
+export class ConcurrentModificationError extends Error {
+	readonly path: string;
+
+	constructor(path: string) {
+		super(`"${path}" was changed on disk by another program since it was loaded.`);
+		this.name = "ConcurrentModificationError";
+		this.path = path;
+	}
+}
+
+/** How the user resolves a file changed on disk while the open document has edits of its own. */
+export type ConflictResolution = "reload" | "overwrite" | "cancel";
 
 /**
  * Adapter interface for file operations.
@@ -11,16 +103,43 @@ export interface FileAdapter {
 	createNewModel(title: string, author: string): Promise<ThreatModel>;
 	/** Show an open dialog and load a threat model from YAML. */
 	openThreatModel(): Promise<{ model: ThreatModel; path: string | null } | null>;
-	/** Show an open dialog for importing from external formats (TM7, etc.) and convert to ThreatModel. */
-	importThreatModel(): Promise<{ model: ThreatModel } | null>;
-	/** Save a threat model to YAML. If no path provided, show save dialog. */
-	saveThreatModel(model: ThreatModel, path: string | null): Promise<string | null>;
+	/** Show an open dialog for importing from external formats (TM7, Threat Dragon, OTM, docker-compose, Kubernetes, Terraform plans, OpenAPI, pytm, Threagile, draw.io) and convert to ThreatModel, reporting what could not be carried over. */
+	importThreatModel(): Promise<ImportedModel | null>;
+	/** Show an open dialog for a threat register CSV and read the mitigation changes it makes to `model`. */
+	importMitigations(model: ThreatModel): Promise<MitigationImport | null>;
+	/**
+	 * Save a threat model to YAML. If no path provided, show save dialog. Saving over a file that
+	 * changed on disk since it was opened or last saved rejects with `ConcurrentModificationError`,
+	 * unless `overwrite` is set.
+	 */
+	saveThreatModel(
+		model: ThreatModel,
+		path: string | null,
+		options?: { overwrite?: boolean },
+	): Promise<string | null>;
+	/** Read the threat model at `path` without a dialog, as the next save's baseline. */
+	readThreatModel(path: string): Promise<ThreatModel>;
+	/**
+	 * Call `onChange` whenever another program changes the file at `path`; the app's own saves are
+	 * not reported. Resolves to a function that stops watching.
+	 */
+	watchThreatModel(path: string, onChange: () => void): Promise<() => void>;
 	/** @deprecated Layout data is now inline in the YAML. Kept for old-format fallback. */
 	openLayout(modelPath: string, layoutFile: string): Promise<DiagramLayout | null>;
 	/** @deprecated Layout data is now inline in the YAML. Will be removed in a future release. */
 	saveLayout(modelPath: string, layoutFile: string, layout: DiagramLayout): Promise<void>;
 	/** Export the threat model as a self-contained HTML report. Returns the saved path, or null if cancelled. */
 	exportAsHtml(htmlContent: string, defaultName: string): Promise<string | null>;
+	/**
+	 * Export the threat model to an external format. Returns the saved path, or null if cancelled.
+	 * `modelPath` is the model's `.thf` file, which a SARIF export points its results at.
+	 */
+	exportModel(
+		model: ThreatModel,
+		format: ExportFormat,
+		defaultName: string,
+		modelPath?: string | null,
+	): Promise<string | null>;
 	/**
 	 * Show a confirmation dialog for discarding unsaved changes. When a `documentTitle` is given the
 	 * prompt names the document, so a user with several tabs open can answer it correctly (`#54`
@@ -28,4 +147,6 @@ export interface FileAdapter {
 	 * site compiling and unchanged.
 	 */
 	confirmDiscard(documentTitle?: string): Promise<boolean>;
+	/** Ask whether to reload a document changed on disk, overwrite the file with it, or do neither. */
+	resolveConflict(documentTitle: string): Promise<ConflictResolution>;
 }
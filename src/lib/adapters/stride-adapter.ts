@@ -9,4 +9,9 @@ import type { Threat, ThreatModel } from "@/types/threat-model";
 export interface StrideAdapter {
 	/** Analyze a threat model and return suggested threats (excluding duplicates). */
 	analyze(model: ThreatModel): Promise<Threat[]>;
+	/**
+	 * Pick a Threat Modeling Tool template (`.tb7`) and run its threat types instead of the built-in
+	 * rules. Resolves to `null` when the user cancels.
+	 */
+	analyzeWithTemplate(model: ThreatModel): Promise<Threat[] | null>;
 }
@@ -1,19 +1,81 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { confirm, open, save } from "@tauri-apps/plugin-dialog";
 import type { DiagramLayout, ThreatModel } from "@/types/threat-model";
-import type { FileAdapter } from "./file-adapter";
+import {
+	ConcurrentModificationError,
+	type ConflictResolution,
+	type ExportFormat,
+	type FileAdapter,
+	type ImportedModel,
+	type MitigationImport,
+} from "./file-adapter";
+
+/** Fingerprint of a `.thf` file as last read or written. Mirrors `file_io::FileStamp`. */
+interface FileStamp {
+	sha256: string;
+	modified_ms?: number;
+}
+
+/** Payload of the backend's `model-changed-on-disk` event. */
+interface ModelChangedOnDisk {
+	path: string;
+	stamp: FileStamp;
+}
+
+/**
+ * The part of the `ThreatForgeError::ConcurrentModification` message that identifies it; commands
+ * reject with the error's display string.
+ */
+const CONCURRENT_MODIFICATION = "was modified by another writer";
 
 const YAML_FILTER = {
 	name: "Threat Forge Model",
 	extensions: ["thf"],
 };
 
-const IMPORT_FILTER = {
-	name: "Microsoft Threat Model",
-	extensions: ["tm7"],
+const IMPORT_FILTERS = [
+	{ name: "Microsoft Threat Model", extensions: ["tm7"] },
+	{ name: "OWASP Threat Dragon", extensions: ["json"] },
+	{ name: "Open Threat Model", extensions: ["otm", "yaml", "yml", "json"] },
+	{ name: "Docker Compose / Kubernetes", extensions: ["yml", "yaml"] },
+	{ name: "Terraform Plan (terraform show -json)", extensions: ["json"] },
+	{ name: "OpenAPI 3", extensions: ["yaml", "yml", "json"] },
+	{ name: "pytm (tm.py --json)", extensions: ["json"] },
+	{ name: "Threagile", extensions: ["yaml", "yml"] },
+	{ name: "draw.io / diagrams.net", extensions: ["drawio", "xml"] },
+];
+
+/** The backend import format for each importable file extension. */
+const IMPORT_FORMATS: Record<string, string> = {
+	tm7: "tm7",
+	drawio: "drawio",
+	xml: "drawio",
+	// Threat Dragon, OTM, a Terraform plan, OpenAPI or pytm; the backend tells them apart.
+	json: "json",
+	otm: "otm",
+	// OTM, OpenAPI, Threagile, docker-compose or Kubernetes; the backend tells them apart.
+	yaml: "yaml",
+	yml: "yaml",
+};
+
+const EXPORT_FILTERS: Record<ExportFormat, { name: string; extensions: string[] }> = {
+	tm7: { name: "Threat Modeling Tool", extensions: ["tm7"] },
+	threatdragon: { name: "OWASP Threat Dragon", extensions: ["json"] },
+	otm: { name: "Open Threat Model", extensions: ["otm"] },
+	markdown: { name: "Markdown report", extensions: ["md"] },
+	csv: { name: "Threat register (CSV)", extensions: ["csv"] },
+	xlsx: { name: "Threat register (Excel)", extensions: ["xlsx"] },
+	"xlsx-architecture": { name: "Threat register (Excel)", extensions: ["xlsx"] },
+	sarif: { name: "SARIF log", extensions: ["sarif"] },
 };
 
 export class TauriFileAdapter implements FileAdapter {
+	/** The stamp of each file as this app last read or wrote it: the baseline of its next save. */
+	private readonly stamps = new Map<string, FileStamp>();
+	/** Saves in flight, so a watch event raced by the app's own write is checked after it lands. */
+	private readonly pendingSaves = new Map<string, Promise<unknown>>();
+
 	async createNewModel(title: string, author: string): Promise<ThreatModel> {
 		return invoke<ThreatModel>("create_new_model", { title, author });
 	}
@@ -25,25 +87,46 @@ export class TauriFileAdapter implements FileAdapter {
 		});
 		if (!selected) return null;
 
-		const model = await invoke<ThreatModel>("open_threat_model", { path: selected });
-		return { model, path: selected };
+		return { model: await this.readThreatModel(selected), path: selected };
 	}
 
-	async importThreatModel(): Promise<{ model: ThreatModel } | null> {
+	async readThreatModel(path: string): Promise<ThreatModel> {
+		// Stamp before reading: a write landing in between then fails the next save rather than
+		// being overwritten by it.
+		const stamp = await invoke<FileStamp>("threat_model_stamp", { path });
+		const model = await invoke<ThreatModel>("open_threat_model", { path });
+		this.stamps.set(path, stamp);
+		return model;
+	}
+
+	async importThreatModel(): Promise<ImportedModel | null> {
 		const selected = await open({
 			multiple: false,
-			filters: [IMPORT_FILTER],
+			filters: IMPORT_FILTERS,
 		});
 		if (!selected) return null;
 
 		const ext = selected.split(".").pop()?.toLowerCase() ?? "";
-		const format = ext === "tm7" ? "tm7" : ext;
+		const format = IMPORT_FORMATS[ext] ?? ext;
 
-		const model = await invoke<ThreatModel>("import_threat_model", { path: selected, format });
-		return { model };
+		return invoke<ImportedModel>("import_threat_model", { path: selected, format });
 	}
 
-	async saveThreatModel(model: ThreatModel, path: string | null): Promise<string | null> {
+	async importMitigations(model: ThreatModel): Promise<MitigationImport | null> {
+		const selected = await open({
+			multiple: false,
+			filters: [{ name: "Threat register (CSV)", extensions: ["csv"] }],
+		});
+		if (!selected) return null;
+
+		return invoke<MitigationImport>("import_mitigations", { path: selected, model });
+	}
+
+	async saveThreatModel(
+		model: ThreatModel,
+		path: string | null,
+		options?: { overwrite?: boolean },
+	): Promise<string | null> {
 		let targetPath = path;
 		if (!targetPath) {
 			const selected = await save({
@@ -54,10 +137,40 @@ export class TauriFileAdapter implements FileAdapter {
 			targetPath = selected;
 		}
 
-		await invoke("save_threat_model", { path: targetPath, model });
+		// Save As picks a new file, so only a plain Save is checked against what was loaded.
+		const expected = path && !options?.overwrite ? (this.stamps.get(path) ?? null) : null;
+		const saving = invoke<FileStamp>("save_threat_model", { path: targetPath, model, expected });
+		this.pendingSaves.set(targetPath, saving);
+		try {
+			this.stamps.set(targetPath, await saving);
+		} catch (err) {
+			if (typeof err === "string" && err.includes(CONCURRENT_MODIFICATION)) {
+				throw new ConcurrentModificationError(targetPath);
+			}
+			throw err;
+		} finally {
+			if (this.pendingSaves.get(targetPath) === saving) this.pendingSaves.delete(targetPath);
+		}
 		return targetPath;
 	}
 
+	async watchThreatModel(path: string, onChange: () => void): Promise<() => void> {
+		const unlisten = await listen<ModelChangedOnDisk>("model-changed-on-disk", (event) => {
+			if (event.payload.path !== path) return;
+			void (async () => {
+				// The app's own save is reported too; it is recognised by the stamp it returned.
+				await this.pendingSaves.get(path)?.catch(() => {});
+				if (this.stamps.get(path)?.sha256 === event.payload.stamp.sha256) return;
+				onChange();
+			})();
+		});
+		await invoke("watch_threat_model", { path, baseline: this.stamps.get(path) ?? null });
+		return () => {
+			unlisten();
+			void invoke("unwatch_threat_model", { path });
+		};
+	}
+
 	async openLayout(modelPath: string, layoutFile: string): Promise<DiagramLayout | null> {
 		const layoutPath = resolveLayoutPath(modelPath, layoutFile);
 		try {
@@ -87,6 +200,28 @@ export class TauriFileAdapter implements FileAdapter {
 		return selected;
 	}
 
+	async exportModel(
+		model: ThreatModel,
+		format: ExportFormat,
+		defaultName: string,
+		modelPath?: string | null,
+	): Promise<string | null> {
+		const filter = EXPORT_FILTERS[format];
+		const selected = await save({
+			filters: [filter],
+			defaultPath: `${defaultName}.${filter.extensions[0]}`,
+		});
+		if (!selected) return null;
+
+		await invoke("export_threat_model", {
+			path: selected,
+			format,
+			model,
+			modelPath: modelPath ?? null,
+		});
+		return selected;
+	}
+
 	async confirmDiscard(documentTitle?: string): Promise<boolean> {
 		const message = documentTitle
 			? `You have unsaved changes in "${documentTitle}". Discard them?`
@@ -98,6 +233,29 @@ export class TauriFileAdapter implements FileAdapter {
 			cancelLabel: "Cancel",
 		});
 	}
+
+	async resolveConflict(documentTitle: string): Promise<ConflictResolution> {
+		const reload = await confirm(
+			`"${documentTitle}" was changed on disk by another program, such as an assistant using the MCP server. Reload it and discard your unsaved changes?`,
+			{
+				title: "File Changed on Disk",
+				kind: "warning",
+				okLabel: "Reload",
+				cancelLabel: "Keep Mine",
+			},
+		);
+		if (reload) return "reload";
+		const overwrite = await confirm(
+			`Overwrite "${documentTitle}" with your version? The changes made on disk will be lost.`,
+			{
+				title: "File Changed on Disk",
+				kind: "warning",
+				okLabel: "Overwrite",
+				cancelLabel: "Cancel",
+			},
+		);
+		return overwrite ? "overwrite" : "cancel";
+	}
 }
 
 function resolveLayoutPath(modelPath: string, layoutFile: string): string {
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
 import type { Threat, ThreatModel } from "@/types/threat-model";
 import type { StrideAdapter } from "./stride-adapter";
 
@@ -6,4 +7,14 @@ export class TauriStrideAdapter implements StrideAdapter {
 	async analyze(model: ThreatModel): Promise<Threat[]> {
 		return invoke<Threat[]>("analyze_stride", { model });
 	}
+
+	async analyzeWithTemplate(model: ThreatModel): Promise<Threat[] | null> {
+		const selected = await open({
+			multiple: false,
+			filters: [{ name: "Threat Modeling Tool template", extensions: ["tb7"] }],
+		});
+		if (!selected) return null;
+
+		return invoke<Threat[]>("analyze_stride_with_template", { model, templatePath: selected });
+	}
 }
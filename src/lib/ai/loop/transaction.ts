@@ -179,7 +179,7 @@ export function undoTurn(ledger: TurnUndoLedger): boolean {
 	const snapshot = useHistoryStore.getState().undo(current);
 	if (snapshot === null) return false;
 
-	const layout = buildLayoutFromModel(snapshot);
+	const layout = buildLayoutFromModel(snapshot, useCanvasStore.getState().activeDiagramId);
 	if (layout) useCanvasStore.getState().setPendingLayout(layout);
 	useModelStore.getState().restoreSnapshot(snapshot);
 	useCanvasStore.getState().syncFromModel();
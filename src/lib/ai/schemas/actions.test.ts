@@ -140,6 +140,7 @@ describe("generated JSON Schema", () => {
 			"technologies",
 			"stores",
 			"encryption",
+			"operations",
 			"layer",
 			"group",
 			"tags",
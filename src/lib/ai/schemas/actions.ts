@@ -19,6 +19,7 @@
 import { z } from "zod";
 import { defineTool, parseToolInput, type ToolInputParseResult } from "@/lib/ai/protocol/tools";
 import type {
+	ApiOperation,
 	DataFlow,
 	Element,
 	Mitigation,
@@ -113,6 +114,16 @@ const mitigationSchema = z.strictObject({
 	description: z.string(),
 } satisfies SchemaFor<Mitigation>);
 
+const apiOperationSchema = z.strictObject({
+	method: z.string(),
+	path: z.string(),
+	summary: z.string().optional(),
+	authenticated: z.boolean(),
+	rate_limited: z.boolean(),
+	request_fields: z.array(z.string()).optional(),
+	response_fields: z.array(z.string()).optional(),
+} satisfies SchemaFor<ApiOperation>);
+
 const elementUpdateShape = {
 	name: z.string().optional(),
 	type: z.string().optional(),
@@ -123,6 +134,7 @@ const elementUpdateShape = {
 	technologies: z.array(z.string()).optional(),
 	stores: z.array(z.string()).optional(),
 	encryption: z.string().optional(),
+	operations: z.array(apiOperationSchema).optional(),
 	// Architecture references from #57. There is no add_layer/add_group action,
 	// so the model can only point at human-created layers and groups — and the
 	// executor validates both refs against the document, because the reader now
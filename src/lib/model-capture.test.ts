@@ -1,5 +1,5 @@
 import { describe, expect, it } from "vitest";
-import type { DfdEdge, DfdNode } from "@/stores/canvas-store-factory";
+import { boundaryToNode, type DfdEdge, type DfdNode } from "@/stores/canvas-store-factory";
 import type { ThreatModel } from "@/types/threat-model";
 import { type CanvasCaptureSource, captureCanvasIntoModel } from "./model-capture";
 
@@ -129,6 +129,31 @@ describe("captureCanvasIntoModel", () => {
 		});
 	});
 
+	it("moves a dragged line boundary's vertices instead of giving it a rectangle", () => {
+		const model = modelWithGeometry();
+		model.trust_boundaries[0] = {
+			id: "boundary-1",
+			name: "Internet",
+			contains: [],
+			shape: "line",
+			points: [
+				{ x: 100, y: 0 },
+				{ x: 100, y: 300 },
+			],
+		};
+		const node = boundaryToNode(model.trust_boundaries[0], { x: 0, y: 0 });
+		node.position = { x: node.position.x + 40, y: node.position.y - 10 };
+
+		const captured = captureCanvasIntoModel(model, canvas({ nodes: [node] }));
+
+		expect(captured.trust_boundaries[0].points).toEqual([
+			{ x: 140, y: -10 },
+			{ x: 140, y: 290 },
+		]);
+		expect(captured.trust_boundaries[0].position).toBeUndefined();
+		expect(captured.trust_boundaries[0].size).toBeUndefined();
+	});
+
 	it("captures a dragged edge label offset, handles, and stroke", () => {
 		const edge: DfdEdge = {
 			id: "flow-1",
@@ -157,7 +182,7 @@ describe("captureCanvasIntoModel", () => {
 		});
 	});
 
-	it("writes the live viewport into every diagram and drops the sidecar layout reference", () => {
+	it("writes the live viewport into the drawn diagram and drops the sidecar layout reference", () => {
 		const captured = captureCanvasIntoModel(
 			modelWithGeometry(),
 			canvas({ viewport: { x: -40, y: 15, zoom: 1.75 } }),
@@ -167,6 +192,23 @@ describe("captureCanvasIntoModel", () => {
 		expect(captured.diagrams[0].layout_file).toBeUndefined();
 	});
 
+	it("leaves the viewport of diagrams that are not on the canvas alone", () => {
+		const model = modelWithGeometry();
+		model.diagrams.push({
+			id: "diagram-2",
+			name: "Deployment",
+			viewport: { x: 5, y: 5, zoom: 0.5 },
+		});
+
+		const captured = captureCanvasIntoModel(
+			model,
+			canvas({ viewport: { x: -40, y: 15, zoom: 1.75 }, activeDiagramId: "diagram-2" }),
+		);
+
+		expect(captured.diagrams[0].viewport).toBeUndefined();
+		expect(captured.diagrams[1].viewport).toEqual({ x: -40, y: 15, zoom: 1.75 });
+	});
+
 	it("captures from the canvas it is given rather than any ambient active canvas", () => {
 		// The workspace autosave relies on this: a write scheduled for one document must record
 		// that document's geometry even if another document became active before it ran.
@@ -1,13 +1,20 @@
-import type { CanvasState, DfdEdgeData } from "@/stores/canvas-store-factory";
+import {
+	type CanvasState,
+	type DfdEdgeData,
+	lineBoundaryFrame,
+	lineBoundaryPointsAt,
+	resolveDiagramId,
+} from "@/stores/canvas-store-factory";
 import type { ThreatModel } from "@/types/threat-model";
 
 /**
- * The canvas state a capture reads. Narrowed to the three geometry fields so a caller can pass
+ * The canvas state a capture reads. Narrowed to the geometry fields so a caller can pass
  * *a specific document's* canvas store rather than whatever the active facade happens to
  * resolve — which is what lets a debounced workspace write finish against the document it was
  * scheduled for, even if the user switched documents in the meantime.
  */
-export type CanvasCaptureSource = Pick<CanvasState, "nodes" | "edges" | "viewport">;
+export type CanvasCaptureSource = Pick<CanvasState, "nodes" | "edges" | "viewport"> &
+	Partial<Pick<CanvasState, "activeDiagramId">>;
 
 /**
  * Write current canvas positions, boundary colors, and label offsets back into the model.
@@ -37,10 +44,14 @@ export function captureCanvasIntoModel(
 		if (!node) return b;
 		const w = node.width ?? (node.style as Record<string, number> | undefined)?.width ?? 400;
 		const h = node.height ?? (node.style as Record<string, number> | undefined)?.height ?? 300;
+		// A line boundary is stored as its vertices, never as a rectangle.
+		const frame = lineBoundaryFrame(b);
+		const geometry = frame
+			? { points: lineBoundaryPointsAt(b, frame, node.position) }
+			: { position: { x: node.position.x, y: node.position.y }, size: { width: w, height: h } };
 		return {
 			...b,
-			position: { x: node.position.x, y: node.position.y },
-			size: { width: w, height: h },
+			...geometry,
 			fill_color: node.data.boundaryFillColor ?? b.fill_color,
 			stroke_color: node.data.boundaryStrokeColor ?? b.stroke_color,
 			fill_opacity: node.data.boundaryFillOpacity ?? b.fill_opacity,
@@ -65,10 +76,12 @@ export function captureCanvasIntoModel(
 		};
 	});
 
+	// The viewport belongs to the diagram on the canvas; the others keep the one they were left at.
+	const drawn = resolveDiagramId(model, canvas.activeDiagramId);
 	const diagrams = model.diagrams.map((d) => ({
 		...d,
 		layout_file: undefined,
-		viewport: { ...viewport },
+		viewport: d.id === drawn ? { ...viewport } : d.viewport,
 	}));
 
 	return { ...model, elements, trust_boundaries, data_flows, diagrams };
@@ -1,10 +1,18 @@
+import { resolveDiagramId } from "@/stores/canvas-store-factory";
 import type { DiagramLayout, ThreatModel } from "@/types/threat-model";
 
-/** Build a DiagramLayout from inline model positions (for setPendingLayout). */
-export function buildLayoutFromModel(model: ThreatModel): DiagramLayout | null {
+/**
+ * Build a DiagramLayout from inline model positions (for setPendingLayout). The viewport is that of
+ * `diagramId`, the diagram the canvas will draw; by default the first.
+ */
+export function buildLayoutFromModel(
+	model: ThreatModel,
+	diagramId?: string | null,
+): DiagramLayout | null {
 	if (model.diagrams.length === 0) return null;
 
-	const diagram = model.diagrams[0];
+	const diagram =
+		model.diagrams.find((d) => d.id === resolveDiagramId(model, diagramId)) ?? model.diagrams[0];
 
 	const hasInlinePositions =
 		model.elements.some((e) => e.position) || model.trust_boundaries.some((b) => b.position);
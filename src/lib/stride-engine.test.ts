@@ -190,6 +190,43 @@ describe("analyzeStride", () => {
 		expect(threats).toHaveLength(14);
 	});
 
+	it("raises per-operation threats for API operations", () => {
+		// Mirrors the Rust test `api_operations_raise_their_own_threats`.
+		const model = sampleModel();
+		model.elements[0].operations = [
+			{
+				method: "GET",
+				path: "/health",
+				authenticated: false,
+				rate_limited: true,
+				response_fields: ["status"],
+			},
+			{
+				method: "GET",
+				path: "/me",
+				authenticated: true,
+				rate_limited: false,
+				response_fields: ["profile.emailAddress", "apiKey"],
+			},
+		];
+		model.threats.push({
+			id: "existing-1",
+			title: "No rate limit on GET /me of Web Application",
+			category: "Denial of Service",
+			element: "web-app",
+			severity: "medium",
+			description: "",
+		});
+
+		const threats = analyzeStride(model);
+		expect(threats.map((t) => t.title).filter((t) => t.includes(" GET /"))).toEqual([
+			"Unauthenticated operation GET /health on Web Application",
+			"Sensitive fields returned by GET /me on Web Application",
+		]);
+		const disclosure = threats.find((t) => t.title.startsWith("Sensitive fields"));
+		expect(disclosure?.description).toMatch(/^GET \/me returns profile\.emailAddress, apiKey\./);
+	});
+
 	it("generates identical threats with and without architecture sections", () => {
 		// The charter property of the relationships section: architecture overlays
 		// never influence threat generation. Relationships are non-data edges, and
@@ -10,6 +10,8 @@ import { generateThreatId } from "@/lib/ai-utils";
 import { resolveComponent } from "@/lib/registry/registry";
 import type { StrideRole } from "@/lib/registry/types";
 import type {
+	ApiOperation,
+	Element,
 	Severity,
 	StrideCategory,
 	Threat,
@@ -164,6 +166,83 @@ function buildRules(): ThreatRule[] {
 	];
 }
 
+/**
+ * Field names that mark a response as carrying secrets or personal data, matched against the
+ * last segment of a dotted field name, lower-cased with `_` and `-` removed.
+ */
+const SENSITIVE_FIELD_MARKERS = [
+	"password",
+	"passwd",
+	"secret",
+	"token",
+	"apikey",
+	"privatekey",
+	"ssn",
+	"socialsecurity",
+	"taxid",
+	"creditcard",
+	"cardnumber",
+	"cvv",
+	"iban",
+	"accountnumber",
+	"dateofbirth",
+	"email",
+	"phone",
+];
+
+function sensitiveFields(fields: string[]): string[] {
+	return fields.filter((field) => {
+		const name = (field.split(".").pop() ?? field).replace(/[_-]/g, "").toLowerCase();
+		return SENSITIVE_FIELD_MARKERS.some((marker) => name.includes(marker));
+	});
+}
+
+/**
+ * Threats one API operation raises: anonymous access, no rate limit, and sensitive fields in
+ * its responses. One element carries one of these per operation, so they are told apart by title.
+ */
+function operationThreats(element: Element, operation: ApiOperation): Threat[] {
+	const name = element.name;
+	const label = `${operation.method} ${operation.path}`;
+	const threats: Threat[] = [];
+	const push = (category: StrideCategory, severity: Severity, title: string, description: string) =>
+		threats.push({
+			id: generateThreatId(),
+			title,
+			category,
+			element: element.id,
+			severity,
+			description,
+		});
+
+	if (!operation.authenticated) {
+		push(
+			"Spoofing",
+			"high",
+			`Unauthenticated operation ${label} on ${name}`,
+			`${label} accepts requests from anonymous callers, so anyone who can reach ${name} can invoke it. Require authentication unless the operation is deliberately public.`,
+		);
+	}
+	if (!operation.rate_limited) {
+		push(
+			"Denial of Service",
+			"medium",
+			`No rate limit on ${label} of ${name}`,
+			`${label} declares no rate limit, so a single caller can exhaust ${name} or brute-force it. Enforce a per-client rate limit and document it in the API description.`,
+		);
+	}
+	const sensitive = sensitiveFields(operation.response_fields ?? []);
+	if (sensitive.length > 0) {
+		push(
+			"Information Disclosure",
+			"high",
+			`Sensitive fields returned by ${label} on ${name}`,
+			`${label} returns ${sensitive.join(", ")}. Return only the fields callers need, and never return secrets or credentials.`,
+		);
+	}
+	return threats;
+}
+
 function elementName(model: ThreatModel, elementId: string): string {
 	const element = model.elements.find((e) => e.id === elementId);
 	return element ? element.name : elementId;
@@ -258,5 +337,18 @@ export function analyzeStride(model: ThreatModel): Threat[] {
 		}
 	}
 
+	// API operation rules
+	const existingTitles = new Set(
+		model.threats.filter((t) => t.element).map((t) => `${t.element}::${t.title}`),
+	);
+	for (const element of model.elements) {
+		if (resolveComponent(element.type).strideRole === "none") continue;
+		for (const operation of element.operations ?? []) {
+			for (const threat of operationThreats(element, operation)) {
+				if (!existingTitles.has(`${element.id}::${threat.title}`)) suggestions.push(threat);
+			}
+		}
+	}
+
 	return suggestions;
 }
@@ -34,6 +34,8 @@ import groupIdCollidesWithElementRaw from "../../tests/fixtures/thf/invalid/grou
 import missingMetadataRaw from "../../tests/fixtures/thf/invalid/missing-metadata.thf?raw";
 import relationshipIdCollidesWithFlowRaw from "../../tests/fixtures/thf/invalid/relationship-id-collides-with-flow.thf?raw";
 import truncatedRaw from "../../tests/fixtures/thf/invalid/truncated.thf?raw";
+import unknownBoundaryDiagramRaw from "../../tests/fixtures/thf/invalid/unknown-boundary-diagram.thf?raw";
+import unknownElementDiagramRaw from "../../tests/fixtures/thf/invalid/unknown-element-diagram.thf?raw";
 import unknownElementGroupRaw from "../../tests/fixtures/thf/invalid/unknown-element-group.thf?raw";
 import unknownElementLayerRaw from "../../tests/fixtures/thf/invalid/unknown-element-layer.thf?raw";
 import unknownFlowTargetRaw from "../../tests/fixtures/thf/invalid/unknown-flow-target.thf?raw";
@@ -146,6 +148,20 @@ const INVALID_CASES: InvalidCase[] = [
 		kind: "invalid-reference",
 		message: `Invalid reference in 'elements[app].group': 'backend' not found. Valid IDs: ["frontend"]`,
 	},
+	{
+		// reader.rs: InvalidReference { field: "elements[app].diagram", reference: "level-1" }
+		name: "invalid/unknown-element-diagram.thf",
+		raw: unknownElementDiagramRaw,
+		kind: "invalid-reference",
+		message: `Invalid reference in 'elements[app].diagram': 'level-1' not found. Valid IDs: ["main-dfd"]`,
+	},
+	{
+		// reader.rs: InvalidReference { field: "trust_boundaries[dmz].diagram", reference: "level-1" }
+		name: "invalid/unknown-boundary-diagram.thf",
+		raw: unknownBoundaryDiagramRaw,
+		kind: "invalid-reference",
+		message: `Invalid reference in 'trust_boundaries[dmz].diagram': 'level-1' not found. Valid IDs: ["main-dfd"]`,
+	},
 	{
 		// reader.rs: InvalidReference { field: "groups[cluster-1].parent", reference: "missing-cluster" }
 		name: "invalid/unknown-group-parent.thf",
@@ -122,9 +122,9 @@ export function validateThreatModelVersion(version: string): void {
  * 2. Namespace collisions: a group ID that collides with an element or trust-boundary ID (shared
  *    ReactFlow node ID space), a relationship ID that collides with a data-flow ID (shared edge ID
  *    space). Both are scoped to the new sections so no pre-existing file is invalidated.
- * 3. Reference integrity: data-flow endpoints, `element.layer`/`element.group`, `group.parent`,
- *    relationship endpoints (element IDs only), then a group-nesting cycle check, then
- *    trust-boundary members and threat targets.
+ * 3. Reference integrity: data-flow endpoints, `element.layer`/`element.group`/`element.diagram`,
+ *    `group.parent`, relationship endpoints (element IDs only), then a group-nesting cycle check,
+ *    then trust-boundary members and diagrams, and threat targets.
  *
  * `layers`, `groups`, and `relationships` are optional and default to empty, matching the reader's
  * `#[serde(default)]` vectors. No check the desktop lacks is added here.
@@ -133,6 +133,7 @@ function validateReferences(model: ThreatModel): void {
 	const layers = entriesOf(model.layers, "layers");
 	const groups = entriesOf(model.groups, "groups");
 	const relationships = entriesOf(model.relationships, "relationships");
+	const diagrams = entriesOf(model.diagrams, "diagrams");
 
 	const elementIds = model.elements.map((entry, index) =>
 		requireString(asRecord(entry, `elements[${index}]`).id, `elements[${index}].id`),
@@ -149,6 +150,9 @@ function validateReferences(model: ThreatModel): void {
 	const relationshipIds = relationships.map((entry, index) =>
 		requireString(asRecord(entry, `relationships[${index}]`).id, `relationships[${index}].id`),
 	);
+	const diagramIds = diagrams.map((entry, index) =>
+		requireString(asRecord(entry, `diagrams[${index}]`).id, `diagrams[${index}].id`),
+	);
 	const boundaryIds = model.trust_boundaries.map((entry, index) =>
 		requireString(
 			asRecord(entry, `trust_boundaries[${index}]`).id,
@@ -192,7 +196,7 @@ function validateReferences(model: ThreatModel): void {
 		);
 	});
 
-	// Element layer and group membership references.
+	// Element layer, group and diagram membership references.
 	model.elements.forEach((entry, index) => {
 		const record = asRecord(entry, `elements[${index}]`);
 		const id = elementIds[index];
@@ -210,6 +214,13 @@ function validateReferences(model: ThreatModel): void {
 				`elements[${id}].group`,
 			);
 		}
+		if (record.diagram !== undefined && record.diagram !== null) {
+			requireKnownId(
+				requireString(record.diagram, `elements[${index}].diagram`),
+				diagramIds,
+				`elements[${id}].diagram`,
+			);
+		}
 	});
 
 	// Group parent references.
@@ -244,7 +255,7 @@ function validateReferences(model: ThreatModel): void {
 	// Circular group nesting (parents already validated above), iterative like the reader.
 	assertNoGroupCycle(groups, groupIds);
 
-	// Trust boundary members resolve to elements.
+	// Trust boundary members resolve to elements, and their diagram to a diagram.
 	model.trust_boundaries.forEach((entry, index) => {
 		const record = asRecord(entry, `trust_boundaries[${index}]`);
 		const id = boundaryIds[index];
@@ -254,6 +265,13 @@ function validateReferences(model: ThreatModel): void {
 		)) {
 			requireKnownId(contained, elementIds, `trust_boundaries[${id}].contains`);
 		}
+		if (record.diagram !== undefined && record.diagram !== null) {
+			requireKnownId(
+				requireString(record.diagram, `trust_boundaries[${index}].diagram`),
+				diagramIds,
+				`trust_boundaries[${id}].diagram`,
+			);
+		}
 	});
 
 	// Threat targets resolve to elements and flows.
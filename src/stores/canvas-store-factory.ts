@@ -53,6 +53,8 @@ export type DfdNodeData = {
 	boundaryFillOpacity?: number;
 	/** Trust boundary stroke opacity (0-1) */
 	boundaryStrokeOpacity?: number;
+	/** Vertices of a line boundary, relative to the node's top-left corner */
+	boundaryLinePoints?: { x: number; y: number }[];
 };
 
 /** ReactFlow edge data payload for data flows.
@@ -86,11 +88,16 @@ export interface CanvasState {
 	/** Layout to apply on next syncFromModel (set before loading a model) */
 	pendingLayout: DiagramLayout | null;
 
+	/** The diagram the canvas draws. `null`, or an id the model no longer has, means the first. */
+	activeDiagramId: string | null;
+
 	// ReactFlow change handlers
 	onNodesChange: OnNodesChange<DfdNode>;
 	onEdgesChange: OnEdgesChange<DfdEdge>;
 	setViewport: (viewport: Viewport) => void;
 	setPendingLayout: (layout: DiagramLayout | null) => void;
+	/** Draw another of the model's diagrams. */
+	setActiveDiagram: (diagramId: string) => void;
 
 	// Canvas actions
 	addElement: (
@@ -133,6 +140,50 @@ export interface CanvasStoreDeps {
 	history: StoreApi<HistoryState>;
 }
 
+/** Space around a line boundary's vertices so a straight line still has a hit area. */
+const LINE_BOUNDARY_PADDING = 12;
+
+/** The node frame of a line boundary: its vertices' padded bounding box. */
+export interface LineBoundaryFrame {
+	origin: { x: number; y: number };
+	width: number;
+	height: number;
+	/** The vertices, relative to `origin`. */
+	points: { x: number; y: number }[];
+}
+
+/**
+ * Frame a line boundary for the canvas. `null` for rectangles, and for lines with fewer than two
+ * points, which are drawn as rectangles.
+ */
+export function lineBoundaryFrame(boundary: TrustBoundary): LineBoundaryFrame | null {
+	const points = boundary.points ?? [];
+	if (boundary.shape !== "line" || points.length < 2) return null;
+	const xs = points.map((p) => p.x);
+	const ys = points.map((p) => p.y);
+	const origin = {
+		x: Math.min(...xs) - LINE_BOUNDARY_PADDING,
+		y: Math.min(...ys) - LINE_BOUNDARY_PADDING,
+	};
+	return {
+		origin,
+		width: Math.max(...xs) - origin.x + LINE_BOUNDARY_PADDING,
+		height: Math.max(...ys) - origin.y + LINE_BOUNDARY_PADDING,
+		points: points.map((p) => ({ x: p.x - origin.x, y: p.y - origin.y })),
+	};
+}
+
+/** A line boundary's vertices after its node was moved to `position`. */
+export function lineBoundaryPointsAt(
+	boundary: TrustBoundary,
+	frame: LineBoundaryFrame,
+	position: { x: number; y: number },
+): { x: number; y: number }[] {
+	const dx = position.x - frame.origin.x;
+	const dy = position.y - frame.origin.y;
+	return (boundary.points ?? []).map((p) => ({ x: p.x + dx, y: p.y + dy }));
+}
+
 /** Write current canvas node positions into the model's inline position fields. */
 function writePositionsToModel(model: ThreatModel, nodes: DfdNode[]): ThreatModel {
 	const nodeMap = new Map(nodes.map((n) => [n.id, n]));
@@ -146,6 +197,9 @@ function writePositionsToModel(model: ThreatModel, nodes: DfdNode[]): ThreatMode
 		trust_boundaries: model.trust_boundaries.map((b) => {
 			const node = nodeMap.get(b.id);
 			if (!node) return b;
+			// A line has no rectangle: moving its node moves its vertices.
+			const frame = lineBoundaryFrame(b);
+			if (frame) return { ...b, points: lineBoundaryPointsAt(b, frame, node.position) };
 			const w = node.width ?? (node.style as Record<string, number> | undefined)?.width ?? 400;
 			const h = node.height ?? (node.style as Record<string, number> | undefined)?.height ?? 300;
 			return {
@@ -185,12 +239,14 @@ export function boundaryToNode(
 	boundary: TrustBoundary,
 	position: { x: number; y: number },
 ): DfdNode {
-	const w = boundary.size?.width ?? 400;
-	const h = boundary.size?.height ?? 300;
+	// A line boundary is placed by its own vertices rather than a stored position.
+	const frame = lineBoundaryFrame(boundary);
+	const w = frame?.width ?? boundary.size?.width ?? 400;
+	const h = frame?.height ?? boundary.size?.height ?? 300;
 	return {
 		id: boundary.id,
 		type: "trustBoundary",
-		position,
+		position: frame?.origin ?? position,
 		width: w,
 		height: h,
 		// pointerEvents:none on the ReactFlow wrapper so clicks inside the boundary
@@ -209,6 +265,7 @@ export function boundaryToNode(
 			boundaryStrokeColor: boundary.stroke_color,
 			boundaryFillOpacity: boundary.fill_opacity,
 			boundaryStrokeOpacity: boundary.stroke_opacity,
+			boundaryLinePoints: frame?.points,
 		},
 	};
 }
@@ -245,6 +302,24 @@ export function flowToEdge(flow: DataFlow): DfdEdge {
 	};
 }
 
+/**
+ * The id of the diagram an item is drawn on, given its `diagram` reference. A missing or unknown
+ * reference means the first diagram; `null` when the model has none.
+ */
+export function resolveDiagramId(model: ThreatModel, diagram?: string | null): string | null {
+	if (diagram && model.diagrams.some((d) => d.id === diagram)) return diagram;
+	return model.diagrams[0]?.id ?? null;
+}
+
+/**
+ * The `diagram` reference for an item added to `diagramId`. Items on the first diagram leave it
+ * unset, as the format defines.
+ */
+function diagramReference(model: ThreatModel, diagramId: string | null): string | undefined {
+	const resolved = resolveDiagramId(model, diagramId);
+	return resolved && resolved !== model.diagrams[0]?.id ? resolved : undefined;
+}
+
 function defaultElementName(type: string): string {
 	return getComponent(type)?.label ?? "New Component";
 }
@@ -275,6 +350,7 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 		edges: [],
 		viewport: { x: 0, y: 0, zoom: 1 },
 		pendingLayout: null,
+		activeDiagramId: null,
 
 		nextElementId: () => {
 			elementCounter++;
@@ -377,9 +453,16 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 		setViewport: (viewport) => set({ viewport }),
 		setPendingLayout: (layout) => set({ pendingLayout: layout }),
 
+		setActiveDiagram: (diagramId) => {
+			if (diagramId === get().activeDiagramId) return;
+			set({ activeDiagramId: diagramId });
+			get().syncFromModel();
+		},
+
 		addElement: (type, position, opts) => {
 			const id = get().nextElementId();
 			const name = opts?.name ?? defaultElementName(type);
+			const currentModel = deps.model.getState().model;
 
 			const newElement: Element = {
 				id,
@@ -391,6 +474,7 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 				description: "",
 				technologies: [],
 				position: { x: position.x, y: position.y },
+				diagram: currentModel ? diagramReference(currentModel, get().activeDiagramId) : undefined,
 			};
 
 			const newNode = elementToNode(newElement, position);
@@ -513,11 +597,13 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 
 		addTrustBoundary: (name, position) => {
 			const id = get().nextBoundaryId();
+			const currentModel = deps.model.getState().model;
 			const newBoundary: TrustBoundary = {
 				id,
 				name,
 				contains: [],
 				position: { x: position.x, y: position.y },
+				diagram: currentModel ? diagramReference(currentModel, get().activeDiagramId) : undefined,
 			};
 
 			const newNode = boundaryToNode(newBoundary, position);
@@ -801,8 +887,17 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 				return existingPositions.get(id) ?? inlinePos ?? defaultPos;
 			}
 
+			// Draw only the active diagram's boundaries and elements, and the flows between them.
+			const activeDiagram = resolveDiagramId(model, get().activeDiagramId);
+			const onActiveDiagram = (item: { diagram?: string }) =>
+				resolveDiagramId(model, item.diagram) === activeDiagram;
+			const boundaries = model.trust_boundaries.filter(onActiveDiagram);
+			const elements = model.elements.filter(onActiveDiagram);
+			const drawnIds = new Set(elements.map((e) => e.id));
+			const flows = model.data_flows.filter((f) => drawnIds.has(f.from) && drawnIds.has(f.to));
+
 			// Convert trust boundaries to group nodes
-			const boundaryNodes: DfdNode[] = model.trust_boundaries.map((b, i) => {
+			const boundaryNodes: DfdNode[] = boundaries.map((b, i) => {
 				const pos = resolvePosition(b.id, b.position, { x: 50 + i * 450, y: 50 });
 				const node = boundaryToNode(b, pos);
 				// Restore dimensions: saved layout > existing canvas > default
@@ -812,7 +907,8 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 					saved?.width ?? existing?.width ?? (existing?.style as Record<string, unknown>)?.width;
 				const h =
 					saved?.height ?? existing?.height ?? (existing?.style as Record<string, unknown>)?.height;
-				if (w != null && h != null) {
+				// A line's frame always follows its vertices.
+				if (w != null && h != null && !node.data.boundaryLinePoints) {
 					node.width = w as number;
 					node.height = h as number;
 					node.style = {
@@ -826,14 +922,14 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 
 			// Build a set of elements that belong to boundaries
 			const elementToBoundary = new Map<string, string>();
-			for (const b of model.trust_boundaries) {
+			for (const b of boundaries) {
 				for (const elementId of b.contains) {
 					elementToBoundary.set(elementId, b.id);
 				}
 			}
 
 			// Convert elements to nodes
-			const elementNodes: DfdNode[] = model.elements.map((e, i) => {
+			const elementNodes: DfdNode[] = elements.map((e, i) => {
 				const pos = resolvePosition(e.id, e.position, {
 					x: 100 + (i % 4) * 250,
 					y: 100 + Math.floor(i / 4) * 200,
@@ -854,7 +950,7 @@ export function createCanvasStore(deps: CanvasStoreDeps): StoreApi<CanvasState>
 			// Model handles are authoritative when present (from save/load, reconnect, addDataFlow);
 			// only fall back to existing canvas state when the model has no handle data.
 			const existingEdgeMap = new Map(get().edges.map((e) => [e.id, e]));
-			const edges: DfdEdge[] = model.data_flows.map((flow) => {
+			const edges: DfdEdge[] = flows.map((flow) => {
 				const edge = flowToEdge(flow);
 				const existing = existingEdgeMap.get(flow.id);
 				if (existing?.sourceHandle && !flow.source_handle)
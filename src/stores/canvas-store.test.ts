@@ -63,6 +63,7 @@ describe("canvas-store", () => {
 			nodes: [],
 			edges: [],
 			viewport: { x: 0, y: 0, zoom: 1 },
+			activeDiagramId: null,
 		});
 		useModelStore.setState({ model: null, filePath: null, isDirty: false });
 		useHistoryStore.setState({ past: [], future: [] });
@@ -624,6 +625,75 @@ describe("canvas-store", () => {
 		expect(edgeAfterResync?.targetHandle).toBe("top-left-target");
 	});
 
+	describe("diagrams", () => {
+		/** The test model split over two diagrams: the database and a new boundary on the second. */
+		function twoDiagramModel(): ThreatModel {
+			const model = createTestModel();
+			model.diagrams = [
+				{ id: "main", name: "Main" },
+				{ id: "storage", name: "Storage" },
+			];
+			model.elements[1].diagram = "storage";
+			model.trust_boundaries[0].contains = ["process-1"];
+			model.trust_boundaries.push({
+				id: "boundary-2",
+				name: "Storage Tier",
+				contains: ["data-store-1"],
+				diagram: "storage",
+			});
+			return model;
+		}
+
+		it("draws the first diagram's items, treating a missing diagram as the first", () => {
+			useModelStore.getState().setModel(twoDiagramModel(), null);
+			useCanvasStore.getState().syncFromModel();
+
+			const { nodes, edges } = useCanvasStore.getState();
+			expect(nodes.map((n) => n.id)).toEqual(["boundary-1", "process-1"]);
+			// The flow crosses diagrams, so neither draws it.
+			expect(edges).toHaveLength(0);
+		});
+
+		it("switches to another diagram's items", () => {
+			useModelStore.getState().setModel(twoDiagramModel(), null);
+			useCanvasStore.getState().syncFromModel();
+
+			useCanvasStore.getState().setActiveDiagram("storage");
+
+			const { nodes } = useCanvasStore.getState();
+			expect(nodes.map((n) => n.id)).toEqual(["boundary-2", "data-store-1"]);
+			expect(nodes[1].parentId).toBe("boundary-2");
+		});
+
+		it("falls back to the first diagram when the active one is gone", () => {
+			useModelStore.getState().setModel(twoDiagramModel(), null);
+			useCanvasStore.getState().setActiveDiagram("storage");
+
+			const model = twoDiagramModel();
+			model.diagrams.pop();
+			useModelStore.getState().setModel(model, null);
+			useCanvasStore.getState().syncFromModel();
+
+			// Items on the removed diagram fall back to the first, like any unknown reference.
+			expect(useCanvasStore.getState().nodes).toHaveLength(4);
+			expect(useCanvasStore.getState().edges).toHaveLength(1);
+		});
+
+		it("adds elements and boundaries to the diagram being drawn", () => {
+			useModelStore.getState().setModel(twoDiagramModel(), null);
+			useCanvasStore.getState().setActiveDiagram("storage");
+
+			useCanvasStore.getState().addElement("web_browser", { x: 900, y: 900 });
+			useCanvasStore.getState().addTrustBoundary("Backups", { x: 1200, y: 1200 });
+			useCanvasStore.getState().setActiveDiagram("main");
+			useCanvasStore.getState().addElement("web_browser", { x: 900, y: 900 });
+
+			const model = useModelStore.getState().model;
+			expect(model?.elements.slice(2).map((e) => e.diagram)).toEqual(["storage", undefined]);
+			expect(model?.trust_boundaries[2].diagram).toBe("storage");
+		});
+	});
+
 	describe("node drag end", () => {
 		beforeEach(() => {
 			useCanvasInstanceStore.getState().setAltDragActive(false);
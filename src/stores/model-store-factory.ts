@@ -1,5 +1,6 @@
 import { createStore, type StoreApi } from "zustand/vanilla";
 import { getStrideAdapter } from "@/lib/adapters/get-stride-adapter";
+import type { StrideAdapter } from "@/lib/adapters/stride-adapter";
 import type {
 	DataFlow,
 	Element,
@@ -54,6 +55,8 @@ export interface ModelState {
 	addThreat: (threat: Threat) => void;
 	addThreats: (threats: Threat[]) => void;
 	updateThreat: (id: string, updates: Partial<Threat>) => void;
+	/** Apply updates to several threats, keyed by threat id, as one undoable edit. */
+	updateThreats: (updates: Record<string, Partial<Threat>>) => void;
 	deleteThreat: (id: string) => void;
 
 	// Undo/redo
@@ -64,6 +67,8 @@ export interface ModelState {
 
 	// STRIDE analysis
 	analyzeThreats: () => Promise<void>;
+	/** Run the threat types of a picked Threat Modeling Tool template (`.tb7`) instead. */
+	analyzeThreatsWithTemplate: () => Promise<void>;
 }
 
 export interface ModelStoreDeps {
@@ -243,6 +248,20 @@ export function createModelStore(deps: ModelStoreDeps): StoreApi<ModelState> {
 			});
 		},
 
+		updateThreats: (updates) => {
+			const { model } = get();
+			if (!model || Object.keys(updates).length === 0) return;
+
+			captureHistory(model);
+			const updatedThreats = model.threats.map((t) =>
+				Object.hasOwn(updates, t.id) ? { ...t, ...updates[t.id] } : t,
+			);
+			set({
+				model: { ...model, threats: updatedThreats },
+				isDirty: true,
+			});
+		},
+
 		deleteThreat: (id) => {
 			const { model, selectedThreatId } = get();
 			if (!model) return;
@@ -265,20 +284,30 @@ export function createModelStore(deps: ModelStoreDeps): StoreApi<ModelState> {
 
 		resetCaptureDebounce: resetDebounce,
 
-		analyzeThreats: async () => {
-			const { model } = get();
-			if (!model) return;
+		analyzeThreats: () => runAnalysis(get, set, (adapter, model) => adapter.analyze(model)),
 
-			set({ isAnalyzing: true });
-			try {
-				const adapter = await getStrideAdapter();
-				const newThreats = await adapter.analyze(model);
-				if (newThreats.length > 0) {
-					get().addThreats(newThreats);
-				}
-			} finally {
-				set({ isAnalyzing: false });
-			}
-		},
+		analyzeThreatsWithTemplate: () =>
+			runAnalysis(get, set, (adapter, model) => adapter.analyzeWithTemplate(model)),
 	}));
 }
+
+/** Run one kind of STRIDE analysis on the store's model and add the threats it suggests. */
+async function runAnalysis(
+	get: () => ModelState,
+	set: (partial: Partial<ModelState>) => void,
+	analyze: (adapter: StrideAdapter, model: ThreatModel) => Promise<Threat[] | null>,
+): Promise<void> {
+	const { model } = get();
+	if (!model) return;
+
+	set({ isAnalyzing: true });
+	try {
+		const adapter = await getStrideAdapter();
+		const newThreats = await analyze(adapter, model);
+		if (newThreats && newThreats.length > 0) {
+			get().addThreats(newThreats);
+		}
+	} finally {
+		set({ isAnalyzing: false });
+	}
+}
@@ -146,6 +146,19 @@ describe("useModelStore", () => {
 		expect(useModelStore.getState().isDirty).toBe(true);
 	});
 
+	it("updates several threats at once", () => {
+		const second = { ...mockThreat, id: "threat-2", title: "XSS" };
+		useModelStore.getState().setModel({ ...mockModel, threats: [mockThreat, second] }, null);
+		useModelStore.getState().updateThreats({
+			"threat-2": { mitigation: { status: "mitigated", description: "Output encoding" } },
+		});
+		const [first, updated] = useModelStore.getState().model?.threats ?? [];
+		expect(first).toEqual(mockThreat);
+		expect(updated?.mitigation).toEqual({ status: "mitigated", description: "Output encoding" });
+		expect(updated?.title).toBe("XSS");
+		expect(useModelStore.getState().isDirty).toBe(true);
+	});
+
 	it("deletes a threat", () => {
 		const modelWithThreat = { ...mockModel, threats: [mockThreat] };
 		useModelStore.getState().setModel(modelWithThreat, null);
@@ -243,6 +256,19 @@ describe("useModelStore", () => {
 			expect(useModelStore.getState().isDirty).toBe(true);
 		});
 
+		it("updateThreats pushes one history entry however many threats change", () => {
+			const second = { ...mockThreat, id: "threat-2" };
+			useModelStore.getState().setModel({ ...mockModel, threats: [mockThreat, second] }, null);
+			useModelStore.getState().updateThreats({
+				"threat-1": { severity: "low" },
+				"threat-2": { severity: "low" },
+			});
+			expect(useHistoryStore.getState().past).toHaveLength(1);
+
+			useModelStore.getState().updateThreats({});
+			expect(useHistoryStore.getState().past).toHaveLength(1);
+		});
+
 		it("addThreats with empty array does not push to history", () => {
 			useModelStore.getState().setModel(mockModel, null);
 			useModelStore.getState().addThreats([]);
@@ -130,6 +130,7 @@ const maxFilledModel: ThreatModel = {
 			trust_zone: "internal",
 			layer: "presentation",
 			group: "frontend-cluster",
+			diagram: "main-arch",
 			description: "React storefront",
 			technologies: ["react", "vite"],
 			tags: ["tier-1"],
@@ -147,6 +148,17 @@ const maxFilledModel: ThreatModel = {
 			description: "Terminates TLS and routes requests",
 			technologies: ["nginx"],
 			tags: ["pci", "tier-1"],
+			operations: [
+				{
+					method: "POST",
+					path: "/orders",
+					summary: "Place an order",
+					authenticated: true,
+					rate_limited: true,
+					request_fields: ["items.sku", "items.quantity"],
+					response_fields: ["order_id"],
+				},
+			],
 			position: { x: 480, y: 120 },
 			fill_color: "#3b82f6",
 			stroke_color: "#1e40af",
@@ -236,6 +248,19 @@ const maxFilledModel: ThreatModel = {
 			fill_opacity: 0.1,
 			stroke_opacity: 0.6,
 		},
+		{
+			id: "internet-edge",
+			name: "Internet Edge",
+			contains: [],
+			diagram: "main-arch",
+			shape: "line",
+			points: [
+				{ x: 0, y: 0 },
+				{ x: 0, y: 440 },
+			],
+			stroke_color: "#dc2626",
+			stroke_opacity: 0.8,
+		},
 	],
 	threats: [
 		{
@@ -73,6 +73,8 @@ export interface Element {
 	layer?: string;
 	/** Optional non-security group this element belongs to (references `Group.id`). */
 	group?: string;
+	/** Optional diagram this element is drawn on (references `Diagram.id`). Absent means the first. */
+	diagram?: string;
 	subtype?: string;
 	icon?: string;
 	description: string;
@@ -81,6 +83,8 @@ export interface Element {
 	tags?: string[];
 	stores?: string[];
 	encryption?: string;
+	/** Operations an API element exposes, e.g. as imported from an OpenAPI document. */
+	operations?: ApiOperation[];
 	position?: Position;
 	fill_color?: string;
 	stroke_color?: string;
@@ -90,6 +94,22 @@ export interface Element {
 	font_weight?: string;
 }
 
+/** One operation of an API element: a method on a path (mirrors Rust `ApiOperation`). */
+export interface ApiOperation {
+	/** HTTP method, upper case. */
+	method: string;
+	path: string;
+	summary?: string;
+	/** Whether every caller must authenticate. */
+	authenticated: boolean;
+	/** Whether the operation declares a rate limit. */
+	rate_limited: boolean;
+	/** Parameter and request body field names; nested fields are dotted. */
+	request_fields?: string[];
+	/** Field names of successful responses, dotted like `request_fields`. */
+	response_fields?: string[];
+}
+
 export interface DataFlow {
 	id: string;
 	flow_number?: number;
@@ -110,6 +130,12 @@ export interface TrustBoundary {
 	id: string;
 	name: string;
 	contains: string[];
+	/** Optional diagram this boundary is drawn on (references `Diagram.id`). Absent means the first. */
+	diagram?: string;
+	/** Absent means a rectangle drawn from `position` and `size`. */
+	shape?: BoundaryShape;
+	/** Vertices of a `line` boundary, in canvas coordinates. */
+	points?: Position[];
 	position?: Position;
 	size?: Size;
 	fill_color?: string;
@@ -118,6 +144,9 @@ export interface TrustBoundary {
 	stroke_opacity?: number;
 }
 
+/** How a trust boundary is drawn. A line separates regions and has no members (mirrors Rust `BoundaryShape`). */
+export type BoundaryShape = "rectangle" | "line";
+
 /**
  * A horizontal architecture layer. Array order is display order (mirrors Rust `Layer`).
  * Member order must match the Rust struct — the browser writer emits keys in this order.